@@ -0,0 +1,45 @@
+//! The diagnostics of the AppKit backend.
+
+use std::ffi::c_void;
+
+use winit_core::diagnostics::{BackendProbe, BackendReport};
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGSessionCopyCurrentDictionary() -> *const c_void;
+}
+
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+    fn CFRelease(object: *const c_void);
+}
+
+/// The probe of the AppKit backend, see [`winit_core::diagnostics::probe`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AppKitProbe;
+
+impl BackendProbe for AppKitProbe {
+    fn name(&self) -> &'static str {
+        "appkit"
+    }
+
+    fn version(&self) -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    fn probe(&self) -> BackendReport {
+        let mut report = BackendReport::new(self.name());
+
+        // The processes outside of the login session, like the ones started
+        // over SSH, can't reach the window server.
+        let session = unsafe { CGSessionCopyCurrentDictionary() };
+        if session.is_null() {
+            report.missing.push(String::from("the process is not in the window server session"));
+            return report;
+        }
+
+        unsafe { CFRelease(session) };
+        report.connectable = true;
+        report
+    }
+}
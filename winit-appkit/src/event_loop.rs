@@ -0,0 +1,589 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::ffi::c_void;
+use std::mem;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use raw_window_handle::{DisplayHandle, HandleError, HasDisplayHandle};
+use raw_window_handle_05::HasRawDisplayHandle as HasRawDisplayHandle05;
+
+use objc2::rc::Retained;
+use objc2::runtime::{AnyObject, ProtocolObject};
+use objc2::{class, declare_class, msg_send, msg_send_id, mutability, ClassType, DeclaredClass};
+use objc2_app_kit::{
+    NSApplication, NSApplicationActivationPolicy, NSApplicationDelegate, NSEvent,
+    NSEventModifierFlags, NSEventSubtype, NSEventType,
+};
+use objc2_foundation::{
+    MainThreadMarker, NSDate, NSDefaultRunLoopMode, NSNotification, NSObject, NSObjectProtocol,
+    NSPoint,
+};
+
+use winit_core::application::{Application, StartCause};
+use winit_core::event_loop::proxy::EventLoopProxy as CoreEventLoopProxy;
+use winit_core::event_loop::{EventLoopError, EventLoopHandle, EventLoopRequests};
+use winit_core::window::{CloseBehavior, DecorationMode, Surface as CoreSurface, WindowId};
+
+use crate::state::WinitState;
+
+/// The subtype of the application defined events posted by the
+/// [`EventLoopProxy`], telling them apart from the ones of the application.
+const PROXY_SUBTYPE: i16 = 0x5749;
+
+/// The `data1` of the event posted by the [`CoreEventLoopProxy::wakeup`].
+const WAKEUP: isize = 0;
+
+/// The `data1` of the event posted by the [`CoreEventLoopProxy::request_exit`].
+const EXIT_REQUESTED: isize = 1;
+
+/// The notification sent by the delegates, queued to be delivered to the
+/// application.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Event {
+    Created(WindowId),
+    CloseRequested(WindowId),
+    Resized(WindowId),
+    ScaleFactorChanged(WindowId),
+    Focused(WindowId, bool),
+    OcclusionChanged(WindowId),
+    Wakeup,
+    ExitRequested,
+    DisplayChanged,
+}
+
+thread_local! {
+    /// The events waiting to be delivered.
+    static QUEUE: RefCell<VecDeque<Event>> = RefCell::new(VecDeque::new());
+
+    /// The [`RuntimeState`] of the running loop along with its dispatcher.
+    static RUNTIME: Cell<Option<(*mut c_void, unsafe fn(*mut c_void))>> = Cell::new(None);
+
+    /// Whether the loop is sending the events, so the delegates can deliver
+    /// the notifications right away.
+    ///
+    /// The delegates are also called from within the application callbacks,
+    /// for example by `setContentSize:`, the notifications are only queued
+    /// then.
+    static PUMPING: Cell<bool> = Cell::new(false);
+}
+
+/// Queue the `event` to be delivered once the current callback returns.
+pub(crate) fn queue_event(event: Event) {
+    QUEUE.with(|queue| queue.borrow_mut().push_back(event));
+}
+
+fn next_event() -> Option<Event> {
+    QUEUE.with(|queue| queue.borrow_mut().pop_front())
+}
+
+fn has_events() -> bool {
+    QUEUE.with(|queue| !queue.borrow().is_empty())
+}
+
+/// Deliver the queued events when the loop is sending the events.
+///
+/// This is the case for the tracking loops as well, like the one of the
+/// interactive resize, which doesn't return to the event loop until done.
+pub(crate) fn dispatch_if_pumping() {
+    if !PUMPING.with(Cell::get) {
+        return;
+    }
+
+    if let Some((state, dispatch)) = RUNTIME.with(Cell::get) {
+        PUMPING.with(|pumping| pumping.set(false));
+        // SAFETY: the loop doesn't access the state while pumping.
+        unsafe { dispatch(state) };
+        PUMPING.with(|pumping| pumping.set(true));
+    }
+}
+
+declare_class!(
+    /// The delegate forwarding the notifications of the application to the
+    /// event loop.
+    struct AppDelegate;
+
+    unsafe impl ClassType for AppDelegate {
+        type Super = NSObject;
+        type Mutability = mutability::MainThreadOnly;
+        const NAME: &'static str = "WinitApplicationDelegate";
+    }
+
+    impl DeclaredClass for AppDelegate {}
+
+    unsafe impl NSObjectProtocol for AppDelegate {}
+
+    unsafe impl NSApplicationDelegate for AppDelegate {
+        #[method(applicationDidChangeScreenParameters:)]
+        fn application_did_change_screen_parameters(&self, _: &NSNotification) {
+            queue_event(Event::DisplayChanged);
+            dispatch_if_pumping();
+        }
+    }
+);
+
+impl AppDelegate {
+    fn new(mtm: MainThreadMarker) -> Retained<Self> {
+        let this = mtm.alloc().set_ivars(());
+        unsafe { msg_send_id![super(this), init] }
+    }
+}
+
+/// The AppKit event loop.
+///
+/// The application type could be provided statically, or left as the
+/// `Box<dyn Application>` when the event loop is used through the
+/// [`EventLoopRequests`].
+pub struct EventLoop<T: Application + 'static = Box<dyn Application>> {
+    pub(crate) state: RuntimeState<T>,
+
+    app: Retained<NSApplication>,
+
+    /// The application only holds the weak reference to its delegate.
+    _delegate: Retained<AppDelegate>,
+}
+
+impl<T: Application + 'static> EventLoop<T> {
+    pub fn new() -> Result<Self, EventLoopError> {
+        let started_at = Instant::now();
+
+        // AppKit is only usable from the main thread.
+        let mtm = match MainThreadMarker::new() {
+            Some(mtm) => mtm,
+            None => {
+                let reason = "the event loop must be created on the main thread";
+                return Err(EventLoopError::Os(reason.into()));
+            },
+        };
+
+        let app = NSApplication::sharedApplication(mtm);
+
+        // Show up in the Dock and get the focus, like the bundled applications.
+        app.setActivationPolicy(NSApplicationActivationPolicy::Regular);
+
+        let delegate = AppDelegate::new(mtm);
+        app.setDelegate(Some(ProtocolObject::from_ref(&*delegate)));
+        unsafe { app.finishLaunching() };
+
+        let proxy = EventLoopProxy::new();
+        let mut state = RuntimeState { user: None, winit: WinitState::new(mtm, proxy) };
+
+        let startup = &mut state.winit.startup;
+        startup.started_at = started_at;
+        startup.timings.connected = Some(started_at.elapsed());
+        startup.timings.globals_bound = Some(started_at.elapsed());
+
+        Ok(Self { state, app, _delegate: delegate })
+    }
+
+    /// Run the event loop.
+    pub fn run(mut self, mut state: T) {
+        // SAFETY: The user state is being used only inside the loop, the pointer is
+        // cleared before returning.
+        self.state.user =
+            Some(unsafe { std::mem::transmute::<&mut T, &'static mut T>(&mut state) });
+
+        let runtime_ptr: *mut RuntimeState<T> = &mut self.state;
+        let dispatch: unsafe fn(*mut c_void) = RuntimeState::<T>::dispatch_raw;
+        RUNTIME.with(|cell| cell.set(Some((runtime_ptr as *mut c_void, dispatch))));
+
+        // SAFETY: the delegates only access the state while pumping, the
+        // references below never live across the pumping.
+        let runtime = unsafe { &mut *runtime_ptr };
+        runtime.user.as_mut().unwrap().new_events(&mut runtime.winit, StartCause::Init);
+
+        let mut timeout = Some(Duration::ZERO);
+        loop {
+            unsafe { (*runtime_ptr).winit.dispatch.start_iteration() };
+            let exhausted = pump_events(&self.app, runtime_ptr, timeout);
+
+            let runtime = unsafe { &mut *runtime_ptr };
+
+            // The events queued from within the callbacks of the previous
+            // iteration.
+            runtime.dispatch_events();
+
+            let winit = &mut runtime.winit;
+            let user = runtime.user.as_mut().unwrap();
+
+            let timer_timeout = winit.fire_timers(user);
+            winit.deliver_redraws(user);
+            winit.resolve_queries(user);
+            let idle_timeout = winit.update_idle(user);
+
+            user.about_to_wait(winit);
+
+            if runtime.winit.exit {
+                break;
+            }
+
+            // Don't block on the events queued by the `about_to_wait`, on the
+            // events left over the budget, or on the requested redraws.
+            let redraw_pending = runtime
+                .winit
+                .windows
+                .values()
+                .any(|window| window.created && window.redraw_requested);
+            timeout = if has_events() || exhausted || redraw_pending {
+                Some(Duration::ZERO)
+            } else {
+                match (timer_timeout, idle_timeout) {
+                    (Some(timer), Some(idle)) => Some(timer.min(idle)),
+                    (timer, idle) => timer.or(idle),
+                }
+            };
+        }
+
+        let runtime = unsafe { &mut *runtime_ptr };
+        runtime.user.as_mut().unwrap().loop_exiting(&mut runtime.winit);
+
+        RUNTIME.with(|cell| cell.set(None));
+        QUEUE.with(|queue| queue.borrow_mut().clear());
+        self.state.user = None;
+    }
+
+    /// Get the proxy to wakeup the event loop.
+    pub fn proxy(&self) -> Arc<dyn CoreEventLoopProxy> {
+        self.state.winit.proxy()
+    }
+}
+
+impl EventLoopRequests for EventLoop {
+    fn new() -> Result<Self, EventLoopError> {
+        EventLoop::new()
+    }
+
+    fn run(self: Box<Self>, app: Box<dyn Application>) {
+        EventLoop::run(*self, app)
+    }
+
+    fn proxy(&self) -> Arc<dyn CoreEventLoopProxy> {
+        EventLoop::proxy(self)
+    }
+}
+
+impl<T: Application + 'static> HasDisplayHandle for EventLoop<T> {
+    fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+        self.state.winit.display_handle()
+    }
+}
+
+unsafe impl<T: Application + 'static> HasRawDisplayHandle05 for EventLoop<T> {
+    fn raw_display_handle(&self) -> raw_window_handle_05::RawDisplayHandle {
+        self.state.winit.raw_display_handle()
+    }
+}
+
+/// Send the pending events within the budget, waiting for the first one up to
+/// the `timeout`, and delivering the notifications right away.
+///
+/// Returns whether the budget ran out before all the events were sent.
+fn pump_events<T: Application + 'static>(
+    app: &NSApplication,
+    runtime: *mut RuntimeState<T>,
+    timeout: Option<Duration>,
+) -> bool {
+    PUMPING.with(|pumping| pumping.set(true));
+
+    let mut until = match timeout {
+        Some(timeout) if timeout.is_zero() => NSDate::distantPast(),
+        Some(timeout) => NSDate::dateWithTimeIntervalSinceNow(timeout.as_secs_f64()),
+        None => NSDate::distantFuture(),
+    };
+
+    let mut exhausted = false;
+    loop {
+        // SAFETY: the delegates are done with the state in between the events.
+        if !unsafe { (*runtime).winit.dispatch.try_continue() } {
+            exhausted = true;
+            break;
+        }
+
+        let event = unsafe {
+            app.nextEventMatchingMask_untilDate_inMode_dequeue(
+                objc2_app_kit::NSEventMask::Any,
+                Some(&until),
+                NSDefaultRunLoopMode,
+                true,
+            )
+        };
+
+        let event = match event {
+            Some(event) => event,
+            None => break,
+        };
+
+        // Only wait for the first event.
+        until = NSDate::distantPast();
+
+        match proxy_event(&event) {
+            Some(event) => {
+                queue_event(event);
+                dispatch_if_pumping();
+            },
+            None => app.sendEvent(&event),
+        }
+    }
+
+    app.updateWindows();
+
+    PUMPING.with(|pumping| pumping.set(false));
+    exhausted
+}
+
+/// The event posted by the [`EventLoopProxy`].
+fn proxy_event(event: &NSEvent) -> Option<Event> {
+    if unsafe { event.r#type() } != NSEventType::ApplicationDefined
+        || unsafe { event.subtype() } != NSEventSubtype(PROXY_SUBTYPE)
+    {
+        return None;
+    }
+
+    match unsafe { event.data1() } {
+        WAKEUP => Some(Event::Wakeup),
+        EXIT_REQUESTED => Some(Event::ExitRequested),
+        _ => None,
+    }
+}
+
+/// Runtime state passed around.
+pub struct RuntimeState<T: Application + 'static> {
+    /// The user state we're using during the runtime.
+    pub(crate) user: Option<&'static mut T>,
+
+    /// The state of the winit.
+    pub(crate) winit: WinitState<T>,
+}
+
+impl<T: Application + 'static> RuntimeState<T> {
+    /// The type-erased [`RuntimeState::dispatch_events`] for the delegates.
+    unsafe fn dispatch_raw(state: *mut c_void) {
+        let state = &mut *(state as *mut Self);
+        state.dispatch_events();
+    }
+
+    /// Deliver the queued events, including the ones queued meanwhile.
+    pub(crate) fn dispatch_events(&mut self) {
+        while let Some(event) = next_event() {
+            self.dispatch_event(event);
+        }
+    }
+
+    fn dispatch_event(&mut self, event: Event) {
+        let winit = &mut self.winit;
+        let user = self.user.as_mut().unwrap();
+        winit.dispatch.record_event();
+
+        let window_id = match event {
+            Event::Wakeup => {
+                winit.proxy.wakeup_pending.store(false, Ordering::Relaxed);
+                user.user_wakeup(winit);
+                return;
+            },
+            Event::ExitRequested => {
+                winit.proxy.exit_pending.store(false, Ordering::Relaxed);
+                if user.exit_requested(winit) {
+                    winit.exit = true;
+                }
+                return;
+            },
+            Event::DisplayChanged => {
+                winit.update_monitors(user);
+                return;
+            },
+            Event::Created(window_id) => {
+                self.window_created(window_id);
+                return;
+            },
+            Event::CloseRequested(window_id)
+            | Event::Resized(window_id)
+            | Event::ScaleFactorChanged(window_id)
+            | Event::Focused(window_id, _)
+            | Event::OcclusionChanged(window_id) => window_id,
+        };
+
+        // The notifications sent while creating or closing the window.
+        let window = match winit.windows.get_mut(&window_id) {
+            Some(window) if window.created => window,
+            _ => return,
+        };
+
+        match event {
+            Event::CloseRequested(_) => self.request_window_close(window_id),
+            Event::Resized(_) => {
+                if !window.update_size() {
+                    return;
+                }
+
+                // The interactive resize doesn't return to the event loop until
+                // done, so redraw right away to keep up with the pointer.
+                let size = window.inner_size();
+                let redraw = window.in_live_resize();
+                if redraw {
+                    window.redraw_requested = false;
+                }
+
+                user.resized(winit, window_id, size);
+                if redraw {
+                    user.redraw_requested(winit, window_id);
+                }
+            },
+            Event::ScaleFactorChanged(_) => {
+                if !window.update_scale_factor() {
+                    return;
+                }
+
+                // The size in points stays the same, so the size in pixels
+                // changes along with the scale.
+                let resized = window.update_size();
+                let scale_factor = window.scale_factor;
+                let size = window.inner_size();
+                window.redraw_requested = true;
+
+                user.scale_factor_changed(winit, window_id, scale_factor);
+                if resized {
+                    user.resized(winit, window_id, size);
+                }
+            },
+            Event::Focused(_, focused) => {
+                if mem::replace(&mut window.has_focus, focused) != focused {
+                    user.focused(winit, window_id, focused);
+                }
+            },
+            Event::OcclusionChanged(_) => {
+                if window.update_occlusion() {
+                    let occluded = window.occluded;
+                    user.occluded(winit, window_id, occluded);
+                }
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    /// Show the created window and deliver its initial state.
+    fn window_created(&mut self, window_id: WindowId) {
+        let winit = &mut self.winit;
+        let user = self.user.as_mut().unwrap();
+
+        let window = match winit.windows.get_mut(&window_id) {
+            Some(window) => window,
+            None => return,
+        };
+
+        // The notifications sent while showing are delivered after the
+        // creation.
+        window.show();
+        window.update_scale_factor();
+        window.update_size();
+        window.created = true;
+        let scale_factor = window.scale_factor;
+        let size = window.inner_size();
+
+        let startup = &mut winit.startup;
+        if startup.timings.first_configure.is_none() {
+            startup.timings.first_configure = Some(startup.started_at.elapsed());
+        }
+
+        user.created(winit, window_id);
+        user.scale_factor_changed(winit, window_id, scale_factor);
+
+        // The title bar is always drawn by the system.
+        user.decoration_mode_changed(winit, window_id, DecorationMode::Server);
+        user.resized(winit, window_id, size);
+    }
+
+    pub(crate) fn request_window_close(&mut self, window_id: WindowId) {
+        if !self.winit.windows.contains_key(&window_id) {
+            return;
+        }
+
+        let user_state = self.user.as_mut().unwrap();
+        if !user_state.close_requested(&mut self.winit, window_id) {
+            return;
+        }
+
+        // The user could change the behavior while asked.
+        let close_behavior = match self.winit.windows.get(&window_id) {
+            Some(window) => window.close_behavior,
+            None => return,
+        };
+
+        match close_behavior {
+            CloseBehavior::NotifyOnly => (),
+            CloseBehavior::DestroyImmediately => {
+                drop(self.winit.windows.remove(&window_id));
+                self.winit.tombstones.bury(window_id);
+                user_state.destroyed(&mut self.winit, window_id);
+            },
+            CloseBehavior::HideInstead => {
+                if let Some(window) = self.winit.windows.get_mut(&window_id) {
+                    window.hide();
+                }
+            },
+        }
+    }
+}
+
+pub struct EventLoopProxy {
+    /// Whether the wakeup is posted and not delivered yet, the wakeups made
+    /// meanwhile are merged.
+    wakeup_pending: AtomicBool,
+
+    /// Same as the `wakeup_pending`, but for the
+    /// [`CoreEventLoopProxy::request_exit`].
+    exit_pending: AtomicBool,
+}
+
+impl EventLoopProxy {
+    fn new() -> Self {
+        Self { wakeup_pending: AtomicBool::new(false), exit_pending: AtomicBool::new(false) }
+    }
+
+    /// Post the application defined event with the `data1` to the main
+    /// thread, waking up the loop.
+    fn post(&self, data1: isize) {
+        let event = unsafe {
+            NSEvent::otherEventWithType_location_modifierFlags_timestamp_windowNumber_context_subtype_data1_data2(
+                NSEventType::ApplicationDefined,
+                NSPoint::new(0., 0.),
+                NSEventModifierFlags(0),
+                0.,
+                0,
+                None,
+                PROXY_SUBTYPE,
+                data1,
+                0,
+            )
+        };
+
+        let event = match event {
+            Some(event) => event,
+            None => {
+                log::warn!("Failed to create the wakeup event");
+                return;
+            },
+        };
+
+        // SAFETY: posting the events is documented to be safe from any
+        // thread, unlike the rest of the `NSApplication`.
+        unsafe {
+            let app: *mut AnyObject = msg_send![class!(NSApplication), sharedApplication];
+            let _: () = msg_send![app, postEvent: &*event, atStart: false];
+        }
+    }
+}
+
+impl CoreEventLoopProxy for EventLoopProxy {
+    fn wakeup(&self) {
+        if !self.wakeup_pending.swap(true, Ordering::Relaxed) {
+            self.post(WAKEUP);
+        }
+    }
+
+    fn request_exit(&self) {
+        if !self.exit_pending.swap(true, Ordering::Relaxed) {
+            self.post(EXIT_REQUESTED);
+        }
+    }
+}
@@ -0,0 +1,34 @@
+//! The AppKit backend.
+//!
+//! The structure follows the Wayland backend: the [`EventLoop`] owns the
+//! [`WinitState`], which implements the [`EventLoopHandle`] passed to the
+//! application.
+//!
+//! The delegates of the windows can be called from within the application
+//! callbacks, for example by `setContentSize:`, so their notifications are
+//! queued and delivered once the callback returns.
+//!
+//! [`EventLoop`]: crate::event_loop::EventLoop
+//! [`WinitState`]: crate::state::WinitState
+//! [`EventLoopHandle`]: winit_core::event_loop::EventLoopHandle
+#![cfg(target_os = "macos")]
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use winit_core::window::WindowId;
+
+pub mod diagnostics;
+pub mod event_loop;
+pub mod monitor;
+pub mod state;
+pub mod window;
+
+/// Allocate the WindowId for the new window.
+///
+/// The address of the freed window could be reused by the next one, so the
+/// ids are never derived from it.
+#[inline]
+pub(crate) fn make_wid() -> WindowId {
+    static NEXT_WINDOW_ID: AtomicU64 = AtomicU64::new(0);
+    WindowId(NEXT_WINDOW_ID.fetch_add(1, Ordering::Relaxed) as u128)
+}
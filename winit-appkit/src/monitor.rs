@@ -0,0 +1,147 @@
+//! The monitors enumerated with the `NSScreen`.
+
+use objc2::rc::Retained;
+use objc2_app_kit::NSScreen;
+use objc2_foundation::{ns_string, MainThreadMarker, NSNumber, NSRect};
+
+use winit_core::dpi::{PhysicalPosition, PhysicalSize, Rect};
+use winit_core::monitor::{Monitor as CoreMonitor, MonitorId};
+
+/// The monitor known to the system.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Monitor {
+    id: MonitorId,
+
+    /// The `CGDirectDisplayID` of the screen.
+    display_id: u32,
+
+    name: Option<String>,
+
+    size: PhysicalSize<u32>,
+    position: PhysicalPosition<i32>,
+
+    /// The area not covered by the menu bar and the Dock, in points.
+    work_area: Rect<i32>,
+
+    refresh_rate_millihertz: Option<u32>,
+    scale_factor: f64,
+    primary: bool,
+}
+
+impl Monitor {
+    /// Query the state of the `screen`.
+    ///
+    /// The `primary_height` is the height of the screen with the menu bar, the
+    /// origin of the AppKit coordinates is at its bottom-left corner.
+    fn new(screen: &NSScreen, primary_height: f64, primary: bool) -> Option<Self> {
+        let display_id = display_id(screen)?;
+        let scale_factor = screen.backingScaleFactor();
+        let frame = screen.frame();
+
+        // The positions are top-left based, like on the other backends.
+        let to_physical = |rect: NSRect| {
+            let top = primary_height - (rect.origin.y + rect.size.height);
+            (
+                PhysicalPosition::new(
+                    (rect.origin.x * scale_factor).round() as i32,
+                    (top * scale_factor).round() as i32,
+                ),
+                PhysicalSize::new(
+                    (rect.size.width * scale_factor).round() as u32,
+                    (rect.size.height * scale_factor).round() as u32,
+                ),
+            )
+        };
+
+        let (position, size) = to_physical(frame);
+        let (work_position, work_size) = to_physical(screen.visibleFrame());
+        let work_area = Rect::new(
+            work_position,
+            PhysicalSize::new(work_size.width as i32, work_size.height as i32),
+        );
+
+        // The fixed refresh rate, the adaptive displays report zero.
+        let refresh_rate = unsafe { screen.maximumFramesPerSecond() };
+
+        Some(Self {
+            // The display ids are kept for the same display across the
+            // reconnects.
+            id: MonitorId(display_id as u128),
+            display_id,
+            name: Some(unsafe { screen.localizedName() }.to_string()),
+            size,
+            position,
+            work_area,
+            refresh_rate_millihertz: (refresh_rate > 0).then(|| refresh_rate as u32 * 1000),
+            scale_factor,
+            primary,
+        })
+    }
+
+    /// All the screens, the first one has the menu bar.
+    pub(crate) fn enumerate(mtm: MainThreadMarker) -> Vec<Self> {
+        let screens = NSScreen::screens(mtm);
+        let primary_height = screens.first().map_or(0., |screen| screen.frame().size.height);
+
+        screens
+            .iter()
+            .enumerate()
+            .filter_map(|(index, screen)| Self::new(&screen, primary_height, index == 0))
+            .collect()
+    }
+
+    #[inline]
+    pub(crate) fn is_primary(&self) -> bool {
+        self.primary
+    }
+
+    #[inline]
+    pub(crate) fn work_area(&self) -> Rect<i32> {
+        self.work_area
+    }
+}
+
+impl CoreMonitor for Monitor {
+    fn id(&self) -> MonitorId {
+        self.id
+    }
+
+    fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    fn connection_name(&self) -> Option<String> {
+        Some(self.display_id.to_string())
+    }
+
+    fn size(&self) -> PhysicalSize<u32> {
+        self.size
+    }
+
+    fn position(&self) -> PhysicalPosition<i32> {
+        self.position
+    }
+
+    fn refresh_rate_millihertz(&self) -> Option<u32> {
+        self.refresh_rate_millihertz
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+}
+
+/// The id of the monitor the `screen` is on.
+pub(crate) fn screen_monitor_id(screen: &NSScreen) -> Option<MonitorId> {
+    display_id(screen).map(|display_id| MonitorId(display_id as u128))
+}
+
+/// The `CGDirectDisplayID` of the `screen`.
+fn display_id(screen: &NSScreen) -> Option<u32> {
+    let description = screen.deviceDescription();
+    let number = description.objectForKey(ns_string!("NSScreenNumber"))?;
+
+    // SAFETY: the screen number is documented to be the `NSNumber`.
+    let number: Retained<NSNumber> = unsafe { Retained::cast(number) };
+    Some(number.unsignedIntValue())
+}
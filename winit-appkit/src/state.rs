@@ -0,0 +1,523 @@
+use std::collections::HashMap;
+use std::io;
+use std::mem;
+use std::os::fd::RawFd;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use raw_window_handle::{DisplayHandle, HandleError, HasDisplayHandle};
+use raw_window_handle_05::HasRawDisplayHandle as HasRawDisplayHandle05;
+
+use objc2_foundation::MainThreadMarker;
+
+use smallvec::SmallVec;
+
+use winit_core::application::Application;
+use winit_core::clipboard::Clipboard as CoreClipboard;
+use winit_core::dpi::{PhysicalPosition, Rounding, RoundingPolicy};
+use winit_core::event_loop::budget::{DispatchBudget, DispatchMeter, DispatchStats};
+use winit_core::event_loop::proxy::EventLoopProxy as CoreEventLoopProxy;
+use winit_core::event_loop::{
+    EventLoopHandle, FdToken, Interest, Query, QueryResult, RequestId, StartupTimings, TimerId,
+};
+use winit_core::input::filter::{EventFilter, EventFilterId};
+use winit_core::input::pointer::MotionCompression;
+use winit_core::input::{DeviceId, DeviceInfo};
+use winit_core::monitor::{Monitor as CoreMonitor, MonitorId};
+use winit_core::window::{
+    CreateWindowError, GroupId, RoleAttributes, Surface as CoreSurface, Tombstones, WindowError,
+    WindowId,
+};
+
+use crate::event_loop::{self, Event, EventLoopProxy};
+use crate::monitor::Monitor;
+use crate::window::{self, Window};
+
+impl<T: Application + 'static> EventLoopHandle for WinitState<T> {
+    fn proxy(&self) -> Arc<dyn CoreEventLoopProxy> {
+        self.proxy.clone()
+    }
+
+    fn create_window(&mut self, attributes: RoleAttributes) -> Result<(), CreateWindowError> {
+        let attributes = winit_core::env::overrides().apply(attributes);
+        let lints = attributes.validate().map_err(CreateWindowError::InvalidAttributes)?;
+        for lint in lints {
+            log::warn!("Suspicious window attributes: {lint:?}");
+        }
+
+        let attributes = match attributes {
+            RoleAttributes::Toplevel(attributes) => attributes,
+            // TODO: popups and subsurfaces.
+            RoleAttributes::Popup(_) | RoleAttributes::Subsurface(_) => {
+                return Err(CreateWindowError::Unsupported)
+            },
+        };
+
+        let window = Window::new(self.mtm, attributes, &self.monitors, self.rounding_policy)
+            .map_err(|_| CreateWindowError::Unsupported)?;
+        let window_id = window.id();
+        self.tombstones.revive(window_id);
+        self.windows.insert(window_id, window);
+
+        // The window is shown once the user is done with the current callback.
+        event_loop::queue_event(Event::Created(window_id));
+        Ok(())
+    }
+
+    fn num_windows(&self) -> usize {
+        self.windows.len()
+    }
+
+    fn get_window(&self, window_id: WindowId) -> Option<&dyn CoreSurface> {
+        self.tombstones.debug_assert_alive(window_id);
+
+        let window = self.windows.get(&window_id)?;
+        if !window.created {
+            return None;
+        } else {
+            Some(window as &dyn CoreSurface)
+        }
+    }
+
+    fn get_window_mut(&mut self, window_id: WindowId) -> Option<&mut dyn CoreSurface> {
+        self.tombstones.debug_assert_alive(window_id);
+
+        let window = self.windows.get_mut(&window_id)?;
+        if !window.created {
+            return None;
+        } else {
+            Some(window as &mut dyn CoreSurface)
+        }
+    }
+
+    fn try_get_window(&self, window_id: WindowId) -> Result<&dyn CoreSurface, WindowError> {
+        match self.windows.get(&window_id) {
+            Some(window) if window.created => Ok(window as &dyn CoreSurface),
+            Some(_) => Err(WindowError::NotConfigured),
+            None => Err(self.missing_window_error(window_id)),
+        }
+    }
+
+    fn try_get_window_mut(
+        &mut self,
+        window_id: WindowId,
+    ) -> Result<&mut dyn CoreSurface, WindowError> {
+        let error = self.missing_window_error(window_id);
+        match self.windows.get_mut(&window_id) {
+            Some(window) if window.created => Ok(window as &mut dyn CoreSurface),
+            Some(_) => Err(WindowError::NotConfigured),
+            None => Err(error),
+        }
+    }
+
+    fn set_timer(&mut self, interval: Duration, timer_id: TimerId) {
+        // The timers are checked by the event loop, which wakes up for the
+        // closest one.
+        self.timers.insert(timer_id, (Instant::now() + interval, interval));
+    }
+
+    fn cancel_timer(&mut self, timer_id: TimerId) {
+        self.timers.remove(&timer_id);
+    }
+
+    unsafe fn register_fd(
+        &mut self,
+        fd: RawFd,
+        interest: Interest,
+        token: FdToken,
+    ) -> io::Result<()> {
+        // TODO: wait for the descriptors with the `CFFileDescriptor`.
+        let _ = (fd, interest, token);
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    fn unregister_fd(&mut self, token: FdToken) {
+        let _ = token;
+    }
+
+    fn request(&mut self, query: Query) -> RequestId {
+        let request_id = RequestId(self.next_request_id);
+        self.next_request_id += 1;
+
+        // The answer is delivered once the user is done with the current
+        // callback, like on the other backends.
+        self.pending_queries.push((request_id, query));
+
+        request_id
+    }
+
+    fn clipboard(&mut self) -> Option<&mut dyn CoreClipboard> {
+        // TODO: the clipboard.
+        None
+    }
+
+    fn set_motion_compression(&mut self, compression: MotionCompression) {
+        self.motion_compression = compression;
+    }
+
+    fn set_dispatch_budget(&mut self, budget: DispatchBudget) {
+        self.dispatch.set_budget(budget);
+    }
+
+    fn dispatch_stats(&self) -> DispatchStats {
+        self.dispatch.stats()
+    }
+
+    fn push_event_filter(&mut self, filter: Box<dyn EventFilter>) -> EventFilterId {
+        // TODO: the input isn't delivered yet, the filters are kept until it is.
+        let filter_id = EventFilterId(self.next_filter);
+        self.next_filter += 1;
+        self.event_filters.push((filter_id, filter));
+        filter_id
+    }
+
+    fn remove_event_filter(&mut self, filter_id: EventFilterId) {
+        self.event_filters.retain(|(id, _)| *id != filter_id);
+    }
+
+    fn idle_since(&self) -> Option<Duration> {
+        let inactive = inactive_duration()?;
+        (inactive >= self.idle_threshold?).then_some(inactive)
+    }
+
+    fn set_idle_threshold(&mut self, threshold: Option<Duration>) {
+        self.idle_threshold = threshold;
+        if threshold.is_none() {
+            self.idle = false;
+        }
+    }
+
+    fn startup_timings(&self) -> StartupTimings {
+        self.startup.timings
+    }
+
+    fn rounding_policy(&self) -> RoundingPolicy {
+        self.rounding_policy
+    }
+
+    fn set_rounding_policy(&mut self, policy: RoundingPolicy) {
+        self.rounding_policy = policy;
+        for window in self.windows.values_mut() {
+            window.rounding_policy = policy;
+        }
+    }
+
+    fn coalesced_pointer_positions(&self) -> &[PhysicalPosition<f64>] {
+        &[]
+    }
+
+    fn dump_state(&self, writer: &mut dyn io::Write) -> io::Result<()> {
+        let monitors: Vec<_> = self
+            .monitors
+            .iter()
+            .map(|monitor| {
+                let size = monitor.size();
+                let position = monitor.position();
+                serde_json::json!({
+                    "id": monitor.id().0,
+                    "connection_name": monitor.connection_name(),
+                    "size": (size.width, size.height),
+                    "position": (position.x, position.y),
+                    "refresh_rate_millihertz": monitor.refresh_rate_millihertz(),
+                    "scale_factor": monitor.scale_factor(),
+                    "primary": monitor.is_primary(),
+                })
+            })
+            .collect();
+
+        let windows: Vec<_> = self.windows.values().map(|window| window.dump()).collect();
+
+        let state = serde_json::json!({
+            "backend": "appkit",
+            "monitors": monitors,
+            "windows": windows,
+            "timers": self.timers.len(),
+            "motion_compression": format!("{:?}", self.motion_compression),
+            "dispatch_stats": format!("{:?}", self.dispatch.stats()),
+        });
+
+        serde_json::to_writer_pretty(writer, &state).map_err(io::Error::from)
+    }
+
+    fn exit(&mut self) {
+        self.exit = true;
+    }
+
+    fn group_members(&self, group: GroupId) -> SmallVec<[WindowId; 4]> {
+        let mut members: SmallVec<[(Instant, WindowId); 4]> = self
+            .windows
+            .iter()
+            .filter_map(|(window_id, window)| match window.group {
+                Some((window_group, joined_at)) if window_group == group => {
+                    Some((joined_at, *window_id))
+                },
+                _ => None,
+            })
+            .collect();
+        members.sort_unstable_by_key(|&(joined_at, window_id)| (joined_at, window_id.0));
+        members.into_iter().map(|(_, window_id)| window_id).collect()
+    }
+
+    fn get_monitor(&self, monitor_id: MonitorId) -> Option<&dyn CoreMonitor> {
+        self.monitors
+            .iter()
+            .find(|monitor| monitor.id() == monitor_id)
+            .map(|monitor| monitor as &dyn CoreMonitor)
+    }
+
+    fn monitors(&self) -> SmallVec<[&dyn CoreMonitor; 4]> {
+        self.monitors.iter().map(|monitor| monitor as &dyn CoreMonitor).collect()
+    }
+
+    fn monitor_generation(&self) -> u64 {
+        self.monitor_generation
+    }
+
+    fn device_info(&self, device_id: DeviceId) -> Option<DeviceInfo> {
+        // TODO: the input devices.
+        let _ = device_id;
+        None
+    }
+}
+
+impl<T: Application + 'static> HasDisplayHandle for WinitState<T> {
+    fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+        Ok(DisplayHandle::appkit())
+    }
+}
+
+unsafe impl<T: Application + 'static> HasRawDisplayHandle05 for WinitState<T> {
+    fn raw_display_handle(&self) -> raw_window_handle_05::RawDisplayHandle {
+        let display_handle = raw_window_handle_05::AppKitDisplayHandle::empty();
+        raw_window_handle_05::RawDisplayHandle::AppKit(display_handle)
+    }
+}
+
+/// Winit's AppKit state.
+pub struct WinitState<T: Application + 'static> {
+    /// The proof of running on the main thread, which AppKit requires.
+    pub(crate) mtm: MainThreadMarker,
+
+    pub windows: HashMap<WindowId, Window>,
+
+    /// The recently destroyed windows.
+    pub(crate) tombstones: Tombstones,
+
+    pub monitors: Vec<Monitor>,
+
+    /// The counter of the changes to the monitors.
+    pub(crate) monitor_generation: u64,
+
+    /// The timers registered by the user, along with their deadlines and
+    /// intervals.
+    pub(crate) timers: HashMap<TimerId, (Instant, Duration)>,
+
+    /// The id of the next query issued by the user.
+    next_request_id: u64,
+
+    /// The queries waiting to be answered.
+    pub(crate) pending_queries: Vec<(RequestId, Query)>,
+
+    /// How the pointer motion is delivered.
+    pub(crate) motion_compression: MotionCompression,
+
+    /// The budget of the event delivery per iteration.
+    pub(crate) dispatch: DispatchMeter,
+
+    /// The rounding of the window sizes.
+    pub(crate) rounding_policy: RoundingPolicy,
+
+    /// The startup milestones reached so far.
+    pub(crate) startup: Startup,
+
+    /// The inactivity after which the user is considered idle.
+    pub(crate) idle_threshold: Option<Duration>,
+
+    /// Whether the user was idle on the last check.
+    pub(crate) idle: bool,
+
+    /// The filters the input events pass through.
+    event_filters: Vec<(EventFilterId, Box<dyn EventFilter>)>,
+
+    /// The id of the next filter.
+    next_filter: u64,
+
+    pub proxy: Arc<EventLoopProxy>,
+
+    pub exit: bool,
+}
+
+impl<T: Application + 'static> WinitState<T> {
+    pub(crate) fn new(mtm: MainThreadMarker, proxy: EventLoopProxy) -> Self {
+        Self {
+            mtm,
+            windows: Default::default(),
+            tombstones: Default::default(),
+            monitors: Monitor::enumerate(mtm),
+            monitor_generation: 0,
+            timers: Default::default(),
+            next_request_id: 0,
+            pending_queries: Default::default(),
+            motion_compression: Default::default(),
+            dispatch: Default::default(),
+            rounding_policy: RoundingPolicy::uniform(Rounding::Round),
+            startup: Startup::default(),
+            idle_threshold: None,
+            idle: false,
+            event_filters: Default::default(),
+            next_filter: 0,
+            proxy: Arc::new(proxy),
+            exit: Default::default(),
+        }
+    }
+
+    /// Why the window with the `window_id` is not in the state.
+    fn missing_window_error(&self, window_id: WindowId) -> WindowError {
+        if self.tombstones.is_destroyed(window_id) {
+            WindowError::Destroyed
+        } else {
+            WindowError::Unknown
+        }
+    }
+
+    /// Fire the timers past their deadlines, returning the time until the
+    /// next one.
+    pub(crate) fn fire_timers(&mut self, user: &mut T) -> Option<Duration> {
+        let now = Instant::now();
+        let expired: Vec<TimerId> = self
+            .timers
+            .iter()
+            .filter(|(_, (deadline, _))| *deadline <= now)
+            .map(|(timer_id, _)| *timer_id)
+            .collect();
+
+        for timer_id in expired {
+            // The timer could be cancelled by the previous callback.
+            match self.timers.get_mut(&timer_id) {
+                Some((deadline, interval)) => *deadline = now + *interval,
+                None => continue,
+            }
+
+            user.timer_fired(self, timer_id);
+        }
+
+        let now = Instant::now();
+        self.timers.values().map(|(deadline, _)| deadline.saturating_duration_since(now)).min()
+    }
+
+    /// Deliver the redraws requested by the user.
+    pub(crate) fn deliver_redraws(&mut self, user: &mut T) {
+        let window_ids: Vec<WindowId> = self
+            .windows
+            .iter_mut()
+            .filter_map(|(window_id, window)| {
+                (window.created && mem::take(&mut window.redraw_requested)).then_some(*window_id)
+            })
+            .collect();
+
+        for window_id in window_ids {
+            user.redraw_requested(self, window_id);
+        }
+    }
+
+    /// Answer the queries issued by the user.
+    pub(crate) fn resolve_queries(&mut self, user: &mut T) {
+        for (request_id, query) in mem::take(&mut self.pending_queries) {
+            let result = self.resolve_query(&query);
+            user.query_complete(self, request_id, result);
+        }
+    }
+
+    fn resolve_query(&self, query: &Query) -> QueryResult {
+        match query {
+            Query::MonitorDescription(monitor_id) => {
+                let monitor = self.monitors.iter().find(|monitor| monitor.id() == *monitor_id);
+                QueryResult::MonitorDescription(monitor.and_then(|monitor| monitor.name()))
+            },
+            Query::Theme => QueryResult::Theme(window::system_theme(self.mtm)),
+            _ => QueryResult::Unsupported,
+        }
+    }
+
+    /// Enumerate the monitors again, notifying the user about the changed
+    /// ones.
+    pub(crate) fn update_monitors(&mut self, user: &mut T) {
+        let monitors = Monitor::enumerate(self.mtm);
+
+        // The monitors which were added, removed, or changed.
+        let mut changed: Vec<MonitorId> = monitors
+            .iter()
+            .filter(|monitor| !self.monitors.contains(monitor))
+            .map(|monitor| monitor.id())
+            .collect();
+        for monitor in &self.monitors {
+            if !monitors.iter().any(|new| new.id() == monitor.id()) {
+                changed.push(monitor.id());
+            }
+        }
+
+        if changed.is_empty() {
+            return;
+        }
+
+        self.monitors = monitors;
+        self.monitor_generation += 1;
+        for monitor_id in changed {
+            user.monitor_changed(self, monitor_id);
+        }
+    }
+
+    /// Notify the user when they become idle or active, returning the time
+    /// until the next check.
+    pub(crate) fn update_idle(&mut self, user: &mut T) -> Option<Duration> {
+        let threshold = self.idle_threshold?;
+        let inactive = inactive_duration()?;
+
+        let idle = inactive >= threshold;
+        if mem::replace(&mut self.idle, idle) != idle {
+            user.user_idle(self, idle);
+        }
+
+        // The input to the other applications doesn't wake up the loop, so poll
+        // for the activity while idle.
+        if idle {
+            Some(Duration::from_secs(1))
+        } else {
+            Some(threshold - inactive)
+        }
+    }
+}
+
+/// The `kCGEventSourceStateCombinedSessionState`.
+const COMBINED_SESSION_STATE: i32 = 0;
+
+/// The `kCGAnyInputEventType`.
+const ANY_INPUT_EVENT_TYPE: u32 = !0;
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGEventSourceSecondsSinceLastEventType(state_id: i32, event_type: u32) -> f64;
+}
+
+/// The time since the last input to any application.
+fn inactive_duration() -> Option<Duration> {
+    let seconds = unsafe {
+        CGEventSourceSecondsSinceLastEventType(COMBINED_SESSION_STATE, ANY_INPUT_EVENT_TYPE)
+    };
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+/// The tracking of the startup milestones.
+#[derive(Debug)]
+pub(crate) struct Startup {
+    /// The time the event loop creation started at.
+    pub(crate) started_at: Instant,
+
+    pub(crate) timings: StartupTimings,
+}
+
+impl Default for Startup {
+    fn default() -> Self {
+        Self { started_at: Instant::now(), timings: Default::default() }
+    }
+}
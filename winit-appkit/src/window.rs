@@ -0,0 +1,700 @@
+//! The toplevel windows.
+
+use std::ffi::c_void;
+use std::mem;
+use std::ptr::NonNull;
+use std::time::Instant;
+
+use raw_window_handle::{
+    AppKitWindowHandle, HandleError, HasWindowHandle, RawWindowHandle, WindowHandle,
+};
+use raw_window_handle_05::HasRawWindowHandle as HasRawWindowHandle05;
+
+use objc2::rc::Retained;
+use objc2::runtime::{AnyObject, ProtocolObject};
+use objc2::{declare_class, msg_send, msg_send_id, mutability, ClassType, DeclaredClass};
+use objc2_app_kit::{
+    NSAppearance, NSAppearanceNameAqua, NSAppearanceNameDarkAqua, NSApplication,
+    NSBackingStoreType, NSColor, NSWindow, NSWindowDelegate, NSWindowOcclusionState,
+    NSWindowStyleMask,
+};
+use objc2_foundation::{
+    CGFloat, MainThreadMarker, NSNotification, NSObject, NSObjectProtocol, NSPoint, NSRect, NSSize,
+    NSString,
+};
+
+use winit_core::dpi::{
+    LogicalSize, PhysicalPosition, PhysicalSize, Position, Rect, RoundingPolicy, Size,
+};
+use winit_core::input::DeviceId;
+use winit_core::monitor::{Monitor as CoreMonitor, MonitorId};
+use winit_core::window::{
+    CloseBehavior, GroupId, RegionKind, Surface as CoreSurface, Theme, TiledEdges, Title,
+    TitlePolicy, Toplevel, ToplevelAttributes, WindowId,
+};
+
+use crate::event_loop::{self, Event};
+use crate::monitor::{self, Monitor};
+
+declare_class!(
+    /// The delegate forwarding the notifications of the window to the event
+    /// loop.
+    pub(crate) struct WindowDelegate;
+
+    unsafe impl ClassType for WindowDelegate {
+        type Super = NSObject;
+        type Mutability = mutability::MainThreadOnly;
+        const NAME: &'static str = "WinitWindowDelegate";
+    }
+
+    impl DeclaredClass for WindowDelegate {
+        type Ivars = WindowId;
+    }
+
+    unsafe impl NSObjectProtocol for WindowDelegate {}
+
+    unsafe impl NSWindowDelegate for WindowDelegate {
+        #[method(windowShouldClose:)]
+        fn window_should_close(&self, _: &NSWindow) -> bool {
+            event_loop::queue_event(Event::CloseRequested(*self.ivars()));
+            event_loop::dispatch_if_pumping();
+
+            // The window is closed by the event loop, according to the
+            // close behavior.
+            false
+        }
+
+        #[method(windowDidResize:)]
+        fn window_did_resize(&self, _: &NSNotification) {
+            event_loop::queue_event(Event::Resized(*self.ivars()));
+            event_loop::dispatch_if_pumping();
+        }
+
+        #[method(windowDidChangeBackingProperties:)]
+        fn window_did_change_backing_properties(&self, _: &NSNotification) {
+            event_loop::queue_event(Event::ScaleFactorChanged(*self.ivars()));
+            event_loop::dispatch_if_pumping();
+        }
+
+        #[method(windowDidBecomeKey:)]
+        fn window_did_become_key(&self, _: &NSNotification) {
+            event_loop::queue_event(Event::Focused(*self.ivars(), true));
+            event_loop::dispatch_if_pumping();
+        }
+
+        #[method(windowDidResignKey:)]
+        fn window_did_resign_key(&self, _: &NSNotification) {
+            event_loop::queue_event(Event::Focused(*self.ivars(), false));
+            event_loop::dispatch_if_pumping();
+        }
+
+        #[method(windowDidChangeOcclusionState:)]
+        fn window_did_change_occlusion_state(&self, _: &NSNotification) {
+            event_loop::queue_event(Event::OcclusionChanged(*self.ivars()));
+            event_loop::dispatch_if_pumping();
+        }
+    }
+);
+
+impl WindowDelegate {
+    fn new(mtm: MainThreadMarker, window_id: WindowId) -> Retained<Self> {
+        let this = mtm.alloc().set_ivars(window_id);
+        unsafe { msg_send_id![super(this), init] }
+    }
+}
+
+pub struct Window {
+    window: Retained<NSWindow>,
+
+    /// The window only holds the weak reference to its delegate.
+    _delegate: Retained<WindowDelegate>,
+    id: WindowId,
+
+    /// Whether the user got the [`ApplicationWindow::created`].
+    ///
+    /// [`ApplicationWindow::created`]: winit_core::application::ApplicationWindow::created
+    pub(crate) created: bool,
+
+    title: String,
+    title_policy: TitlePolicy,
+    theme: Option<Theme>,
+    decorate: bool,
+    resizable: bool,
+
+    /// The size of the content view.
+    pub(crate) size: PhysicalSize<u32>,
+
+    pub(crate) scale_factor: f64,
+    pub(crate) rounding_policy: RoundingPolicy,
+    pub(crate) close_behavior: CloseBehavior,
+
+    /// Whether the window should be maximized when it's shown.
+    initial_maximized: bool,
+
+    /// Whether the window should enter the fullscreen when it's shown.
+    initial_fullscreen: bool,
+
+    /// The group the window belongs to along with the time it joined it.
+    pub(crate) group: Option<(GroupId, Instant)>,
+
+    pub(crate) has_focus: bool,
+    pub(crate) occluded: bool,
+
+    /// Whether the user asked for the redraw.
+    pub(crate) redraw_requested: bool,
+}
+
+impl Window {
+    pub(crate) fn new(
+        mtm: MainThreadMarker,
+        attributes: ToplevelAttributes,
+        monitors: &[Monitor],
+        rounding_policy: RoundingPolicy,
+    ) -> Result<Self, ()> {
+        let decorate = attributes.decorations();
+        let resizable = attributes.resizable();
+
+        // The window is likely to be placed on the primary monitor, the size is
+        // fixed up once the actual one is known.
+        let primary = monitors.iter().find(|monitor| monitor.is_primary());
+        let scale_factor = primary.map_or(1., |monitor| monitor.scale_factor());
+        let scale_factor = winit_core::env::overrides().window_scale_factor(scale_factor);
+
+        let primary = primary.map(|monitor| monitor as &dyn CoreMonitor);
+        let size = attributes.initial_inner_size(primary, scale_factor);
+        let content_rect = NSRect::new(NSPoint::new(0., 0.), NSSize::new(size.width, size.height));
+        let window = unsafe {
+            NSWindow::initWithContentRect_styleMask_backing_defer(
+                mtm.alloc(),
+                content_rect,
+                window_style(decorate, resizable),
+                NSBackingStoreType::NSBackingStoreBuffered,
+                false,
+            )
+        };
+
+        // The window is owned by us, not released by the `close`.
+        unsafe { window.setReleasedWhenClosed(false) };
+
+        match attributes.position() {
+            Some(position) => {
+                let position: PhysicalPosition<f64> = position.to_physical(scale_factor);
+                window.setFrameTopLeftPoint(top_left_point(position, scale_factor, mtm));
+            },
+            None => window.center(),
+        }
+
+        let title_policy = attributes.title_policy();
+        let title = title_policy.apply(attributes.title());
+        window.setTitle(&NSString::from_str(title.as_str()));
+
+        let id = crate::make_wid();
+        let delegate = WindowDelegate::new(mtm, id);
+        window.setDelegate(Some(ProtocolObject::from_ref(&*delegate)));
+
+        let mut window = Self {
+            scale_factor: winit_core::env::overrides()
+                .window_scale_factor(window.backingScaleFactor()),
+            window,
+            _delegate: delegate,
+            id,
+            created: false,
+            title: title.into(),
+            title_policy,
+            theme: attributes.theme(),
+            decorate,
+            resizable,
+            size: PhysicalSize::new(0, 0),
+            rounding_policy,
+            close_behavior: attributes.close_behavior(),
+            initial_maximized: attributes.maximized(),
+            initial_fullscreen: attributes.fullscreen_monitor().is_some(),
+            group: None,
+            has_focus: false,
+            occluded: false,
+            redraw_requested: true,
+        };
+
+        window.set_min_inner_size(attributes.min_inner_size());
+        window.set_max_inner_size(attributes.max_inner_size());
+        window.update_size();
+        window.apply_theme();
+
+        Ok(window)
+    }
+
+    #[inline]
+    pub fn ns_window(&self) -> &NSWindow {
+        &self.window
+    }
+
+    /// Show the window for the first time, along with the initial state.
+    pub(crate) fn show(&mut self) {
+        self.window.makeKeyAndOrderFront(None);
+
+        if self.initial_maximized && !self.window.isZoomed() {
+            self.window.zoom(None);
+        }
+
+        // NOTE: the fullscreen always takes the monitor the window is on, the
+        // window is moved to the requested one by the user.
+        if mem::take(&mut self.initial_fullscreen) {
+            self.window.toggleFullScreen(None);
+        }
+    }
+
+    /// Hide the window, for the [`CloseBehavior::HideInstead`].
+    pub(crate) fn hide(&mut self) {
+        self.set_visible(false);
+    }
+
+    /// Read the size of the content view, returning whether it changed.
+    pub(crate) fn update_size(&mut self) -> bool {
+        let frame = self.window.contentRectForFrameRect(self.window.frame());
+        let size = LogicalSize::new(frame.size.width, frame.size.height);
+        let size = self.rounding_policy.to_physical_size(size, self.scale_factor);
+        mem::replace(&mut self.size, size) != size
+    }
+
+    /// Read the scale factor, returning whether it changed.
+    pub(crate) fn update_scale_factor(&mut self) -> bool {
+        let scale_factor =
+            winit_core::env::overrides().window_scale_factor(self.window.backingScaleFactor());
+        mem::replace(&mut self.scale_factor, scale_factor) != scale_factor
+    }
+
+    /// Whether the window is in the interactive resize, the redraws can't
+    /// wait for the event loop then.
+    pub(crate) fn in_live_resize(&self) -> bool {
+        unsafe { self.window.inLiveResize() }
+    }
+
+    /// Read the visibility of the window, returning whether it changed.
+    pub(crate) fn update_occlusion(&mut self) -> bool {
+        let state = unsafe { self.window.occlusionState() };
+        let occluded = state.0 & NSWindowOcclusionState::Visible.0 == 0;
+        mem::replace(&mut self.occluded, occluded) != occluded
+    }
+
+    /// Set the resizable state on the window.
+    #[inline]
+    pub fn set_resizable(&mut self, resizable: bool) {
+        if self.resizable == resizable {
+            return;
+        }
+
+        self.resizable = resizable;
+        self.update_style();
+    }
+
+    pub fn set_min_inner_size(&mut self, size: Option<Size>) {
+        let size = size.map_or(LogicalSize::new(0., 0.), |size| size.to_logical(self.scale_factor));
+        self.window.setContentMinSize(NSSize::new(size.width, size.height));
+    }
+
+    pub fn set_max_inner_size(&mut self, size: Option<Size>) {
+        let size = size.map_or(LogicalSize::new(f64::MAX, f64::MAX), |size| {
+            size.to_logical(self.scale_factor)
+        });
+        self.window.setContentMaxSize(NSSize::new(size.width, size.height));
+    }
+
+    fn update_style(&self) {
+        // The style is restored by the system when leaving the fullscreen.
+        if self.is_fullscreen() {
+            return;
+        }
+
+        self.window.setStyleMask(window_style(self.decorate, self.resizable));
+    }
+
+    /// Apply the theme to the window, following the system without one.
+    pub(crate) fn apply_theme(&self) {
+        let name = match self.theme {
+            Some(Theme::Dark) => unsafe { NSAppearanceNameDarkAqua },
+            Some(Theme::Light) => unsafe { NSAppearanceNameAqua },
+            None => {
+                self.window.setAppearance(None);
+                return;
+            },
+        };
+
+        let appearance = NSAppearance::appearanceNamed(name);
+        self.window.setAppearance(appearance.as_deref());
+    }
+
+    /// The state of the window for the [`EventLoopHandle::dump_state`],
+    /// without the title.
+    ///
+    /// [`EventLoopHandle::dump_state`]: winit_core::event_loop::EventLoopHandle::dump_state
+    pub(crate) fn dump(&self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id.0,
+            "created": self.created,
+            "size": (self.size.width, self.size.height),
+            "scale_factor": self.scale_factor,
+            "decorated": self.decorate,
+            "resizable": self.resizable,
+            "maximized": self.is_maximized(),
+            "minimized": self.window.isMiniaturized(),
+            "fullscreen": self.is_fullscreen(),
+            "occluded": self.occluded,
+            "focused": self.has_focus,
+            "group": self.group.map(|(group, _)| group.0),
+        })
+    }
+}
+
+impl CoreSurface for Window {
+    fn id(&self) -> WindowId {
+        self.id
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    fn request_redraw(&mut self) {
+        // The redraw is delivered on the next iteration of the event loop.
+        self.redraw_requested = true;
+    }
+
+    fn inner_size(&self) -> PhysicalSize<u32> {
+        self.size
+    }
+
+    fn surface_geometry(&self) -> Rect<u32> {
+        // The title bar is outside of the content view.
+        Rect::new(PhysicalPosition::new(0, 0), self.size)
+    }
+
+    fn current_monitor(&self) -> Option<MonitorId> {
+        monitor::screen_monitor_id(&self.window.screen()?)
+    }
+
+    fn primary_monitor(&self) -> Option<MonitorId> {
+        let mtm = MainThreadMarker::from(&*self.window);
+        let screens = objc2_app_kit::NSScreen::screens(mtm);
+        monitor::screen_monitor_id(&screens.first()?)
+    }
+
+    fn set_opaque_region(&mut self, region: Option<&[Rect<u32>]>) {
+        // The window server only takes whether the whole window is opaque.
+        let opaque = region.is_some_and(|rects| {
+            rects.iter().any(|rect| {
+                rect.origin == PhysicalPosition::new(0, 0)
+                    && rect.size.width >= self.size.width
+                    && rect.size.height >= self.size.height
+            })
+        });
+
+        self.window.setOpaque(opaque);
+        let background =
+            if opaque { NSColor::windowBackgroundColor() } else { NSColor::clearColor() };
+        self.window.setBackgroundColor(Some(&background));
+    }
+
+    fn set_input_region(&mut self, region: Option<&[Rect<u32>]>) {
+        // The window server passes the input through the whole window or
+        // nothing, the partial regions need the hit testing of the view.
+        let pass_through = region.is_some_and(|rects| {
+            rects.iter().all(|rect| rect.size.width == 0 || rect.size.height == 0)
+        });
+        self.window.setIgnoresMouseEvents(pass_through);
+    }
+
+    fn is_throttled(&self) -> bool {
+        self.occluded
+    }
+
+    fn cursor_position(&self, seat: Option<DeviceId>) -> Option<PhysicalPosition<f64>> {
+        // AppKit merges all the mice into one pointer.
+        let _ = seat;
+
+        let point = self.window.mouseLocationOutsideOfEventStream();
+        let content = self.window.contentRectForFrameRect(self.window.frame());
+
+        // The window coordinates are bottom-left based.
+        let position = PhysicalPosition::new(
+            point.x * self.scale_factor,
+            (content.size.height - point.y) * self.scale_factor,
+        );
+        let inside = position.x >= 0.
+            && position.y >= 0.
+            && position.x < self.size.width as f64
+            && position.y < self.size.height as f64;
+        inside.then_some(position)
+    }
+
+    fn as_toplevel(&self) -> Option<&dyn Toplevel> {
+        Some(self)
+    }
+
+    fn as_toplevel_mut(&mut self) -> Option<&mut dyn Toplevel> {
+        Some(self)
+    }
+}
+
+impl Toplevel for Window {
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    fn set_title(&mut self, title: Title) -> Title {
+        let title = self.title_policy.apply(title.as_str());
+        if title.is_truncated() {
+            log::debug!("Window title truncated to {} bytes", self.title_policy.max_len);
+        }
+
+        self.window.setTitle(&NSString::from_str(title.as_str()));
+        self.title = title.as_str().to_owned();
+        title
+    }
+
+    fn title_policy(&self) -> TitlePolicy {
+        self.title_policy
+    }
+
+    fn theme(&self) -> Option<Theme> {
+        self.theme
+    }
+
+    fn set_theme(&mut self, theme: Option<Theme>) {
+        self.theme = theme;
+        self.apply_theme();
+    }
+
+    fn is_decorated(&self) -> bool {
+        self.decorate
+    }
+
+    fn set_decorations(&mut self, decorate: bool) {
+        if self.decorate == decorate {
+            return;
+        }
+
+        self.decorate = decorate;
+        self.update_style();
+    }
+
+    fn request_inner_size(&mut self, size: Size) -> Option<PhysicalSize<u32>> {
+        // The maximized and the fullscreen windows keep their size.
+        if !self.is_fullscreen() && !self.is_maximized() {
+            let size: LogicalSize<f64> = size.to_logical(self.scale_factor);
+            self.window.setContentSize(NSSize::new(size.width, size.height));
+        }
+
+        // The change is delivered with the `windowDidResize:`.
+        None
+    }
+
+    fn set_minimized(&mut self, minimize: bool) {
+        if minimize {
+            self.window.miniaturize(None);
+        } else if self.window.isMiniaturized() {
+            self.window.deminiaturize(None);
+        }
+    }
+
+    fn set_maximized(&mut self, maximized: bool) {
+        if self.is_maximized() != maximized {
+            self.window.zoom(None);
+        }
+    }
+
+    fn is_maximized(&self) -> bool {
+        self.window.isZoomed()
+    }
+
+    fn request_activation(&mut self) {
+        let mtm = MainThreadMarker::from(&*self.window);
+
+        // The activation of the other applications is only a request since
+        // macOS 14, the focus arrives with the `windowDidBecomeKey:`.
+        #[allow(deprecated)]
+        NSApplication::sharedApplication(mtm).activateIgnoringOtherApps(true);
+        self.window.makeKeyAndOrderFront(None);
+    }
+
+    fn is_fullscreen(&self) -> bool {
+        self.window.styleMask().0 & NSWindowStyleMask::FullScreen.0 != 0
+    }
+
+    fn outer_position(&self) -> Option<PhysicalPosition<i32>> {
+        let frame = self.window.frame();
+        let top_left = NSPoint::new(frame.origin.x, frame.origin.y + frame.size.height);
+        let mtm = MainThreadMarker::from(&*self.window);
+        Some(top_left_position(top_left, self.scale_factor, mtm).cast())
+    }
+
+    fn close_behavior(&self) -> CloseBehavior {
+        self.close_behavior
+    }
+
+    fn set_close_behavior(&mut self, close_behavior: CloseBehavior) {
+        self.close_behavior = close_behavior;
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        if visible {
+            self.window.makeKeyAndOrderFront(None);
+        } else {
+            self.window.orderOut(None);
+        }
+    }
+
+    fn hit_test(&self, position: Position) -> RegionKind {
+        let position: PhysicalPosition<f64> = position.to_physical(self.scale_factor);
+        let frame = self.window.frame();
+        let content = self.window.contentRectForFrameRect(frame);
+
+        // The title bar is on top of the content view, the resizing happens
+        // outside of the frame.
+        let (x, y) = (position.x / self.scale_factor, position.y / self.scale_factor);
+        let title_bar = frame.size.height - content.size.height;
+        if x < 0. || y < -title_bar || x >= content.size.width || y >= content.size.height {
+            RegionKind::Outside
+        } else if y < 0. {
+            RegionKind::Frame
+        } else {
+            RegionKind::Content
+        }
+    }
+
+    fn tiled_edges(&self) -> TiledEdges {
+        // The tiling isn't revealed to the applications, only the zoomed
+        // windows are known to touch the edges.
+        if self.is_maximized() || self.is_fullscreen() {
+            TiledEdges::all()
+        } else {
+            TiledEdges::empty()
+        }
+    }
+
+    fn recommended_bounds(&self) -> Option<LogicalSize<u32>> {
+        let size = self.window.screen()?.visibleFrame().size;
+        Some(LogicalSize::new(size.width as u32, size.height as u32))
+    }
+
+    fn group(&self) -> Option<GroupId> {
+        self.group.map(|(group, _)| group)
+    }
+
+    fn set_group(&mut self, group: Option<GroupId>) {
+        if self.group() == group {
+            return;
+        }
+
+        // NOTE: the child windows of AppKit move along with their parent, so
+        // the group is only tracked for the `group_members`.
+        self.group = group.map(|group| (group, Instant::now()));
+    }
+
+    fn set_shadow(&mut self, shadow: bool) {
+        self.window.setHasShadow(shadow);
+    }
+
+    fn set_corner_radius(&mut self, radius: Option<f32>) {
+        let Some(view) = self.window.contentView() else {
+            return;
+        };
+
+        // The content is clipped by its layer, so the corners only show
+        // through when the window isn't opaque.
+        view.setWantsLayer(true);
+        let radius = radius.unwrap_or(0.) as CGFloat;
+        unsafe {
+            let layer: *mut AnyObject = msg_send![&view, layer];
+            if !layer.is_null() {
+                let _: () = msg_send![layer, setCornerRadius: radius];
+                let _: () = msg_send![layer, setMasksToBounds: radius > 0.];
+            }
+
+            // The shadow follows the shape of the content.
+            self.window.invalidateShadow();
+        }
+    }
+}
+
+impl Drop for Window {
+    fn drop(&mut self) {
+        // Don't queue the notifications sent while closing.
+        self.window.setDelegate(None);
+        self.window.close();
+    }
+}
+
+impl HasWindowHandle for Window {
+    fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+        let view = self.window.contentView().ok_or(HandleError::Unavailable)?;
+        let ns_view = NonNull::from(&*view).cast::<c_void>();
+        let handle = AppKitWindowHandle::new(ns_view);
+
+        unsafe { Ok(WindowHandle::borrow_raw(RawWindowHandle::AppKit(handle))) }
+    }
+}
+
+unsafe impl HasRawWindowHandle05 for Window {
+    fn raw_window_handle(&self) -> raw_window_handle_05::RawWindowHandle {
+        let mut window_handle = raw_window_handle_05::AppKitWindowHandle::empty();
+        window_handle.ns_window = Retained::as_ptr(&self.window) as *mut _;
+        window_handle.ns_view = self
+            .window
+            .contentView()
+            .map_or(std::ptr::null_mut(), |view| Retained::as_ptr(&view) as *mut _);
+        raw_window_handle_05::RawWindowHandle::AppKit(window_handle)
+    }
+}
+
+/// The style of the window with the `decorate` and `resizable` state.
+fn window_style(decorate: bool, resizable: bool) -> NSWindowStyleMask {
+    let style = if decorate {
+        NSWindowStyleMask::Titled.0
+            | NSWindowStyleMask::Closable.0
+            | NSWindowStyleMask::Miniaturizable.0
+    } else {
+        NSWindowStyleMask::Borderless.0
+    };
+
+    if resizable {
+        NSWindowStyleMask(style | NSWindowStyleMask::Resizable.0)
+    } else {
+        NSWindowStyleMask(style)
+    }
+}
+
+/// The AppKit point of the top-left based physical `position`.
+fn top_left_point(
+    position: PhysicalPosition<f64>,
+    scale_factor: f64,
+    mtm: MainThreadMarker,
+) -> NSPoint {
+    // The origin of the AppKit coordinates is at the bottom-left corner of
+    // the screen with the menu bar.
+    let screens = objc2_app_kit::NSScreen::screens(mtm);
+    let primary_height = screens.first().map_or(0., |screen| screen.frame().size.height);
+    NSPoint::new(position.x / scale_factor, primary_height - position.y / scale_factor)
+}
+
+/// The position of the top-left `point` of the window, the inverse of the
+/// [`top_left_point`].
+fn top_left_position(
+    point: NSPoint,
+    scale_factor: f64,
+    mtm: MainThreadMarker,
+) -> PhysicalPosition<f64> {
+    let screens = objc2_app_kit::NSScreen::screens(mtm);
+    let primary_height = screens.first().map_or(0., |screen| screen.frame().size.height);
+    PhysicalPosition::new(point.x * scale_factor, (primary_height - point.y) * scale_factor)
+}
+
+/// The theme preferred by the user for the applications.
+pub(crate) fn system_theme(mtm: MainThreadMarker) -> Option<Theme> {
+    let app = NSApplication::sharedApplication(mtm);
+    let name = unsafe { app.effectiveAppearance().name() };
+    if name.to_string().contains("Dark") {
+        Some(Theme::Dark)
+    } else {
+        Some(Theme::Light)
+    }
+}
@@ -0,0 +1,343 @@
+//! The conformance cases.
+
+use winit_core::dpi::PhysicalSize;
+use winit_core::event_loop::EventLoopHandle;
+use winit_core::window::{ToplevelAttributes, WindowId};
+
+use crate::recorder::{Driver, Event};
+
+/// The size requested by the resize case.
+const REQUESTED_SIZE: PhysicalSize<u32> = PhysicalSize::new(320, 240);
+
+/// The behavior expected from every backend.
+pub(crate) struct Case {
+    pub(crate) name: &'static str,
+
+    /// Take the actions of the case.
+    pub(crate) drive: Driver,
+
+    /// Check the recorded events.
+    pub(crate) check: fn(&[Event]) -> Result<(), String>,
+}
+
+pub(crate) const CASES: &[Case] = &[
+    Case { name: "window_lifecycle", drive: drive_until_redraw, check: check_lifecycle },
+    Case { name: "scale_factor_delivery", drive: drive_until_redraw, check: check_scale_factor },
+    Case { name: "resize_semantics", drive: drive_resize, check: check_resize },
+    Case { name: "focus_ordering", drive: drive_until_redraw, check: check_focus },
+    Case { name: "redraw_contract", drive: drive_redraw, check: check_redraw },
+];
+
+/// Check the invariants of every case.
+pub(crate) fn check_common(log: &[Event]) -> Result<(), String> {
+    if log.contains(&Event::Timeout) {
+        return Err(String::from("the case didn't finish in time"));
+    }
+
+    if log.contains(&Event::Marker("create_window_failed")) {
+        return Err(String::from("failed to create the toplevel window"));
+    }
+
+    match log.iter().position(|event| *event == Event::LoopExiting) {
+        Some(index) if index == log.len() - 1 => Ok(()),
+        Some(_) => Err(String::from("the events were delivered after the loop_exiting")),
+        None => Err(String::from("the loop_exiting wasn't delivered")),
+    }
+}
+
+fn create_window(loop_handle: &mut dyn EventLoopHandle, log: &mut Vec<Event>) {
+    let attributes = ToplevelAttributes::default().with_title("winit-conformance");
+    match loop_handle.create_window(attributes.into()) {
+        Ok(()) => log.push(Event::Marker("create_window")),
+        Err(_) => {
+            log.push(Event::Marker("create_window_failed"));
+            loop_handle.exit();
+        },
+    }
+}
+
+fn redraws(log: &[Event]) -> usize {
+    log.iter().filter(|event| matches!(event, Event::RedrawRequested(_))).count()
+}
+
+/// Create the window and exit after its first redraw.
+fn drive_until_redraw(loop_handle: &mut dyn EventLoopHandle, log: &mut Vec<Event>) {
+    match log.last() {
+        None => create_window(loop_handle, log),
+        Some(Event::RedrawRequested(_)) => loop_handle.exit(),
+        _ => (),
+    }
+}
+
+/// Request the size after the first redraw and exit after the next one.
+fn drive_resize(loop_handle: &mut dyn EventLoopHandle, log: &mut Vec<Event>) {
+    let window_id = match log.last() {
+        None => return create_window(loop_handle, log),
+        Some(Event::RedrawRequested(window_id)) => *window_id,
+        _ => return,
+    };
+
+    if redraws(log) > 1 {
+        return loop_handle.exit();
+    }
+
+    let toplevel =
+        loop_handle.get_window_mut(window_id).and_then(|window| window.as_toplevel_mut());
+    let toplevel = match toplevel {
+        Some(toplevel) => toplevel,
+        None => return loop_handle.exit(),
+    };
+
+    let returned = toplevel.request_inner_size(REQUESTED_SIZE.into());
+    let inner_size = toplevel.inner_size();
+    log.push(Event::SizeRequested { window_id, returned, inner_size });
+
+    // The size could be applied without the redraw.
+    toplevel.request_redraw();
+}
+
+/// Request the redraw after the first one and exit after it's delivered.
+fn drive_redraw(loop_handle: &mut dyn EventLoopHandle, log: &mut Vec<Event>) {
+    let window_id = match log.last() {
+        None => return create_window(loop_handle, log),
+        Some(Event::RedrawRequested(window_id)) => *window_id,
+        _ => return,
+    };
+
+    if log.contains(&Event::Marker("request_redraw")) {
+        return loop_handle.exit();
+    }
+
+    if let Some(window) = loop_handle.get_window_mut(window_id) {
+        window.request_redraw();
+        log.push(Event::Marker("request_redraw"));
+    }
+}
+
+/// The window the `event` is delivered for.
+fn event_window(event: &Event) -> Option<WindowId> {
+    match *event {
+        Event::Created(window_id)
+        | Event::Resized { window_id, .. }
+        | Event::ScaleFactorChanged { window_id, .. }
+        | Event::SizeRequested { window_id, .. }
+        | Event::RedrawRequested(window_id)
+        | Event::CloseRequested(window_id)
+        | Event::Focused(window_id, _)
+        | Event::Destroyed(window_id) => Some(window_id),
+        Event::LoopExiting | Event::Timeout | Event::Marker(_) => None,
+    }
+}
+
+/// The events delivered for the `window_id`.
+fn window_events(log: &[Event], window_id: WindowId) -> impl Iterator<Item = &Event> {
+    log.iter().filter(move |event| event_window(event) == Some(window_id))
+}
+
+/// The windows in the order they were created.
+fn created_windows(log: &[Event]) -> Vec<WindowId> {
+    let mut windows = Vec::new();
+    for event in log {
+        if let Some(window_id) = event_window(event) {
+            if !windows.contains(&window_id) {
+                windows.push(window_id);
+            }
+        }
+    }
+    windows
+}
+
+/// The window is created once before its other events, and gets nothing after
+/// it's destroyed.
+fn check_lifecycle(log: &[Event]) -> Result<(), String> {
+    let windows = created_windows(log);
+    if windows.is_empty() {
+        return Err(String::from("no window was created"));
+    }
+
+    for window_id in windows {
+        let events: Vec<_> = window_events(log, window_id).collect();
+        if *events[0] != Event::Created(window_id) {
+            return Err(format!("{:?} was delivered before the created", events[0]));
+        }
+
+        let created = events.iter().filter(|event| ***event == Event::Created(window_id)).count();
+        if created > 1 {
+            return Err(format!("the created was delivered {created} times for {window_id:?}"));
+        }
+
+        if let Some(index) = events.iter().position(|event| **event == Event::Destroyed(window_id))
+        {
+            if let Some(event) = events.get(index + 1) {
+                return Err(format!("{event:?} was delivered after the destroyed"));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The scale factor is delivered before the first redraw and matches the one
+/// of the surface.
+fn check_scale_factor(log: &[Event]) -> Result<(), String> {
+    for window_id in created_windows(log) {
+        let mut delivered = false;
+        for event in window_events(log, window_id) {
+            match *event {
+                Event::ScaleFactorChanged { scale_factor, surface_scale_factor, .. } => {
+                    if !scale_factor.is_finite() || scale_factor <= 0. {
+                        return Err(format!("the invalid scale factor {scale_factor}"));
+                    }
+
+                    if surface_scale_factor != Some(scale_factor) {
+                        return Err(format!(
+                            "the delivered scale factor {scale_factor} doesn't match the surface \
+                             one {surface_scale_factor:?}"
+                        ));
+                    }
+
+                    delivered = true;
+                },
+                Event::RedrawRequested(_) if !delivered => {
+                    return Err(String::from("the redraw was delivered before the scale factor"));
+                },
+                _ => (),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The delivered sizes match the surface, and the size applied right away is
+/// reported right away.
+fn check_resize(log: &[Event]) -> Result<(), String> {
+    if !log.iter().any(|event| matches!(event, Event::SizeRequested { .. })) {
+        return Err(String::from("the size wasn't requested"));
+    }
+
+    for event in log {
+        match *event {
+            Event::Resized { size, inner_size, .. } => {
+                if size.width == 0 || size.height == 0 {
+                    return Err(format!("the empty size {size:?} was delivered"));
+                }
+
+                if inner_size != Some(size) {
+                    return Err(format!(
+                        "the delivered size {size:?} doesn't match the surface one \
+                         {inner_size:?}"
+                    ));
+                }
+            },
+            Event::SizeRequested { returned: Some(returned), inner_size, .. }
+                if returned != inner_size =>
+            {
+                return Err(format!(
+                    "the applied size {returned:?} doesn't match the surface one {inner_size:?}"
+                ));
+            },
+            _ => (),
+        }
+    }
+
+    Ok(())
+}
+
+/// The focus of each window alternates starting with the gain.
+fn check_focus(log: &[Event]) -> Result<(), String> {
+    for window_id in created_windows(log) {
+        let mut has_focus = false;
+        for event in window_events(log, window_id) {
+            if let Event::Focused(_, focused) = *event {
+                if focused == has_focus {
+                    return Err(format!("the focused({focused}) was delivered twice in a row"));
+                }
+                has_focus = focused;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The redraw is delivered after the configure and once requested.
+fn check_redraw(log: &[Event]) -> Result<(), String> {
+    let requested = match log.iter().position(|event| *event == Event::Marker("request_redraw")) {
+        Some(requested) => requested,
+        None => return Err(String::from("the initial redraw wasn't delivered")),
+    };
+
+    if redraws(&log[requested..]) == 0 {
+        return Err(String::from("the requested redraw wasn't delivered"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: WindowId = WindowId(1);
+
+    fn resized(size: PhysicalSize<u32>, inner_size: PhysicalSize<u32>) -> Event {
+        Event::Resized { window_id: WINDOW, size, inner_size: Some(inner_size) }
+    }
+
+    #[test]
+    fn common_requires_loop_exiting_last() {
+        let log = [Event::Created(WINDOW), Event::LoopExiting];
+        assert!(check_common(&log).is_ok());
+
+        let log = [Event::LoopExiting, Event::Created(WINDOW)];
+        assert!(check_common(&log).is_err());
+
+        let log = [Event::Timeout, Event::LoopExiting];
+        assert!(check_common(&log).is_err());
+    }
+
+    #[test]
+    fn lifecycle_orders_created_first() {
+        let log = [
+            Event::Created(WINDOW),
+            Event::RedrawRequested(WINDOW),
+            Event::Destroyed(WINDOW),
+            Event::LoopExiting,
+        ];
+        assert!(check_lifecycle(&log).is_ok());
+
+        let log = [Event::RedrawRequested(WINDOW), Event::Created(WINDOW)];
+        assert!(check_lifecycle(&log).is_err());
+
+        let log = [Event::Created(WINDOW), Event::Destroyed(WINDOW), Event::Focused(WINDOW, true)];
+        assert!(check_lifecycle(&log).is_err());
+    }
+
+    #[test]
+    fn resize_matches_surface() {
+        let size = PhysicalSize::new(320, 240);
+        let requested =
+            Event::SizeRequested { window_id: WINDOW, returned: None, inner_size: size };
+
+        let log = [resized(size, size), requested.clone()];
+        assert!(check_resize(&log).is_ok());
+
+        let log = [resized(size, PhysicalSize::new(640, 480)), requested];
+        assert!(check_resize(&log).is_err());
+    }
+
+    #[test]
+    fn focus_alternates() {
+        let log = [
+            Event::Created(WINDOW),
+            Event::Focused(WINDOW, true),
+            Event::Focused(WINDOW, false),
+            Event::Focused(WINDOW, true),
+        ];
+        assert!(check_focus(&log).is_ok());
+
+        let log = [Event::Created(WINDOW), Event::Focused(WINDOW, false)];
+        assert!(check_focus(&log).is_err());
+    }
+}
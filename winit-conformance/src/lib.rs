@@ -0,0 +1,165 @@
+//! Backend agnostic conformance tests.
+//!
+//! The cases are written purely against the winit-core traits, so every
+//! backend could be validated by running them with its event loop. Each case
+//! runs on the fresh event loop, records the events delivered to the
+//! application and checks them against the contract, like the window being
+//! created before it gets any other event.
+//!
+//! The canonical [`patterns`] of the applications are run as well, so the
+//! documented usage keeps working on every backend.
+//!
+//! [`patterns`]: winit_core::patterns
+//!
+//! ```no_run
+//! # use winit_core::event_loop::{EventLoopError, EventLoopRequests};
+//! # fn backend() -> Result<Box<dyn EventLoopRequests>, EventLoopError> { unimplemented!() }
+//! let report = winit_conformance::run(backend);
+//! for (name, error) in report.failures() {
+//!     eprintln!("{name}: {error}");
+//! }
+//! assert!(report.passed());
+//! ```
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use winit_core::application::Application;
+use winit_core::event_loop::{EventLoopError, EventLoopRequests};
+use winit_core::patterns::{
+    ContinuousRedraw, MultiWindow, OneShot, Outcome, ProxyWakeup, RedrawOnDemand,
+};
+
+use crate::cases::Case;
+use crate::recorder::Recorder;
+
+mod cases;
+mod recorder;
+
+pub use recorder::{Driver, Event};
+
+/// The outcome of the conformance cases.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub results: Vec<CaseResult>,
+}
+
+impl Report {
+    /// Whether all the cases have passed.
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(|result| result.outcome.is_ok())
+    }
+
+    /// The names of the failed cases along with the errors.
+    pub fn failures(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        self.results
+            .iter()
+            .filter_map(|result| Some((result.name, result.outcome.as_ref().err()?.as_str())))
+    }
+}
+
+/// The outcome of the single case.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaseResult {
+    pub name: &'static str,
+
+    /// The error describing the violated contract.
+    pub outcome: Result<(), String>,
+
+    /// The recorded events, to investigate the failures.
+    pub log: Vec<Event>,
+}
+
+/// The pattern along with the name it's reported with.
+struct Pattern {
+    name: &'static str,
+    app: fn(Outcome) -> Box<dyn Application>,
+}
+
+const PATTERNS: &[Pattern] = &[
+    Pattern { name: "pattern_one_shot", app: |outcome| Box::new(OneShot::new(outcome)) },
+    Pattern { name: "pattern_multi_window", app: |outcome| Box::new(MultiWindow::new(outcome)) },
+    Pattern {
+        name: "pattern_redraw_on_demand",
+        app: |outcome| Box::new(RedrawOnDemand::new(outcome)),
+    },
+    Pattern {
+        name: "pattern_continuous_redraw",
+        app: |outcome| Box::new(ContinuousRedraw::new(outcome)),
+    },
+    Pattern { name: "pattern_proxy_wakeup", app: |outcome| Box::new(ProxyWakeup::new(outcome)) },
+];
+
+/// The names of all the cases.
+pub fn case_names() -> impl Iterator<Item = &'static str> {
+    cases::CASES.iter().map(|case| case.name).chain(PATTERNS.iter().map(|pattern| pattern.name))
+}
+
+/// Run all the cases, creating the event loop for each with the
+/// `backend_factory`.
+pub fn run<F>(backend_factory: F) -> Report
+where
+    F: FnMut() -> Result<Box<dyn EventLoopRequests>, EventLoopError>,
+{
+    run_filtered(backend_factory, |_| true)
+}
+
+/// Run the cases with the names matching the `filter`.
+pub fn run_filtered<F, P>(mut backend_factory: F, mut filter: P) -> Report
+where
+    F: FnMut() -> Result<Box<dyn EventLoopRequests>, EventLoopError>,
+    P: FnMut(&str) -> bool,
+{
+    let mut results: Vec<_> = cases::CASES
+        .iter()
+        .filter(|case| filter(case.name))
+        .map(|case| run_case(&mut backend_factory, case))
+        .collect();
+    results.extend(
+        PATTERNS
+            .iter()
+            .filter(|pattern| filter(pattern.name))
+            .map(|pattern| run_pattern(&mut backend_factory, pattern)),
+    );
+    Report { results }
+}
+
+fn run_case<F>(backend_factory: &mut F, case: &Case) -> CaseResult
+where
+    F: FnMut() -> Result<Box<dyn EventLoopRequests>, EventLoopError>,
+{
+    let event_loop = match backend_factory() {
+        Ok(event_loop) => event_loop,
+        Err(err) => {
+            let outcome = Err(err.to_string());
+            return CaseResult { name: case.name, outcome, log: Vec::new() };
+        },
+    };
+
+    let log = Rc::new(RefCell::new(Vec::new()));
+    event_loop.run(Box::new(Recorder::new(log.clone(), case.drive)));
+
+    let log = log.take();
+    let outcome = cases::check_common(&log).and_then(|()| (case.check)(&log));
+    CaseResult { name: case.name, outcome, log }
+}
+
+fn run_pattern<F>(backend_factory: &mut F, pattern: &Pattern) -> CaseResult
+where
+    F: FnMut() -> Result<Box<dyn EventLoopRequests>, EventLoopError>,
+{
+    let event_loop = match backend_factory() {
+        Ok(event_loop) => event_loop,
+        Err(err) => {
+            let outcome = Err(err.to_string());
+            return CaseResult { name: pattern.name, outcome, log: Vec::new() };
+        },
+    };
+
+    let outcome = Outcome::default();
+    event_loop.run((pattern.app)(outcome.clone()));
+
+    let outcome =
+        outcome.get().unwrap_or_else(|| Err(String::from("the loop exited before the pattern")));
+    CaseResult { name: pattern.name, outcome, log: Vec::new() }
+}
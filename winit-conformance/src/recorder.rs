@@ -0,0 +1,161 @@
+//! The application recording the events delivered by the backend.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use winit_core::application::{Application, ApplicationWindow, StartCause};
+use winit_core::dpi::PhysicalSize;
+use winit_core::event_loop::{EventLoopHandle, TimerId};
+use winit_core::window::WindowId;
+
+/// The time the case has to finish.
+const CASE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The timer bailing out of the stuck case.
+const TIMEOUT_TIMER: TimerId = TimerId(u64::MAX);
+
+/// The event delivered by the backend, or the action taken by the case.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Created(WindowId),
+    Resized {
+        window_id: WindowId,
+        size: PhysicalSize<u32>,
+        /// The [`Surface::inner_size`] at the time of the event.
+        ///
+        /// [`Surface::inner_size`]: winit_core::window::Surface::inner_size
+        inner_size: Option<PhysicalSize<u32>>,
+    },
+    ScaleFactorChanged {
+        window_id: WindowId,
+        scale_factor: f64,
+        /// The [`Surface::scale_factor`] at the time of the event.
+        ///
+        /// [`Surface::scale_factor`]: winit_core::window::Surface::scale_factor
+        surface_scale_factor: Option<f64>,
+    },
+    /// The case requested the size of the window.
+    SizeRequested {
+        window_id: WindowId,
+        /// The size returned by the [`Toplevel::request_inner_size`].
+        ///
+        /// [`Toplevel::request_inner_size`]: winit_core::window::Toplevel::request_inner_size
+        returned: Option<PhysicalSize<u32>>,
+        /// The [`Surface::inner_size`] right after the request.
+        ///
+        /// [`Surface::inner_size`]: winit_core::window::Surface::inner_size
+        inner_size: PhysicalSize<u32>,
+    },
+    RedrawRequested(WindowId),
+    CloseRequested(WindowId),
+    Focused(WindowId, bool),
+    Destroyed(WindowId),
+    LoopExiting,
+
+    /// The case didn't finish in time.
+    Timeout,
+
+    /// The action taken by the case, to order the events around it.
+    Marker(&'static str),
+}
+
+/// Drive the case, called on the start and after each recorded event.
+pub type Driver = fn(&mut dyn EventLoopHandle, &mut Vec<Event>);
+
+/// The application recording the events into the shared log.
+pub(crate) struct Recorder {
+    log: Rc<RefCell<Vec<Event>>>,
+    drive: Driver,
+}
+
+impl Recorder {
+    pub(crate) fn new(log: Rc<RefCell<Vec<Event>>>, drive: Driver) -> Self {
+        Self { log, drive }
+    }
+
+    fn record(&mut self, loop_handle: &mut dyn EventLoopHandle, event: Event) {
+        let mut log = self.log.borrow_mut();
+        log.push(event);
+        (self.drive)(loop_handle, &mut log);
+    }
+}
+
+impl Application for Recorder {
+    fn user_wakeup(&mut self, _: &mut dyn EventLoopHandle) {}
+
+    fn new_events(&mut self, loop_handle: &mut dyn EventLoopHandle, start_cause: StartCause) {
+        if let StartCause::Init = start_cause {
+            loop_handle.set_timer(CASE_TIMEOUT, TIMEOUT_TIMER);
+            (self.drive)(loop_handle, &mut self.log.borrow_mut());
+        }
+    }
+
+    fn about_to_wait(&mut self, _: &mut dyn EventLoopHandle) {}
+
+    fn loop_exiting(&mut self, loop_handle: &mut dyn EventLoopHandle) {
+        self.log.borrow_mut().push(Event::LoopExiting);
+        loop_handle.cancel_timer(TIMEOUT_TIMER);
+    }
+
+    fn timer_fired(&mut self, loop_handle: &mut dyn EventLoopHandle, timer_id: TimerId) {
+        if timer_id == TIMEOUT_TIMER {
+            self.log.borrow_mut().push(Event::Timeout);
+            loop_handle.exit();
+        }
+    }
+}
+
+impl ApplicationWindow for Recorder {
+    fn created(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        self.record(loop_handle, Event::Created(window_id));
+    }
+
+    fn resized(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        size: PhysicalSize<u32>,
+    ) {
+        let inner_size = loop_handle.get_window(window_id).map(|window| window.inner_size());
+        self.record(loop_handle, Event::Resized { window_id, size, inner_size });
+    }
+
+    fn scale_factor_changed(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        scale_factor: f64,
+    ) {
+        let surface_scale_factor =
+            loop_handle.get_window(window_id).map(|window| window.scale_factor());
+        let event = Event::ScaleFactorChanged { window_id, scale_factor, surface_scale_factor };
+        self.record(loop_handle, event);
+    }
+
+    fn redraw_requested(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        self.record(loop_handle, Event::RedrawRequested(window_id));
+    }
+
+    fn close_requested(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+    ) -> bool {
+        self.record(loop_handle, Event::CloseRequested(window_id));
+        true
+    }
+
+    fn focused(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        focused: bool,
+    ) {
+        self.record(loop_handle, Event::Focused(window_id, focused));
+    }
+
+    fn destroyed(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        self.record(loop_handle, Event::Destroyed(window_id));
+    }
+}
@@ -1,9 +1,15 @@
 use std::time::Instant;
 
 use crate::dpi::PhysicalSize;
-use crate::event_loop::EventLoopHandle;
+use crate::event_loop::{
+    ActivationRequest, BackendDisconnected, EventLoopHandle, FdToken, FileChange, QueryResult,
+    Readiness, RequestId, TimerId, WatchId,
+};
+use crate::input::keyboard::KeyboardInputHandler;
+use crate::input::pointer::PointerInputHandler;
 use crate::input::touch::TouchInputHandler;
-use crate::window::WindowId;
+use crate::monitor::MonitorId;
+use crate::window::{DecorationAction, DecorationMode, FocusDirection, WindowId};
 
 pub trait Application: ApplicationWindow {
     /// Wake up due to user request.
@@ -18,6 +24,104 @@ pub trait Application: ApplicationWindow {
     /// Emitted when the event loop is being shut down.
     fn loop_exiting(&mut self, loop_handle: &mut dyn EventLoopHandle);
 
+    /// The exit was requested with the [`EventLoopProxy::request_exit`].
+    ///
+    /// Return `true` to exit the event loop, the [`Application::loop_exiting`]
+    /// is delivered then as usual. Return `false` to cancel the exit, the
+    /// application could still exit later with the [`EventLoopHandle::exit`].
+    ///
+    /// [`EventLoopProxy::request_exit`]: crate::event_loop::proxy::EventLoopProxy::request_exit
+    fn exit_requested(&mut self, loop_handle: &mut dyn EventLoopHandle) -> bool {
+        let _ = loop_handle;
+        true
+    }
+
+    /// The connection to the display server was lost.
+    ///
+    /// All the windows are destroyed right after that and the event loop
+    /// exits.
+    fn backend_disconnected(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        error: BackendDisconnected,
+    ) {
+        let _ = loop_handle;
+        let _ = error;
+    }
+
+    /// The timer set with [`EventLoopHandle::set_timer`] has fired.
+    fn timer_fired(&mut self, loop_handle: &mut dyn EventLoopHandle, timer_id: TimerId) {
+        let _ = loop_handle;
+        let _ = timer_id;
+    }
+
+    /// The file descriptor registered with [`EventLoopHandle::register_fd`]
+    /// is ready.
+    fn fd_ready(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        token: FdToken,
+        readiness: Readiness,
+    ) {
+        let _ = loop_handle;
+        let _ = token;
+        let _ = readiness;
+    }
+
+    /// The path watched with [`EventLoopHandle::watch_path`] has changed.
+    fn file_changed(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        watch_id: WatchId,
+        change: FileChange,
+    ) {
+        let _ = loop_handle;
+        let _ = watch_id;
+        let _ = change;
+    }
+
+    /// The query issued with [`EventLoopHandle::request`] was answered.
+    fn query_complete(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        request_id: RequestId,
+        result: QueryResult,
+    ) {
+        let _ = loop_handle;
+        let _ = request_id;
+        let _ = result;
+    }
+
+    /// The other instance of the application asked this one to activate.
+    ///
+    /// Only delivered when the backend was asked to keep the application
+    /// single-instance. The application usually opens the window for the
+    /// given `request.arguments` or raises the existing one.
+    fn activated(&mut self, loop_handle: &mut dyn EventLoopHandle, request: ActivationRequest) {
+        let _ = loop_handle;
+        let _ = request;
+    }
+
+    /// The properties of the monitor have changed.
+    ///
+    /// That includes the [`Monitor::adaptive_sync`] and the
+    /// [`Monitor::power_state`].
+    ///
+    /// [`Monitor::adaptive_sync`]: crate::monitor::Monitor::adaptive_sync
+    /// [`Monitor::power_state`]: crate::monitor::Monitor::power_state
+    fn monitor_changed(&mut self, loop_handle: &mut dyn EventLoopHandle, monitor_id: MonitorId) {
+        let _ = loop_handle;
+        let _ = monitor_id;
+    }
+
+    /// The user became idle or active again.
+    ///
+    /// See [`EventLoopHandle::set_idle_threshold`].
+    fn user_idle(&mut self, loop_handle: &mut dyn EventLoopHandle, idle: bool) {
+        let _ = loop_handle;
+        let _ = idle;
+    }
+
     // The APIs which we consider optional, thus the application may opt-in/out the
     // behavior.
     //
@@ -28,6 +132,16 @@ pub trait Application: ApplicationWindow {
         None
     }
 
+    #[inline(always)]
+    fn keyboard_handler(&mut self) -> Option<&mut dyn KeyboardInputHandler> {
+        None
+    }
+
+    #[inline(always)]
+    fn pointer_handler(&mut self) -> Option<&mut dyn PointerInputHandler> {
+        None
+    }
+
     #[inline(always)]
     fn device_events_handelr(&mut self) -> Option<&mut dyn DeviceEventsHandler> {
         None
@@ -59,12 +173,62 @@ pub trait ApplicationWindow {
     fn redraw_requested(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId);
 
     /// The window has been requested to close.
+    ///
+    /// Return `true` to allow closing, the window is then handled according
+    /// to its [`Toplevel::close_behavior`].
+    ///
+    /// [`Toplevel::close_behavior`]: crate::window::Toplevel::close_behavior
     fn close_requested(
         &mut self,
         loop_handle: &mut dyn EventLoopHandle,
         window_id: WindowId,
     ) -> bool;
 
+    /// The button of the decorations drawn by winit was clicked.
+    ///
+    /// Return `false` to prevent the default behavior, for example to confirm
+    /// closing. When allowed, the [`DecorationAction::Close`] results in the
+    /// [`ApplicationWindow::close_requested`].
+    fn decoration_action(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        action: DecorationAction,
+    ) -> bool {
+        let _ = loop_handle;
+        let _ = window_id;
+        let _ = action;
+        true
+    }
+
+    /// The decoration mode of the window was negotiated with the system.
+    ///
+    /// Delivered right after the [`ApplicationWindow::created`] with the
+    /// initial mode, and then on every change. Applications drawing their
+    /// own titlebars could do that only for the [`DecorationMode::Client`].
+    fn decoration_mode_changed(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        mode: DecorationMode,
+    ) {
+        let _ = loop_handle;
+        let _ = window_id;
+        let _ = mode;
+    }
+
+    /// The system refused to focus the window after the
+    /// [`Toplevel::request_activation`].
+    ///
+    /// The application could get the user attention in the other ways
+    /// instead.
+    ///
+    /// [`Toplevel::request_activation`]: crate::window::Toplevel::request_activation
+    fn activation_denied(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        let _ = loop_handle;
+        let _ = window_id;
+    }
+
     /// The window gained or lost focus.
     fn focused(
         &mut self,
@@ -77,6 +241,27 @@ pub trait ApplicationWindow {
         let _ = focused;
     }
 
+    /// The system navigation, like the remote control of the TV or the
+    /// kiosk, asked to move the focus within the focused window.
+    ///
+    /// The application should move the focus between its own elements, and
+    /// could pass it to the next window when there's no element in the
+    /// `direction`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Unsupported, there's no protocol for that.
+    fn focus_directional(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        direction: FocusDirection,
+    ) {
+        let _ = loop_handle;
+        let _ = window_id;
+        let _ = direction;
+    }
+
     /// The window has been occluded (completely hidden from view).
     ///
     /// This is different to window visibility as it depends on whether the
@@ -93,6 +278,37 @@ pub trait ApplicationWindow {
         let _ = occluded;
     }
 
+    /// The state of the window, like its [`tiled_edges`], has changed.
+    ///
+    /// [`tiled_edges`]: crate::window::Toplevel::tiled_edges
+    fn state_changed(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        let _ = loop_handle;
+        let _ = window_id;
+    }
+
+    /// The [`recommended_bounds`] of the window have changed.
+    ///
+    /// [`recommended_bounds`]: crate::window::Toplevel::recommended_bounds
+    fn recommended_bounds_changed(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+    ) {
+        let _ = loop_handle;
+        let _ = window_id;
+    }
+
+    /// The frame of the window wasn't presented within the refresh interval
+    /// of the monitor.
+    ///
+    /// The accumulated statistics are available with [`Surface::frame_stats`].
+    ///
+    /// [`Surface::frame_stats`]: crate::window::Surface::frame_stats
+    fn frame_missed(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        let _ = loop_handle;
+        let _ = window_id;
+    }
+
     /// The window has been destroyed.
     fn destroyed(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
         let _ = loop_handle;
@@ -102,6 +318,235 @@ pub trait ApplicationWindow {
 
 pub trait DeviceEventsHandler: Application {}
 
+// NOTE: the boxed application is used by the backends when running with the
+// `dyn Application`, thus every callback must be forwarded, otherwise the
+// default implementation will be silently used instead.
+impl<A: Application + ?Sized> Application for Box<A> {
+    #[inline]
+    fn user_wakeup(&mut self, loop_handle: &mut dyn EventLoopHandle) {
+        (**self).user_wakeup(loop_handle)
+    }
+
+    #[inline]
+    fn new_events(&mut self, loop_handle: &mut dyn EventLoopHandle, start_cause: StartCause) {
+        (**self).new_events(loop_handle, start_cause)
+    }
+
+    #[inline]
+    fn about_to_wait(&mut self, loop_handle: &mut dyn EventLoopHandle) {
+        (**self).about_to_wait(loop_handle)
+    }
+
+    #[inline]
+    fn loop_exiting(&mut self, loop_handle: &mut dyn EventLoopHandle) {
+        (**self).loop_exiting(loop_handle)
+    }
+
+    #[inline]
+    fn exit_requested(&mut self, loop_handle: &mut dyn EventLoopHandle) -> bool {
+        (**self).exit_requested(loop_handle)
+    }
+
+    #[inline]
+    fn backend_disconnected(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        error: BackendDisconnected,
+    ) {
+        (**self).backend_disconnected(loop_handle, error)
+    }
+
+    #[inline]
+    fn timer_fired(&mut self, loop_handle: &mut dyn EventLoopHandle, timer_id: TimerId) {
+        (**self).timer_fired(loop_handle, timer_id)
+    }
+
+    #[inline]
+    fn fd_ready(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        token: FdToken,
+        readiness: Readiness,
+    ) {
+        (**self).fd_ready(loop_handle, token, readiness)
+    }
+
+    #[inline]
+    fn file_changed(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        watch_id: WatchId,
+        change: FileChange,
+    ) {
+        (**self).file_changed(loop_handle, watch_id, change)
+    }
+
+    #[inline]
+    fn query_complete(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        request_id: RequestId,
+        result: QueryResult,
+    ) {
+        (**self).query_complete(loop_handle, request_id, result)
+    }
+
+    #[inline]
+    fn activated(&mut self, loop_handle: &mut dyn EventLoopHandle, request: ActivationRequest) {
+        (**self).activated(loop_handle, request)
+    }
+
+    #[inline]
+    fn monitor_changed(&mut self, loop_handle: &mut dyn EventLoopHandle, monitor_id: MonitorId) {
+        (**self).monitor_changed(loop_handle, monitor_id)
+    }
+
+    #[inline]
+    fn user_idle(&mut self, loop_handle: &mut dyn EventLoopHandle, idle: bool) {
+        (**self).user_idle(loop_handle, idle)
+    }
+
+    #[inline(always)]
+    fn touch_handler(&mut self) -> Option<&mut dyn TouchInputHandler> {
+        (**self).touch_handler()
+    }
+
+    #[inline(always)]
+    fn keyboard_handler(&mut self) -> Option<&mut dyn KeyboardInputHandler> {
+        (**self).keyboard_handler()
+    }
+
+    #[inline(always)]
+    fn pointer_handler(&mut self) -> Option<&mut dyn PointerInputHandler> {
+        (**self).pointer_handler()
+    }
+
+    #[inline(always)]
+    fn device_events_handelr(&mut self) -> Option<&mut dyn DeviceEventsHandler> {
+        (**self).device_events_handelr()
+    }
+}
+
+impl<A: ApplicationWindow + ?Sized> ApplicationWindow for Box<A> {
+    #[inline]
+    fn created(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        (**self).created(loop_handle, window_id)
+    }
+
+    #[inline]
+    fn resized(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        size: PhysicalSize<u32>,
+    ) {
+        (**self).resized(loop_handle, window_id, size)
+    }
+
+    #[inline]
+    fn scale_factor_changed(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        scale_factor: f64,
+    ) {
+        (**self).scale_factor_changed(loop_handle, window_id, scale_factor)
+    }
+
+    #[inline]
+    fn redraw_requested(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        (**self).redraw_requested(loop_handle, window_id)
+    }
+
+    #[inline]
+    fn close_requested(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+    ) -> bool {
+        (**self).close_requested(loop_handle, window_id)
+    }
+
+    #[inline]
+    fn decoration_action(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        action: DecorationAction,
+    ) -> bool {
+        (**self).decoration_action(loop_handle, window_id, action)
+    }
+
+    #[inline]
+    fn decoration_mode_changed(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        mode: DecorationMode,
+    ) {
+        (**self).decoration_mode_changed(loop_handle, window_id, mode)
+    }
+
+    #[inline]
+    fn activation_denied(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        (**self).activation_denied(loop_handle, window_id)
+    }
+
+    #[inline]
+    fn focused(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        focused: bool,
+    ) {
+        (**self).focused(loop_handle, window_id, focused)
+    }
+
+    #[inline]
+    fn focus_directional(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        direction: FocusDirection,
+    ) {
+        (**self).focus_directional(loop_handle, window_id, direction)
+    }
+
+    #[inline]
+    fn occluded(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        occluded: bool,
+    ) {
+        (**self).occluded(loop_handle, window_id, occluded)
+    }
+
+    #[inline]
+    fn state_changed(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        (**self).state_changed(loop_handle, window_id)
+    }
+
+    #[inline]
+    fn recommended_bounds_changed(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+    ) {
+        (**self).recommended_bounds_changed(loop_handle, window_id)
+    }
+
+    #[inline]
+    fn frame_missed(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        (**self).frame_missed(loop_handle, window_id)
+    }
+
+    #[inline]
+    fn destroyed(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        (**self).destroyed(loop_handle, window_id)
+    }
+}
+
 /// Describes the reason the event loop is resuming.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StartCause {
@@ -0,0 +1,773 @@
+//! The closure based [`Application`].
+//!
+//! Small applications and tests could register the closures only for the
+//! callbacks they care about instead of implementing the whole trait family.
+//!
+//! ```no_run
+//! use winit_core::application::StartCause;
+//! use winit_core::builder::ApplicationBuilder;
+//! use winit_core::window::ToplevelAttributes;
+//!
+//! let app = ApplicationBuilder::new()
+//!     .on_new_events(|handle, start_cause| {
+//!         if start_cause == StartCause::Init {
+//!             let _ = handle.create_window(ToplevelAttributes::default().into());
+//!         }
+//!     })
+//!     .on_resized(|handle, window_id, _| {
+//!         if let Some(window) = handle.get_window_mut(window_id) {
+//!             window.request_redraw();
+//!         }
+//!     })
+//!     .on_key_input(|handle, _, event| {
+//!         if event.text.as_deref() == Some("q") {
+//!             handle.exit();
+//!         }
+//!     })
+//!     .build();
+//! ```
+
+use smol_str::SmolStr;
+
+use crate::application::{Application, ApplicationWindow, StartCause};
+use crate::dpi::{PhysicalPosition, PhysicalSize};
+use crate::event_loop::{
+    ActivationRequest, BackendDisconnected, EventLoopHandle, FdToken, FileChange, QueryResult,
+    Readiness, RequestId, TimerId, WatchId,
+};
+use crate::input::keyboard::{KeyEvent, KeyboardInputHandler};
+use crate::input::pointer::{
+    PointerButtonEvent, PointerInputHandler, PointerMotion, PointerScroll,
+};
+use crate::input::touch::TouchInputHandler;
+use crate::input::DeviceId;
+use crate::monitor::MonitorId;
+use crate::window::{DecorationAction, DecorationMode, FocusDirection, WindowId};
+
+type LoopCallback<A = (), R = ()> = Box<dyn FnMut(&mut dyn EventLoopHandle, A) -> R>;
+type WindowCallback<A = (), R = ()> = Box<dyn FnMut(&mut dyn EventLoopHandle, WindowId, A) -> R>;
+type TouchCallback = Box<dyn FnMut()>;
+
+/// The builder of the [`Application`] out of closures.
+///
+/// The callbacks without the closure do nothing, except for the
+/// [`Application::exit_requested`], the
+/// [`ApplicationWindow::close_requested`] and the
+/// [`ApplicationWindow::decoration_action`] which allow the default
+/// behavior.
+///
+/// The input handlers, like the [`Application::pointer_handler`], are only
+/// provided once a closure for any of their callbacks is registered.
+#[derive(Default)]
+pub struct ApplicationBuilder {
+    user_wakeup: Option<LoopCallback>,
+    new_events: Option<LoopCallback<StartCause>>,
+    about_to_wait: Option<LoopCallback>,
+    loop_exiting: Option<LoopCallback>,
+    exit_requested: Option<LoopCallback<(), bool>>,
+    backend_disconnected: Option<LoopCallback<BackendDisconnected>>,
+    timer_fired: Option<LoopCallback<TimerId>>,
+    fd_ready: Option<LoopCallback<(FdToken, Readiness)>>,
+    file_changed: Option<LoopCallback<(WatchId, FileChange)>>,
+    query_complete: Option<LoopCallback<(RequestId, QueryResult)>>,
+    activated: Option<LoopCallback<ActivationRequest>>,
+    monitor_changed: Option<LoopCallback<MonitorId>>,
+    user_idle: Option<LoopCallback<bool>>,
+    created: Option<WindowCallback>,
+    resized: Option<WindowCallback<PhysicalSize<u32>>>,
+    scale_factor_changed: Option<WindowCallback<f64>>,
+    redraw_requested: Option<WindowCallback>,
+    close_requested: Option<WindowCallback<(), bool>>,
+    decoration_action: Option<WindowCallback<DecorationAction, bool>>,
+    decoration_mode_changed: Option<WindowCallback<DecorationMode>>,
+    activation_denied: Option<WindowCallback>,
+    focused: Option<WindowCallback<bool>>,
+    focus_directional: Option<WindowCallback<FocusDirection>>,
+    occluded: Option<WindowCallback<bool>>,
+    state_changed: Option<WindowCallback>,
+    recommended_bounds_changed: Option<WindowCallback>,
+    frame_missed: Option<WindowCallback>,
+    destroyed: Option<WindowCallback>,
+    pointer_moved: Option<WindowCallback<PointerMotion>>,
+    pointer_entered: Option<WindowCallback<(PhysicalPosition<f64>, DeviceId)>>,
+    pointer_left: Option<WindowCallback<DeviceId>>,
+    pointer_scrolled: Option<WindowCallback<PointerScroll>>,
+    pointer_button: Option<WindowCallback<PointerButtonEvent>>,
+    key_input: Option<WindowCallback<KeyEvent>>,
+    text_input: Option<WindowCallback<SmolStr>>,
+    touch_down: Option<TouchCallback>,
+    touch_up: Option<TouchCallback>,
+}
+
+impl ApplicationBuilder {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Build the application.
+    pub fn build(self) -> Box<dyn Application> {
+        Box::new(BuiltApplication(self))
+    }
+
+    /// See [`Application::user_wakeup`].
+    pub fn on_user_wakeup<F>(mut self, mut f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle) + 'static,
+    {
+        self.user_wakeup = Some(Box::new(move |handle, ()| f(handle)));
+        self
+    }
+
+    /// See [`Application::new_events`].
+    pub fn on_new_events<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, StartCause) + 'static,
+    {
+        self.new_events = Some(Box::new(f));
+        self
+    }
+
+    /// See [`Application::about_to_wait`].
+    pub fn on_about_to_wait<F>(mut self, mut f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle) + 'static,
+    {
+        self.about_to_wait = Some(Box::new(move |handle, ()| f(handle)));
+        self
+    }
+
+    /// See [`Application::loop_exiting`].
+    pub fn on_loop_exiting<F>(mut self, mut f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle) + 'static,
+    {
+        self.loop_exiting = Some(Box::new(move |handle, ()| f(handle)));
+        self
+    }
+
+    /// See [`Application::exit_requested`].
+    pub fn on_exit_requested<F>(mut self, mut f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle) -> bool + 'static,
+    {
+        self.exit_requested = Some(Box::new(move |handle, ()| f(handle)));
+        self
+    }
+
+    /// See [`Application::backend_disconnected`].
+    pub fn on_backend_disconnected<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, BackendDisconnected) + 'static,
+    {
+        self.backend_disconnected = Some(Box::new(f));
+        self
+    }
+
+    /// See [`Application::timer_fired`].
+    pub fn on_timer_fired<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, TimerId) + 'static,
+    {
+        self.timer_fired = Some(Box::new(f));
+        self
+    }
+
+    /// See [`Application::fd_ready`].
+    pub fn on_fd_ready<F>(mut self, mut f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, FdToken, Readiness) + 'static,
+    {
+        self.fd_ready =
+            Some(Box::new(move |handle, (token, readiness)| f(handle, token, readiness)));
+        self
+    }
+
+    /// See [`Application::file_changed`].
+    pub fn on_file_changed<F>(mut self, mut f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, WatchId, FileChange) + 'static,
+    {
+        self.file_changed =
+            Some(Box::new(move |handle, (watch_id, change)| f(handle, watch_id, change)));
+        self
+    }
+
+    /// See [`Application::query_complete`].
+    pub fn on_query_complete<F>(mut self, mut f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, RequestId, QueryResult) + 'static,
+    {
+        self.query_complete =
+            Some(Box::new(move |handle, (request_id, result)| f(handle, request_id, result)));
+        self
+    }
+
+    /// See [`Application::activated`].
+    pub fn on_activated<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, ActivationRequest) + 'static,
+    {
+        self.activated = Some(Box::new(f));
+        self
+    }
+
+    /// See [`Application::monitor_changed`].
+    pub fn on_monitor_changed<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, MonitorId) + 'static,
+    {
+        self.monitor_changed = Some(Box::new(f));
+        self
+    }
+
+    /// See [`Application::user_idle`].
+    pub fn on_user_idle<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, bool) + 'static,
+    {
+        self.user_idle = Some(Box::new(f));
+        self
+    }
+
+    /// See [`ApplicationWindow::created`].
+    pub fn on_created<F>(mut self, mut f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, WindowId) + 'static,
+    {
+        self.created = Some(Box::new(move |handle, window_id, ()| f(handle, window_id)));
+        self
+    }
+
+    /// See [`ApplicationWindow::resized`].
+    pub fn on_resized<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, WindowId, PhysicalSize<u32>) + 'static,
+    {
+        self.resized = Some(Box::new(f));
+        self
+    }
+
+    /// See [`ApplicationWindow::scale_factor_changed`].
+    pub fn on_scale_factor_changed<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, WindowId, f64) + 'static,
+    {
+        self.scale_factor_changed = Some(Box::new(f));
+        self
+    }
+
+    /// See [`ApplicationWindow::redraw_requested`].
+    pub fn on_redraw_requested<F>(mut self, mut f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, WindowId) + 'static,
+    {
+        self.redraw_requested = Some(Box::new(move |handle, window_id, ()| f(handle, window_id)));
+        self
+    }
+
+    /// See [`ApplicationWindow::close_requested`].
+    pub fn on_close_requested<F>(mut self, mut f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, WindowId) -> bool + 'static,
+    {
+        self.close_requested = Some(Box::new(move |handle, window_id, ()| f(handle, window_id)));
+        self
+    }
+
+    /// See [`ApplicationWindow::decoration_action`].
+    pub fn on_decoration_action<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, WindowId, DecorationAction) -> bool + 'static,
+    {
+        self.decoration_action = Some(Box::new(f));
+        self
+    }
+
+    /// See [`ApplicationWindow::decoration_mode_changed`].
+    pub fn on_decoration_mode_changed<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, WindowId, DecorationMode) + 'static,
+    {
+        self.decoration_mode_changed = Some(Box::new(f));
+        self
+    }
+
+    /// See [`ApplicationWindow::activation_denied`].
+    pub fn on_activation_denied<F>(mut self, mut f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, WindowId) + 'static,
+    {
+        self.activation_denied = Some(Box::new(move |handle, window_id, ()| f(handle, window_id)));
+        self
+    }
+
+    /// See [`ApplicationWindow::focused`].
+    pub fn on_focused<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, WindowId, bool) + 'static,
+    {
+        self.focused = Some(Box::new(f));
+        self
+    }
+
+    /// See [`ApplicationWindow::focus_directional`].
+    pub fn on_focus_directional<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, WindowId, FocusDirection) + 'static,
+    {
+        self.focus_directional = Some(Box::new(f));
+        self
+    }
+
+    /// See [`ApplicationWindow::occluded`].
+    pub fn on_occluded<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, WindowId, bool) + 'static,
+    {
+        self.occluded = Some(Box::new(f));
+        self
+    }
+
+    /// See [`ApplicationWindow::state_changed`].
+    pub fn on_state_changed<F>(mut self, mut f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, WindowId) + 'static,
+    {
+        self.state_changed = Some(Box::new(move |handle, window_id, ()| f(handle, window_id)));
+        self
+    }
+
+    /// See [`ApplicationWindow::recommended_bounds_changed`].
+    pub fn on_recommended_bounds_changed<F>(mut self, mut f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, WindowId) + 'static,
+    {
+        self.recommended_bounds_changed =
+            Some(Box::new(move |handle, window_id, ()| f(handle, window_id)));
+        self
+    }
+
+    /// See [`ApplicationWindow::frame_missed`].
+    pub fn on_frame_missed<F>(mut self, mut f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, WindowId) + 'static,
+    {
+        self.frame_missed = Some(Box::new(move |handle, window_id, ()| f(handle, window_id)));
+        self
+    }
+
+    /// See [`ApplicationWindow::destroyed`].
+    pub fn on_destroyed<F>(mut self, mut f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, WindowId) + 'static,
+    {
+        self.destroyed = Some(Box::new(move |handle, window_id, ()| f(handle, window_id)));
+        self
+    }
+
+    /// See [`PointerInputHandler::pointer_moved`].
+    pub fn on_pointer_moved<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, WindowId, PointerMotion) + 'static,
+    {
+        self.pointer_moved = Some(Box::new(f));
+        self
+    }
+
+    /// See [`PointerInputHandler::pointer_entered`].
+    pub fn on_pointer_entered<F>(mut self, mut f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, WindowId, PhysicalPosition<f64>, DeviceId) + 'static,
+    {
+        self.pointer_entered = Some(Box::new(move |handle, window_id, (position, device_id)| {
+            f(handle, window_id, position, device_id)
+        }));
+        self
+    }
+
+    /// See [`PointerInputHandler::pointer_left`].
+    pub fn on_pointer_left<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, WindowId, DeviceId) + 'static,
+    {
+        self.pointer_left = Some(Box::new(f));
+        self
+    }
+
+    /// See [`PointerInputHandler::pointer_scrolled`].
+    pub fn on_pointer_scrolled<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, WindowId, PointerScroll) + 'static,
+    {
+        self.pointer_scrolled = Some(Box::new(f));
+        self
+    }
+
+    /// See [`PointerInputHandler::pointer_button`].
+    pub fn on_pointer_button<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, WindowId, PointerButtonEvent) + 'static,
+    {
+        self.pointer_button = Some(Box::new(f));
+        self
+    }
+
+    /// See [`KeyboardInputHandler::key_input`].
+    pub fn on_key_input<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, WindowId, KeyEvent) + 'static,
+    {
+        self.key_input = Some(Box::new(f));
+        self
+    }
+
+    /// See [`KeyboardInputHandler::text_input`].
+    pub fn on_text_input<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&mut dyn EventLoopHandle, WindowId, SmolStr) + 'static,
+    {
+        self.text_input = Some(Box::new(f));
+        self
+    }
+
+    /// See [`TouchInputHandler::touch_down`].
+    pub fn on_touch_down<F>(mut self, f: F) -> Self
+    where
+        F: FnMut() + 'static,
+    {
+        self.touch_down = Some(Box::new(f));
+        self
+    }
+
+    /// See [`TouchInputHandler::touch_up`].
+    pub fn on_touch_up<F>(mut self, f: F) -> Self
+    where
+        F: FnMut() + 'static,
+    {
+        self.touch_up = Some(Box::new(f));
+        self
+    }
+}
+
+/// The application built by the [`ApplicationBuilder`].
+struct BuiltApplication(ApplicationBuilder);
+
+impl BuiltApplication {
+    fn loop_callback<A>(
+        callback: &mut Option<LoopCallback<A>>,
+        handle: &mut dyn EventLoopHandle,
+        arg: A,
+    ) {
+        if let Some(callback) = callback.as_mut() {
+            callback(handle, arg);
+        }
+    }
+
+    fn window_callback<A>(
+        callback: &mut Option<WindowCallback<A>>,
+        handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        arg: A,
+    ) {
+        if let Some(callback) = callback.as_mut() {
+            callback(handle, window_id, arg);
+        }
+    }
+}
+
+impl Application for BuiltApplication {
+    fn user_wakeup(&mut self, loop_handle: &mut dyn EventLoopHandle) {
+        Self::loop_callback(&mut self.0.user_wakeup, loop_handle, ())
+    }
+
+    fn new_events(&mut self, loop_handle: &mut dyn EventLoopHandle, start_cause: StartCause) {
+        Self::loop_callback(&mut self.0.new_events, loop_handle, start_cause)
+    }
+
+    fn about_to_wait(&mut self, loop_handle: &mut dyn EventLoopHandle) {
+        Self::loop_callback(&mut self.0.about_to_wait, loop_handle, ())
+    }
+
+    fn loop_exiting(&mut self, loop_handle: &mut dyn EventLoopHandle) {
+        Self::loop_callback(&mut self.0.loop_exiting, loop_handle, ())
+    }
+
+    fn exit_requested(&mut self, loop_handle: &mut dyn EventLoopHandle) -> bool {
+        match self.0.exit_requested.as_mut() {
+            Some(callback) => callback(loop_handle, ()),
+            None => true,
+        }
+    }
+
+    fn backend_disconnected(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        error: BackendDisconnected,
+    ) {
+        Self::loop_callback(&mut self.0.backend_disconnected, loop_handle, error)
+    }
+
+    fn timer_fired(&mut self, loop_handle: &mut dyn EventLoopHandle, timer_id: TimerId) {
+        Self::loop_callback(&mut self.0.timer_fired, loop_handle, timer_id)
+    }
+
+    fn fd_ready(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        token: FdToken,
+        readiness: Readiness,
+    ) {
+        Self::loop_callback(&mut self.0.fd_ready, loop_handle, (token, readiness))
+    }
+
+    fn file_changed(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        watch_id: WatchId,
+        change: FileChange,
+    ) {
+        Self::loop_callback(&mut self.0.file_changed, loop_handle, (watch_id, change))
+    }
+
+    fn query_complete(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        request_id: RequestId,
+        result: QueryResult,
+    ) {
+        Self::loop_callback(&mut self.0.query_complete, loop_handle, (request_id, result))
+    }
+
+    fn activated(&mut self, loop_handle: &mut dyn EventLoopHandle, request: ActivationRequest) {
+        Self::loop_callback(&mut self.0.activated, loop_handle, request)
+    }
+
+    fn monitor_changed(&mut self, loop_handle: &mut dyn EventLoopHandle, monitor_id: MonitorId) {
+        Self::loop_callback(&mut self.0.monitor_changed, loop_handle, monitor_id)
+    }
+
+    fn user_idle(&mut self, loop_handle: &mut dyn EventLoopHandle, idle: bool) {
+        Self::loop_callback(&mut self.0.user_idle, loop_handle, idle)
+    }
+
+    fn touch_handler(&mut self) -> Option<&mut dyn TouchInputHandler> {
+        let builder = &self.0;
+        let touch = builder.touch_down.is_some() || builder.touch_up.is_some();
+        touch.then_some(self)
+    }
+
+    fn keyboard_handler(&mut self) -> Option<&mut dyn KeyboardInputHandler> {
+        let builder = &self.0;
+        let keyboard = builder.key_input.is_some() || builder.text_input.is_some();
+        keyboard.then_some(self)
+    }
+
+    fn pointer_handler(&mut self) -> Option<&mut dyn PointerInputHandler> {
+        let builder = &self.0;
+        let pointer = builder.pointer_moved.is_some()
+            || builder.pointer_entered.is_some()
+            || builder.pointer_left.is_some()
+            || builder.pointer_scrolled.is_some()
+            || builder.pointer_button.is_some();
+        pointer.then_some(self)
+    }
+}
+
+impl ApplicationWindow for BuiltApplication {
+    fn created(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        Self::window_callback(&mut self.0.created, loop_handle, window_id, ())
+    }
+
+    fn resized(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        size: PhysicalSize<u32>,
+    ) {
+        Self::window_callback(&mut self.0.resized, loop_handle, window_id, size)
+    }
+
+    fn scale_factor_changed(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        scale_factor: f64,
+    ) {
+        Self::window_callback(
+            &mut self.0.scale_factor_changed,
+            loop_handle,
+            window_id,
+            scale_factor,
+        )
+    }
+
+    fn redraw_requested(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        Self::window_callback(&mut self.0.redraw_requested, loop_handle, window_id, ())
+    }
+
+    fn close_requested(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+    ) -> bool {
+        match self.0.close_requested.as_mut() {
+            Some(callback) => callback(loop_handle, window_id, ()),
+            None => true,
+        }
+    }
+
+    fn decoration_action(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        action: DecorationAction,
+    ) -> bool {
+        match self.0.decoration_action.as_mut() {
+            Some(callback) => callback(loop_handle, window_id, action),
+            None => true,
+        }
+    }
+
+    fn decoration_mode_changed(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        mode: DecorationMode,
+    ) {
+        Self::window_callback(&mut self.0.decoration_mode_changed, loop_handle, window_id, mode)
+    }
+
+    fn activation_denied(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        Self::window_callback(&mut self.0.activation_denied, loop_handle, window_id, ())
+    }
+
+    fn focused(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        focused: bool,
+    ) {
+        Self::window_callback(&mut self.0.focused, loop_handle, window_id, focused)
+    }
+
+    fn focus_directional(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        direction: FocusDirection,
+    ) {
+        Self::window_callback(&mut self.0.focus_directional, loop_handle, window_id, direction)
+    }
+
+    fn occluded(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        occluded: bool,
+    ) {
+        Self::window_callback(&mut self.0.occluded, loop_handle, window_id, occluded)
+    }
+
+    fn state_changed(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        Self::window_callback(&mut self.0.state_changed, loop_handle, window_id, ())
+    }
+
+    fn recommended_bounds_changed(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+    ) {
+        Self::window_callback(&mut self.0.recommended_bounds_changed, loop_handle, window_id, ())
+    }
+
+    fn frame_missed(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        Self::window_callback(&mut self.0.frame_missed, loop_handle, window_id, ())
+    }
+
+    fn destroyed(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        Self::window_callback(&mut self.0.destroyed, loop_handle, window_id, ())
+    }
+}
+
+impl PointerInputHandler for BuiltApplication {
+    fn pointer_moved(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        event: PointerMotion,
+    ) {
+        Self::window_callback(&mut self.0.pointer_moved, loop_handle, window_id, event)
+    }
+
+    fn pointer_entered(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        position: PhysicalPosition<f64>,
+        device_id: DeviceId,
+    ) {
+        let arg = (position, device_id);
+        Self::window_callback(&mut self.0.pointer_entered, loop_handle, window_id, arg)
+    }
+
+    fn pointer_left(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        device_id: DeviceId,
+    ) {
+        Self::window_callback(&mut self.0.pointer_left, loop_handle, window_id, device_id)
+    }
+
+    fn pointer_scrolled(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        scroll: PointerScroll,
+    ) {
+        Self::window_callback(&mut self.0.pointer_scrolled, loop_handle, window_id, scroll)
+    }
+
+    fn pointer_button(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        event: PointerButtonEvent,
+    ) {
+        Self::window_callback(&mut self.0.pointer_button, loop_handle, window_id, event)
+    }
+}
+
+impl KeyboardInputHandler for BuiltApplication {
+    fn key_input(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        event: KeyEvent,
+    ) {
+        Self::window_callback(&mut self.0.key_input, loop_handle, window_id, event)
+    }
+
+    fn text_input(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        text: SmolStr,
+    ) {
+        Self::window_callback(&mut self.0.text_input, loop_handle, window_id, text)
+    }
+}
+
+impl TouchInputHandler for BuiltApplication {
+    fn touch_down(&mut self) {
+        if let Some(callback) = self.0.touch_down.as_mut() {
+            callback();
+        }
+    }
+
+    fn touch_up(&mut self) {
+        if let Some(callback) = self.0.touch_up.as_mut() {
+            callback();
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Reading of the clipboard content.
+//!
+//! The content is transferred as a stream, so pasting the large payloads, like
+//! the images or the long file lists, neither blocks the event loop nor loads
+//! the whole payload into memory. The source of the content writes it into
+//! the pipe as fast as the application reads it.
+//!
+//! ```no_run
+//! use std::io::Read;
+//!
+//! use winit_core::event_loop::{EventLoopHandle, FdToken, Interest};
+//! # use winit_core::clipboard::ClipboardReader;
+//! # use std::os::fd::AsRawFd;
+//!
+//! const PASTE: FdToken = FdToken(0);
+//!
+//! fn paste(loop_handle: &mut dyn EventLoopHandle) -> Option<ClipboardReader> {
+//!     let reader = loop_handle.clipboard()?.read("text/plain;charset=utf-8").ok()?;
+//!     // SAFETY: the reader is kept until the transfer ends.
+//!     unsafe { loop_handle.register_fd(reader.as_raw_fd(), Interest::READABLE, PASTE).ok()? };
+//!     Some(reader)
+//! }
+//!
+//! // Call from the `Application::fd_ready` for the `PASTE`.
+//! fn on_readable(loop_handle: &mut dyn EventLoopHandle, reader: &mut ClipboardReader) {
+//!     let mut chunk = [0; 4096];
+//!     match reader.read(&mut chunk) {
+//!         Ok(0) | Err(_) => loop_handle.unregister_fd(PASTE),
+//!         Ok(_len) => { /* Consume the chunk. */ },
+//!     }
+//! }
+//! ```
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+#[cfg(unix)]
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, RawFd};
+
+/// The clipboard of the seat.
+pub trait Clipboard {
+    /// The MIME types the current content is offered in, empty when the
+    /// clipboard is empty.
+    fn mime_types(&self) -> Vec<String>;
+
+    /// Start the transfer of the current content in the `mime_type`.
+    fn read(&mut self, mime_type: &str) -> Result<ClipboardReader, ClipboardError>;
+}
+
+/// The error of the [`Clipboard::read`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ClipboardError {
+    /// The clipboard is empty.
+    Empty,
+
+    /// The content is not offered in the requested MIME type.
+    UnsupportedMimeType,
+
+    /// Failed to set up the transfer.
+    Io(io::Error),
+}
+
+impl fmt::Display for ClipboardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("the clipboard is empty"),
+            Self::UnsupportedMimeType => f.write_str("the MIME type is not offered"),
+            Self::Io(err) => write!(f, "failed to read the clipboard: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ClipboardError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ClipboardError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// The stream of the clipboard content, ending once the source has written
+/// all of it.
+///
+/// The stream is the read end of the pipe in the blocking mode, read it once
+/// per the readiness reported for the [`EventLoopHandle::register_fd`] to not
+/// block the event loop. The async runtimes could wrap the file descriptor
+/// with their own readiness tracking instead.
+///
+/// [`EventLoopHandle::register_fd`]: crate::event_loop::EventLoopHandle::register_fd
+#[derive(Debug)]
+pub struct ClipboardReader {
+    file: File,
+}
+
+impl ClipboardReader {
+    /// Wrap the read end of the pipe the content is written into.
+    pub fn new(file: File) -> Self {
+        Self { file }
+    }
+
+    /// Unwrap the read end of the pipe.
+    pub fn into_inner(self) -> File {
+        self.file
+    }
+}
+
+impl Read for ClipboardReader {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.file.read(buf)
+    }
+}
+
+#[cfg(unix)]
+impl AsFd for ClipboardReader {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.file.as_fd()
+    }
+}
+
+#[cfg(unix)]
+impl AsRawFd for ClipboardReader {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.file.as_raw_fd()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(unix)]
+    fn read_through_pipe() {
+        use std::io::Write;
+        use std::os::fd::OwnedFd;
+        use std::thread;
+
+        // Larger than the pipe buffer, so the source blocks until it's read.
+        let payload: Vec<u8> = (0..256 * 1024).map(|i| (i % 251) as u8).collect();
+
+        let (reader, mut writer) = io::pipe().unwrap();
+        let source = thread::spawn({
+            let payload = payload.clone();
+            move || writer.write_all(&payload).unwrap()
+        });
+
+        let mut reader = ClipboardReader::new(File::from(OwnedFd::from(reader)));
+        let mut content = Vec::new();
+        let mut chunk = [0; 4096];
+        loop {
+            match reader.read(&mut chunk).unwrap() {
+                0 => break,
+                len => content.extend_from_slice(&chunk[..len]),
+            }
+        }
+
+        source.join().unwrap();
+        assert_eq!(content, payload);
+    }
+}
@@ -0,0 +1,86 @@
+//! The cursor icons.
+//!
+//! The icons come from the [`cursor_icon`] crate shared with the toolkits,
+//! and follow the [CSS cursor names], which are also used by the cursor
+//! themes and the Wayland cursor-shape protocol.
+//!
+//! [CSS cursor names]: https://www.w3.org/TR/css-ui-4/#cursor
+
+pub use cursor_icon::{CursorIcon, ParseError};
+
+/// Parse the icon from the CSS cursor name, like `nwse-resize`.
+///
+/// The names are ASCII case-insensitive, as in CSS. Returns `None` for the
+/// unknown names, and the `auto` and `none` values, which are not icons.
+pub fn from_css_name(name: &str) -> Option<CursorIcon> {
+    name.to_ascii_lowercase().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// All the icons of the `cursor-icon`.
+    const ICONS: [CursorIcon; 34] = [
+        CursorIcon::Default,
+        CursorIcon::ContextMenu,
+        CursorIcon::Help,
+        CursorIcon::Pointer,
+        CursorIcon::Progress,
+        CursorIcon::Wait,
+        CursorIcon::Cell,
+        CursorIcon::Crosshair,
+        CursorIcon::Text,
+        CursorIcon::VerticalText,
+        CursorIcon::Alias,
+        CursorIcon::Copy,
+        CursorIcon::Move,
+        CursorIcon::NoDrop,
+        CursorIcon::NotAllowed,
+        CursorIcon::Grab,
+        CursorIcon::Grabbing,
+        CursorIcon::EResize,
+        CursorIcon::NResize,
+        CursorIcon::NeResize,
+        CursorIcon::NwResize,
+        CursorIcon::SResize,
+        CursorIcon::SeResize,
+        CursorIcon::SwResize,
+        CursorIcon::WResize,
+        CursorIcon::EwResize,
+        CursorIcon::NsResize,
+        CursorIcon::NeswResize,
+        CursorIcon::NwseResize,
+        CursorIcon::ColResize,
+        CursorIcon::RowResize,
+        CursorIcon::AllScroll,
+        CursorIcon::ZoomIn,
+        CursorIcon::ZoomOut,
+    ];
+
+    #[test]
+    fn css_name_round_trip() {
+        for icon in ICONS {
+            assert_eq!(from_css_name(icon.name()), Some(icon), "{}", icon.name());
+        }
+    }
+
+    #[test]
+    fn css_name_case_insensitive() {
+        assert_eq!(from_css_name("NWSE-Resize"), Some(CursorIcon::NwseResize));
+        assert_eq!(from_css_name("Pointer"), Some(CursorIcon::Pointer));
+
+        for icon in ICONS {
+            let name = icon.name().to_ascii_uppercase();
+            assert_eq!(from_css_name(&name), Some(icon), "{name}");
+        }
+    }
+
+    #[test]
+    fn css_name_unknown() {
+        assert_eq!(from_css_name("auto"), None);
+        assert_eq!(from_css_name("none"), None);
+        assert_eq!(from_css_name("hand"), None);
+        assert_eq!(from_css_name(""), None);
+    }
+}
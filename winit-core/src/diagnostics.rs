@@ -0,0 +1,209 @@
+//! Diagnostics of the backends, to triage the windows which don't open.
+//!
+//! The backends are compiled into the application, so their probes are passed
+//! to the [`probe`]. The known backends without the probe are still checked
+//! against the environment, to tell whether compiling them in would help.
+//!
+//! ```no_run
+//! # use winit_core::diagnostics::BackendProbe;
+//! # fn wayland_probe() -> &'static dyn BackendProbe { unimplemented!() }
+//! let report = winit_core::diagnostics::probe(&[wayland_probe()]);
+//! print!("{report}");
+//! ```
+
+use std::env;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The backends of the platform checked even when they're not compiled in.
+#[cfg(all(unix, not(target_os = "macos")))]
+const KNOWN_BACKENDS: &[&str] = &["wayland", "x11"];
+#[cfg(windows)]
+const KNOWN_BACKENDS: &[&str] = &["win32"];
+#[cfg(target_os = "macos")]
+const KNOWN_BACKENDS: &[&str] = &["appkit"];
+#[cfg(target_arch = "wasm32")]
+const KNOWN_BACKENDS: &[&str] = &["web"];
+#[cfg(not(any(unix, windows, target_arch = "wasm32")))]
+const KNOWN_BACKENDS: &[&str] = &[];
+
+/// The probe of the backend compiled into the application.
+pub trait BackendProbe {
+    /// The name of the backend, like `wayland`.
+    fn name(&self) -> &'static str;
+
+    /// The version of the backend.
+    fn version(&self) -> &'static str;
+
+    /// Try to connect to the display server and check what the backend needs.
+    ///
+    /// The [`BackendReport::compiled_in`] and the [`BackendReport::version`]
+    /// are filled by the [`probe`].
+    fn probe(&self) -> BackendReport;
+}
+
+/// The diagnostics of the single backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackendReport {
+    pub name: &'static str,
+
+    /// Whether the backend is compiled into the application.
+    pub compiled_in: bool,
+
+    /// The version of the compiled in backend.
+    pub version: Option<&'static str>,
+
+    /// Whether the display server accepted the connection.
+    pub connectable: bool,
+
+    /// The requirements which are not met, like the environment variables or
+    /// the protocols the display server doesn't support.
+    pub missing: Vec<String>,
+}
+
+impl BackendReport {
+    pub fn new(name: &'static str) -> Self {
+        Self { name, compiled_in: false, version: None, connectable: false, missing: Vec::new() }
+    }
+
+    /// Whether the backend could be used to open the windows.
+    pub fn is_usable(&self) -> bool {
+        self.compiled_in && self.connectable && self.missing.is_empty()
+    }
+}
+
+/// The diagnostics of all the backends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub backends: Vec<BackendReport>,
+}
+
+impl Report {
+    /// The backends which could be used to open the windows.
+    pub fn usable(&self) -> impl Iterator<Item = &BackendReport> {
+        self.backends.iter().filter(|backend| backend.is_usable())
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for backend in &self.backends {
+            let status = if backend.is_usable() { "ok" } else { "unusable" };
+            write!(f, "{}: {status}", backend.name)?;
+            match backend.version {
+                Some(version) => writeln!(f, " (version {version})")?,
+                None => writeln!(f, " (not compiled in)")?,
+            }
+
+            let connection = if backend.connectable { "connected" } else { "failed" };
+            writeln!(f, "  connection: {connection}")?;
+            for missing in &backend.missing {
+                writeln!(f, "  missing: {missing}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Probe the compiled in `backends` and check the environment of the rest of
+/// the known ones.
+pub fn probe(backends: &[&dyn BackendProbe]) -> Report {
+    let mut reports: Vec<_> = backends
+        .iter()
+        .map(|backend| BackendReport {
+            compiled_in: true,
+            version: Some(backend.version()),
+            ..backend.probe()
+        })
+        .collect();
+
+    for &name in KNOWN_BACKENDS {
+        if reports.iter().any(|report| report.name == name) {
+            continue;
+        }
+
+        // The rest of the backends don't depend on the environment.
+        let mut report = match name {
+            "wayland" => probe_wayland_env(),
+            "x11" => probe_x11_env(),
+            _ => BackendReport::new(name),
+        };
+        report.missing.push(String::from("the backend is not compiled in"));
+        reports.push(report);
+    }
+
+    Report { backends: reports }
+}
+
+/// Check that the Wayland socket from the environment accepts the connection.
+pub fn probe_wayland_env() -> BackendReport {
+    let mut report = BackendReport::new("wayland");
+
+    // The socket is passed by the parent process.
+    if env::var_os("WAYLAND_SOCKET").is_some() {
+        report.connectable = true;
+        return report;
+    }
+
+    let display = env::var_os("WAYLAND_DISPLAY").unwrap_or_else(|| "wayland-0".into());
+    let mut path = PathBuf::from(display);
+    if path.is_relative() {
+        match env::var_os("XDG_RUNTIME_DIR") {
+            Some(runtime_dir) => path = PathBuf::from(runtime_dir).join(path),
+            None => {
+                report.missing.push(String::from("XDG_RUNTIME_DIR is not set"));
+                return report;
+            },
+        }
+    }
+
+    match connect(path.as_ref()) {
+        Ok(_) => report.connectable = true,
+        Err(err) => report.missing.push(format!("can't connect to {}: {err}", path.display())),
+    }
+
+    report
+}
+
+/// Check that the local X server from the `DISPLAY` accepts the connection.
+///
+/// The remote displays are assumed to be reachable.
+pub fn probe_x11_env() -> BackendReport {
+    let mut report = BackendReport::new("x11");
+
+    let display = match env::var("DISPLAY") {
+        Ok(display) if !display.is_empty() => display,
+        _ => {
+            report.missing.push(String::from("DISPLAY is not set"));
+            return report;
+        },
+    };
+
+    // The local display looks like `:0` or `:0.0`.
+    let number = match display.strip_prefix(':') {
+        Some(number) => number.split('.').next().unwrap_or_default(),
+        None => {
+            report.connectable = true;
+            return report;
+        },
+    };
+
+    let path = format!("/tmp/.X11-unix/X{number}");
+    match connect(path.as_ref()) {
+        Ok(_) => report.connectable = true,
+        Err(err) => report.missing.push(format!("can't connect to {path}: {err}")),
+    }
+
+    report
+}
+
+/// Try to connect to the unix socket at the `path`.
+fn connect(path: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    return std::os::unix::net::UnixStream::connect(path).map(drop);
+
+    #[cfg(not(unix))]
+    return Err(io::ErrorKind::Unsupported.into());
+}
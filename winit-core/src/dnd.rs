@@ -0,0 +1,123 @@
+//! The typed payloads of the drag and drop.
+//!
+//! The systems transfer the dropped data as bytes tagged with the MIME type,
+//! this module parses the common ones, so the applications don't need to
+//! negotiate the MIME types themselves.
+
+use std::path::PathBuf;
+
+use url::Url;
+
+/// The MIME types [`DropPayload::parse`] understands, in the order of
+/// preference.
+pub const MIME_TYPES: &[&str] =
+    &["text/uri-list", "text/plain;charset=utf-8", "UTF8_STRING", "text/plain"];
+
+/// The parsed data of the drop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DropPayload {
+    /// The local files.
+    FilePaths(Vec<PathBuf>),
+
+    /// The text.
+    Text(String),
+
+    /// The URIs, when at least one of them is not a local file.
+    Uri(Vec<Url>),
+}
+
+impl DropPayload {
+    /// Pick the MIME type to request from the ones `offered` by the source.
+    pub fn preferred_mime_type<S: AsRef<str>>(offered: &[S]) -> Option<&'static str> {
+        MIME_TYPES.iter().copied().find(|mime_type| {
+            offered.iter().any(|offered| offered.as_ref().eq_ignore_ascii_case(mime_type))
+        })
+    }
+
+    /// Parse the `data` received for the `mime_type`.
+    ///
+    /// Returns `None` when the MIME type is not in the [`MIME_TYPES`] or the
+    /// data is malformed.
+    pub fn parse(mime_type: &str, data: &[u8]) -> Option<Self> {
+        let mime_type = MIME_TYPES.iter().find(|known| known.eq_ignore_ascii_case(mime_type))?;
+        let text = std::str::from_utf8(data).ok()?;
+
+        if *mime_type != "text/uri-list" {
+            return Some(Self::Text(text.to_owned()));
+        }
+
+        // The list is separated by CRLF, the lines starting with `#` are
+        // comments.
+        let uris = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(Url::parse)
+            .collect::<Result<Vec<_>, _>>()
+            .ok()?;
+
+        match file_paths(&uris) {
+            Some(paths) if !paths.is_empty() => Some(Self::FilePaths(paths)),
+            _ => Some(Self::Uri(uris)),
+        }
+    }
+}
+
+/// Convert the `uris` to the local file paths, when all of them are files.
+#[cfg(any(unix, windows))]
+fn file_paths(uris: &[Url]) -> Option<Vec<PathBuf>> {
+    // The file paths are percent-decoded.
+    uris.iter()
+        .map(|uri| if uri.scheme() == "file" { uri.to_file_path().ok() } else { None })
+        .collect()
+}
+
+/// The platform has no file paths, the URIs are kept as is.
+#[cfg(not(any(unix, windows)))]
+fn file_paths(_uris: &[Url]) -> Option<Vec<PathBuf>> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(unix)]
+    fn parse_uri_list() {
+        let data = b"# comment\r\nfile:///tmp/hello%20world.txt\r\nfile:///tmp/a\r\n";
+        assert_eq!(
+            DropPayload::parse("text/uri-list", data),
+            Some(DropPayload::FilePaths(vec![
+                PathBuf::from("/tmp/hello world.txt"),
+                PathBuf::from("/tmp/a"),
+            ]))
+        );
+
+        let data = b"file:///tmp/a\r\nhttps://example.com/\r\n";
+        assert_eq!(
+            DropPayload::parse("text/uri-list", data),
+            Some(DropPayload::Uri(vec![
+                Url::parse("file:///tmp/a").unwrap(),
+                Url::parse("https://example.com/").unwrap(),
+            ]))
+        );
+    }
+
+    #[test]
+    fn parse_text() {
+        assert_eq!(
+            DropPayload::parse("text/plain;charset=UTF-8", "жук".as_bytes()),
+            Some(DropPayload::Text(String::from("жук")))
+        );
+        assert_eq!(DropPayload::parse("text/plain", &[0xff]), None);
+        assert_eq!(DropPayload::parse("image/png", &[]), None);
+    }
+
+    #[test]
+    fn preferred_mime_type() {
+        let offered = ["text/plain", "text/uri-list"];
+        assert_eq!(DropPayload::preferred_mime_type(&offered), Some("text/uri-list"));
+        assert_eq!(DropPayload::preferred_mime_type(&["image/png"]), None);
+    }
+}
@@ -97,13 +97,17 @@
 //! [points]: https://en.wikipedia.org/wiki/Point_(typography)
 //! [picas]: https://en.wikipedia.org/wiki/Pica_(typography)
 //! [`ScaleFactorChanged`]: crate::event::WindowEvent::ScaleFactorChanged
-//! [`window.scale_factor()`]: crate::window::Window::scale_factor
+//! [`window.scale_factor()`]: crate::window::Surface::scale_factor
 //! [windows_1]: https://docs.microsoft.com/en-us/windows/win32/hidpi/high-dpi-desktop-application-development-on-windows
 //! [apple_1]: https://developer.apple.com/library/archive/documentation/DeviceInformation/Reference/iOSDeviceCompatibility/Displays/Displays.html
 //! [apple_2]: https://developer.apple.com/design/human-interface-guidelines/macos/icons-and-images/image-size-and-resolution/
 //! [android_1]: https://developer.android.com/training/multiscreen/screendensities
 //! [web_1]: https://developer.mozilla.org/en-US/docs/Web/API/Window/devicePixelRatio
 
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -585,6 +589,394 @@ impl<P: Pixel> From<LogicalPosition<P>> for Position {
     }
 }
 
+/// A rectangle represented in physical pixels.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Rect<P> {
+    pub origin: PhysicalPosition<P>,
+    pub size: PhysicalSize<P>,
+}
+
+impl<P> Rect<P> {
+    #[inline]
+    pub const fn new(origin: PhysicalPosition<P>, size: PhysicalSize<P>) -> Self {
+        Rect { origin, size }
+    }
+}
+
+impl<P: Pixel> Rect<P> {
+    /// Whether the `position` is inside the rectangle.
+    ///
+    /// The top and left edges are inclusive, while the bottom and right are
+    /// exclusive.
+    #[inline]
+    pub fn contains<X: Pixel>(&self, position: PhysicalPosition<X>) -> bool {
+        let (x, y): (f64, f64) = position.into();
+        let (left, top): (f64, f64) = self.origin.into();
+        let (width, height): (f64, f64) = self.size.into();
+        x >= left && y >= top && x < left + width && y < top + height
+    }
+
+    /// Shrink the rectangle by the given `insets`.
+    ///
+    /// The resulting size is clamped to zero.
+    #[inline]
+    pub fn inset(&self, insets: Insets<P>) -> Rect<P> {
+        let (left, top): (f64, f64) = self.origin.into();
+        let (width, height): (f64, f64) = self.size.into();
+        let width = (width - insets.left.into() - insets.right.into()).max(0.);
+        let height = (height - insets.top.into() - insets.bottom.into()).max(0.);
+        Rect {
+            origin: PhysicalPosition::new(left + insets.left.into(), top + insets.top.into())
+                .cast(),
+            size: PhysicalSize::new(width, height).cast(),
+        }
+    }
+
+    /// The [`Border`] of the rectangle under the `position`, when the borders
+    /// have the width of the `insets`.
+    ///
+    /// Returns `None` when the `position` is outside of the rectangle or
+    /// inside of its inset area.
+    pub fn border_at<X: Pixel>(
+        &self,
+        position: PhysicalPosition<X>,
+        insets: Insets<P>,
+    ) -> Option<Border> {
+        if !self.contains(position) {
+            return None;
+        }
+
+        let (x, y): (f64, f64) = position.into();
+        let (left, top): (f64, f64) = self.origin.into();
+        let (width, height): (f64, f64) = self.size.into();
+
+        let is_left = x < left + insets.left.into();
+        let is_right = x >= left + width - insets.right.into();
+        let is_top = y < top + insets.top.into();
+        let is_bottom = y >= top + height - insets.bottom.into();
+
+        match (is_top, is_bottom, is_left, is_right) {
+            (true, _, true, _) => Some(Border::TopLeft),
+            (true, _, _, true) => Some(Border::TopRight),
+            (_, true, true, _) => Some(Border::BottomLeft),
+            (_, true, _, true) => Some(Border::BottomRight),
+            (true, ..) => Some(Border::Top),
+            (_, true, ..) => Some(Border::Bottom),
+            (_, _, true, _) => Some(Border::Left),
+            (_, _, _, true) => Some(Border::Right),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn cast<X: Pixel>(&self) -> Rect<X> {
+        Rect { origin: self.origin.cast(), size: self.size.cast() }
+    }
+}
+
+/// Distances from each edge of a rectangle, represented in physical pixels.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Insets<P> {
+    pub top: P,
+    pub left: P,
+    pub bottom: P,
+    pub right: P,
+}
+
+impl<P> Insets<P> {
+    #[inline]
+    pub const fn new(top: P, left: P, bottom: P, right: P) -> Self {
+        Insets { top, left, bottom, right }
+    }
+}
+
+impl<P: Copy> Insets<P> {
+    /// Insets with the same distance from all the edges.
+    #[inline]
+    pub const fn uniform(inset: P) -> Self {
+        Insets::new(inset, inset, inset, inset)
+    }
+}
+
+impl<P: Pixel> Insets<P> {
+    #[inline]
+    pub fn cast<X: Pixel>(&self) -> Insets<X> {
+        Insets {
+            top: self.top.cast(),
+            left: self.left.cast(),
+            bottom: self.bottom.cast(),
+            right: self.right.cast(),
+        }
+    }
+}
+
+/// The border of a rectangle, including its corners.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Border {
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// How the fractional physical pixels are rounded.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Rounding {
+    Floor,
+    #[default]
+    Round,
+    Ceil,
+}
+
+impl Rounding {
+    #[inline]
+    pub fn apply(self, value: f64) -> f64 {
+        match self {
+            Rounding::Floor => value.floor(),
+            Rounding::Round => value.round(),
+            Rounding::Ceil => value.ceil(),
+        }
+    }
+}
+
+/// The rounding used by the backend to convert the logical sizes and
+/// positions into the physical ones.
+///
+/// Toolkits doing their own layout should use the same policy as the event
+/// loop, otherwise the content could be off by a pixel.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RoundingPolicy {
+    pub horizontal: Rounding,
+    pub vertical: Rounding,
+}
+
+impl RoundingPolicy {
+    #[inline]
+    pub const fn new(horizontal: Rounding, vertical: Rounding) -> Self {
+        RoundingPolicy { horizontal, vertical }
+    }
+
+    /// The same rounding for both axes.
+    #[inline]
+    pub const fn uniform(rounding: Rounding) -> Self {
+        RoundingPolicy::new(rounding, rounding)
+    }
+
+    #[inline]
+    pub fn to_physical_size<P: Pixel>(
+        &self,
+        size: LogicalSize<P>,
+        scale_factor: f64,
+    ) -> PhysicalSize<u32> {
+        assert!(validate_scale_factor(scale_factor));
+        let width = self.horizontal.apply(size.width.into() * scale_factor);
+        let height = self.vertical.apply(size.height.into() * scale_factor);
+        PhysicalSize::new(width as u32, height as u32)
+    }
+
+    #[inline]
+    pub fn to_physical_position<P: Pixel>(
+        &self,
+        position: LogicalPosition<P>,
+        scale_factor: f64,
+    ) -> PhysicalPosition<i32> {
+        assert!(validate_scale_factor(scale_factor));
+        let x = self.horizontal.apply(position.x.into() * scale_factor);
+        let y = self.vertical.apply(position.y.into() * scale_factor);
+        PhysicalPosition::new(x as i32, y as i32)
+    }
+}
+
+/// The unit of the parsed dimensions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Unit {
+    /// No suffix.
+    Logical,
+    /// The `px` suffix.
+    Physical,
+    /// The `%` suffix.
+    Percent,
+}
+
+/// The error of parsing the [`Size`] or the [`Position`] from the string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    input: String,
+    reason: &'static str,
+}
+
+impl ParseError {
+    pub(crate) fn new(input: &str, reason: &'static str) -> Self {
+        Self { input: input.to_owned(), reason }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid dimensions {:?}: {}", self.input, self.reason)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parse the pair of dimensions split by the `separator`, like `800x600`.
+///
+/// Each dimension is a number with the optional `px` or `%` suffix, the
+/// single dimension is used for both. The suffix of the second dimension
+/// applies to the first one without its own, so `800x600px` is the same as
+/// `800pxx600px`.
+pub(crate) fn parse_dimensions(
+    input: &str,
+    separator: char,
+) -> Result<(f64, f64, Unit), ParseError> {
+    fn dimension(input: &str) -> Option<(f64, Option<Unit>)> {
+        let input = input.trim();
+        let (number, unit) = if let Some(number) = input.strip_suffix("px") {
+            (number, Some(Unit::Physical))
+        } else if let Some(number) = input.strip_suffix('%') {
+            (number, Some(Unit::Percent))
+        } else {
+            (input, None)
+        };
+
+        let number = number.trim_end().parse::<f64>().ok().filter(|number| number.is_finite())?;
+        Some((number, unit))
+    }
+
+    let error = |reason| ParseError::new(input, reason);
+
+    // Skip the `x` of the `px` suffix.
+    let split = input.char_indices().find(|&(index, char)| {
+        char == separator && !(separator == 'x' && input[..index].ends_with('p'))
+    });
+    let (first, second) = match split {
+        Some((index, _)) => (&input[..index], Some(&input[index + 1..])),
+        None => (input, None),
+    };
+
+    let (first, first_unit) = dimension(first).ok_or_else(|| error("expected a number"))?;
+    let (second, second_unit) = match second {
+        Some(second) => dimension(second).ok_or_else(|| error("expected a number"))?,
+        None => (first, first_unit),
+    };
+
+    let unit = match (first_unit, second_unit) {
+        (Some(first), Some(second)) if first != second => {
+            return Err(error("the dimensions have different units"))
+        },
+        (_, Some(unit)) | (Some(unit), None) => unit,
+        (None, None) => Unit::Logical,
+    };
+
+    Ok((first, second, unit))
+}
+
+/// Parse the size in the `<width>x<height>` form.
+///
+/// The size without the suffix is logical, while the `px` one is physical,
+/// so `800x600` is the [`LogicalSize`] and `1024px` is the square
+/// [`PhysicalSize`]. The percentages, like `50%x50%`, need the monitor size,
+/// so they're parsed by the [`SizeOverride`] into the
+/// [`SizePolicy::FractionOfMonitor`] instead.
+///
+/// [`SizeOverride`]: crate::env::SizeOverride
+/// [`SizePolicy::FractionOfMonitor`]: crate::window::SizePolicy::FractionOfMonitor
+impl FromStr for Size {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (width, height, unit) = parse_dimensions(input, 'x')?;
+        match unit {
+            Unit::Logical if width >= 0. && height >= 0. => {
+                Ok(LogicalSize::new(width, height).into())
+            },
+            Unit::Physical if width >= 0. && height >= 0. => {
+                Ok(PhysicalSize::new(width, height).into())
+            },
+            Unit::Logical | Unit::Physical => Err(ParseError::new(input, "the size is negative")),
+            Unit::Percent => Err(ParseError::new(input, "the size can't be a percentage")),
+        }
+    }
+}
+
+impl fmt::Display for Size {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Size::Physical(size) => write!(f, "{}x{}px", size.width, size.height),
+            Size::Logical(size) => write!(f, "{}x{}", size.width, size.height),
+        }
+    }
+}
+
+/// Parse the position in the `<x>,<y>` form, with the same suffixes as the
+/// [`Size`].
+impl FromStr for Position {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match parse_dimensions(input, ',')? {
+            (x, y, Unit::Logical) => Ok(LogicalPosition::new(x, y).into()),
+            (x, y, Unit::Physical) => Ok(PhysicalPosition::new(x, y).into()),
+            (.., Unit::Percent) => {
+                Err(ParseError::new(input, "the position can't be a percentage"))
+            },
+        }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Position::Physical(position) => write!(f, "{},{}px", position.x, position.y),
+            Position::Logical(position) => write!(f, "{},{}", position.x, position.y),
+        }
+    }
+}
+
+/// (De)serialize the [`Size`] or the [`Position`] as the string, like
+/// `"800x600"`, instead of the structure.
+///
+/// ```ignore
+/// #[derive(Serialize, Deserialize)]
+/// struct Config {
+///     #[serde(with = "winit_core::dpi::as_str")]
+///     size: Size,
+/// }
+/// ```
+#[cfg(feature = "serde")]
+pub mod as_str {
+    use std::fmt::Display;
+    use std::str::FromStr;
+
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<T: Display, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::dpi;
@@ -983,6 +1375,92 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rect() {
+        let rect =
+            dpi::Rect::new(dpi::PhysicalPosition::new(10, 20), dpi::PhysicalSize::new(30, 40));
+        assert!(rect.contains(dpi::PhysicalPosition::new(10, 20)));
+        assert!(rect.contains(dpi::PhysicalPosition::new(39.5, 59.5)));
+        assert!(!rect.contains(dpi::PhysicalPosition::new(40, 20)));
+        assert!(!rect.contains(dpi::PhysicalPosition::new(10, 60)));
+        assert!(!rect.contains(dpi::PhysicalPosition::new(9, 20)));
+
+        assert_eq!(
+            rect.inset(dpi::Insets::new(1, 2, 3, 4)),
+            dpi::Rect::new(dpi::PhysicalPosition::new(12, 21), dpi::PhysicalSize::new(24, 36))
+        );
+        assert_eq!(rect.inset(dpi::Insets::uniform(100)).size, dpi::PhysicalSize::new(0, 0));
+
+        let insets = dpi::Insets::uniform(5);
+        assert_eq!(rect.border_at(dpi::PhysicalPosition::new(25, 40), insets), None);
+        assert_eq!(rect.border_at(dpi::PhysicalPosition::new(0, 0), insets), None);
+        assert_eq!(
+            rect.border_at(dpi::PhysicalPosition::new(10, 20), insets),
+            Some(dpi::Border::TopLeft)
+        );
+        assert_eq!(
+            rect.border_at(dpi::PhysicalPosition::new(39, 59), insets),
+            Some(dpi::Border::BottomRight)
+        );
+        assert_eq!(
+            rect.border_at(dpi::PhysicalPosition::new(25, 21), insets),
+            Some(dpi::Border::Top)
+        );
+        assert_eq!(
+            rect.border_at(dpi::PhysicalPosition::new(11, 40), insets),
+            Some(dpi::Border::Left)
+        );
+    }
+
+    #[test]
+    fn test_rounding_policy() {
+        let size = dpi::LogicalSize::new(101u32, 101u32);
+        let policy = dpi::RoundingPolicy::default();
+        assert_eq!(policy.to_physical_size(size, 1.5), dpi::PhysicalSize::new(152, 152));
+
+        let policy = dpi::RoundingPolicy::uniform(dpi::Rounding::Floor);
+        assert_eq!(policy.to_physical_size(size, 1.5), dpi::PhysicalSize::new(151, 151));
+
+        let policy = dpi::RoundingPolicy::new(dpi::Rounding::Floor, dpi::Rounding::Ceil);
+        assert_eq!(policy.to_physical_size(size, 1.25), dpi::PhysicalSize::new(126, 127));
+
+        let position = dpi::LogicalPosition::new(-10.5f64, 10.5f64);
+        let policy = dpi::RoundingPolicy::uniform(dpi::Rounding::Ceil);
+        assert_eq!(policy.to_physical_position(position, 1.), dpi::PhysicalPosition::new(-10, 11));
+    }
+
+    #[test]
+    fn test_parse_size() {
+        let size: dpi::Size = "800x600".parse().unwrap();
+        assert_eq!(size, dpi::LogicalSize::new(800., 600.).into());
+
+        let size: dpi::Size = "800 x 600px".parse().unwrap();
+        assert_eq!(size, dpi::PhysicalSize::new(800, 600).into());
+
+        let size: dpi::Size = "1024px".parse().unwrap();
+        assert_eq!(size, dpi::PhysicalSize::new(1024, 1024).into());
+        assert_eq!(size.to_string().parse::<dpi::Size>().unwrap(), size);
+
+        assert!("50%x50%".parse::<dpi::Size>().is_err());
+        assert!("800pxx600".parse::<dpi::Size>().is_ok());
+        assert!("800%x600px".parse::<dpi::Size>().is_err());
+        assert!("-800x600".parse::<dpi::Size>().is_err());
+        assert!("800x".parse::<dpi::Size>().is_err());
+        assert!("".parse::<dpi::Size>().is_err());
+    }
+
+    #[test]
+    fn test_parse_position() {
+        let position: dpi::Position = "10,-20.5".parse().unwrap();
+        assert_eq!(position, dpi::LogicalPosition::new(10., -20.5).into());
+
+        let position: dpi::Position = "10px,20px".parse().unwrap();
+        assert_eq!(position, dpi::PhysicalPosition::new(10, 20).into());
+        assert_eq!(position.to_string().parse::<dpi::Position>().unwrap(), position);
+
+        assert!("10x20".parse::<dpi::Position>().is_err());
+    }
+
     // Eat coverage for the Debug impls et al
     #[test]
     fn ensure_attrs_do_not_panic() {
@@ -0,0 +1,191 @@
+//! The overrides of the window attributes from the environment.
+//!
+//! The overrides ease debugging and the kiosk deployments, where the size and
+//! the scale of the windows should be adjusted without rebuilding the
+//! application. They're read once and applied by the backends to the
+//! attributes passed to the [`EventLoopHandle::create_window`] and to the scale
+//! factors of the windows with the [`EnvOverrides::window_scale_factor`], so
+//! they take priority over the ones picked by the application and the system.
+//!
+//! - `WINIT_DEFAULT_SIZE` - the initial size of the toplevel windows, like
+//!   `800x600`, `1024x768px`, `50%` or `50%x40%` of the monitor, see
+//!   [`SizeOverride`].
+//! - `WINIT_SCALE_FACTOR_OVERRIDE` - the scale factor used instead of the one
+//!   suggested by the system.
+//!
+//! The invalid values are logged and ignored.
+//!
+//! [`EventLoopHandle::create_window`]: crate::event_loop::EventLoopHandle::create_window
+
+use std::env;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::dpi::{self, ParseError, Size, Unit};
+use crate::window::{RoleAttributes, SizePolicy};
+
+/// The variable with the initial size of the toplevel windows.
+pub const DEFAULT_SIZE_VAR: &str = "WINIT_DEFAULT_SIZE";
+
+/// The variable with the scale factor used for all the windows.
+pub const SCALE_FACTOR_VAR: &str = "WINIT_SCALE_FACTOR_OVERRIDE";
+
+/// The initial size of the toplevel windows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SizeOverride {
+    /// The size as is, parsed as the [`Size`].
+    Size(Size),
+
+    /// The fractions of the monitor width and height, like `50%` or
+    /// `50%x40%`.
+    ///
+    /// See [`SizePolicy::FractionOfMonitor`].
+    FractionOfMonitor { width: f32, height: f32 },
+}
+
+impl FromStr for SizeOverride {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match dpi::parse_dimensions(input, 'x')? {
+            (width, height, _) if width == 0. || height == 0. => {
+                Err(ParseError::new(input, "the size is empty"))
+            },
+            (width, height, Unit::Percent)
+                if (0. ..=100.).contains(&width) && (0. ..=100.).contains(&height) =>
+            {
+                let (width, height) = (width as f32 / 100., height as f32 / 100.);
+                Ok(SizeOverride::FractionOfMonitor { width, height })
+            },
+            (_, _, Unit::Percent) => Err(ParseError::new(input, "the percentage is out of range")),
+            _ => input.parse().map(SizeOverride::Size),
+        }
+    }
+}
+
+/// The overrides read from the environment.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EnvOverrides {
+    /// The value of the [`DEFAULT_SIZE_VAR`].
+    pub default_size: Option<SizeOverride>,
+
+    /// The value of the [`SCALE_FACTOR_VAR`].
+    pub scale_factor: Option<f64>,
+}
+
+impl EnvOverrides {
+    /// Read the overrides from the environment.
+    pub fn from_env() -> Self {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    /// Read the overrides from the variables returned by the `var`.
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        let var = |name| var(name).filter(|value| !value.trim().is_empty());
+
+        let default_size = var(DEFAULT_SIZE_VAR).and_then(|value| match value.parse() {
+            Ok(size) => Some(size),
+            Err(err) => {
+                log::warn!("Ignoring {DEFAULT_SIZE_VAR}: {err}");
+                None
+            },
+        });
+
+        let scale_factor = var(SCALE_FACTOR_VAR).and_then(|value| {
+            match value.trim().parse().ok().filter(|&scale| dpi::validate_scale_factor(scale)) {
+                Some(scale_factor) => Some(scale_factor),
+                None => {
+                    log::warn!("Ignoring {SCALE_FACTOR_VAR}: invalid scale factor {value:?}");
+                    None
+                },
+            }
+        });
+
+        Self { default_size, scale_factor }
+    }
+
+    /// The scale factor of the window, the override or the one `suggested`
+    /// by the system without it.
+    #[inline]
+    pub fn window_scale_factor(&self, suggested: f64) -> f64 {
+        self.scale_factor.unwrap_or(suggested)
+    }
+
+    /// Apply the overrides to the `attributes` of the new window.
+    pub fn apply(&self, attributes: RoleAttributes) -> RoleAttributes {
+        let attributes = match attributes {
+            RoleAttributes::Toplevel(attributes) => attributes,
+            attributes => return attributes,
+        };
+
+        let attributes = match self.default_size {
+            Some(SizeOverride::Size(size)) => {
+                attributes.with_inner_size(size).with_size_policy(SizePolicy::Fixed)
+            },
+            Some(SizeOverride::FractionOfMonitor { width, height }) => {
+                attributes.with_size_policy(SizePolicy::FractionOfMonitor { width, height })
+            },
+            None => attributes,
+        };
+
+        attributes.into()
+    }
+}
+
+/// The overrides of the current process, read on the first access.
+pub fn overrides() -> &'static EnvOverrides {
+    static OVERRIDES: OnceLock<EnvOverrides> = OnceLock::new();
+    OVERRIDES.get_or_init(EnvOverrides::from_env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dpi::{LogicalSize, PhysicalSize};
+
+    fn from_vars(vars: &[(&str, &str)]) -> EnvOverrides {
+        EnvOverrides::from_vars(|name| {
+            vars.iter().find(|(var, _)| *var == name).map(|(_, value)| value.to_string())
+        })
+    }
+
+    #[test]
+    fn test_parse_size_override() {
+        let size: SizeOverride = "800x600".parse().unwrap();
+        assert_eq!(size, SizeOverride::Size(LogicalSize::new(800., 600.).into()));
+
+        let size: SizeOverride = "1024x768px".parse().unwrap();
+        assert_eq!(size, SizeOverride::Size(PhysicalSize::new(1024, 768).into()));
+
+        let size = SizeOverride::FractionOfMonitor { width: 0.5, height: 0.5 };
+        assert_eq!("50%".parse::<SizeOverride>().unwrap(), size);
+        assert_eq!("50%x50%".parse::<SizeOverride>().unwrap(), size);
+
+        let size = SizeOverride::FractionOfMonitor { width: 0.5, height: 0.25 };
+        assert_eq!("50%x25%".parse::<SizeOverride>().unwrap(), size);
+
+        assert!("50%x40px".parse::<SizeOverride>().is_err());
+        assert!("50%x150%".parse::<SizeOverride>().is_err());
+        assert!("150%".parse::<SizeOverride>().is_err());
+        assert!("-50%".parse::<SizeOverride>().is_err());
+        assert!("0%".parse::<SizeOverride>().is_err());
+        assert!("0x0".parse::<SizeOverride>().is_err());
+        assert!("800x0px".parse::<SizeOverride>().is_err());
+    }
+
+    #[test]
+    fn test_from_vars() {
+        let overrides = from_vars(&[(DEFAULT_SIZE_VAR, "50%"), (SCALE_FACTOR_VAR, " 1.5 ")]);
+        let size = SizeOverride::FractionOfMonitor { width: 0.5, height: 0.5 };
+        assert_eq!(overrides.default_size, Some(size));
+        assert_eq!(overrides.scale_factor, Some(1.5));
+        assert_eq!(overrides.window_scale_factor(2.), 1.5);
+
+        let overrides = from_vars(&[(DEFAULT_SIZE_VAR, "0x0"), (SCALE_FACTOR_VAR, "-1")]);
+        assert_eq!(overrides, EnvOverrides::default());
+        assert_eq!(overrides.window_scale_factor(2.), 2.);
+
+        let overrides = from_vars(&[(DEFAULT_SIZE_VAR, " "), (SCALE_FACTOR_VAR, "two")]);
+        assert_eq!(overrides, EnvOverrides::default());
+    }
+}
@@ -0,0 +1,219 @@
+//! The limit of the work done in one iteration of the event loop.
+
+use std::time::Duration;
+
+// The `std::time::Instant::now` panics on the web.
+use web_time::Instant;
+
+/// The limit of the events delivered in one iteration of the event loop, see
+/// [`EventLoopHandle::set_dispatch_budget`].
+///
+/// Once the budget is spent, the backend postpones the events which can wait
+/// and goes on to the redraws and the [`Application::about_to_wait`], so the
+/// high frequency devices, like the 8kHz mice, can't starve the rendering.
+///
+/// [`EventLoopHandle::set_dispatch_budget`]: super::EventLoopHandle::set_dispatch_budget
+/// [`Application::about_to_wait`]: crate::application::Application::about_to_wait
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DispatchBudget {
+    /// The number of the events, `None` for no limit.
+    pub max_events: Option<usize>,
+
+    /// The time spent since the first event of the iteration, `None` for no
+    /// limit.
+    pub max_time: Option<Duration>,
+}
+
+impl DispatchBudget {
+    /// No limit, which is the default.
+    pub const UNLIMITED: Self = Self { max_events: None, max_time: None };
+
+    #[inline]
+    pub const fn with_max_events(mut self, max_events: usize) -> Self {
+        self.max_events = Some(max_events);
+        self
+    }
+
+    #[inline]
+    pub const fn with_max_time(mut self, max_time: Duration) -> Self {
+        self.max_time = Some(max_time);
+        self
+    }
+}
+
+/// The metrics to tune the [`DispatchBudget`] with, see
+/// [`EventLoopHandle::dispatch_stats`].
+///
+/// [`EventLoopHandle::dispatch_stats`]: super::EventLoopHandle::dispatch_stats
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DispatchStats {
+    /// The iterations of the event loop.
+    pub iterations: u64,
+
+    /// The iterations which ran out of the budget.
+    pub exhausted_iterations: u64,
+
+    /// The events postponed to the next iteration, for the backends which can
+    /// count them.
+    pub deferred_events: u64,
+
+    /// The most events delivered in one iteration.
+    pub max_events: usize,
+
+    /// The longest time spent delivering the events of one iteration.
+    pub max_time: Duration,
+}
+
+/// The tracking of the [`DispatchBudget`] for the backends.
+#[derive(Debug, Default, Clone)]
+pub struct DispatchMeter {
+    budget: DispatchBudget,
+    stats: DispatchStats,
+
+    /// The events delivered in the current iteration.
+    events: usize,
+
+    /// The time of the first event of the current iteration, the time spent
+    /// waiting for it doesn't count.
+    started_at: Option<Instant>,
+
+    /// Whether the current iteration ran out of the budget.
+    exhausted: bool,
+}
+
+impl DispatchMeter {
+    pub fn new(budget: DispatchBudget) -> Self {
+        Self { budget, ..Default::default() }
+    }
+
+    #[inline]
+    pub fn budget(&self) -> DispatchBudget {
+        self.budget
+    }
+
+    #[inline]
+    pub fn set_budget(&mut self, budget: DispatchBudget) {
+        self.budget = budget;
+    }
+
+    #[inline]
+    pub fn stats(&self) -> DispatchStats {
+        self.stats
+    }
+
+    /// Start the new iteration of the event loop.
+    pub fn start_iteration(&mut self) {
+        self.stats.iterations += 1;
+        self.events = 0;
+        self.started_at = None;
+        self.exhausted = false;
+    }
+
+    /// Record the event delivered in the current iteration.
+    pub fn record_event(&mut self) {
+        let started_at = *self.started_at.get_or_insert_with(Instant::now);
+        self.events += 1;
+        self.stats.max_events = self.stats.max_events.max(self.events);
+        self.stats.max_time = self.stats.max_time.max(started_at.elapsed());
+    }
+
+    /// Record the event postponed to the next iteration.
+    #[inline]
+    pub fn record_deferred(&mut self) {
+        self.stats.deferred_events += 1;
+    }
+
+    /// Whether the current iteration can go on delivering the events.
+    ///
+    /// Once the budget is spent, the iteration stays exhausted until the
+    /// [`DispatchMeter::start_iteration`].
+    pub fn try_continue(&mut self) -> bool {
+        if self.exhausted {
+            return false;
+        }
+
+        let events_exceeded = self.budget.max_events.is_some_and(|max| self.events >= max);
+        let time_exceeded = match (self.budget.max_time, self.started_at) {
+            (Some(max), Some(started_at)) => started_at.elapsed() >= max,
+            _ => false,
+        };
+
+        if events_exceeded || time_exceeded {
+            self.exhausted = true;
+            self.stats.exhausted_iterations += 1;
+        }
+
+        !self.exhausted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unlimited_budget() {
+        let mut meter = DispatchMeter::new(DispatchBudget::UNLIMITED);
+        meter.start_iteration();
+        for _ in 0..1000 {
+            assert!(meter.try_continue());
+            meter.record_event();
+        }
+
+        let stats = meter.stats();
+        assert_eq!(stats.iterations, 1);
+        assert_eq!(stats.exhausted_iterations, 0);
+        assert_eq!(stats.max_events, 1000);
+    }
+
+    #[test]
+    fn max_events() {
+        let mut meter = DispatchMeter::new(DispatchBudget::default().with_max_events(2));
+        meter.start_iteration();
+        assert!(meter.try_continue());
+        meter.record_event();
+        assert!(meter.try_continue());
+        meter.record_event();
+        assert!(!meter.try_continue());
+        meter.record_deferred();
+
+        // The exhaustion is counted once per iteration.
+        assert!(!meter.try_continue());
+        assert_eq!(meter.stats().exhausted_iterations, 1);
+        assert_eq!(meter.stats().deferred_events, 1);
+
+        meter.start_iteration();
+        assert!(meter.try_continue());
+        meter.record_event();
+
+        let stats = meter.stats();
+        assert_eq!(stats.iterations, 2);
+        assert_eq!(stats.exhausted_iterations, 1);
+        assert_eq!(stats.max_events, 2);
+    }
+
+    #[test]
+    fn max_time() {
+        let budget = DispatchBudget::default().with_max_time(Duration::ZERO);
+        let mut meter = DispatchMeter::new(budget);
+        meter.start_iteration();
+
+        // The time only counts from the first event.
+        assert!(meter.try_continue());
+        meter.record_event();
+        assert!(!meter.try_continue());
+        assert_eq!(meter.stats().exhausted_iterations, 1);
+    }
+
+    #[test]
+    fn set_budget() {
+        let mut meter = DispatchMeter::default();
+        let budget = DispatchBudget::default().with_max_events(1);
+        meter.set_budget(budget);
+        assert_eq!(meter.budget(), budget);
+
+        meter.start_iteration();
+        meter.record_event();
+        assert!(!meter.try_continue());
+    }
+}
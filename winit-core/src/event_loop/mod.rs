@@ -1,49 +1,441 @@
+use std::error::Error;
+use std::{fmt, io};
+#[cfg(unix)]
+use std::os::fd::RawFd;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use raw_window_handle::HasDisplayHandle;
 use raw_window_handle_05::HasRawDisplayHandle as HasRawDisplayHandle05;
+use smallvec::SmallVec;
 
 use crate::application::Application;
-use crate::monitor::{Monitor, MonitorId};
-use crate::window::{Window, WindowAttributes, WindowId};
+use crate::clipboard::Clipboard;
+use crate::dpi::{PhysicalPosition, RoundingPolicy};
+use crate::input::filter::{EventFilter, EventFilterId};
+use crate::input::pointer::MotionCompression;
+use crate::input::{DeviceId, DeviceInfo};
+use crate::monitor::{Monitor, MonitorId, MonitorSnapshot};
+use crate::window::{
+    CreateWindowError, GroupId, RoleAttributes, Surface, Theme, WindowError, WindowId,
+};
 
+use self::budget::{DispatchBudget, DispatchStats};
 use self::proxy::EventLoopProxy;
 
+pub mod budget;
 pub mod proxy;
 
+/// Identifier of the timer provided by the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerId(pub u64);
+
+/// Identifier of the file descriptor registered by the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FdToken(pub u64);
+
+bitflags::bitflags! {
+    /// The readiness of the file descriptor to wait for.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct Interest: u8 {
+        const READABLE = 1 << 0;
+        const WRITABLE = 1 << 1;
+    }
+}
+
+bitflags::bitflags! {
+    /// The readiness of the file descriptor.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct Readiness: u8 {
+        const READABLE = 1 << 0;
+        const WRITABLE = 1 << 1;
+        /// The error condition on the file descriptor.
+        const ERROR = 1 << 2;
+    }
+}
+
+/// Identifier of the path watched by the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WatchId(pub u64);
+
+/// The change of the path watched with [`EventLoopHandle::watch_path`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    /// The changed file, either the watched path itself or the entry of the
+    /// watched directory.
+    pub path: PathBuf,
+
+    pub kind: FileChangeKind,
+}
+
+/// The kind of the [`FileChange`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FileChangeKind {
+    /// The file was written and closed.
+    Modified,
+
+    /// The file was created or moved in.
+    Created,
+
+    /// The file was removed or moved out.
+    Removed,
+}
+
+/// Identifier of the query issued with [`EventLoopHandle::request`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RequestId(pub u64);
+
+/// The query which needs a roundtrip to the system to get the answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Query {
+    /// The human-readable description of the monitor.
+    MonitorDescription(MonitorId),
+
+    /// The theme preferred by the system.
+    Theme,
+}
+
+/// The answer to the [`Query`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum QueryResult {
+    /// The answer to [`Query::MonitorDescription`], `None` if the monitor
+    /// doesn't exist or has no description.
+    MonitorDescription(Option<String>),
+
+    /// The answer to [`Query::Theme`], `None` if there's no preference.
+    Theme(Option<Theme>),
+
+    /// The backend doesn't support the query.
+    Unsupported,
+}
+
+/// The request to activate the running instance of the application.
+///
+/// Sent by the other instance of the same application when it was started
+/// while this one is running.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActivationRequest {
+    /// The command line arguments of the other instance, without the program
+    /// name.
+    pub arguments: Vec<String>,
+
+    /// The working directory of the other instance.
+    pub working_directory: Option<PathBuf>,
+
+    /// The token to activate the window with, if the other instance was
+    /// given one.
+    pub activation_token: Option<String>,
+}
+
+/// The time it took to reach the startup milestones, measured from the
+/// creation of the event loop.
+///
+/// The milestones not reached yet, or not tracked by the backend, are `None`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StartupTimings {
+    /// The connection to the display server was established.
+    pub connected: Option<Duration>,
+
+    /// The globals of the display server were bound.
+    pub globals_bound: Option<Duration>,
+
+    /// The first window got its initial configure.
+    pub first_configure: Option<Duration>,
+
+    /// The first frame of any window was presented.
+    pub first_frame_presented: Option<Duration>,
+}
+
+/// The connection to the system's display server was lost.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackendDisconnected {
+    /// Human-readable reason of the disconnect.
+    pub reason: String,
+
+    /// The protocol error which caused the disconnect, if any.
+    pub protocol_error: Option<ProtocolError>,
+}
+
+/// The error in the protocol used to talk to the display server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtocolError {
+    /// The error code.
+    pub code: u32,
+
+    /// The id of the object the error occurred on.
+    pub object_id: u32,
+
+    /// The interface of the object the error occurred on.
+    pub object_interface: String,
+
+    /// The message sent along with the error.
+    pub message: String,
+}
+
+/// The error of the [`EventLoopRequests::new`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EventLoopError {
+    /// The backend isn't supported on the running system.
+    Unsupported,
+
+    /// The backend failed to connect to the display server or to set up the
+    /// event loop.
+    Os(String),
+}
+
+impl fmt::Display for EventLoopError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported => f.write_str("the backend is not supported"),
+            Self::Os(err) => write!(f, "failed to create the event loop: {err}"),
+        }
+    }
+}
+
+impl Error for EventLoopError {}
+
 /// API to run the event loop.
 pub struct EventLoop {}
 
-pub trait EventLoopRequests<T>: HasDisplayHandle + HasRawDisplayHandle05 + Sized
-where
-    T: Application + 'static,
-{
-    fn new() -> Result<Self, ()>;
+/// The event loop of the particular backend.
+///
+/// The trait is object safe, so the backend could be picked at runtime.
+pub trait EventLoopRequests: HasDisplayHandle + HasRawDisplayHandle05 {
+    fn new() -> Result<Self, EventLoopError>
+    where
+        Self: Sized;
 
     /// Run the event loop.
-    fn run(self, state: T);
+    fn run(self: Box<Self>, app: Box<dyn Application>);
 
     /// Get the proxy to wakeup the event loop.
     fn proxy(&self) -> Arc<dyn EventLoopProxy>;
 }
 
+/// Create the event loop with the `backend_factory` and run the `app` on it.
+///
+/// ```no_run
+/// # use winit_core::application::Application;
+/// # use winit_core::event_loop::{EventLoopError, EventLoopRequests};
+/// # fn backend() -> Result<Box<dyn EventLoopRequests>, EventLoopError> { unimplemented!() }
+/// # fn app() -> Box<dyn Application> { unimplemented!() }
+/// winit_core::run(backend, app()).unwrap();
+/// ```
+pub fn run<F>(backend_factory: F, app: Box<dyn Application>) -> Result<(), EventLoopError>
+where
+    F: FnOnce() -> Result<Box<dyn EventLoopRequests>, EventLoopError>,
+{
+    let event_loop = backend_factory()?;
+    event_loop.run(app);
+    Ok(())
+}
+
 /// Handle for the event loop.
 pub trait EventLoopHandle: HasDisplayHandle {
     /// Get the proxy to wakeup the event loop.
     fn proxy(&self) -> Arc<dyn EventLoopProxy>;
 
-    /// Request to create a window.
-    fn create_window(&mut self, attributes: &WindowAttributes) -> Result<(), ()>;
+    /// Request to create a window with the role defined by the `attributes`.
+    ///
+    /// Returns `Err` when the backend doesn't support the requested role, or
+    /// when the attributes are invalid, see [`RoleAttributes::validate`].
+    fn create_window(&mut self, attributes: RoleAttributes) -> Result<(), CreateWindowError>;
 
     fn num_windows(&self) -> usize;
 
-    fn get_window(&self, window_id: WindowId) -> Option<&dyn Window>;
+    /// Get the window surface, use [`Surface::as_toplevel`] to access the
+    /// role specific requests.
+    ///
+    /// Using the id of the destroyed window panics in the debug builds, use
+    /// the [`EventLoopHandle::try_get_window`] when the window could be gone.
+    fn get_window(&self, window_id: WindowId) -> Option<&dyn Surface>;
+
+    fn get_window_mut(&mut self, window_id: WindowId) -> Option<&mut dyn Surface>;
+
+    /// Get the window surface, telling why it's not available.
+    fn try_get_window(&self, window_id: WindowId) -> Result<&dyn Surface, WindowError> {
+        self.get_window(window_id).ok_or(WindowError::Unknown)
+    }
+
+    fn try_get_window_mut(&mut self, window_id: WindowId) -> Result<&mut dyn Surface, WindowError> {
+        self.get_window_mut(window_id).ok_or(WindowError::Unknown)
+    }
 
-    fn get_window_mut(&mut self, window_id: WindowId) -> Option<&mut dyn Window>;
+    /// The windows in the `group`, starting with the main window of the
+    /// group.
+    ///
+    /// See [`Toplevel::set_group`].
+    ///
+    /// [`Toplevel::set_group`]: crate::window::Toplevel::set_group
+    fn group_members(&self, group: GroupId) -> SmallVec<[WindowId; 4]>;
 
     fn get_monitor(&self, monitor_id: MonitorId) -> Option<&dyn Monitor>;
 
-    fn monitors(&self) -> Vec<&dyn Monitor>;
+    /// All the monitors, stored inline for the usual setups to not allocate.
+    fn monitors(&self) -> SmallVec<[&dyn Monitor; 4]>;
+
+    /// The counter of the changes to the monitors.
+    ///
+    /// The counter is bumped each time a monitor is added, removed or changed,
+    /// so the cached [`MonitorSnapshot`] could be checked cheaply.
+    fn monitor_generation(&self) -> u64;
+
+    /// Take the owned snapshot of all the monitors.
+    fn monitor_snapshot(&self) -> MonitorSnapshot {
+        MonitorSnapshot::new(self.monitor_generation(), self.monitors())
+    }
+
+    /// Get the information about the input device.
+    ///
+    /// Returns `None` if the device doesn't exist anymore.
+    fn device_info(&self, device_id: DeviceId) -> Option<DeviceInfo>;
+
+    /// Set a repeating timer firing every `interval`.
+    ///
+    /// The [`Application::timer_fired`] is called with the given `timer_id`
+    /// each time the timer fires. Setting the timer with the same `timer_id`
+    /// again replaces the previous one.
+    fn set_timer(&mut self, interval: Duration, timer_id: TimerId);
+
+    /// Cancel the timer with the given `timer_id`.
+    fn cancel_timer(&mut self, timer_id: TimerId);
+
+    /// Wait for the `fd` readiness on the event loop.
+    ///
+    /// The [`Application::fd_ready`] is called with the given `token` while
+    /// the `fd` is ready. Registering the same `token` again replaces the
+    /// previous registration.
+    ///
+    /// Returns `Err` when the `fd` can't be polled, or the backend doesn't
+    /// support waiting for the file descriptors.
+    ///
+    /// # Safety
+    ///
+    /// The `fd` must stay open until it's unregistered with
+    /// [`EventLoopHandle::unregister_fd`].
+    #[cfg(unix)]
+    unsafe fn register_fd(
+        &mut self,
+        fd: RawFd,
+        interest: Interest,
+        token: FdToken,
+    ) -> io::Result<()>;
+
+    /// Stop waiting for the file descriptor registered with the `token`.
+    #[cfg(unix)]
+    fn unregister_fd(&mut self, token: FdToken);
+
+    /// Watch the file or directory at `path` for changes.
+    ///
+    /// The [`Application::file_changed`] is called with the given `watch_id`
+    /// on the event loop thread, useful to hot-reload the shaders or themes.
+    /// Watching with the same `watch_id` again replaces the previous watch.
+    ///
+    /// The editors saving the files atomically replace them, so watch the
+    /// parent directory to keep getting the changes of such files.
+    ///
+    /// Returns `Err` when the path can't be watched or the watching isn't
+    /// supported.
+    fn watch_path(&mut self, path: &Path, watch_id: WatchId) -> io::Result<()> {
+        let _ = path;
+        let _ = watch_id;
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    /// Stop watching the path watched with the `watch_id`.
+    fn unwatch_path(&mut self, watch_id: WatchId) {
+        let _ = watch_id;
+    }
+
+    /// Issue the `query` without blocking the event loop.
+    ///
+    /// The answer is delivered with [`Application::query_complete`] with the
+    /// returned [`RequestId`].
+    fn request(&mut self, query: Query) -> RequestId;
+
+    /// The clipboard of the seat the user interacted with last.
+    ///
+    /// Returns `None` when the system doesn't provide the clipboard.
+    fn clipboard(&mut self) -> Option<&mut dyn Clipboard>;
+
+    /// Set how the pointer motion is delivered, the default is
+    /// [`MotionCompression::PerFrame`].
+    fn set_motion_compression(&mut self, compression: MotionCompression);
+
+    /// Limit the events delivered in one iteration of the event loop, the
+    /// default is [`DispatchBudget::UNLIMITED`].
+    ///
+    /// The events postponed once the budget is spent depend on the backend,
+    /// the ones which could be merged, like the pointer motion, are the first
+    /// to go.
+    fn set_dispatch_budget(&mut self, budget: DispatchBudget) {
+        let _ = budget;
+    }
+
+    /// The metrics of the event delivery, to tune the budget with.
+    fn dispatch_stats(&self) -> DispatchStats {
+        DispatchStats::default()
+    }
+
+    /// Add the `filter` to run before the input events reach the
+    /// application.
+    ///
+    /// The filters run starting with the most recently pushed one, the filter
+    /// consuming the event stops the chain. The filters pushed from within the
+    /// filters start applying to the next event.
+    fn push_event_filter(&mut self, filter: Box<dyn EventFilter>) -> EventFilterId;
+
+    /// Remove the filter added with the [`EventLoopHandle::push_event_filter`].
+    fn remove_event_filter(&mut self, filter_id: EventFilterId);
+
+    /// For how long the user is idle.
+    ///
+    /// Returns `None` when the user is active, or when the idle detection is
+    /// disabled or not supported.
+    fn idle_since(&self) -> Option<Duration>;
+
+    /// Set the inactivity after which the user is considered idle, the
+    /// changes are delivered with [`Application::user_idle`].
+    ///
+    /// `None` disables the idle detection, which is the default.
+    fn set_idle_threshold(&mut self, threshold: Option<Duration>);
+
+    /// The time it took to reach the startup milestones.
+    fn startup_timings(&self) -> StartupTimings;
+
+    /// The rounding used to convert the logical sizes of the windows into the
+    /// physical ones.
+    ///
+    /// The default depends on the backend, on Wayland it's
+    /// [`Rounding::Round`].
+    ///
+    /// [`Rounding::Round`]: crate::dpi::Rounding::Round
+    fn rounding_policy(&self) -> RoundingPolicy;
+
+    /// Set the rounding used for the windows, it applies to the existing
+    /// windows starting with their next resize.
+    fn set_rounding_policy(&mut self, policy: RoundingPolicy);
+
+    /// All the positions the pointer went through for the current
+    /// [`PointerInputHandler::pointer_moved`], the last one is the position
+    /// of the event.
+    ///
+    /// Drawing applications could use it to get the smooth strokes when the
+    /// motion is compressed. Empty outside of the motion handling.
+    ///
+    /// [`PointerInputHandler::pointer_moved`]: crate::input::pointer::PointerInputHandler::pointer_moved
+    fn coalesced_pointer_positions(&self) -> &[PhysicalPosition<f64>];
+
+    /// Write the state of the backend as a JSON document into the `writer`.
+    ///
+    /// The document is meant to be attached to the bug reports, so the user
+    /// data, like the window titles, is redacted. The format is not stable.
+    fn dump_state(&self, writer: &mut dyn io::Write) -> io::Result<()>;
 
     fn exit(&mut self);
 }
@@ -2,4 +2,14 @@
 pub trait EventLoopProxy: Send + Sync {
     /// Wakeup the event loop.
     fn wakeup(&self);
+
+    /// Ask the event loop to exit, from any thread.
+    ///
+    /// The request is delivered on the event loop thread with the
+    /// [`Application::exit_requested`], which could cancel it, for example to
+    /// ask the user to save their work first. Usable for the graceful
+    /// shutdown from the signal handlers or the background threads.
+    ///
+    /// [`Application::exit_requested`]: crate::application::Application::exit_requested
+    fn request_exit(&self);
 }
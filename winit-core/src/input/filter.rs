@@ -0,0 +1,65 @@
+//! Interception of the input events before they reach the application.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+
+use crate::dpi::PhysicalPosition;
+use crate::event_loop::EventLoopHandle;
+use crate::input::keyboard::KeyEvent;
+use crate::input::pointer::{PointerButtonEvent, PointerMotion, PointerScroll};
+use crate::input::DeviceId;
+use crate::window::WindowId;
+
+/// The input event passed through the [`EventFilter`]s.
+///
+/// Each variant is delivered with the matching method of the input handlers,
+/// like the [`PointerInputHandler::pointer_moved`] for the
+/// [`InputEvent::PointerMoved`].
+///
+/// [`PointerInputHandler::pointer_moved`]: crate::input::pointer::PointerInputHandler::pointer_moved
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum InputEvent {
+    PointerEntered { position: PhysicalPosition<f64>, device_id: DeviceId },
+    PointerLeft { device_id: DeviceId },
+    PointerMoved(PointerMotion),
+    PointerScrolled(PointerScroll),
+    PointerButton(PointerButtonEvent),
+    Key(KeyEvent),
+    Text(SmolStr),
+}
+
+/// What happens to the event after the [`EventFilter::filter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FilterAction {
+    /// Pass the event, possibly modified, to the next filter and then to the
+    /// application.
+    Pass,
+
+    /// Drop the event, neither the rest of the filters nor the application
+    /// get it.
+    Consume,
+}
+
+/// The filter observing, modifying or consuming the input events before they
+/// reach the application, for example for the global hotkeys, debug overlays
+/// or input recording.
+///
+/// The filters are added with the [`EventLoopHandle::push_event_filter`] and
+/// run starting with the most recently pushed one.
+pub trait EventFilter {
+    fn filter(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        event: &mut InputEvent,
+    ) -> FilterAction;
+}
+
+/// Identifier of the [`EventFilter`] to remove it with the
+/// [`EventLoopHandle::remove_event_filter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EventFilterId(pub u64);
@@ -1 +1,80 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
 
+use crate::application::Application;
+use crate::event_loop::EventLoopHandle;
+use crate::input::ElementState;
+use crate::window::WindowId;
+
+pub trait KeyboardInputHandler: Application {
+    /// The key was pressed or released while the window had focus.
+    fn key_input(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        event: KeyEvent,
+    );
+
+    /// The text was committed to the window.
+    ///
+    /// Unlike the [`KeyEvent::text`] the text is delivered once the input is
+    /// complete, like after the compose sequence or the IME commit, thus this
+    /// should be used for the text editing.
+    fn text_input(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        text: SmolStr,
+    ) {
+        let _ = loop_handle;
+        let _ = window_id;
+        let _ = text;
+    }
+}
+
+/// The key press or release.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct KeyEvent {
+    /// The platform specific code of the physical key, which doesn't depend
+    /// on the layout.
+    pub physical_key: u32,
+
+    /// The key with the current layout and modifiers applied.
+    pub logical_key: Key,
+
+    /// The text produced by the key press.
+    ///
+    /// The control modifier is not applied to it, so `Ctrl+A` produces `a`.
+    /// Always `None` for the releases and the keys in the middle of the
+    /// compose sequence.
+    pub text: Option<String>,
+
+    /// The text produced by the key press with all the modifiers applied,
+    /// like `\u{1}` for `Ctrl+A`.
+    ///
+    /// Always `None` for the releases.
+    pub text_with_all_modifiers: Option<String>,
+
+    /// The key without any modifiers applied, useful to match the shortcuts
+    /// the same way regardless of the active modifiers, like `Ctrl+Shift+Z`.
+    pub key_without_modifiers: Key,
+
+    pub state: ElementState,
+
+    /// Whether the event is a result of the key repeat.
+    pub repeat: bool,
+}
+
+/// The meaning of the key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Key {
+    /// The key producing the text.
+    Character(String),
+
+    /// The key which winit doesn't know how to map, contains the platform
+    /// specific value, like keysym on Linux.
+    Unidentified(u32),
+}
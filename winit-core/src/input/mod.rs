@@ -1,4 +1,49 @@
+pub mod filter;
 pub mod ime;
 pub mod keyboard;
 pub mod pointer;
 pub mod touch;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The state of the key or button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ElementState {
+    Pressed,
+    Released,
+}
+
+/// Identifier of the input device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DeviceId(pub u128);
+
+/// Information about the input device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DeviceInfo {
+    /// Human-readable name of the device.
+    pub name: Option<String>,
+
+    /// The USB vendor id of the device, if known.
+    pub vendor_id: Option<u16>,
+
+    /// The USB product id of the device, if known.
+    pub product_id: Option<u16>,
+
+    /// The kinds of input the device provides.
+    pub capabilities: DeviceCapabilities,
+}
+
+bitflags::bitflags! {
+    /// The kinds of input provided by the device.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct DeviceCapabilities: u32 {
+        const POINTER  = 1 << 0;
+        const KEYBOARD  = 1 << 1;
+        const TOUCH  = 1 << 2;
+    }
+}
@@ -1 +1,167 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
+use crate::application::Application;
+use crate::dpi::PhysicalPosition;
+use crate::event_loop::EventLoopHandle;
+use crate::input::{DeviceId, ElementState};
+use crate::window::WindowId;
+
+pub trait PointerInputHandler: Application {
+    /// The pointer has moved over the window.
+    ///
+    /// When the motion is compressed, use
+    /// [`EventLoopHandle::coalesced_pointer_positions`] to get all the
+    /// positions the pointer went through.
+    fn pointer_moved(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        event: PointerMotion,
+    );
+
+    /// The pointer of the `device_id` has entered the window at the
+    /// `position`.
+    ///
+    /// Every enter is paired with the [`PointerInputHandler::pointer_left`]
+    /// for the same device, so the hover state could be tracked without
+    /// looking at the motion.
+    fn pointer_entered(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        position: PhysicalPosition<f64>,
+        device_id: DeviceId,
+    ) {
+        let _ = loop_handle;
+        let _ = window_id;
+        let _ = position;
+        let _ = device_id;
+    }
+
+    /// The pointer of the `device_id` has left the window.
+    fn pointer_left(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        device_id: DeviceId,
+    ) {
+        let _ = loop_handle;
+        let _ = window_id;
+        let _ = device_id;
+    }
+
+    /// The content under the pointer was scrolled.
+    fn pointer_scrolled(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        scroll: PointerScroll,
+    ) {
+        let _ = loop_handle;
+        let _ = window_id;
+        let _ = scroll;
+    }
+
+    /// The button of the pointer was pressed or released over the window.
+    ///
+    /// The release is delivered to the window the button was pressed in,
+    /// even when the pointer has left it in between.
+    fn pointer_button(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        event: PointerButtonEvent,
+    ) {
+        let _ = loop_handle;
+        let _ = window_id;
+        let _ = event;
+    }
+}
+
+/// The motion of the pointer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PointerMotion {
+    /// The position of the pointer relative to the top-left corner of the
+    /// window content.
+    pub position: PhysicalPosition<f64>,
+
+    /// The distance the pointer has traveled since the previous
+    /// [`PointerInputHandler::pointer_moved`], zero on the first motion after
+    /// entering the window.
+    pub delta: PhysicalPosition<f64>,
+}
+
+/// The press or the release of the pointer button.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PointerButtonEvent {
+    pub button: PointerButton,
+    pub state: ElementState,
+
+    /// The position of the pointer relative to the top-left corner of the
+    /// window content.
+    pub position: PhysicalPosition<f64>,
+
+    pub device_id: DeviceId,
+}
+
+/// The button of the pointer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PointerButton {
+    Left,
+    Right,
+    Middle,
+    Back,
+    Forward,
+
+    /// The other button, with the platform-specific code.
+    Other(u16),
+}
+
+/// The scroll of the content under the pointer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PointerScroll {
+    pub delta: ScrollDelta,
+    pub device_id: DeviceId,
+}
+
+/// The amount of the scroll.
+///
+/// The positive values scroll towards the right and the bottom of the
+/// content.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ScrollDelta {
+    /// The scroll by the mouse wheel.
+    Wheel {
+        /// The whole clicks of the wheel, accumulated from the
+        /// [`ScrollDelta::Wheel::value120`], for the scrolling by the notches.
+        clicks: PhysicalPosition<i32>,
+
+        /// The scroll in the 120ths of the click, the free-spinning and the
+        /// high resolution wheels report the fractions of the click, for the
+        /// smooth scrolling.
+        value120: PhysicalPosition<i32>,
+    },
+
+    /// The scroll by the touchpad or the other continuous source, in
+    /// pixels.
+    Pixels(PhysicalPosition<f64>),
+}
+
+/// How the pointer motion is delivered to the application.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MotionCompression {
+    /// Deliver every motion event the system sends.
+    None,
+
+    /// Deliver one motion per event loop iteration, with the delta
+    /// accumulated from all the compressed motions.
+    #[default]
+    PerFrame,
+}
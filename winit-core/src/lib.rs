@@ -1,9 +1,25 @@
 //! The next generation of the winit.
 
 pub mod application;
+pub mod builder;
+pub mod clipboard;
+pub mod cursor;
+pub mod diagnostics;
+pub mod dnd;
 pub mod dpi;
+pub mod env;
 pub mod event_loop;
 pub mod monitor;
 pub mod input;
+pub mod patterns;
+pub mod prelude;
 pub mod view;
 pub mod window;
+
+pub use event_loop::run;
+
+/// The crate of the [`SmallVec`] returned by the [`EventLoopHandle`].
+///
+/// [`SmallVec`]: smallvec::SmallVec
+/// [`EventLoopHandle`]: event_loop::EventLoopHandle
+pub use smallvec;
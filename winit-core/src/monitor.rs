@@ -1,16 +1,59 @@
+use std::sync::Arc;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use crate::dpi::{PhysicalPosition, PhysicalSize};
+use crate::event_loop::EventLoopHandle;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MonitorId(pub u128);
 
+/// The adaptive sync state of the monitor, also known as the variable
+/// refresh rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AdaptiveSync {
+    /// The monitor doesn't support adaptive sync.
+    Unsupported,
+
+    /// The monitor could use adaptive sync, but it's disabled.
+    Disabled,
+
+    /// The refresh of the monitor follows the presented frames.
+    Enabled,
+}
+
+/// The power state of the monitor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PowerState {
+    /// The monitor is displaying the content.
+    On,
+
+    /// The monitor is turned off, for example by the idle timeout.
+    Off,
+}
+
 pub trait Monitor {
     /// Return the given monitor id.
+    ///
+    /// The backends try to keep the id the same when the same monitor is
+    /// reconnected, however that is not guaranteed.
     fn id(&self) -> MonitorId;
     /// Returns a human-readable name of the monitor.
     ///
     /// Returns `None` if the monitor doesn't exist anymore.
     fn name(&self) -> Option<String>;
 
+    /// Returns the name of the connector the monitor is plugged into, like
+    /// `DP-1`.
+    ///
+    /// Unlike the [`Monitor::id`], the name stays the same when the monitor
+    /// is reconnected, thus it could be used to persist the window placement.
+    fn connection_name(&self) -> Option<String>;
+
     /// Returns the monitor's resolution.
     fn size(&self) -> PhysicalSize<u32>;
 
@@ -29,4 +72,137 @@ pub trait Monitor {
     fn refresh_rate_millihertz(&self) -> Option<u32>;
 
     fn scale_factor(&self) -> f64;
+    /// The adaptive sync state of the monitor.
+    ///
+    /// Returns `None` when the system doesn't reveal it. The changes are
+    /// delivered with [`Application::monitor_changed`].
+    ///
+    /// [`Application::monitor_changed`]: crate::application::Application::monitor_changed
+    fn adaptive_sync(&self) -> Option<AdaptiveSync> {
+        None
+    }
+
+    /// The power state of the monitor.
+    ///
+    /// Returns `None` when the system doesn't reveal it. The changes are
+    /// delivered with [`Application::monitor_changed`].
+    ///
+    /// [`Application::monitor_changed`]: crate::application::Application::monitor_changed
+    fn power_state(&self) -> Option<PowerState> {
+        None
+    }
+}
+
+/// The owned copy of the monitor state, see [`MonitorSnapshot`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MonitorInfo {
+    pub id: MonitorId,
+    pub name: Option<String>,
+    pub connection_name: Option<String>,
+    pub size: PhysicalSize<u32>,
+    pub position: PhysicalPosition<i32>,
+    pub refresh_rate_millihertz: Option<u32>,
+    pub scale_factor: f64,
+    pub adaptive_sync: Option<AdaptiveSync>,
+    pub power_state: Option<PowerState>,
+}
+
+impl MonitorInfo {
+    pub fn new(monitor: &dyn Monitor) -> Self {
+        Self {
+            id: monitor.id(),
+            name: monitor.name(),
+            connection_name: monitor.connection_name(),
+            size: monitor.size(),
+            position: monitor.position(),
+            refresh_rate_millihertz: monitor.refresh_rate_millihertz(),
+            scale_factor: monitor.scale_factor(),
+            adaptive_sync: monitor.adaptive_sync(),
+            power_state: monitor.power_state(),
+        }
+    }
+}
+
+impl Monitor for MonitorInfo {
+    fn id(&self) -> MonitorId {
+        self.id
+    }
+
+    fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    fn connection_name(&self) -> Option<String> {
+        self.connection_name.clone()
+    }
+
+    fn size(&self) -> PhysicalSize<u32> {
+        self.size
+    }
+
+    fn position(&self) -> PhysicalPosition<i32> {
+        self.position
+    }
+
+    fn refresh_rate_millihertz(&self) -> Option<u32> {
+        self.refresh_rate_millihertz
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    fn adaptive_sync(&self) -> Option<AdaptiveSync> {
+        self.adaptive_sync
+    }
+
+    fn power_state(&self) -> Option<PowerState> {
+        self.power_state
+    }
+}
+
+/// The owned list of the monitors, which doesn't borrow the event loop.
+///
+/// The snapshot is cheap to clone, so the UI could keep it and retake it only
+/// when the [`EventLoopHandle::monitor_generation`] changes.
+///
+/// ```no_run
+/// # use winit_core::event_loop::EventLoopHandle;
+/// # use winit_core::monitor::MonitorSnapshot;
+/// fn monitors(cache: &mut MonitorSnapshot, loop_handle: &dyn EventLoopHandle) {
+///     if cache.is_outdated(loop_handle) {
+///         *cache = loop_handle.monitor_snapshot();
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MonitorSnapshot {
+    generation: u64,
+    monitors: Arc<[MonitorInfo]>,
+}
+
+impl MonitorSnapshot {
+    pub fn new<'a>(generation: u64, monitors: impl IntoIterator<Item = &'a dyn Monitor>) -> Self {
+        let monitors = monitors.into_iter().map(MonitorInfo::new).collect();
+        Self { generation, monitors }
+    }
+
+    /// The [`EventLoopHandle::monitor_generation`] the snapshot was taken at.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn monitors(&self) -> &[MonitorInfo] {
+        &self.monitors
+    }
+
+    pub fn get(&self, monitor_id: MonitorId) -> Option<&MonitorInfo> {
+        self.monitors.iter().find(|monitor| monitor.id == monitor_id)
+    }
+
+    /// Whether the monitors changed since the snapshot was taken.
+    pub fn is_outdated(&self, loop_handle: &dyn EventLoopHandle) -> bool {
+        self.generation != loop_handle.monitor_generation()
+    }
 }
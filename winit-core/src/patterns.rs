@@ -0,0 +1,385 @@
+//! The canonical patterns of the applications.
+//!
+//! Each pattern is the complete [`Application`] doing one common thing and
+//! exiting the event loop once done, so it doubles as the executable
+//! documentation of the contract every backend upholds. The conformance suite
+//! runs them on the backends and checks their [`Outcome`].
+//!
+//! ```no_run
+//! # use winit_core::event_loop::{EventLoopError, EventLoopRequests};
+//! # fn backend() -> Result<Box<dyn EventLoopRequests>, EventLoopError> { unimplemented!() }
+//! use winit_core::patterns::{OneShot, Outcome};
+//!
+//! let outcome = Outcome::default();
+//! winit_core::run(backend, Box::new(OneShot::new(outcome.clone()))).unwrap();
+//! assert_eq!(outcome.get(), Some(Ok(())));
+//! ```
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::thread;
+use std::time::Duration;
+
+use crate::application::{Application, ApplicationWindow, StartCause};
+use crate::dpi::PhysicalSize;
+use crate::event_loop::{EventLoopHandle, TimerId};
+use crate::window::{ToplevelAttributes, WindowId};
+
+/// The time the pattern has to finish.
+const PATTERN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The timer bailing out of the stuck pattern.
+const TIMEOUT_TIMER: TimerId = TimerId(u64::MAX);
+
+/// The number of windows created by the [`MultiWindow`].
+const WINDOWS: usize = 2;
+
+/// The number of frames drawn by the [`ContinuousRedraw`].
+const FRAMES: u32 = 10;
+
+/// The outcome of the pattern, shared with the application running it.
+#[derive(Debug, Clone, Default)]
+pub struct Outcome(Rc<RefCell<Option<Result<(), String>>>>);
+
+impl Outcome {
+    /// The result of the pattern, `None` when it didn't finish.
+    pub fn get(&self) -> Option<Result<(), String>> {
+        self.0.borrow().clone()
+    }
+
+    /// Set up the timeout of the pattern.
+    fn start(&self, loop_handle: &mut dyn EventLoopHandle) {
+        loop_handle.set_timer(PATTERN_TIMEOUT, TIMEOUT_TIMER);
+    }
+
+    /// Finish with the `result` and exit, only the first result is kept.
+    fn finish(&self, loop_handle: &mut dyn EventLoopHandle, result: Result<(), String>) {
+        self.0.borrow_mut().get_or_insert(result);
+        loop_handle.cancel_timer(TIMEOUT_TIMER);
+        loop_handle.exit();
+    }
+
+    fn timer_fired(&self, loop_handle: &mut dyn EventLoopHandle, timer_id: TimerId) {
+        if timer_id == TIMEOUT_TIMER {
+            self.finish(loop_handle, Err(String::from("the pattern didn't finish in time")));
+        }
+    }
+
+    fn create_window(&self, loop_handle: &mut dyn EventLoopHandle, title: &str) {
+        let attributes = ToplevelAttributes::default().with_title(title);
+        if loop_handle.create_window(attributes.into()).is_err() {
+            self.finish(loop_handle, Err(String::from("failed to create the toplevel window")));
+        }
+    }
+}
+
+/// Create the window, draw it once and exit.
+///
+/// The window is usable from the [`created`] on, and the first redraw
+/// is delivered without requesting it.
+///
+/// [`created`]: ApplicationWindow::created
+#[derive(Debug)]
+pub struct OneShot {
+    outcome: Outcome,
+    window_id: Option<WindowId>,
+}
+
+impl OneShot {
+    pub fn new(outcome: Outcome) -> Self {
+        Self { outcome, window_id: None }
+    }
+}
+
+impl Application for OneShot {
+    fn user_wakeup(&mut self, _: &mut dyn EventLoopHandle) {}
+
+    fn new_events(&mut self, loop_handle: &mut dyn EventLoopHandle, start_cause: StartCause) {
+        if let StartCause::Init = start_cause {
+            self.outcome.start(loop_handle);
+            self.outcome.create_window(loop_handle, "one-shot");
+        }
+    }
+
+    fn about_to_wait(&mut self, _: &mut dyn EventLoopHandle) {}
+
+    fn loop_exiting(&mut self, _: &mut dyn EventLoopHandle) {}
+
+    fn timer_fired(&mut self, loop_handle: &mut dyn EventLoopHandle, timer_id: TimerId) {
+        self.outcome.timer_fired(loop_handle, timer_id);
+    }
+}
+
+impl ApplicationWindow for OneShot {
+    fn created(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        if loop_handle.get_window(window_id).is_none() {
+            let error = format!("{window_id:?} isn't available in the created");
+            return self.outcome.finish(loop_handle, Err(error));
+        }
+
+        self.window_id = Some(window_id);
+    }
+
+    fn resized(&mut self, _: &mut dyn EventLoopHandle, _: WindowId, _: PhysicalSize<u32>) {}
+
+    fn scale_factor_changed(&mut self, _: &mut dyn EventLoopHandle, _: WindowId, _: f64) {}
+
+    fn redraw_requested(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        let result = match self.window_id {
+            Some(created) if created == window_id => Ok(()),
+            _ => Err(format!("the redraw of {window_id:?} was delivered before the created")),
+        };
+
+        self.outcome.finish(loop_handle, result);
+    }
+
+    fn close_requested(&mut self, _: &mut dyn EventLoopHandle, _: WindowId) -> bool {
+        true
+    }
+}
+
+/// Create several windows and exit once each of them is drawn.
+///
+/// The windows get the distinct ids and are all alive at the same time.
+#[derive(Debug)]
+pub struct MultiWindow {
+    outcome: Outcome,
+    created: Vec<WindowId>,
+    drawn: Vec<WindowId>,
+}
+
+impl MultiWindow {
+    pub fn new(outcome: Outcome) -> Self {
+        Self { outcome, created: Vec::new(), drawn: Vec::new() }
+    }
+}
+
+impl Application for MultiWindow {
+    fn user_wakeup(&mut self, _: &mut dyn EventLoopHandle) {}
+
+    fn new_events(&mut self, loop_handle: &mut dyn EventLoopHandle, start_cause: StartCause) {
+        if let StartCause::Init = start_cause {
+            self.outcome.start(loop_handle);
+            for _ in 0..WINDOWS {
+                self.outcome.create_window(loop_handle, "multi-window");
+            }
+        }
+    }
+
+    fn about_to_wait(&mut self, _: &mut dyn EventLoopHandle) {}
+
+    fn loop_exiting(&mut self, _: &mut dyn EventLoopHandle) {}
+
+    fn timer_fired(&mut self, loop_handle: &mut dyn EventLoopHandle, timer_id: TimerId) {
+        self.outcome.timer_fired(loop_handle, timer_id);
+    }
+}
+
+impl ApplicationWindow for MultiWindow {
+    fn created(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        if self.created.contains(&window_id) {
+            let error = format!("{window_id:?} was created twice");
+            return self.outcome.finish(loop_handle, Err(error));
+        }
+
+        self.created.push(window_id);
+    }
+
+    fn resized(&mut self, _: &mut dyn EventLoopHandle, _: WindowId, _: PhysicalSize<u32>) {}
+
+    fn scale_factor_changed(&mut self, _: &mut dyn EventLoopHandle, _: WindowId, _: f64) {}
+
+    fn redraw_requested(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        if !self.drawn.contains(&window_id) {
+            self.drawn.push(window_id);
+        }
+
+        if self.drawn.len() < WINDOWS {
+            return;
+        }
+
+        let result = match loop_handle.num_windows() {
+            WINDOWS => Ok(()),
+            num_windows => Err(format!("{num_windows} windows are alive instead of {WINDOWS}")),
+        };
+        self.outcome.finish(loop_handle, result);
+    }
+
+    fn close_requested(&mut self, _: &mut dyn EventLoopHandle, _: WindowId) -> bool {
+        true
+    }
+}
+
+/// Draw only when the content changes, by requesting the redraw.
+///
+/// The requested redraw is delivered once the system is ready for the next
+/// frame, the application doesn't redraw otherwise.
+#[derive(Debug)]
+pub struct RedrawOnDemand {
+    outcome: Outcome,
+    requested: bool,
+}
+
+impl RedrawOnDemand {
+    pub fn new(outcome: Outcome) -> Self {
+        Self { outcome, requested: false }
+    }
+}
+
+impl Application for RedrawOnDemand {
+    fn user_wakeup(&mut self, _: &mut dyn EventLoopHandle) {}
+
+    fn new_events(&mut self, loop_handle: &mut dyn EventLoopHandle, start_cause: StartCause) {
+        if let StartCause::Init = start_cause {
+            self.outcome.start(loop_handle);
+            self.outcome.create_window(loop_handle, "redraw-on-demand");
+        }
+    }
+
+    fn about_to_wait(&mut self, _: &mut dyn EventLoopHandle) {}
+
+    fn loop_exiting(&mut self, _: &mut dyn EventLoopHandle) {}
+
+    fn timer_fired(&mut self, loop_handle: &mut dyn EventLoopHandle, timer_id: TimerId) {
+        self.outcome.timer_fired(loop_handle, timer_id);
+    }
+}
+
+impl ApplicationWindow for RedrawOnDemand {
+    fn created(&mut self, _: &mut dyn EventLoopHandle, _: WindowId) {}
+
+    fn resized(&mut self, _: &mut dyn EventLoopHandle, _: WindowId, _: PhysicalSize<u32>) {}
+
+    fn scale_factor_changed(&mut self, _: &mut dyn EventLoopHandle, _: WindowId, _: f64) {}
+
+    fn redraw_requested(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        if self.requested {
+            return self.outcome.finish(loop_handle, Ok(()));
+        }
+
+        // The content has changed after the initial redraw.
+        match loop_handle.get_window_mut(window_id) {
+            Some(window) => window.request_redraw(),
+            None => {
+                let error = format!("{window_id:?} isn't available in the redraw");
+                return self.outcome.finish(loop_handle, Err(error));
+            },
+        }
+
+        self.requested = true;
+    }
+
+    fn close_requested(&mut self, _: &mut dyn EventLoopHandle, _: WindowId) -> bool {
+        true
+    }
+}
+
+/// Draw continuously, like the games do, by requesting the next redraw from
+/// the current one.
+///
+/// The redraws are paced by the system, so the loop doesn't spin.
+#[derive(Debug)]
+pub struct ContinuousRedraw {
+    outcome: Outcome,
+    frames: u32,
+}
+
+impl ContinuousRedraw {
+    pub fn new(outcome: Outcome) -> Self {
+        Self { outcome, frames: 0 }
+    }
+}
+
+impl Application for ContinuousRedraw {
+    fn user_wakeup(&mut self, _: &mut dyn EventLoopHandle) {}
+
+    fn new_events(&mut self, loop_handle: &mut dyn EventLoopHandle, start_cause: StartCause) {
+        if let StartCause::Init = start_cause {
+            self.outcome.start(loop_handle);
+            self.outcome.create_window(loop_handle, "continuous-redraw");
+        }
+    }
+
+    fn about_to_wait(&mut self, _: &mut dyn EventLoopHandle) {}
+
+    fn loop_exiting(&mut self, _: &mut dyn EventLoopHandle) {}
+
+    fn timer_fired(&mut self, loop_handle: &mut dyn EventLoopHandle, timer_id: TimerId) {
+        self.outcome.timer_fired(loop_handle, timer_id);
+    }
+}
+
+impl ApplicationWindow for ContinuousRedraw {
+    fn created(&mut self, _: &mut dyn EventLoopHandle, _: WindowId) {}
+
+    fn resized(&mut self, _: &mut dyn EventLoopHandle, _: WindowId, _: PhysicalSize<u32>) {}
+
+    fn scale_factor_changed(&mut self, _: &mut dyn EventLoopHandle, _: WindowId, _: f64) {}
+
+    fn redraw_requested(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        self.frames += 1;
+        if self.frames == FRAMES {
+            return self.outcome.finish(loop_handle, Ok(()));
+        }
+
+        if let Some(window) = loop_handle.get_window_mut(window_id) {
+            window.request_redraw();
+        }
+    }
+
+    fn close_requested(&mut self, _: &mut dyn EventLoopHandle, _: WindowId) -> bool {
+        true
+    }
+}
+
+/// Wake the event loop up from the other thread with the proxy.
+///
+/// The [`Application::user_wakeup`] is delivered on the event loop thread,
+/// the data itself is passed with the channel of the choice.
+#[derive(Debug)]
+pub struct ProxyWakeup {
+    outcome: Outcome,
+}
+
+impl ProxyWakeup {
+    pub fn new(outcome: Outcome) -> Self {
+        Self { outcome }
+    }
+}
+
+impl Application for ProxyWakeup {
+    fn user_wakeup(&mut self, loop_handle: &mut dyn EventLoopHandle) {
+        self.outcome.finish(loop_handle, Ok(()));
+    }
+
+    fn new_events(&mut self, loop_handle: &mut dyn EventLoopHandle, start_cause: StartCause) {
+        if let StartCause::Init = start_cause {
+            self.outcome.start(loop_handle);
+
+            let proxy = loop_handle.proxy();
+            thread::spawn(move || proxy.wakeup());
+        }
+    }
+
+    fn about_to_wait(&mut self, _: &mut dyn EventLoopHandle) {}
+
+    fn loop_exiting(&mut self, _: &mut dyn EventLoopHandle) {}
+
+    fn timer_fired(&mut self, loop_handle: &mut dyn EventLoopHandle, timer_id: TimerId) {
+        self.outcome.timer_fired(loop_handle, timer_id);
+    }
+}
+
+impl ApplicationWindow for ProxyWakeup {
+    fn created(&mut self, _: &mut dyn EventLoopHandle, _: WindowId) {}
+
+    fn resized(&mut self, _: &mut dyn EventLoopHandle, _: WindowId, _: PhysicalSize<u32>) {}
+
+    fn scale_factor_changed(&mut self, _: &mut dyn EventLoopHandle, _: WindowId, _: f64) {}
+
+    fn redraw_requested(&mut self, _: &mut dyn EventLoopHandle, _: WindowId) {}
+
+    fn close_requested(&mut self, _: &mut dyn EventLoopHandle, _: WindowId) -> bool {
+        true
+    }
+}
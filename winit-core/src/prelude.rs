@@ -0,0 +1,11 @@
+//! The commonly used types, to glob import them.
+
+pub use crate::application::{Application, ApplicationWindow, StartCause};
+pub use crate::builder::ApplicationBuilder;
+pub use crate::cursor::CursorIcon;
+pub use crate::dpi::{
+    LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, Position, Size,
+};
+pub use crate::event_loop::{EventLoopHandle, EventLoopRequests};
+pub use crate::monitor::{Monitor, MonitorId};
+pub use crate::window::{Surface, Toplevel, ToplevelAttributes, WindowId};
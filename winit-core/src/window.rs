@@ -3,53 +3,962 @@
 pub use raw_window_handle::HasWindowHandle;
 pub use raw_window_handle_05::HasRawWindowHandle as HasRawWindowHandle05;
 
-use crate::dpi::{LogicalSize, PhysicalSize, Position, Size};
-use crate::monitor::MonitorId;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::cursor::CursorIcon;
+use crate::dpi::{
+    Border, Insets, LogicalSize, PhysicalPosition, PhysicalSize, Position, Rect, Size,
+};
+use crate::event_loop::EventLoopHandle;
+use crate::input::DeviceId;
+use crate::monitor::{Monitor, MonitorId};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct WindowId(pub u128);
 
-/// Common requests to perform on the window.
-pub trait Window: HasWindowHandle + HasRawWindowHandle05 {
+/// The error of the requests to the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WindowError {
+    /// The window was destroyed, the id is stale.
+    Destroyed,
+
+    /// The window is not configured yet, it's usable once the
+    /// [`Application::created`] is called for it.
+    ///
+    /// [`Application::created`]: crate::application::Application::created
+    NotConfigured,
+
+    /// The window never existed, or was destroyed too long ago to tell.
+    Unknown,
+}
+
+impl fmt::Display for WindowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Destroyed => f.write_str("the window was destroyed"),
+            Self::NotConfigured => f.write_str("the window is not configured yet"),
+            Self::Unknown => f.write_str("the window is unknown"),
+        }
+    }
+}
+
+impl Error for WindowError {}
+
+/// The error of the [`EventLoopHandle::create_window`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum CreateWindowError {
+    /// The backend doesn't support the requested role.
+    Unsupported,
+
+    /// The attributes were rejected by the [`RoleAttributes::validate`].
+    InvalidAttributes(AttributesError),
+
+    /// The window system failed to create the window.
+    Os(String),
+}
+
+impl fmt::Display for CreateWindowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported => f.write_str("the window role is not supported"),
+            Self::InvalidAttributes(err) => write!(f, "invalid window attributes: {err}"),
+            Self::Os(err) => write!(f, "failed to create the window: {err}"),
+        }
+    }
+}
+
+impl Error for CreateWindowError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Unsupported | Self::Os(_) => None,
+            Self::InvalidAttributes(err) => Some(err),
+        }
+    }
+}
+
+/// The size attribute the [`AttributesError`] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SizeAttribute {
+    Inner,
+    Min,
+    Max,
+}
+
+/// The attributes which the backends can't create the window with.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum AttributesError {
+    /// The size is empty, negative or not finite. The minimum size could be
+    /// zero, meaning no minimum.
+    InvalidSize { attribute: SizeAttribute, size: Size },
+
+    /// The minimum size is larger than the maximum size.
+    ///
+    /// Only the sizes in the same units are compared, the others depend on
+    /// the scale factor which isn't known yet.
+    MinExceedsMax { min: Size, max: Size },
+
+    /// The resize increments are empty, negative, not finite or larger than
+    /// the maximum size.
+    InvalidResizeIncrements(Size),
+
+    /// The fractions of the [`SizePolicy::FractionOfMonitor`] are zero,
+    /// negative, not finite or larger than one.
+    InvalidMonitorFractions { width: f32, height: f32 },
+}
+
+impl fmt::Display for AttributesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSize { attribute, size } => {
+                write!(f, "the {attribute:?} size {size} is invalid")
+            },
+            Self::MinExceedsMax { min, max } => {
+                write!(f, "the min size {min} exceeds the max size {max}")
+            },
+            Self::InvalidResizeIncrements(size) => {
+                write!(f, "the resize increments {size} are invalid")
+            },
+            Self::InvalidMonitorFractions { width, height } => {
+                write!(f, "the monitor fractions {width}x{height} are invalid")
+            },
+        }
+    }
+}
+
+impl Error for AttributesError {}
+
+/// The suspicious attributes, legal but likely not doing what was meant.
+///
+/// The backends log them when creating the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AttributesLint {
+    /// The blur is requested for the opaque window, so it's not visible.
+    BlurWithoutTransparency,
+
+    /// Both the maximized and the fullscreen states are requested, the window
+    /// becomes fullscreen.
+    MaximizedAndFullscreen,
+
+    /// The size limits are set for the window which is not resizable.
+    SizeLimitsNotResizable,
+
+    /// The title is longer than the [`TitlePolicy::max_len`] and is
+    /// truncated, the lengths are in bytes.
+    TitleTruncated { len: usize, max_len: usize },
+}
+
+/// The ids of the recently destroyed windows, to tell the stale ids apart
+/// from the unknown ones.
+///
+/// The backends could reuse the ids, so the id is revived once the window with
+/// it is created again. Only the latest [`Tombstones::CAPACITY`] ids are kept.
+#[derive(Debug, Default, Clone)]
+pub struct Tombstones {
+    ids: VecDeque<WindowId>,
+}
+
+impl Tombstones {
+    pub const CAPACITY: usize = 64;
+
+    /// Remember that the window with the `window_id` was destroyed.
+    pub fn bury(&mut self, window_id: WindowId) {
+        self.revive(window_id);
+        if self.ids.len() == Self::CAPACITY {
+            self.ids.pop_front();
+        }
+
+        self.ids.push_back(window_id);
+    }
+
+    /// Forget the `window_id`, since the new window got it.
+    pub fn revive(&mut self, window_id: WindowId) {
+        self.ids.retain(|id| *id != window_id);
+    }
+
+    pub fn is_destroyed(&self, window_id: WindowId) -> bool {
+        self.ids.contains(&window_id)
+    }
+
+    /// Catch the use of the destroyed window in the debug builds.
+    #[track_caller]
+    #[inline]
+    pub fn debug_assert_alive(&self, window_id: WindowId) {
+        debug_assert!(
+            !self.is_destroyed(window_id),
+            "{window_id:?} is used after it was destroyed, use the `try_get_window` to handle that"
+        );
+    }
+}
+
+/// Common requests to perform on the window surface of any role.
+pub trait Surface: HasWindowHandle + HasRawWindowHandle05 {
     fn id(&self) -> WindowId;
 
+    fn scale_factor(&self) -> f64;
+
+    fn request_redraw(&mut self);
+
+    fn inner_size(&self) -> PhysicalSize<u32>;
+
+    /// The area of the window content relative to the whole surface of the
+    /// window, including client side decorations drawn by winit.
+    ///
+    /// Renderers placing their own surfaces, like video subsurfaces, should
+    /// use the origin of this rectangle as an offset.
+    fn surface_geometry(&self) -> Rect<u32>;
+
+    fn current_monitor(&self) -> Option<MonitorId>;
+
+    fn primary_monitor(&self) -> Option<MonitorId>;
+
+    /// Set the region of the surface content which is fully opaque, in
+    /// physical pixels relative to the top-left corner of the content.
+    ///
+    /// The compositor could skip drawing what's behind the opaque region.
+    /// `None` means that any part of the surface could be transparent. The
+    /// parts of the rectangles outside of the surface are ignored.
+    fn set_opaque_region(&mut self, region: Option<&[Rect<u32>]>);
+
+    /// Set the region of the surface content which accepts the pointer and
+    /// the touch input, in physical pixels relative to the top-left corner of
+    /// the content.
+    ///
+    /// The input outside of the region passes through to whatever is behind
+    /// the surface, which is how the overlays like the HUDs stay
+    /// click-through. `None` means the whole surface, which is the default,
+    /// while the empty region passes all the input through.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Windows:** The input outside of the non-empty region passes only to
+    ///   the windows of the same thread, the empty region passes it to the
+    ///   other applications as well.
+    fn set_input_region(&mut self, region: Option<&[Rect<u32>]>) {
+        let _ = region;
+    }
+
+    /// Mark the whole surface as transparent or opaque, see
+    /// [`Surface::set_opaque_region`].
+    fn set_transparent(&mut self, transparent: bool) {
+        if transparent {
+            self.set_opaque_region(None);
+        } else {
+            let whole =
+                Rect::new(PhysicalPosition::new(0, 0), PhysicalSize::new(u32::MAX, u32::MAX));
+            self.set_opaque_region(Some(&[whole]));
+        }
+    }
+
+    /// The presentation statistics of the surface.
+    ///
+    /// The backends not tracking the presentation return the empty stats.
+    fn frame_stats(&self) -> FrameStats {
+        FrameStats::default()
+    }
+
+    /// Whether the redraws are suppressed, since the surface is known to be
+    /// hidden, minimized or occluded.
+    ///
+    /// The redraws requested while throttled are delivered once the surface
+    /// is visible again. The changes are delivered with
+    /// [`ApplicationWindow::occluded`].
+    ///
+    /// [`ApplicationWindow::occluded`]: crate::application::ApplicationWindow::occluded
+    fn is_throttled(&self) -> bool {
+        false
+    }
+
+    /// Whether to suppress the redraws of the invisible surface, the default
+    /// is `true`.
+    ///
+    /// Applications which must keep rendering, like the ones capturing their
+    /// content into a stream, could disable that.
+    fn set_throttling(&mut self, throttling: bool) {
+        let _ = throttling;
+    }
+
+    /// Whether the surface could take the keyboard focus, see
+    /// [`Surface::set_focusable`].
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    /// Whether the surface could take the keyboard focus, the default is
+    /// `true`.
+    ///
+    /// The surfaces which are not focusable, like the on-screen keyboards or
+    /// the notifications, are skipped by the system navigation and don't
+    /// request the activation.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** The compositor could still focus the surface, the
+    ///   activation requests are dropped though.
+    fn set_focusable(&mut self, focusable: bool) {
+        let _ = focusable;
+    }
+
+    /// Set the cursor shown while the pointer is over the surface.
+    ///
+    /// With the `seat`, the [`DeviceId`] of the pointer, only the cursor of
+    /// that seat is changed, so each user of the multi-pointer setup could
+    /// see their own cursor. Otherwise the cursor of all the seats is changed,
+    /// overriding the per-seat ones.
+    fn set_cursor(&mut self, cursor: CursorIcon, seat: Option<DeviceId>) {
+        let _ = cursor;
+        let _ = seat;
+    }
+
+    /// The position of the pointer over the surface, relative to the top-left
+    /// corner of its content.
+    ///
+    /// With the `seat`, the [`DeviceId`] of the pointer, the position of that
+    /// pointer is returned. Otherwise the position of the most recently moved
+    /// pointer. `None` when the pointer isn't over the surface.
+    fn cursor_position(&self, seat: Option<DeviceId>) -> Option<PhysicalPosition<f64>> {
+        let _ = seat;
+        None
+    }
+
+    /// Downcast to the [`Toplevel`], if the surface has such role.
+    fn as_toplevel(&self) -> Option<&dyn Toplevel> {
+        None
+    }
+
+    /// Downcast to the [`Toplevel`], if the surface has such role.
+    fn as_toplevel_mut(&mut self) -> Option<&mut dyn Toplevel> {
+        None
+    }
+}
+
+/// Requests to perform on the toplevel window.
+pub trait Toplevel: Surface {
     /// Gets the current title of the window.
     fn title(&self) -> &str;
 
-    fn set_title(&mut self, title: &str);
+    /// Set the title of the window.
+    ///
+    /// The title is normalized according to the [`Toplevel::title_policy`],
+    /// the returned title tells whether it was truncated or normalized.
+    fn set_title(&mut self, title: Title) -> Title;
+
+    /// The policy applied to the titles passed to [`Toplevel::set_title`].
+    fn title_policy(&self) -> TitlePolicy;
 
     fn theme(&self) -> Option<Theme>;
 
     fn set_theme(&mut self, theme: Option<Theme>);
 
-    fn scale_factor(&self) -> f64;
+    /// Whether the window has the decorations, like the titlebar.
+    fn is_decorated(&self) -> bool;
 
-    fn request_redraw(&mut self);
+    /// Turn the decorations of the window on or off.
+    ///
+    /// The size of the whole window is kept, so the content takes over the
+    /// space of the decorations drawn by winit and [`resized`] is called with
+    /// the new content size. The system may ignore the request.
+    ///
+    /// [`resized`]: crate::application::ApplicationWindow::resized
+    fn set_decorations(&mut self, decorate: bool);
 
-    fn inner_size(&self) -> PhysicalSize<u32>;
+    /// Request the new size for the window content.
+    ///
+    /// Returns `Some` with the new size when it was applied immediately, in
+    /// such case the [`resized`] is not called. Otherwise the request is
+    /// asynchronous and [`resized`] will be called once the size is known.
+    ///
+    /// The size doesn't include the client side decorations drawn by winit,
+    /// those are added around the content.
+    ///
+    /// [`resized`]: crate::application::ApplicationWindow::resized
+    fn request_inner_size(&mut self, size: Size) -> Option<PhysicalSize<u32>>;
 
+    /// Minimize the window.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Before [`created`], the request is deferred until the
+    ///   window is created, since the compositors ignore it for the windows not
+    ///   shown yet.
+    ///
+    /// [`created`]: crate::application::ApplicationWindow::created
     fn set_minimized(&mut self, minimize: bool);
 
+    /// Maximize or unmaximize the window.
+    ///
+    /// Use [`ToplevelAttributes::with_maximized`] to create the window
+    /// maximized, so it's shown with the maximized size right away.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Before [`created`], the request is deferred until the
+    ///   window is created, since the compositors ignore it for the windows not
+    ///   shown yet.
+    ///
+    /// [`created`]: crate::application::ApplicationWindow::created
     fn set_maximized(&mut self, maximized: bool);
 
-    fn current_monitor(&self) -> Option<MonitorId>;
+    /// Whether the window is maximized.
+    fn is_maximized(&self) -> bool;
 
-    fn primary_monitor(&self) -> Option<MonitorId>;
+    /// Ask the system to focus the window.
+    ///
+    /// The request is tied to the latest user interaction with the
+    /// application, the system may refuse it to prevent focus stealing. In
+    /// such case the [`ApplicationWindow::activation_denied`] is called.
+    ///
+    /// [`ApplicationWindow::activation_denied`]: crate::application::ApplicationWindow::activation_denied
+    fn request_activation(&mut self);
+
+    /// Whether the window is fullscreen.
+    fn is_fullscreen(&self) -> bool;
+
+    /// The position of the top-left corner of the whole window on the
+    /// desktop, `None` when the system doesn't expose it.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland / Web:** Unsupported.
+    fn outer_position(&self) -> Option<PhysicalPosition<i32>> {
+        None
+    }
+
+    /// What happens when the user allows closing the window, see
+    /// [`ApplicationWindow::close_requested`].
+    ///
+    /// [`ApplicationWindow::close_requested`]: crate::application::ApplicationWindow::close_requested
+    fn close_behavior(&self) -> CloseBehavior;
+
+    fn set_close_behavior(&mut self, close_behavior: CloseBehavior);
+
+    /// Show or hide the window.
+    ///
+    /// The hidden window keeps its state, so it could be shown again, for
+    /// example after the [`CloseBehavior::HideInstead`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** The window is shown once the compositor configures it
+    ///   again, it's redrawn then.
+    fn set_visible(&mut self, visible: bool);
+
+    /// The region of the window under the `position`, relative to the
+    /// top-left corner of the whole window surface.
+    ///
+    /// Toolkits drawing their own titlebars could use that to route the
+    /// input the same way the decorations drawn by winit do.
+    fn hit_test(&self, position: Position) -> RegionKind;
+
+    /// Create the [`OverlayPlane`] attached to the window.
+    ///
+    /// Returns `Err` when the system doesn't support the overlay planes.
+    fn create_overlay_plane(&mut self) -> Result<OverlayPlaneId, OverlayPlaneError> {
+        Err(OverlayPlaneError::Unsupported)
+    }
+
+    fn overlay_plane(&self, plane_id: OverlayPlaneId) -> Option<&dyn OverlayPlane> {
+        let _ = plane_id;
+        None
+    }
+
+    fn overlay_plane_mut(&mut self, plane_id: OverlayPlaneId) -> Option<&mut dyn OverlayPlane> {
+        let _ = plane_id;
+        None
+    }
+
+    /// Destroy the overlay plane, the planes are also destroyed along with
+    /// the window.
+    fn destroy_overlay_plane(&mut self, plane_id: OverlayPlaneId) {
+        let _ = plane_id;
+    }
+
+    /// Hint whether the system should draw the shadow around the window.
+    ///
+    /// Mostly useful for the windows without decorations, which still want
+    /// to look consistent with the rest of the system. Ignored when not
+    /// supported.
+    fn set_shadow(&mut self, shadow: bool) {
+        let _ = shadow;
+    }
+
+    /// Hint the radius of the window corners in logical pixels, `None`
+    /// leaves it to the system.
+    ///
+    /// Ignored when not supported.
+    fn set_corner_radius(&mut self, radius: Option<f32>) {
+        let _ = radius;
+    }
+
+    /// Mark the margins of the content which are outside of the window, like
+    /// the drop shadows drawn by the application.
+    ///
+    /// The system doesn't consider the margins as part of the window, so the
+    /// snapping and the maximized windows align with the visible edges. The
+    /// size in [`resized`] still includes the margins, while the minimum and
+    /// the maximum sizes apply to the window without them.
+    ///
+    /// Ignored while the decorations are drawn around the content, and when
+    /// not supported.
+    ///
+    /// [`resized`]: crate::application::ApplicationWindow::resized
+    fn set_content_insets(&mut self, insets: Insets<u32>) {
+        let _ = insets;
+    }
+
+    /// The edges of the window which are adjacent to other windows or
+    /// screen edges, usually when the window is snapped.
+    ///
+    /// Toolkits drawing their own decorations could use that to square off
+    /// the corners.
+    fn tiled_edges(&self) -> TiledEdges;
+
+    /// The largest size the system recommends for the window, usually the
+    /// area of the monitor not covered by the panels.
+    ///
+    /// Applications could use that to pick the size fitting the small
+    /// screens. The changes are delivered with
+    /// [`ApplicationWindow::recommended_bounds_changed`].
+    ///
+    /// [`ApplicationWindow::recommended_bounds_changed`]: crate::application::ApplicationWindow::recommended_bounds_changed
+    fn recommended_bounds(&self) -> Option<LogicalSize<u32>>;
+
+    /// The group the window belongs to.
+    fn group(&self) -> Option<GroupId>;
+
+    /// Associate the window with the related windows, like the tool palettes
+    /// with the main window, `None` removes it from its group.
+    ///
+    /// The window which joined the group first is its main window, the system
+    /// keeps the rest of the group above it. Use
+    /// [`EventLoopHandle::group_members`] to iterate the group.
+    ///
+    /// [`EventLoopHandle::group_members`]: crate::event_loop::EventLoopHandle::group_members
+    fn set_group(&mut self, group: Option<GroupId>);
 }
 
-/// Attributes to use when creating a window.
+/// Identifier of the group of the related windows, picked by the application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GroupId(pub u64);
+
+/// The direction to move the focus in, requested by the system navigation.
+///
+/// See [`ApplicationWindow::focus_directional`].
+///
+/// [`ApplicationWindow::focus_directional`]: crate::application::ApplicationWindow::focus_directional
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum FocusDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+
+    /// The next element in the tab order.
+    Next,
+
+    /// The previous element in the tab order.
+    Previous,
+}
+
+/// Identifier of the [`OverlayPlane`] within its window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OverlayPlaneId(pub u64);
+
+/// The error of the [`Toplevel::create_overlay_plane`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OverlayPlaneError {
+    /// The backend doesn't support the overlay planes.
+    Unsupported,
+
+    /// The display server doesn't advertise the global with the given
+    /// interface name, which the overlay planes are built on.
+    MissingGlobal(&'static str),
+}
+
+impl fmt::Display for OverlayPlaneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported => f.write_str("the overlay planes are not supported"),
+            Self::MissingGlobal(interface) => write!(f, "the `{interface}` global is missing"),
+        }
+    }
+}
+
+impl Error for OverlayPlaneError {}
+
+/// The surface stacked over or under the window content, for example to let
+/// the video players present the decoded frames without copying them into
+/// the window content.
+///
+/// The renderer attaches the buffers to the plane through its window handle
+/// and presents them independently from the window. The changes to the
+/// position and stacking are applied along with the next commit of the window
+/// content, while the size along with the next commit of the plane.
+pub trait OverlayPlane: HasWindowHandle + HasRawWindowHandle05 {
+    fn id(&self) -> OverlayPlaneId;
+
+    /// Set the position of the plane relative to the top-left corner of the
+    /// window content.
+    fn set_position(&mut self, position: Position);
+
+    /// Scale the buffer of the plane to the given `size`, `None` uses the
+    /// size of the buffer.
+    fn set_size(&mut self, size: Option<Size>);
+
+    /// The stacking order of the plane.
+    fn z_index(&self) -> i32;
+
+    /// Set the stacking order of the plane, the planes with the higher index
+    /// are stacked above the others. The planes with the negative index are
+    /// placed under the window content.
+    fn set_z_index(&mut self, z_index: i32);
+}
+
+/// What happens to the window after the
+/// [`ApplicationWindow::close_requested`] returns `true`.
+///
+/// [`ApplicationWindow::close_requested`]: crate::application::ApplicationWindow::close_requested
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CloseBehavior {
+    /// Only notify the application, the return value is ignored and the
+    /// window stays as is.
+    NotifyOnly,
+
+    /// Destroy the window right away.
+    #[default]
+    DestroyImmediately,
+
+    /// Hide the window, so it could be shown again, for example from the
+    /// tray.
+    HideInstead,
+}
+
+/// When the new size from the system is applied to the window, see
+/// [`ToplevelAttributes::with_configure_policy`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ConfigurePolicy {
+    /// Apply the new size right away, along with the decorations drawn by
+    /// winit.
+    #[default]
+    Immediate,
+
+    /// Deliver the new size to the application and apply it along with the
+    /// next redraw, so the decorations drawn by winit change together with
+    /// the content.
+    ///
+    /// The size is applied anyway when the application doesn't redraw in
+    /// time.
+    Staged,
+}
+
+/// Which of the sizes from the system are delivered with the
+/// [`Application::resized`], see [`ToplevelAttributes::with_resize_delivery`].
+///
+/// The interactive resize sends the new size on each pointer motion, so
+/// resizing the content for each of them is wasted work.
+///
+/// [`Application::resized`]: crate::application::Application::resized
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ResizeDelivery {
+    /// Deliver the latest size of the ones the system sent at once.
+    #[default]
+    Coalesced,
+
+    /// Deliver at most one size per frame, the latest one.
+    PerFrame,
+
+    /// Deliver every size, for the applications which track the resize.
+    Every,
+}
+
+/// The action requested with the decorations drawn by winit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DecorationAction {
+    Minimize,
+    Maximize,
+    Unmaximize,
+    Close,
+}
+
+/// Who draws the decorations of the window, negotiated with the system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DecorationMode {
+    /// The system draws the decorations.
+    Server,
+
+    /// The decorations are drawn on the client side, by winit unless the
+    /// [`Toplevel::set_decorations`] turned them off.
+    Client,
+}
+
+/// The region of the window, see [`Toplevel::hit_test`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegionKind {
+    /// The content of the window, see [`Surface::surface_geometry`].
+    Content,
+
+    /// The decorations drawn by winit, like the titlebar.
+    Frame,
+
+    /// The border used to resize the window.
+    ResizeBorder(Border),
+
+    /// Outside of the window.
+    Outside,
+}
+
+/// Attributes common to all the surface roles.
 #[derive(Debug, Clone)]
-pub struct WindowAttributes {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SurfaceAttributes {
     pub inner_size: Size,
+    pub transparent: bool,
+}
+
+impl Default for SurfaceAttributes {
+    fn default() -> Self {
+        Self { inner_size: LogicalSize::new(800, 600).into(), transparent: true }
+    }
+}
+
+/// Attributes to use when creating a window with the specific role.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RoleAttributes {
+    Toplevel(ToplevelAttributes),
+    Popup(PopupAttributes),
+    Subsurface(SubsurfaceAttributes),
+}
+
+impl RoleAttributes {
+    /// The attributes common to all the roles.
+    pub fn surface(&self) -> &SurfaceAttributes {
+        match self {
+            Self::Toplevel(attributes) => &attributes.surface,
+            Self::Popup(attributes) => &attributes.surface,
+            Self::Subsurface(attributes) => &attributes.surface,
+        }
+    }
+
+    /// Check the attributes before creating the window.
+    ///
+    /// The backends reject the invalid attributes in the
+    /// [`EventLoopHandle::create_window`], so the application could check
+    /// them upfront. Returns the [`AttributesLint`]s of the valid attributes.
+    pub fn validate(&self) -> Result<Vec<AttributesLint>, AttributesError> {
+        let surface = self.surface();
+        if !valid_size(surface.inner_size, false) {
+            let size = surface.inner_size;
+            return Err(AttributesError::InvalidSize { attribute: SizeAttribute::Inner, size });
+        }
+
+        let attributes = match self {
+            Self::Toplevel(attributes) => attributes,
+            Self::Popup(_) | Self::Subsurface(_) => return Ok(Vec::new()),
+        };
+
+        if let Some(size) = attributes.min_inner_size.filter(|size| !valid_size(*size, true)) {
+            return Err(AttributesError::InvalidSize { attribute: SizeAttribute::Min, size });
+        }
+
+        if let Some(size) = attributes.max_inner_size.filter(|size| !valid_size(*size, false)) {
+            return Err(AttributesError::InvalidSize { attribute: SizeAttribute::Max, size });
+        }
+
+        if let (Some(min), Some(max)) = (attributes.min_inner_size, attributes.max_inner_size) {
+            if exceeds(min, max) {
+                return Err(AttributesError::MinExceedsMax { min, max });
+            }
+        }
+
+        if let Some(increments) = attributes.resize_increments {
+            let too_large = attributes.max_inner_size.is_some_and(|max| exceeds(increments, max));
+            if !valid_size(increments, false) || too_large {
+                return Err(AttributesError::InvalidResizeIncrements(increments));
+            }
+        }
+
+        if let SizePolicy::FractionOfMonitor { width, height } = attributes.size_policy {
+            if !valid_fraction(width) || !valid_fraction(height) {
+                return Err(AttributesError::InvalidMonitorFractions { width, height });
+            }
+        }
+
+        let mut lints = Vec::new();
+        let max_len = attributes.title_policy.max_len;
+        if attributes.title.len() > max_len {
+            lints.push(AttributesLint::TitleTruncated { len: attributes.title.len(), max_len });
+        }
+
+        if attributes.blur && !attributes.surface.transparent {
+            lints.push(AttributesLint::BlurWithoutTransparency);
+        }
+
+        if attributes.maximized && attributes.fullscreen_monitor.is_some() {
+            lints.push(AttributesLint::MaximizedAndFullscreen);
+        }
+
+        let limited = attributes.min_inner_size.is_some() || attributes.max_inner_size.is_some();
+        if limited && !attributes.resizable {
+            lints.push(AttributesLint::SizeLimitsNotResizable);
+        }
+
+        Ok(lints)
+    }
+}
+
+/// Whether both dimensions of the `size` are positive and finite, or zero
+/// when `allow_zero`.
+fn valid_size(size: Size, allow_zero: bool) -> bool {
+    let (width, height) = match size {
+        Size::Physical(size) => (size.width as f64, size.height as f64),
+        Size::Logical(size) => (size.width, size.height),
+    };
+
+    [width, height]
+        .into_iter()
+        .all(|value| value.is_finite() && (value > 0. || (allow_zero && value == 0.)))
+}
+
+/// Whether the `fraction` of the monitor is within `0.0..=1.0` and not zero.
+fn valid_fraction(fraction: f32) -> bool {
+    fraction > 0. && fraction <= 1.
+}
+
+/// Whether any dimension of the `size` is larger than the one of the `limit`,
+/// `false` when they are in different units.
+fn exceeds(size: Size, limit: Size) -> bool {
+    match (size, limit) {
+        (Size::Physical(size), Size::Physical(limit)) => {
+            size.width > limit.width || size.height > limit.height
+        },
+        (Size::Logical(size), Size::Logical(limit)) => {
+            size.width > limit.width || size.height > limit.height
+        },
+        _ => false,
+    }
+}
+
+impl From<ToplevelAttributes> for RoleAttributes {
+    fn from(attributes: ToplevelAttributes) -> Self {
+        Self::Toplevel(attributes)
+    }
+}
+
+impl From<PopupAttributes> for RoleAttributes {
+    fn from(attributes: PopupAttributes) -> Self {
+        Self::Popup(attributes)
+    }
+}
+
+impl From<SubsurfaceAttributes> for RoleAttributes {
+    fn from(attributes: SubsurfaceAttributes) -> Self {
+        Self::Subsurface(attributes)
+    }
+}
+
+/// Attributes to use when creating a popup, like menus and tooltips.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PopupAttributes {
+    pub surface: SurfaceAttributes,
+
+    /// The window the popup is attached to.
+    pub parent: WindowId,
+
+    /// The position of the popup relative to the parent content.
+    pub position: Position,
+}
+
+impl PopupAttributes {
+    pub fn new<P: Into<Position>>(parent: WindowId, position: P) -> Self {
+        Self { surface: Default::default(), parent, position: position.into() }
+    }
+
+    /// Requests the popup to be of specific dimensions.
+    #[inline]
+    pub fn with_inner_size<S: Into<Size>>(mut self, inner_size: S) -> Self {
+        self.surface.inner_size = inner_size.into();
+        self
+    }
+}
+
+/// Attributes to use when creating a subsurface, a surface placed relative to
+/// its parent, like the video overlay.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SubsurfaceAttributes {
+    pub surface: SurfaceAttributes,
+
+    /// The window the subsurface is attached to.
+    pub parent: WindowId,
+
+    /// The position of the subsurface relative to the parent content.
+    pub position: Position,
+}
+
+impl SubsurfaceAttributes {
+    pub fn new<P: Into<Position>>(parent: WindowId, position: P) -> Self {
+        Self { surface: Default::default(), parent, position: position.into() }
+    }
+
+    /// Requests the subsurface to be of specific dimensions.
+    #[inline]
+    pub fn with_inner_size<S: Into<Size>>(mut self, inner_size: S) -> Self {
+        self.surface.inner_size = inner_size.into();
+        self
+    }
+}
+
+/// Attributes to use when creating a toplevel window.
+///
+/// The attributes are applied before the window is shown, unlike the
+/// [`Toplevel`] requests issued before [`created`], which some systems defer.
+///
+/// [`created`]: crate::application::ApplicationWindow::created
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ToplevelAttributes {
+    pub surface: SurfaceAttributes,
     pub min_inner_size: Option<Size>,
     pub max_inner_size: Option<Size>,
     pub position: Option<Position>,
     pub resizable: bool,
     pub enabled_buttons: WindowButtons,
     pub title: String,
+    pub title_policy: TitlePolicy,
+    pub size_policy: SizePolicy,
     pub maximized: bool,
+    /// The [`Monitor::connection_name`] of the monitor to make the window
+    /// fullscreen on.
+    ///
+    /// [`Monitor::connection_name`]: crate::monitor::Monitor::connection_name
+    pub fullscreen_monitor: Option<String>,
+    pub close_behavior: CloseBehavior,
+    pub configure_policy: ConfigurePolicy,
+    pub resize_delivery: ResizeDelivery,
     pub visible: bool,
-    pub transparent: bool,
     pub blur: bool,
     pub decorations: bool,
     // pub window_icon: Option<Icon>,
@@ -60,23 +969,29 @@ pub struct WindowAttributes {
     pub active: bool,
     // pub cursor: Cursor,
     #[cfg(feature = "rwh_06")]
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub(crate) parent_window: Option<SendSyncRawWindowHandle>,
     // pub fullscreen: Option<Fullscreen>,
 }
 
-impl Default for WindowAttributes {
+impl Default for ToplevelAttributes {
     fn default() -> Self {
         Self {
-            inner_size: LogicalSize::new(800, 600).into(),
+            surface: Default::default(),
             enabled_buttons: WindowButtons::all(),
             title: String::from("Winit window"),
+            title_policy: Default::default(),
+            size_policy: Default::default(),
             content_protected: false,
             resize_increments: None,
             max_inner_size: None,
             min_inner_size: None,
-            transparent: true,
             decorations: true,
             maximized: false,
+            fullscreen_monitor: None,
+            close_behavior: Default::default(),
+            configure_policy: Default::default(),
+            resize_delivery: Default::default(),
             resizable: true,
             position: None,
             visible: false,
@@ -88,16 +1003,16 @@ impl Default for WindowAttributes {
     }
 }
 
-impl WindowAttributes {
+impl ToplevelAttributes {
     #[inline]
     pub fn inner_size(&self) -> Size {
-        self.inner_size
+        self.surface.inner_size
     }
 
     /// Requests the window to be of specific dimensions.
     #[inline]
     pub fn with_inner_size<S: Into<Size>>(mut self, inner_size: S) -> Self {
-        self.inner_size = inner_size.into();
+        self.surface.inner_size = inner_size.into();
         self
     }
 
@@ -156,6 +1071,53 @@ impl WindowAttributes {
         self
     }
 
+    #[inline]
+    pub fn title_policy(&self) -> TitlePolicy {
+        self.title_policy
+    }
+
+    /// Sets the policy applied to the window titles.
+    ///
+    /// The default is [`TitlePolicy::default`].
+    #[inline]
+    pub fn with_title_policy(mut self, title_policy: TitlePolicy) -> Self {
+        self.title_policy = title_policy;
+        self
+    }
+
+    #[inline]
+    pub fn size_policy(&self) -> SizePolicy {
+        self.size_policy
+    }
+
+    /// Sets how the initial size of the window is picked, resolved once the
+    /// monitor the window is placed on is known.
+    ///
+    /// The default is [`SizePolicy::Fixed`].
+    #[inline]
+    pub fn with_size_policy(mut self, size_policy: SizePolicy) -> Self {
+        self.size_policy = size_policy;
+        self
+    }
+
+    /// The initial size of the window, with the [`SizePolicy`] resolved
+    /// against the `monitor` the window is likely to be placed on.
+    ///
+    /// Without the monitor the [`ToplevelAttributes::inner_size`] is used.
+    pub fn initial_inner_size(
+        &self,
+        monitor: Option<&dyn Monitor>,
+        scale_factor: f64,
+    ) -> LogicalSize<f64> {
+        match monitor {
+            Some(monitor) => {
+                let bounds = monitor.size().to_logical(monitor.scale_factor());
+                self.size_policy.resolve(self.surface.inner_size, bounds, scale_factor)
+            },
+            None => self.surface.inner_size.to_logical(scale_factor),
+        }
+    }
+
     #[inline]
     pub fn maximized(&self) -> bool {
         self.maximized
@@ -165,13 +1127,87 @@ impl WindowAttributes {
     ///
     /// The default is `false`.
     ///
-    /// See [`Window::set_maximized`] for details.
+    /// See [`Toplevel::set_maximized`] for details.
     #[inline]
     pub fn with_maximized(mut self, maximized: bool) -> Self {
         self.maximized = maximized;
         self
     }
 
+    #[inline]
+    pub fn fullscreen_monitor(&self) -> Option<&str> {
+        self.fullscreen_monitor.as_deref()
+    }
+
+    /// Request that the window is fullscreen upon creation on the monitor
+    /// with the given [`Monitor::connection_name`].
+    ///
+    /// The default is `None`.
+    ///
+    /// [`Monitor::connection_name`]: crate::monitor::Monitor::connection_name
+    #[inline]
+    pub fn with_fullscreen_monitor(mut self, connection_name: Option<String>) -> Self {
+        self.fullscreen_monitor = connection_name;
+        self
+    }
+
+    #[inline]
+    pub fn close_behavior(&self) -> CloseBehavior {
+        self.close_behavior
+    }
+
+    /// Sets what happens when the user allows closing the window.
+    ///
+    /// The default is [`CloseBehavior::DestroyImmediately`].
+    ///
+    /// See [`Toplevel::set_close_behavior`] for details.
+    #[inline]
+    pub fn with_close_behavior(mut self, close_behavior: CloseBehavior) -> Self {
+        self.close_behavior = close_behavior;
+        self
+    }
+
+    #[inline]
+    pub fn configure_policy(&self) -> ConfigurePolicy {
+        self.configure_policy
+    }
+
+    /// Sets when the new size from the system is applied to the window.
+    ///
+    /// The default is [`ConfigurePolicy::Immediate`].
+    #[inline]
+    pub fn with_configure_policy(mut self, configure_policy: ConfigurePolicy) -> Self {
+        self.configure_policy = configure_policy;
+        self
+    }
+
+    #[inline]
+    pub fn resize_delivery(&self) -> ResizeDelivery {
+        self.resize_delivery
+    }
+
+    /// Sets which of the sizes from the system are delivered.
+    ///
+    /// The default is [`ResizeDelivery::Coalesced`].
+    #[inline]
+    pub fn with_resize_delivery(mut self, resize_delivery: ResizeDelivery) -> Self {
+        self.resize_delivery = resize_delivery;
+        self
+    }
+
+    /// Restore the window state captured with
+    /// [`WindowStateSnapshot::capture`].
+    #[inline]
+    pub fn with_state_snapshot(mut self, snapshot: WindowStateSnapshot) -> Self {
+        self.surface.inner_size = snapshot.size.into();
+        self.maximized = snapshot.maximized;
+        self.fullscreen_monitor = snapshot.fullscreen_monitor;
+        if let Some(position) = snapshot.position {
+            self.position = Some(position.into());
+        }
+        self
+    }
+
     #[inline]
     pub fn visible(&self) -> bool {
         self.visible
@@ -181,7 +1217,7 @@ impl WindowAttributes {
     ///
     /// The default is to show the window.
     ///
-    /// See [`Window::set_visible`] for details.
+    /// See [`Toplevel::set_visible`] for details.
     #[inline]
     pub fn with_visible(mut self, visible: bool) -> Self {
         self.visible = visible;
@@ -197,7 +1233,7 @@ impl WindowAttributes {
     ///
     /// The default is `true`.
     ///
-    /// See [`Window::set_resizable`] for details.
+    /// See [`Toplevel::set_resizable`] for details.
     #[inline]
     pub fn with_resizable(mut self, resizable: bool) -> Self {
         self.resizable = resizable;
@@ -206,7 +1242,7 @@ impl WindowAttributes {
 
     #[inline]
     pub fn transparent(&self) -> bool {
-        self.transparent
+        self.surface.transparent
     }
 
     /// Sets whether the background of the window should be transparent.
@@ -214,12 +1250,12 @@ impl WindowAttributes {
     /// If this is `true`, writing colors with alpha values different than
     /// `1.0` will produce a transparent window. On some platforms this
     /// is more of a hint for the system and you'd still have the alpha
-    /// buffer. To control it see [`Window::set_transparent`].
+    /// buffer. To control it see [`Surface::set_opaque_region`].
     ///
     /// The default is `false`.
     #[inline]
     pub fn with_transparent(mut self, transparent: bool) -> Self {
-        self.transparent = transparent;
+        self.surface.transparent = transparent;
         self
     }
 
@@ -233,7 +1269,7 @@ impl WindowAttributes {
     ///
     /// The default is `false`.
     ///
-    /// See [`Window::set_blur`] for details.
+    /// See [`Toplevel::set_blur`] for details.
     #[inline]
     pub fn with_blur(mut self, blur: bool) -> Self {
         self.blur = blur;
@@ -249,7 +1285,7 @@ impl WindowAttributes {
     ///
     /// The default is `true`.
     ///
-    /// See [`Window::set_decorations`] for details.
+    /// See [`Toplevel::set_decorations`] for details.
     #[inline]
     pub fn with_decorations(mut self, decorations: bool) -> Self {
         self.decorations = decorations;
@@ -274,6 +1310,23 @@ impl WindowAttributes {
         self
     }
 
+    #[inline]
+    pub fn active(&self) -> bool {
+        self.active
+    }
+
+    /// Whether the window should take the keyboard focus when created.
+    ///
+    /// The system could ignore the request, for example when the user is
+    /// interacting with another window.
+    ///
+    /// The default is `true`.
+    #[inline]
+    pub fn with_active(mut self, active: bool) -> Self {
+        self.active = active;
+        self
+    }
+
     #[inline]
     pub fn theme(&self) -> Option<Theme> {
         self.theme
@@ -291,6 +1344,219 @@ impl WindowAttributes {
     }
 }
 
+/// The policy applied to the window titles before sending them to the
+/// system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TitlePolicy {
+    /// The maximum length of the title in bytes.
+    pub max_len: usize,
+
+    /// Whether to strip the control characters. The line breaks and tabs are
+    /// replaced with spaces instead.
+    pub strip_control: bool,
+}
+
+impl TitlePolicy {
+    /// The default limit on the title length, so it doesn't blow up the
+    /// protocol messages.
+    pub const DEFAULT_MAX_LEN: usize = 1024;
+
+    /// Apply the policy to the `title`.
+    pub fn apply(&self, title: &str) -> Title {
+        let mut normalized = false;
+        let mut result = String::with_capacity(title.len().min(self.max_len));
+
+        for ch in title.chars() {
+            let ch = if self.strip_control && ch.is_control() {
+                normalized = true;
+                match ch {
+                    '\n' | '\r' | '\t' => ' ',
+                    _ => continue,
+                }
+            } else {
+                ch
+            };
+
+            if result.len() + ch.len_utf8() > self.max_len {
+                return Title { title: result, truncated: true, normalized };
+            }
+
+            result.push(ch);
+        }
+
+        Title { title: result, truncated: false, normalized }
+    }
+}
+
+impl Default for TitlePolicy {
+    fn default() -> Self {
+        Self { max_len: Self::DEFAULT_MAX_LEN, strip_control: true }
+    }
+}
+
+/// The window title passed to the [`Toplevel::set_title`], and the one with
+/// the [`TitlePolicy`] applied it returns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Title {
+    title: String,
+    truncated: bool,
+    normalized: bool,
+}
+
+impl Title {
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.title
+    }
+
+    /// Whether the title was truncated to the [`TitlePolicy::max_len`].
+    #[inline]
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Whether the control characters were stripped from the title.
+    #[inline]
+    pub fn is_normalized(&self) -> bool {
+        self.normalized
+    }
+}
+
+impl From<Title> for String {
+    fn from(title: Title) -> Self {
+        title.title
+    }
+}
+
+impl From<String> for Title {
+    fn from(title: String) -> Self {
+        Self { title, truncated: false, normalized: false }
+    }
+}
+
+impl From<&str> for Title {
+    fn from(title: &str) -> Self {
+        title.to_owned().into()
+    }
+}
+
+/// How the initial size of the window is picked, see
+/// [`ToplevelAttributes::with_size_policy`].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SizePolicy {
+    /// Use the [`ToplevelAttributes::inner_size`] as is.
+    #[default]
+    Fixed,
+
+    /// Use the `preferred` size, shrunk down to fit the monitor, but not
+    /// below the `min` size.
+    FitContent { preferred: Size, min: Size },
+
+    /// Use the fractions of the monitor width and height, within `0.0..=1.0`
+    /// and not zero.
+    FractionOfMonitor { width: f32, height: f32 },
+}
+
+impl SizePolicy {
+    /// Resolve the initial size of the window.
+    ///
+    /// The `monitor` is the logical area the window could occupy, the
+    /// `inner_size` is used for the [`SizePolicy::Fixed`] and the invalid
+    /// fractions of the [`SizePolicy::FractionOfMonitor`].
+    pub fn resolve(
+        &self,
+        inner_size: Size,
+        monitor: LogicalSize<u32>,
+        scale_factor: f64,
+    ) -> LogicalSize<f64> {
+        let monitor = LogicalSize::<f64>::new(monitor.width as f64, monitor.height as f64);
+        match *self {
+            SizePolicy::Fixed => inner_size.to_logical(scale_factor),
+            SizePolicy::FitContent { preferred, min } => {
+                let preferred = preferred.to_logical::<f64>(scale_factor);
+                let min = min.to_logical::<f64>(scale_factor);
+                LogicalSize::new(
+                    preferred.width.min(monitor.width).max(min.width),
+                    preferred.height.min(monitor.height).max(min.height),
+                )
+            },
+            SizePolicy::FractionOfMonitor { width, height }
+                if valid_fraction(width) && valid_fraction(height) =>
+            {
+                LogicalSize::new(monitor.width * width as f64, monitor.height * height as f64)
+            },
+            SizePolicy::FractionOfMonitor { .. } => inner_size.to_logical(scale_factor),
+        }
+    }
+}
+
+/// The state of the toplevel window to restore it across the launches.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WindowStateSnapshot {
+    /// The size of the window content.
+    ///
+    /// The logical size is stored, so the window has the same size when
+    /// restored on the monitor with the different scale factor.
+    pub size: LogicalSize<f64>,
+
+    /// Whether the window is maximized.
+    pub maximized: bool,
+
+    /// The [`Monitor::connection_name`] of the monitor the window is
+    /// fullscreen on.
+    ///
+    /// [`Monitor::connection_name`]: crate::monitor::Monitor::connection_name
+    pub fullscreen_monitor: Option<String>,
+
+    /// The position of the window, when the system exposes it.
+    pub position: Option<PhysicalPosition<i32>>,
+}
+
+impl WindowStateSnapshot {
+    /// Capture the current state of the `toplevel`.
+    pub fn capture(toplevel: &dyn Toplevel, loop_handle: &dyn EventLoopHandle) -> Self {
+        let size = toplevel.inner_size().to_logical(toplevel.scale_factor());
+
+        let fullscreen_monitor = if toplevel.is_fullscreen() {
+            toplevel
+                .current_monitor()
+                .and_then(|monitor_id| loop_handle.get_monitor(monitor_id))
+                .and_then(|monitor| monitor.connection_name())
+        } else {
+            None
+        };
+
+        Self {
+            size,
+            maximized: toplevel.is_maximized(),
+            fullscreen_monitor,
+            position: toplevel.outer_position(),
+        }
+    }
+}
+
+/// The presentation statistics of the surface.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FrameStats {
+    /// The number of frames requested from the compositor.
+    pub requested: u64,
+
+    /// The number of frames the compositor has presented.
+    pub presented: u64,
+
+    /// The number of frames which weren't presented within the refresh
+    /// interval of the monitor.
+    ///
+    /// See [`ApplicationWindow::frame_missed`].
+    ///
+    /// [`ApplicationWindow::frame_missed`]: crate::application::ApplicationWindow::frame_missed
+    pub missed: u64,
+}
+
 /// A window level groups windows with respect to their z-position.
 ///
 /// The relative ordering between windows in different window levels is fixed.
@@ -301,6 +1567,7 @@ impl WindowAttributes {
 ///
 /// - **iOS / Android / Web / Wayland:** Unsupported.
 #[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum WindowLevel {
     /// The window will always be below normal windows.
     ///
@@ -317,6 +1584,7 @@ pub enum WindowLevel {
 
 bitflags::bitflags! {
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct WindowButtons: u32 {
         const CLOSE  = 1 << 0;
         const MINIMIZE  = 1 << 1;
@@ -324,8 +1592,21 @@ bitflags::bitflags! {
     }
 }
 
+bitflags::bitflags! {
+    /// The edges of the window which are tiled.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct TiledEdges: u32 {
+        const LEFT  = 1 << 0;
+        const RIGHT  = 1 << 1;
+        const TOP  = 1 << 2;
+        const BOTTOM  = 1 << 3;
+    }
+}
+
 /// The theme variant to use.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Theme {
     /// Use the light variant.
     Light,
@@ -333,3 +1614,175 @@ pub enum Theme {
     /// Use the dark variant.
     Dark,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_rejects_invalid_sizes() {
+        let attributes = ToplevelAttributes::default().with_inner_size(PhysicalSize::new(0, 600));
+        assert!(matches!(
+            RoleAttributes::from(attributes).validate(),
+            Err(AttributesError::InvalidSize { attribute: SizeAttribute::Inner, .. })
+        ));
+
+        let attributes = ToplevelAttributes::default()
+            .with_min_inner_size(LogicalSize::new(400., 300.))
+            .with_max_inner_size(LogicalSize::new(300., 300.));
+        assert!(matches!(
+            RoleAttributes::from(attributes).validate(),
+            Err(AttributesError::MinExceedsMax { .. })
+        ));
+
+        // The sizes in different units depend on the scale factor.
+        let attributes = ToplevelAttributes::default()
+            .with_min_inner_size(PhysicalSize::new(400, 300))
+            .with_max_inner_size(LogicalSize::new(300., 300.));
+        assert_eq!(RoleAttributes::from(attributes).validate(), Ok(Vec::new()));
+
+        let attributes = ToplevelAttributes {
+            resize_increments: Some(LogicalSize::new(f64::NAN, 1.).into()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            RoleAttributes::from(attributes).validate(),
+            Err(AttributesError::InvalidResizeIncrements(_))
+        ));
+
+        let max_len = TitlePolicy::DEFAULT_MAX_LEN;
+        let attributes = ToplevelAttributes::default().with_title("a".repeat(max_len + 1));
+        assert_eq!(
+            RoleAttributes::from(attributes).validate(),
+            Ok(vec![AttributesLint::TitleTruncated { len: max_len + 1, max_len }])
+        );
+
+        // The limit follows the policy.
+        let attributes = ToplevelAttributes::default()
+            .with_title("a".repeat(max_len + 1))
+            .with_title_policy(TitlePolicy { max_len: 2 * max_len, strip_control: true });
+        assert_eq!(RoleAttributes::from(attributes).validate(), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn validate_reports_lints() {
+        assert_eq!(RoleAttributes::from(ToplevelAttributes::default()).validate(), Ok(Vec::new()));
+
+        let attributes = ToplevelAttributes::default()
+            .with_transparent(false)
+            .with_blur(true)
+            .with_min_inner_size(LogicalSize::new(0., 0.))
+            .with_resizable(false);
+        assert_eq!(
+            RoleAttributes::from(attributes).validate(),
+            Ok(vec![
+                AttributesLint::BlurWithoutTransparency,
+                AttributesLint::SizeLimitsNotResizable
+            ])
+        );
+    }
+
+    #[test]
+    fn tombstones_track_destroyed_windows() {
+        let mut tombstones = Tombstones::default();
+        let window_id = WindowId(1);
+        assert!(!tombstones.is_destroyed(window_id));
+
+        tombstones.bury(window_id);
+        assert!(tombstones.is_destroyed(window_id));
+        assert!(!tombstones.is_destroyed(WindowId(2)));
+
+        // Closing the window twice keeps a single tombstone.
+        tombstones.bury(window_id);
+        assert_eq!(tombstones.ids.len(), 1);
+
+        // The new window got the same id.
+        tombstones.revive(window_id);
+        assert!(!tombstones.is_destroyed(window_id));
+        tombstones.debug_assert_alive(window_id);
+    }
+
+    #[test]
+    fn tombstones_forget_oldest() {
+        let mut tombstones = Tombstones::default();
+        for id in 0..=Tombstones::CAPACITY as u128 {
+            tombstones.bury(WindowId(id));
+        }
+
+        assert!(!tombstones.is_destroyed(WindowId(0)));
+        assert!(tombstones.is_destroyed(WindowId(1)));
+        assert!(tombstones.is_destroyed(WindowId(Tombstones::CAPACITY as u128)));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "used after it was destroyed")]
+    fn tombstones_assert_use_after_destroy() {
+        let mut tombstones = Tombstones::default();
+        tombstones.bury(WindowId(1));
+        tombstones.debug_assert_alive(WindowId(1));
+    }
+
+    #[test]
+    fn title_policy_truncates_on_char_boundary() {
+        let policy = TitlePolicy { max_len: 4, strip_control: true };
+
+        let title = policy.apply("abc");
+        assert_eq!(title.as_str(), "abc");
+        assert!(!title.is_truncated());
+
+        // The `ж` takes two bytes, so it doesn't fit.
+        let title = policy.apply("abcж");
+        assert_eq!(title.as_str(), "abc");
+        assert!(title.is_truncated());
+    }
+
+    #[test]
+    fn title_policy_strips_control() {
+        let policy = TitlePolicy::default();
+
+        let title = policy.apply("a\tb\nc\u{7}d");
+        assert_eq!(title.as_str(), "a b cd");
+        assert!(title.is_normalized());
+        assert!(!title.is_truncated());
+
+        let policy = TitlePolicy { strip_control: false, ..policy };
+        let title = policy.apply("a\tb");
+        assert_eq!(title.as_str(), "a\tb");
+        assert!(!title.is_normalized());
+    }
+
+    #[test]
+    fn size_policy_resolves_against_monitor() {
+        let inner_size = Size::from(LogicalSize::new(800., 600.));
+        let monitor = LogicalSize::new(1000, 500);
+
+        let size = SizePolicy::Fixed.resolve(inner_size, monitor, 2.);
+        assert_eq!(size, LogicalSize::new(800., 600.));
+
+        let policy = SizePolicy::FitContent {
+            preferred: LogicalSize::new(800., 600.).into(),
+            min: PhysicalSize::new(400, 1200).into(),
+        };
+        assert_eq!(policy.resolve(inner_size, monitor, 2.), LogicalSize::new(800., 600.));
+
+        let policy = SizePolicy::FractionOfMonitor { width: 0.5, height: 0.25 };
+        assert_eq!(policy.resolve(inner_size, monitor, 2.), LogicalSize::new(500., 125.));
+
+        let policy = SizePolicy::FractionOfMonitor { width: 1., height: 1. };
+        assert_eq!(policy.resolve(inner_size, monitor, 2.), LogicalSize::new(1000., 500.));
+
+        // The invalid fractions are rejected and fall back to the inner size.
+        let policy = SizePolicy::FractionOfMonitor { width: 2., height: -1. };
+        assert_eq!(policy.resolve(inner_size, monitor, 2.), LogicalSize::new(800., 600.));
+        let attributes = ToplevelAttributes::default().with_size_policy(policy);
+        assert_eq!(
+            RoleAttributes::from(attributes).validate(),
+            Err(AttributesError::InvalidMonitorFractions { width: 2., height: -1. })
+        );
+
+        let policy = SizePolicy::FractionOfMonitor { width: 0.5, height: 0. };
+        let attributes = ToplevelAttributes::default().with_size_policy(policy);
+        assert!(RoleAttributes::from(attributes).validate().is_err());
+    }
+}
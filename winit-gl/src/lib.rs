@@ -0,0 +1,111 @@
+//! EGL helpers to render with OpenGL into the winit windows.
+//!
+//! The [`GlDisplay`] is created from the event loop once, and then the
+//! [`GlWindow`] for each window when it's created. The size of the EGL surface
+//! should be kept in sync with the window by calling [`GlWindow::resize`] from
+//! the [`resized`].
+//!
+//! [`resized`]: winit_core::application::ApplicationWindow::resized
+
+use std::ffi::{c_void, CStr};
+use std::num::NonZeroU32;
+
+use glutin::config::{Config, ConfigTemplateBuilder};
+use glutin::context::{ContextAttributesBuilder, PossiblyCurrentContext};
+use glutin::display::{Display, DisplayApiPreference, GlDisplay as _};
+use glutin::error::{ErrorKind, Result};
+use glutin::prelude::*;
+use glutin::surface::{Surface, SurfaceAttributesBuilder, WindowSurface};
+use raw_window_handle_05::HasRawDisplayHandle as HasRawDisplayHandle05;
+
+use winit_core::dpi::PhysicalSize;
+use winit_core::window::Surface as CoreSurface;
+
+/// The EGL display created from the event loop.
+pub struct GlDisplay {
+    display: Display,
+    config: Config,
+}
+
+impl GlDisplay {
+    /// Create the EGL display for the given event loop.
+    ///
+    /// # Safety
+    ///
+    /// The event loop must outlive the created display.
+    pub unsafe fn new<D: HasRawDisplayHandle05>(event_loop: &D) -> Result<Self> {
+        let display = Display::new(event_loop.raw_display_handle(), DisplayApiPreference::Egl)?;
+
+        // Pick the config with the most samples, since the windows are transparent
+        // by default ask for the alpha as well.
+        let template = ConfigTemplateBuilder::new().with_alpha_size(8).build();
+        let config = display
+            .find_configs(template)?
+            .reduce(
+                |acc, config| if config.num_samples() > acc.num_samples() { config } else { acc },
+            )
+            .ok_or(ErrorKind::NotFound)?;
+
+        Ok(Self { display, config })
+    }
+
+    /// Get the address of the OpenGL function.
+    pub fn get_proc_address(&self, name: &CStr) -> *const c_void {
+        self.display.get_proc_address(name)
+    }
+}
+
+/// The EGL surface and the context bound to the window.
+pub struct GlWindow {
+    // NOTE: the context must be dropped before the surface.
+    context: PossiblyCurrentContext,
+    surface: Surface<WindowSurface>,
+}
+
+impl GlWindow {
+    /// Create the EGL surface for the `window` and make its context current.
+    ///
+    /// # Safety
+    ///
+    /// The window must outlive the created surface.
+    pub unsafe fn new(display: &GlDisplay, window: &dyn CoreSurface) -> Result<Self> {
+        let raw_window_handle = window.raw_window_handle();
+        let (width, height) = non_zero_size(window.inner_size());
+
+        let attributes = SurfaceAttributesBuilder::<WindowSurface>::new().build(
+            raw_window_handle,
+            width,
+            height,
+        );
+        let surface = display.display.create_window_surface(&display.config, &attributes)?;
+
+        let attributes = ContextAttributesBuilder::new().build(Some(raw_window_handle));
+        let context =
+            display.display.create_context(&display.config, &attributes)?.make_current(&surface)?;
+
+        Ok(Self { context, surface })
+    }
+
+    /// Resize the EGL surface, should be called from the [`resized`].
+    ///
+    /// [`resized`]: winit_core::application::ApplicationWindow::resized
+    pub fn resize(&self, size: PhysicalSize<u32>) {
+        let (width, height) = non_zero_size(size);
+        self.surface.resize(&self.context, width, height);
+    }
+
+    /// Make the context of the window current.
+    pub fn make_current(&self) -> Result<()> {
+        self.context.make_current(&self.surface)
+    }
+
+    /// Present the rendered frame.
+    pub fn swap_buffers(&self) -> Result<()> {
+        self.surface.swap_buffers(&self.context)
+    }
+}
+
+/// EGL surfaces can't be empty, thus clamp the size to at least one pixel.
+fn non_zero_size(size: PhysicalSize<u32>) -> (NonZeroU32, NonZeroU32) {
+    (NonZeroU32::new(size.width.max(1)).unwrap(), NonZeroU32::new(size.height.max(1)).unwrap())
+}
@@ -0,0 +1,152 @@
+//! The document-style application with a window per document.
+//!
+//! Press `n` to open a new document.
+
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+
+use winit_core::application::{Application, ApplicationWindow, StartCause};
+use winit_core::dpi::PhysicalSize;
+use winit_core::event_loop::EventLoopHandle;
+use winit_core::input::keyboard::{Key, KeyEvent, KeyboardInputHandler};
+use winit_core::input::ElementState;
+use winit_core::window::{ToplevelAttributes, WindowId};
+use winit_wayland::event_loop::EventLoop;
+
+use softbuffer::{Context, Surface};
+
+const COLORS: [u32; 3] = [0xFF181818, 0xFF1D3557, 0xFF2A9D8F];
+
+const INITIAL_DOCUMENTS: usize = 2;
+
+struct Document {
+    surface: Surface,
+    color: u32,
+}
+
+struct State {
+    context: Context,
+    documents: HashMap<WindowId, Document>,
+    opened: usize,
+}
+
+impl State {
+    fn open_document(&mut self, loop_handle: &mut dyn EventLoopHandle) {
+        self.opened += 1;
+        let attributes = ToplevelAttributes::default()
+            .with_title(format!("Document {}", self.opened))
+            .with_inner_size(PhysicalSize::new(480, 360));
+        let _ = loop_handle.create_window(attributes.into());
+    }
+}
+
+impl Application for State {
+    fn user_wakeup(&mut self, _: &mut dyn EventLoopHandle) {}
+
+    fn new_events(&mut self, loop_handle: &mut dyn EventLoopHandle, start_cause: StartCause) {
+        if start_cause == StartCause::Init {
+            for _ in 0..INITIAL_DOCUMENTS {
+                self.open_document(loop_handle);
+            }
+        }
+    }
+
+    fn about_to_wait(&mut self, _: &mut dyn EventLoopHandle) {}
+
+    fn loop_exiting(&mut self, _: &mut dyn EventLoopHandle) {
+        println!("Opened {} documents in total", self.opened);
+    }
+
+    fn keyboard_handler(&mut self) -> Option<&mut dyn KeyboardInputHandler> {
+        Some(self)
+    }
+}
+
+impl KeyboardInputHandler for State {
+    fn key_input(&mut self, loop_handle: &mut dyn EventLoopHandle, _: WindowId, event: KeyEvent) {
+        if event.state == ElementState::Pressed
+            && !event.repeat
+            && event.key_without_modifiers == Key::Character("n".into())
+        {
+            self.open_document(loop_handle);
+        }
+    }
+}
+
+impl ApplicationWindow for State {
+    fn created(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        let window = loop_handle.get_window(window_id).unwrap();
+        let surface =
+            unsafe { Surface::new(&self.context, &window) }.expect("failed to create surface");
+        let color = COLORS[self.documents.len() % COLORS.len()];
+        self.documents.insert(window_id, Document { surface, color });
+    }
+
+    fn resized(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        _: PhysicalSize<u32>,
+    ) {
+        if let Some(window) = loop_handle.get_window_mut(window_id) {
+            window.request_redraw();
+        }
+    }
+
+    fn scale_factor_changed(&mut self, _: &mut dyn EventLoopHandle, _: WindowId, _: f64) {}
+
+    fn redraw_requested(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        let (window, document) =
+            match (loop_handle.get_window(window_id), self.documents.get_mut(&window_id)) {
+                (Some(window), Some(document)) => (window, document),
+                _ => return,
+            };
+
+        let size = window.inner_size();
+        let (width, height) = match (NonZeroU32::new(size.width), NonZeroU32::new(size.height)) {
+            (Some(width), Some(height)) => (width, height),
+            _ => return,
+        };
+
+        let _ = document.surface.resize(width, height);
+        let mut buffer = document.surface.buffer_mut().unwrap();
+        buffer.fill(document.color);
+        buffer.present().unwrap();
+    }
+
+    fn focused(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        focused: bool,
+    ) {
+        if !focused {
+            return;
+        }
+
+        if let Some(toplevel) = loop_handle.get_window(window_id).and_then(|w| w.as_toplevel()) {
+            println!("Focused {:?}", toplevel.title());
+        }
+    }
+
+    fn close_requested(&mut self, _: &mut dyn EventLoopHandle, _: WindowId) -> bool {
+        true
+    }
+
+    fn destroyed(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        self.documents.remove(&window_id);
+        if loop_handle.num_windows() == 0 {
+            loop_handle.exit();
+        }
+    }
+}
+
+fn main() {
+    let event_loop = EventLoop::<State>::new().unwrap();
+
+    let context =
+        unsafe { Context::new(&event_loop).expect("failed to create softbuffer context") };
+    let state = State { context, documents: HashMap::new(), opened: 0 };
+
+    event_loop.run(state);
+}
@@ -0,0 +1,146 @@
+//! The click-through HUD.
+//!
+//! Only the close button in the top-right corner accepts the input, the
+//! clicks anywhere else land on the window beneath.
+
+use std::num::NonZeroU32;
+
+use winit_core::application::{Application, ApplicationWindow, StartCause};
+use winit_core::dpi::{LogicalSize, PhysicalPosition, PhysicalSize, Rect};
+use winit_core::event_loop::EventLoopHandle;
+use winit_core::input::pointer::{
+    PointerButton, PointerButtonEvent, PointerInputHandler, PointerMotion,
+};
+use winit_core::input::ElementState;
+use winit_core::window::{Surface as CoreSurface, ToplevelAttributes, WindowId};
+use winit_wayland::event_loop::EventLoop;
+
+use softbuffer::{Context, Surface};
+
+const HUD_COLOR: u32 = 0xFF181818;
+const BUTTON_COLOR: u32 = 0xFFE63946;
+
+/// The logical size of the close button.
+const BUTTON_SIZE: u32 = 32;
+
+struct State {
+    context: Context,
+    surface: Option<Surface>,
+}
+
+/// The close button of the `window`, in physical pixels.
+fn button_rect(window: &dyn CoreSurface) -> Rect<u32> {
+    let size: PhysicalSize<u32> =
+        LogicalSize::new(BUTTON_SIZE, BUTTON_SIZE).to_physical(window.scale_factor());
+    let x = window.inner_size().width.saturating_sub(size.width);
+    Rect::new(PhysicalPosition::new(x, 0), size)
+}
+
+impl Application for State {
+    fn user_wakeup(&mut self, _: &mut dyn EventLoopHandle) {}
+
+    fn new_events(&mut self, loop_handle: &mut dyn EventLoopHandle, start_cause: StartCause) {
+        if start_cause == StartCause::Init {
+            let attributes = ToplevelAttributes::default()
+                .with_title("HUD")
+                .with_decorations(false)
+                .with_transparent(true)
+                .with_inner_size(LogicalSize::new(320., 120.));
+            let _ = loop_handle.create_window(attributes.into());
+        }
+    }
+
+    fn about_to_wait(&mut self, _: &mut dyn EventLoopHandle) {}
+
+    fn loop_exiting(&mut self, _: &mut dyn EventLoopHandle) {}
+
+    fn pointer_handler(&mut self) -> Option<&mut dyn PointerInputHandler> {
+        Some(self)
+    }
+}
+
+impl PointerInputHandler for State {
+    fn pointer_moved(&mut self, _: &mut dyn EventLoopHandle, _: WindowId, _: PointerMotion) {}
+
+    fn pointer_button(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        _: WindowId,
+        event: PointerButtonEvent,
+    ) {
+        // The input only arrives over the button.
+        if event.button == PointerButton::Left && event.state == ElementState::Released {
+            loop_handle.exit();
+        }
+    }
+}
+
+impl ApplicationWindow for State {
+    fn created(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        let window = loop_handle.get_window(window_id).unwrap();
+        self.surface = Some(
+            unsafe { Surface::new(&self.context, &window) }.expect("failed to create surface"),
+        );
+    }
+
+    fn resized(
+        &mut self,
+        loop_handle: &mut dyn EventLoopHandle,
+        window_id: WindowId,
+        _: PhysicalSize<u32>,
+    ) {
+        if let Some(window) = loop_handle.get_window_mut(window_id) {
+            // The button stays in the corner, so the region follows the size.
+            let button = button_rect(window);
+            window.set_input_region(Some(&[button]));
+            window.request_redraw();
+        }
+    }
+
+    fn scale_factor_changed(&mut self, _: &mut dyn EventLoopHandle, _: WindowId, _: f64) {}
+
+    fn redraw_requested(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
+        let (window, surface) = match (loop_handle.get_window(window_id), self.surface.as_mut()) {
+            (Some(window), Some(surface)) => (window, surface),
+            _ => return,
+        };
+
+        let size = window.inner_size();
+        let (width, height) = match (NonZeroU32::new(size.width), NonZeroU32::new(size.height)) {
+            (Some(width), Some(height)) => (width, height),
+            _ => return,
+        };
+
+        let _ = surface.resize(width, height);
+        let mut buffer = surface.buffer_mut().unwrap();
+        buffer.fill(HUD_COLOR);
+
+        let button = button_rect(window);
+        for y in button.origin.y..(button.origin.y + button.size.height).min(size.height) {
+            let row = (y * size.width) as usize;
+            let start = row + button.origin.x as usize;
+            let end = row + (button.origin.x + button.size.width).min(size.width) as usize;
+            buffer[start..end].fill(BUTTON_COLOR);
+        }
+
+        buffer.present().unwrap();
+    }
+
+    fn close_requested(&mut self, _: &mut dyn EventLoopHandle, _: WindowId) -> bool {
+        true
+    }
+
+    fn destroyed(&mut self, loop_handle: &mut dyn EventLoopHandle, _: WindowId) {
+        loop_handle.exit();
+    }
+}
+
+fn main() {
+    let event_loop = EventLoop::<State>::new().unwrap();
+
+    let context =
+        unsafe { Context::new(&event_loop).expect("failed to create softbuffer context") };
+    let state = State { context, surface: None };
+
+    event_loop.run(state);
+}
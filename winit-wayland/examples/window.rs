@@ -3,8 +3,8 @@ use std::time::Duration;
 
 use winit_core::application::{Application, ApplicationWindow, StartCause};
 use winit_core::dpi::PhysicalSize;
-use winit_core::event_loop::{EventLoopHandle, EventLoopRequests};
-use winit_core::window::WindowId;
+use winit_core::event_loop::EventLoopHandle;
+use winit_core::window::{ToplevelAttributes, WindowId};
 use winit_wayland::event_loop::EventLoop;
 use winit_wayland::MyCoolTrait;
 
@@ -39,7 +39,7 @@ impl Application for State {
 
     fn new_events(&mut self, loop_handle: &mut dyn EventLoopHandle, start_cause: StartCause) {
         println!("Start cause {start_cause:?}");
-        let _ = loop_handle.create_window(&Default::default());
+        let _ = loop_handle.create_window(ToplevelAttributes::default().into());
     }
 
     fn about_to_wait(&mut self, _: &mut dyn EventLoopHandle) {
@@ -54,6 +54,10 @@ impl Application for State {
 impl ApplicationWindow for State {
     fn created(&mut self, loop_handle: &mut dyn EventLoopHandle, window_id: WindowId) {
         let window = loop_handle.get_window(window_id).unwrap();
+        if let Some(toplevel) = window.as_toplevel() {
+            println!("Created toplevel {:?}", toplevel.title());
+        }
+
         self.surface = unsafe {
             Some(Surface::new(&self.context, &window).expect("failed to create surface"))
         };
@@ -110,8 +114,7 @@ impl ApplicationWindow for State {
 }
 
 fn main() {
-    // TODO this is ugly.
-    let mut event_loop = <EventLoop<State> as EventLoopRequests<State>>::new().unwrap();
+    let mut event_loop = EventLoop::<State>::new().unwrap();
 
     event_loop.register_my_cool_trait_handler();
 
@@ -119,7 +122,7 @@ fn main() {
         unsafe { Context::new(&event_loop).expect("failed to create softbuffer context") };
     let state = State { context, surface: None };
 
-    let proxy = EventLoopRequests::<State>::proxy(&mut event_loop);
+    let proxy = event_loop.proxy();
 
     // Test out the proxy.
     std::thread::spawn(move || loop {
@@ -0,0 +1,18 @@
+//! Print which backends could open the windows, to attach to the bug reports.
+
+use std::process::ExitCode;
+
+use winit_core::diagnostics;
+use winit_wayland::diagnostics::WaylandProbe;
+
+fn main() -> ExitCode {
+    let report = diagnostics::probe(&[&WaylandProbe]);
+    print!("{report}");
+
+    if report.usable().next().is_some() {
+        ExitCode::SUCCESS
+    } else {
+        println!("no usable backend found");
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,146 @@
+//! The clipboard backed by the data devices of the seats.
+
+use std::fs::File;
+use std::io;
+use std::os::fd::{FromRawFd, IntoRawFd};
+
+use sctk::data_device_manager::data_device::{DataDevice, DataDeviceData, DataDeviceHandler};
+use sctk::data_device_manager::data_offer::{
+    DataOfferError, DataOfferHandler, DragOffer, SelectionOffer,
+};
+use sctk::data_device_manager::data_source::DataSourceHandler;
+use sctk::data_device_manager::{DataDeviceManagerState, WritePipe};
+use sctk::reexports::client::protocol::wl_data_device::WlDataDevice;
+use sctk::reexports::client::protocol::wl_data_device_manager::DndAction;
+use sctk::reexports::client::protocol::wl_data_source::WlDataSource;
+use sctk::reexports::client::protocol::wl_seat::WlSeat;
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
+use sctk::reexports::client::{Connection, Proxy, QueueHandle};
+
+use winit_core::application::Application;
+use winit_core::clipboard::{Clipboard as CoreClipboard, ClipboardError, ClipboardReader};
+
+use crate::event_loop::RuntimeState;
+
+/// The clipboard of the seats.
+#[derive(Debug, Default)]
+pub struct Clipboard {
+    /// The content of the clipboard, as announced by the latest selection.
+    selection: Option<SelectionOffer>,
+}
+
+impl Clipboard {
+    /// Create the data device to track the clipboard of the `seat`.
+    pub(crate) fn data_device<T: Application + 'static>(
+        manager: Option<&DataDeviceManagerState>,
+        seat: &WlSeat,
+        queue_handle: &QueueHandle<RuntimeState<T>>,
+    ) -> Option<DataDevice> {
+        manager.map(|manager| manager.get_data_device(queue_handle, seat))
+    }
+}
+
+impl CoreClipboard for Clipboard {
+    fn mime_types(&self) -> Vec<String> {
+        self.selection.as_ref().map_or_else(Vec::new, |offer| offer.with_mime_types(<[_]>::to_vec))
+    }
+
+    fn read(&mut self, mime_type: &str) -> Result<ClipboardReader, ClipboardError> {
+        let offer = self.selection.as_ref().ok_or(ClipboardError::Empty)?;
+        let offered = offer.with_mime_types(|mime_types| mime_types.iter().any(|m| m == mime_type));
+        if !offered {
+            return Err(ClipboardError::UnsupportedMimeType);
+        }
+
+        // The source writes into the pipe once the request reaches it, which
+        // happens with the flush of the next loop iteration.
+        let pipe = offer.receive(mime_type.to_owned()).map_err(|err| match err {
+            DataOfferError::Io(err) => err,
+            err => io::Error::other(err),
+        })?;
+
+        // SAFETY: the descriptor is owned by the pipe, which is consumed.
+        let file = unsafe { File::from_raw_fd(pipe.into_raw_fd()) };
+        Ok(ClipboardReader::new(file))
+    }
+}
+
+impl<T: Application + 'static> DataDeviceHandler for RuntimeState<T> {
+    fn enter(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &WlDataDevice,
+        _: f64,
+        _: f64,
+        _: &WlSurface,
+    ) {
+    }
+
+    fn leave(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &WlDataDevice) {}
+
+    fn motion(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &WlDataDevice, _: f64, _: f64) {}
+
+    fn selection(&mut self, _: &Connection, _: &QueueHandle<Self>, data_device: &WlDataDevice) {
+        // The compositor sends the selection to the focused client only, so
+        // the latest one is the clipboard of the seat in use.
+        self.winit.clipboard.selection =
+            data_device.data::<DataDeviceData>().and_then(|data| data.selection_offer());
+    }
+
+    fn drop_performed(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &WlDataDevice) {}
+}
+
+impl<T: Application + 'static> DataOfferHandler for RuntimeState<T> {
+    fn source_actions(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &mut DragOffer,
+        _: DndAction,
+    ) {
+    }
+
+    fn selected_action(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &mut DragOffer,
+        _: DndAction,
+    ) {
+    }
+}
+
+// TODO: writing to the clipboard, the sources are never created for now.
+impl<T: Application + 'static> DataSourceHandler for RuntimeState<T> {
+    fn accept_mime(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &WlDataSource,
+        _: Option<String>,
+    ) {
+    }
+
+    fn send_request(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &WlDataSource,
+        _: String,
+        _: WritePipe,
+    ) {
+    }
+
+    fn cancelled(&mut self, _: &Connection, _: &QueueHandle<Self>, source: &WlDataSource) {
+        source.destroy();
+    }
+
+    fn dnd_dropped(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &WlDataSource) {}
+
+    fn dnd_finished(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &WlDataSource) {}
+
+    fn action(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &WlDataSource, _: DndAction) {}
+}
+
+sctk::delegate_data_device!(@<T: Application + 'static> RuntimeState<T>);
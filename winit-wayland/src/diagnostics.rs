@@ -0,0 +1,82 @@
+//! The diagnostics of the Wayland backend.
+
+use std::env;
+
+use sctk::reexports::client::globals::{self, GlobalListContents};
+use sctk::reexports::client::protocol::wl_registry::{self, WlRegistry};
+use sctk::reexports::client::{Connection, Dispatch, QueueHandle};
+
+use winit_core::diagnostics::{self, BackendProbe, BackendReport};
+
+/// The globals the backend can't work without.
+const REQUIRED_GLOBALS: &[&str] = &["wl_compositor", "wl_shm", "xdg_wm_base"];
+
+/// The probe of the Wayland backend, see [`diagnostics::probe`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WaylandProbe;
+
+impl BackendProbe for WaylandProbe {
+    fn name(&self) -> &'static str {
+        "wayland"
+    }
+
+    fn version(&self) -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    fn probe(&self) -> BackendReport {
+        let mut report = BackendReport::new(self.name());
+
+        // Connecting takes the socket passed by the parent process away from
+        // the event loop, so the globals can't be checked.
+        if env::var_os("WAYLAND_SOCKET").is_some() {
+            report.connectable = true;
+            return report;
+        }
+
+        let connection = match Connection::connect_to_env() {
+            Ok(connection) => connection,
+            Err(err) => {
+                // Explain why the socket from the environment doesn't work.
+                report.missing = diagnostics::probe_wayland_env().missing;
+                report.missing.push(format!("failed to connect: {err}"));
+                return report;
+            },
+        };
+
+        report.connectable = true;
+
+        let globals = match globals::registry_queue_init::<ProbeState>(&connection) {
+            Ok((globals, _)) => globals,
+            Err(err) => {
+                report.missing.push(format!("failed to get the globals: {err}"));
+                return report;
+            },
+        };
+
+        globals.contents().with_list(|list| {
+            for &interface in REQUIRED_GLOBALS {
+                if !list.iter().any(|global| global.interface == interface) {
+                    report.missing.push(format!("the compositor doesn't support {interface}"));
+                }
+            }
+        });
+
+        report
+    }
+}
+
+/// The state to dispatch the registry of the probe.
+struct ProbeState;
+
+impl Dispatch<WlRegistry, GlobalListContents> for ProbeState {
+    fn event(
+        _: &mut Self,
+        _: &WlRegistry,
+        _: wl_registry::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
@@ -1,14 +1,16 @@
 use std::collections::HashMap;
+use std::io;
 use std::mem;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use calloop::generic::Generic;
 use calloop::ping::Ping;
-use calloop::LoopHandle;
+use calloop::{Interest, LoopHandle, Mode, PostAction};
 use raw_window_handle::{DisplayHandle, HandleError, HasDisplayHandle};
 use raw_window_handle_05::HasRawDisplayHandle as HasRawDisplayHandle05;
 
-use sctk::reexports::calloop_wayland_source::WaylandSource;
-use sctk::reexports::client::globals::GlobalList;
+use sctk::reexports::client::globals::{BindError, GlobalList};
 use sctk::reexports::client::protocol::wl_output::WlOutput;
 use sctk::reexports::client::protocol::wl_seat::WlSeat;
 use sctk::reexports::client::{globals, Connection, QueueHandle};
@@ -16,33 +18,68 @@ use sctk::registry::{ProvidesRegistryState, RegistryState};
 
 use sctk::output::{OutputHandler, OutputState};
 use sctk::seat::{Capability as SeatCapability, SeatHandler, SeatState};
+use sctk::shell::WaylandSurface;
+
+use smallvec::SmallVec;
+use wayland_backend::client::WaylandError;
 
 use winit_core::application::Application;
+use winit_core::dpi::PhysicalSize;
 use winit_core::event_loop::proxy::EventLoopProxy as CoreEventLoopProxy;
-use winit_core::event_loop::{EventLoopHandle, EventLoopRequests};
-use winit_core::window::{Window as CoreWindow, WindowId};
-
+use winit_core::event_loop::{
+    BackendDisconnected, EventLoopError, EventLoopHandle, EventLoopRequests, ProtocolError,
+};
+use winit_core::window::{Surface as CoreSurface, WindowId};
+
+use crate::role::RoleHandler;
+#[cfg(feature = "single-instance")]
+use crate::single_instance::{self, SingleInstance, SingleInstanceError};
 use crate::state::WinitState;
+#[cfg(feature = "tokio")]
+use crate::tokio_runtime::TokioState;
 use crate::MyCoolTrait;
 
-pub struct EventLoop<T: Application + 'static> {
-    state: RuntimeState<T>,
+/// The Wayland event loop.
+///
+/// The application type could be provided statically, or left as the
+/// `Box<dyn Application>` when the event loop is used through the
+/// [`EventLoopRequests`].
+pub struct EventLoop<T: Application + 'static = Box<dyn Application>> {
+    pub(crate) state: RuntimeState<T>,
+
+    /// The globals advertised by the compositor.
+    globals: GlobalList,
+
+    /// The handlers of the custom surface roles.
+    role_handlers: Vec<Box<dyn RoleHandler<T>>>,
+
+    pub(crate) event_loop: calloop::EventLoop<'static, RuntimeState<T>>,
 
-    event_loop: calloop::EventLoop<'static, RuntimeState<T>>,
+    /// The tokio runtime entered while running.
+    #[cfg(feature = "tokio")]
+    pub(crate) tokio: Option<TokioState>,
+
+    /// The session bus connection owning the application name.
+    #[cfg(feature = "single-instance")]
+    single_instance: Option<zbus::blocking::Connection>,
 }
 
-impl<T: Application + 'static> EventLoopRequests<T> for EventLoop<T> {
-    fn new() -> Result<Self, ()> {
-        let connection = Connection::connect_to_env().unwrap();
+impl<T: Application + 'static> EventLoop<T> {
+    pub fn new() -> Result<Self, EventLoopError> {
+        let started_at = Instant::now();
+        let connection = Connection::connect_to_env().map_err(os_error)?;
+        let connected = started_at.elapsed();
 
-        let (globals, mut event_queue) = globals::registry_queue_init(&connection).unwrap();
+        let (globals, mut event_queue) =
+            globals::registry_queue_init(&connection).map_err(os_error)?;
         let queue_handle = event_queue.handle();
 
-        let event_loop = calloop::EventLoop::<RuntimeState<T>>::try_new().unwrap();
+        let event_loop = calloop::EventLoop::<RuntimeState<T>>::try_new().map_err(os_error)?;
 
         // Insert the proxy source.
-        let (ping, ping_source) = calloop::ping::make_ping().unwrap();
-        let proxy = EventLoopProxy::new(ping);
+        let (ping, ping_source) = calloop::ping::make_ping().map_err(os_error)?;
+        let (exit_ping, exit_ping_source) = calloop::ping::make_ping().map_err(os_error)?;
+        let proxy = EventLoopProxy::new(ping, exit_ping);
 
         let _ =
             event_loop.handle().insert_source(ping_source, |_, _, state: &mut RuntimeState<T>| {
@@ -51,21 +88,143 @@ impl<T: Application + 'static> EventLoopRequests<T> for EventLoop<T> {
                 user.user_wakeup(winit);
             });
 
+        let _ = event_loop.handle().insert_source(
+            exit_ping_source,
+            |_, _, state: &mut RuntimeState<T>| {
+                let winit = &mut state.winit;
+                let user = &mut state.user.as_mut().unwrap();
+                if user.exit_requested(winit) {
+                    winit.exit = true;
+                }
+            },
+        );
+
         let mut state = RuntimeState {
             user: None,
-            winit: WinitState::new(connection.clone(), &globals, &queue_handle, proxy).unwrap(),
+            winit: WinitState::new(
+                connection.clone(),
+                &globals,
+                &queue_handle,
+                event_loop.handle(),
+                proxy,
+            )
+            .unwrap(),
             vtable: Vtable::default(),
         };
 
         let _ = event_queue.roundtrip(&mut state);
 
-        let wayland_source = WaylandSource::new(connection, event_queue);
-        wayland_source.insert(event_loop.handle()).unwrap();
+        let startup = &mut state.winit.startup;
+        startup.started_at = started_at;
+        startup.timings.connected = Some(connected);
+        startup.timings.globals_bound = Some(started_at.elapsed());
+
+        // Read the socket until it's drained or the dispatch budget is spent,
+        // the rest wakes up the next iteration right away.
+        let fd = connection.backend().poll_fd().try_clone_to_owned().unwrap();
+        let wayland_source = Generic::new(fd, Interest::READ, Mode::Level);
+        let _ = event_loop.handle().insert_source(wayland_source, move |_, _, state| loop {
+            event_queue.dispatch_pending(state).map_err(io::Error::other)?;
+            if !state.winit.dispatch.try_continue() {
+                return Ok(PostAction::Continue);
+            }
+
+            let Some(guard) = event_queue.prepare_read() else {
+                continue;
+            };
+
+            match guard.read() {
+                Ok(_) => (),
+                Err(WaylandError::Io(err)) if err.kind() == io::ErrorKind::WouldBlock => {
+                    return Ok(PostAction::Continue);
+                },
+                Err(err) => return Err(io::Error::other(err)),
+            }
+        });
+
+        Ok(Self {
+            event_loop,
+            state,
+            globals,
+            role_handlers: Vec::new(),
+            #[cfg(feature = "tokio")]
+            tokio: None,
+            #[cfg(feature = "single-instance")]
+            single_instance: None,
+        })
+    }
+
+    /// Keep the application single-instance under the DBus name `app_id`.
+    ///
+    /// When the name is already owned, the command line of this process is
+    /// forwarded to the running instance, which gets it with
+    /// [`Application::activated`], and [`SingleInstance::Forwarded`] is
+    /// returned. The application should exit right after that.
+    #[cfg(feature = "single-instance")]
+    pub fn claim_single_instance(
+        &mut self,
+        app_id: &str,
+    ) -> Result<SingleInstance, SingleInstanceError> {
+        let (sender, channel) = calloop::channel::channel();
+        let connection = match single_instance::serve(app_id, sender)? {
+            Some(connection) => connection,
+            None => {
+                let activation_token = self.state.winit.activation_token.take();
+                single_instance::forward(app_id, activation_token)?;
+                return Ok(SingleInstance::Forwarded);
+            },
+        };
+
+        self.event_loop
+            .handle()
+            .insert_source(channel, |event, _, state: &mut RuntimeState<T>| {
+                let calloop::channel::Event::Msg(request) = event else {
+                    return;
+                };
+
+                // Raise the next created window with the token of the other instance.
+                if request.activation_token.is_some() {
+                    state.winit.activation_token = request.activation_token.clone();
+                }
+
+                let user = state.user.as_mut().unwrap();
+                user.activated(&mut state.winit, request);
+            })
+            .map_err(|err| SingleInstanceError::Io(err.error.into()))?;
 
-        Ok(Self { event_loop, state })
+        self.single_instance = Some(connection);
+        Ok(SingleInstance::Primary)
     }
 
-    fn run(mut self, mut state: T) {
+    /// Exit gracefully on the `SIGINT` and the `SIGTERM`.
+    ///
+    /// The signals are delivered as the [`EventLoopProxy::request_exit`], so
+    /// the application could cancel the exit with the
+    /// [`Application::exit_requested`].
+    ///
+    /// The signals are blocked for the calling thread, so this should be
+    /// called before spawning the other threads, which inherit the mask.
+    /// Otherwise the signal could be delivered to them instead, terminating the
+    /// process.
+    ///
+    /// [`EventLoopProxy::request_exit`]: CoreEventLoopProxy::request_exit
+    #[cfg(feature = "signals")]
+    pub fn handle_exit_signals(&mut self) -> io::Result<()> {
+        use calloop::signals::{Signal, Signals};
+
+        let signals = Signals::new(&[Signal::SIGINT, Signal::SIGTERM])?;
+
+        let proxy = self.state.winit.proxy.clone();
+        self.event_loop
+            .handle()
+            .insert_source(signals, move |_, _, _| proxy.request_exit())
+            .map_err(|err| io::Error::from(err.error))?;
+
+        Ok(())
+    }
+
+    /// Run the event loop.
+    pub fn run(mut self, mut state: T) {
         // SAFETY: The user state is being used only inside the loop and can't have
         // Wayland objects in it. Calloop itself allow the state to have a
         // non-static lifetime attached to it, however wayland-rs forces bound
@@ -82,33 +241,140 @@ impl<T: Application + 'static> EventLoopRequests<T> for EventLoop<T> {
             .unwrap()
             .new_events(&mut self.state.winit, winit_core::application::StartCause::Init);
 
+        // Let the callbacks use the libraries built on top of the runtime.
+        #[cfg(feature = "tokio")]
+        let tokio_handle = self.tokio.as_ref().map(|tokio| tokio.handle().clone());
+        #[cfg(feature = "tokio")]
+        let _tokio_guard = tokio_handle.as_ref().map(tokio::runtime::Handle::enter);
+
         let mut redraw = Vec::new();
 
         loop {
-            if self.state.winit.connection.flush().is_err() {
-                return;
+            // Apply the latest of the configures received during the dispatch.
+            self.state.apply_pending_configures();
+
+            if let Err(err) = self.state.winit.connection.flush() {
+                self.disconnected(err.to_string());
+                break;
             }
 
             let winit = &mut self.state.winit;
             let user = self.state.user.as_mut().unwrap();
 
+            // Create the surfaces with the custom roles.
+            for handler in &mut self.role_handlers {
+                for role in handler.create_pending(&winit.compositor, &winit.queue_handle) {
+                    let window_id = role.as_surface().id();
+                    debug_assert!(
+                        !winit.windows.contains_key(&window_id),
+                        "{window_id:?} is already in use"
+                    );
+                    winit.tombstones.revive(window_id);
+                    winit.roles.insert(window_id, role);
+                }
+            }
+
+            // Deliver the compressed pointer motion before redrawing.
+            winit.flush_all_pointer_motion(user);
+
+            winit.deliver_idle_reset(user);
+            winit.request_activations(user);
+            winit.update_window_groups();
+            winit.update_cursors();
+
+            // Deliver the redraws of the windows which are due, the rest are
+            // delivered on their own frame callbacks.
+            let now = Instant::now();
+            let mut occluded = SmallVec::<[(WindowId, bool); 2]>::new();
+            let mut rescaled = SmallVec::<[(WindowId, f64); 2]>::new();
+            let mut resized = SmallVec::<[(WindowId, PhysicalSize<u32>); 2]>::new();
             for (window_id, window) in &mut winit.windows {
-                if mem::take(&mut window.redraw) {
+                window.restack_overlay_planes();
+
+                // The changes before the initial configure come along with it.
+                if window.configured() && mem::take(&mut window.scale_changed) {
+                    rescaled.push((*window_id, window.scale_factor));
+                }
+
+                if window.configured() && mem::take(&mut window.size_changed) {
+                    resized.push((*window_id, window.inner_size()));
+                }
+
+                let throttled = window.is_throttled();
+                if mem::replace(&mut window.throttled, throttled) != throttled {
+                    occluded.push((*window_id, throttled));
+                }
+
+                if window.redraw && window.frame_deadline() <= now {
+                    // Apply the staged size along with the content drawn for it.
+                    window.apply_pending_resize();
+                    window.redraw = false;
+                    window.request_frame_callback(&winit.queue_handle);
                     redraw.push(*window_id);
+                } else if window.pending_resize_deadline().is_some_and(|deadline| deadline <= now) {
+                    // The application didn't redraw in time, so apply the size with
+                    // the old content.
+                    window.apply_pending_resize();
+                    window.window.wl_surface().commit();
                 }
             }
 
+            for (window_id, role) in &mut winit.roles {
+                if winit.configured_roles.contains(window_id) && role.take_redraw() {
+                    redraw.push(*window_id);
+                }
+            }
+
+            for (window_id, occluded) in occluded {
+                user.occluded(winit, window_id, occluded);
+            }
+
+            for (window_id, scale_factor) in rescaled {
+                user.scale_factor_changed(winit, window_id, scale_factor);
+            }
+
+            for (window_id, size) in resized {
+                user.resized(winit, window_id, size);
+            }
+
             // Issue synthetic redraws issued by users.
             for window_id in redraw.drain(..) {
                 user.redraw_requested(winit, window_id)
             }
 
-            self.state.user.as_mut().unwrap().about_to_wait(&mut self.state.winit);
+            // Poll the local futures between the dispatches, the ones over the
+            // budget are polled in the next iteration without blocking.
+            #[cfg(feature = "tokio")]
+            let tokio_pending = self.tokio.as_ref().is_some_and(TokioState::poll);
+            #[cfg(not(feature = "tokio"))]
+            let tokio_pending = false;
 
-            // TODO: we should handle waking up for the next iteration due to
-            // redraw-requested here.
+            self.state.user.as_mut().unwrap().about_to_wait(&mut self.state.winit);
 
-            self.event_loop.dispatch(None, &mut self.state).unwrap();
+            // Wake up for the earliest pending redraw, staged size, configure or
+            // throttle.
+            let timeout = self
+                .state
+                .winit
+                .windows
+                .values()
+                .flat_map(|window| {
+                    let redraw = window.redraw.then(|| window.frame_deadline());
+                    redraw
+                        .into_iter()
+                        .chain(window.pending_resize_deadline())
+                        .chain(window.pending_configure_deadline())
+                        .chain(window.throttle_deadline())
+                })
+                .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+                .min();
+            let timeout = if tokio_pending { Some(Duration::ZERO) } else { timeout };
+
+            self.state.winit.dispatch.start_iteration();
+            if let Err(err) = self.event_loop.dispatch(timeout, &mut self.state) {
+                self.disconnected(err.to_string());
+                break;
+            }
 
             if self.state.winit.exit {
                 break;
@@ -118,11 +384,71 @@ impl<T: Application + 'static> EventLoopRequests<T> for EventLoop<T> {
         self.state.user.as_mut().unwrap().loop_exiting(&mut self.state.winit);
     }
 
-    fn proxy(&self) -> Arc<dyn CoreEventLoopProxy> {
+    /// Get the proxy to wakeup the event loop.
+    pub fn proxy(&self) -> Arc<dyn CoreEventLoopProxy> {
         self.state.winit.proxy()
     }
 }
 
+impl EventLoopRequests for EventLoop {
+    fn new() -> Result<Self, EventLoopError> {
+        EventLoop::new()
+    }
+
+    fn run(self: Box<Self>, app: Box<dyn Application>) {
+        EventLoop::run(*self, app)
+    }
+
+    fn proxy(&self) -> Arc<dyn CoreEventLoopProxy> {
+        EventLoop::proxy(self)
+    }
+}
+
+impl<T: Application + 'static> EventLoop<T> {
+    /// Notify the user that the connection is gone and destroy all the windows.
+    fn disconnected(&mut self, reason: String) {
+        let winit = &mut self.state.winit;
+        let user = self.state.user.as_mut().unwrap();
+
+        let protocol_error = winit.connection.protocol_error().map(|error| ProtocolError {
+            code: error.code,
+            object_id: error.object_id,
+            object_interface: error.object_interface,
+            message: error.message,
+        });
+
+        user.backend_disconnected(winit, BackendDisconnected { reason, protocol_error });
+
+        let window_ids: Vec<WindowId> = winit.windows.keys().copied().collect();
+        for window_id in window_ids {
+            drop(winit.windows.remove(&window_id));
+            winit.tombstones.bury(window_id);
+            user.destroyed(winit, window_id);
+        }
+
+        let window_ids: Vec<WindowId> = winit.roles.keys().copied().collect();
+        for window_id in window_ids {
+            drop(winit.roles.remove(&window_id));
+            winit.tombstones.bury(window_id);
+            if winit.configured_roles.remove(&window_id) {
+                user.destroyed(winit, window_id);
+            }
+        }
+    }
+
+    /// Register the handler for the custom surface role.
+    ///
+    /// The globals required by the role are bound right away.
+    pub fn register_role_handler(
+        &mut self,
+        mut handler: Box<dyn RoleHandler<T>>,
+    ) -> Result<(), BindError> {
+        handler.bind(&self.globals, &self.state.winit.queue_handle)?;
+        self.role_handlers.push(handler);
+        Ok(())
+    }
+}
+
 impl<T: Application + 'static + MyCoolTrait> EventLoop<T> {
     /// This sets up handelr for `MyCoolTrait` but doesn't force it through-out the codebase.
     pub fn register_my_cool_trait_handler(&mut self) {
@@ -143,14 +469,14 @@ unsafe impl<T: Application + 'static> HasRawDisplayHandle05 for EventLoop<T> {
 }
 
 /// Runtime state passed around.
-pub(crate) struct RuntimeState<T: Application + 'static> {
+pub struct RuntimeState<T: Application + 'static> {
     /// The user state we're using during the runtime.
-    pub user: Option<&'static mut T>,
+    pub(crate) user: Option<&'static mut T>,
 
     /// The state of the winit.
-    pub winit: WinitState<T>,
+    pub(crate) winit: WinitState<T>,
 
-    pub vtable: Vtable<T>,
+    pub(crate) vtable: Vtable<T>,
 }
 
 pub struct Vtable<T: Application + 'static> {
@@ -165,11 +491,15 @@ impl<T: Application + 'static> Default for Vtable<T> {
 
 pub struct EventLoopProxy {
     ping: Ping,
+
+    /// The ping of the [`CoreEventLoopProxy::request_exit`], the requests made
+    /// before the loop got to them are merged.
+    exit_ping: Ping,
 }
 
 impl EventLoopProxy {
-    fn new(ping: Ping) -> Self {
-        Self { ping }
+    fn new(ping: Ping, exit_ping: Ping) -> Self {
+        Self { ping, exit_ping }
     }
 }
 
@@ -177,4 +507,12 @@ impl CoreEventLoopProxy for EventLoopProxy {
     fn wakeup(&self) {
         self.ping.ping();
     }
+
+    fn request_exit(&self) {
+        self.exit_ping.ping();
+    }
+}
+
+fn os_error(err: impl std::fmt::Display) -> EventLoopError {
+    EventLoopError::Os(err.to_string())
 }
@@ -0,0 +1,181 @@
+//! The explicit synchronization of the GPU rendered buffers, with the
+//! linux-drm-syncobj protocol.
+//!
+//! The renderer passes the timeline point the compositor waits on before
+//! reading the buffer, and the point the compositor signals once it's done
+//! with it, instead of relying on the implicit synchronization of the
+//! dma-bufs.
+//!
+//! The synchronization is used through the [`WaylandEventLoopExt`].
+//!
+//! To get notified of the buffer release without blocking the event loop,
+//! create the eventfd for the release point with the
+//! `DRM_IOCTL_SYNCOBJ_EVENTFD` and wait for it with the
+//! [`EventLoopHandle::register_fd`].
+//!
+//! [`WaylandEventLoopExt`]: crate::protocols::WaylandEventLoopExt
+//! [`EventLoopHandle::register_fd`]: winit_core::event_loop::EventLoopHandle::register_fd
+
+use std::error::Error;
+use std::fmt;
+
+use sctk::globals::GlobalData;
+use sctk::reexports::client::{Connection, Dispatch, QueueHandle};
+use sctk::shell::WaylandSurface;
+use wayland_protocols::wp::linux_drm_syncobj::v1::client::wp_linux_drm_syncobj_manager_v1::{
+    self, WpLinuxDrmSyncobjManagerV1,
+};
+use wayland_protocols::wp::linux_drm_syncobj::v1::client::wp_linux_drm_syncobj_surface_v1::{
+    self, WpLinuxDrmSyncobjSurfaceV1,
+};
+use wayland_protocols::wp::linux_drm_syncobj::v1::client::wp_linux_drm_syncobj_timeline_v1::{
+    self, WpLinuxDrmSyncobjTimelineV1,
+};
+
+use winit_core::application::Application;
+use winit_core::window::WindowError;
+
+use crate::event_loop::RuntimeState;
+use crate::window::Window;
+
+impl<T: Application + 'static> Window<T> {
+    /// Commit the buffer attached by the renderer along with its `acquire`
+    /// and `release` points.
+    pub(crate) fn commit_with_sync(
+        &mut self,
+        acquire: SyncPoint<'_>,
+        release: SyncPoint<'_>,
+    ) -> Result<(), SyncError> {
+        // The configure is acked along with this commit.
+        self.apply_pending_resize();
+
+        let surface = self.window.wl_surface().clone();
+        if self.syncobj_surface.is_none() {
+            let manager = self.syncobj_manager.as_ref().ok_or(SyncError::Unsupported)?;
+            self.syncobj_surface =
+                Some(manager.get_surface(&surface, &self.queue_handle, GlobalData));
+        }
+
+        let syncobj_surface = self.syncobj_surface.as_ref().unwrap();
+        let (hi, lo) = acquire.split();
+        syncobj_surface.set_acquire_point(&acquire.timeline.timeline, hi, lo);
+        let (hi, lo) = release.split();
+        syncobj_surface.set_release_point(&release.timeline.timeline, hi, lo);
+
+        surface.commit();
+        Ok(())
+    }
+}
+
+/// The error of the explicit synchronization.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SyncError {
+    /// The compositor doesn't support the linux-drm-syncobj protocol.
+    Unsupported,
+
+    /// The window to commit is not usable.
+    Window(WindowError),
+}
+
+impl fmt::Display for SyncError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported => f.write_str("the explicit synchronization is not supported"),
+            Self::Window(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for SyncError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Unsupported => None,
+            Self::Window(err) => Some(err),
+        }
+    }
+}
+
+/// The DRM synchronization object imported with the
+/// [`WaylandEventLoopExt::import_timeline`].
+///
+/// [`WaylandEventLoopExt::import_timeline`]: crate::protocols::WaylandEventLoopExt::import_timeline
+#[derive(Debug)]
+pub struct SyncTimeline {
+    timeline: WpLinuxDrmSyncobjTimelineV1,
+}
+
+impl SyncTimeline {
+    pub(crate) fn new(timeline: WpLinuxDrmSyncobjTimelineV1) -> Self {
+        Self { timeline }
+    }
+}
+
+impl Drop for SyncTimeline {
+    fn drop(&mut self) {
+        self.timeline.destroy();
+    }
+}
+
+/// The point on the [`SyncTimeline`].
+#[derive(Debug, Clone, Copy)]
+pub struct SyncPoint<'a> {
+    pub timeline: &'a SyncTimeline,
+    pub point: u64,
+}
+
+impl<'a> SyncPoint<'a> {
+    pub fn new(timeline: &'a SyncTimeline, point: u64) -> Self {
+        Self { timeline, point }
+    }
+
+    /// The high and low 32 bits of the point, as sent over the wire.
+    fn split(&self) -> (u32, u32) {
+        ((self.point >> 32) as u32, self.point as u32)
+    }
+}
+
+impl<T: Application + 'static> Dispatch<WpLinuxDrmSyncobjManagerV1, GlobalData>
+    for RuntimeState<T>
+{
+    fn event(
+        _: &mut Self,
+        _: &WpLinuxDrmSyncobjManagerV1,
+        _: wp_linux_drm_syncobj_manager_v1::Event,
+        _: &GlobalData,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        // No events.
+    }
+}
+
+impl<T: Application + 'static> Dispatch<WpLinuxDrmSyncobjSurfaceV1, GlobalData>
+    for RuntimeState<T>
+{
+    fn event(
+        _: &mut Self,
+        _: &WpLinuxDrmSyncobjSurfaceV1,
+        _: wp_linux_drm_syncobj_surface_v1::Event,
+        _: &GlobalData,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        // No events.
+    }
+}
+
+impl<T: Application + 'static> Dispatch<WpLinuxDrmSyncobjTimelineV1, GlobalData>
+    for RuntimeState<T>
+{
+    fn event(
+        _: &mut Self,
+        _: &WpLinuxDrmSyncobjTimelineV1,
+        _: wp_linux_drm_syncobj_timeline_v1::Event,
+        _: &GlobalData,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        // No events.
+    }
+}
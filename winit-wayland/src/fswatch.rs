@@ -0,0 +1,179 @@
+//! Watching the paths for changes with inotify on the event loop thread.
+
+use std::collections::HashMap;
+use std::io;
+use std::os::fd::{AsRawFd, BorrowedFd};
+use std::path::{Path, PathBuf};
+
+use inotify::{EventMask, Inotify, WatchDescriptor, WatchMask};
+use sctk::reexports::calloop::generic::Generic;
+use sctk::reexports::calloop::{Interest, LoopHandle, Mode, PostAction, RegistrationToken};
+use smallvec::SmallVec;
+
+use winit_core::application::Application;
+use winit_core::event_loop::{FileChange, FileChangeKind, WatchId};
+
+use crate::event_loop::RuntimeState;
+
+/// The events mapped to the [`FileChangeKind`].
+///
+/// The modification is reported once the file is closed, so the partially
+/// written files aren't picked up.
+const WATCH_MASK: WatchMask = WatchMask::CLOSE_WRITE
+    .union(WatchMask::CREATE)
+    .union(WatchMask::MOVED_TO)
+    .union(WatchMask::DELETE)
+    .union(WatchMask::MOVED_FROM)
+    .union(WatchMask::DELETE_SELF)
+    .union(WatchMask::MOVE_SELF);
+
+/// The paths watched by the user.
+pub(crate) struct FileWatcher<T: Application + 'static> {
+    inotify: Inotify,
+
+    /// The watched paths along with the ids they were watched with, the same
+    /// path watched twice shares the descriptor.
+    watches: HashMap<WatchDescriptor, (PathBuf, SmallVec<[WatchId; 1]>)>,
+
+    ids: HashMap<WatchId, WatchDescriptor>,
+
+    loop_handle: LoopHandle<'static, RuntimeState<T>>,
+    registration: RegistrationToken,
+}
+
+impl<T: Application + 'static> FileWatcher<T> {
+    pub(crate) fn new(loop_handle: &LoopHandle<'static, RuntimeState<T>>) -> io::Result<Self> {
+        let inotify = Inotify::init()?;
+
+        // SAFETY: the source is removed before the inotify is closed.
+        let fd = unsafe { BorrowedFd::borrow_raw(inotify.as_raw_fd()) };
+        let source = Generic::new(fd, Interest::READ, Mode::Level);
+        let registration = loop_handle
+            .insert_source(source, |_, _, state| {
+                let changes = match state.winit.file_watcher.as_mut() {
+                    Some(file_watcher) => file_watcher.read_changes(),
+                    None => return Ok(PostAction::Remove),
+                };
+
+                let winit = &mut state.winit;
+                let user = state.user.as_mut().unwrap();
+                for (watch_id, change) in changes {
+                    user.file_changed(winit, watch_id, change);
+                }
+
+                Ok(PostAction::Continue)
+            })
+            .map_err(|err| io::Error::from(err.error))?;
+
+        Ok(Self {
+            inotify,
+            watches: Default::default(),
+            ids: Default::default(),
+            loop_handle: loop_handle.clone(),
+            registration,
+        })
+    }
+
+    pub(crate) fn watch(&mut self, path: &Path, watch_id: WatchId) -> io::Result<()> {
+        let descriptor = self.inotify.watches().add(path, WATCH_MASK)?;
+
+        let (_, watch_ids) = self
+            .watches
+            .entry(descriptor.clone())
+            .or_insert_with(|| (path.into(), SmallVec::new()));
+        watch_ids.push(watch_id);
+        self.ids.insert(watch_id, descriptor);
+
+        Ok(())
+    }
+
+    pub(crate) fn unwatch(&mut self, watch_id: WatchId) {
+        let descriptor = match self.ids.remove(&watch_id) {
+            Some(descriptor) => descriptor,
+            None => return,
+        };
+
+        let unused = match self.watches.get_mut(&descriptor) {
+            Some((_, watch_ids)) => {
+                watch_ids.retain(|id| *id != watch_id);
+                watch_ids.is_empty()
+            },
+            None => false,
+        };
+
+        if unused {
+            self.watches.remove(&descriptor);
+            let _ = self.inotify.watches().remove(descriptor);
+        }
+    }
+
+    /// Read the pending events without blocking.
+    fn read_changes(&mut self) -> Vec<(WatchId, FileChange)> {
+        let mut buffer = [0; 4096];
+        let mut changes = Vec::new();
+
+        loop {
+            let events = match self.inotify.read_events(&mut buffer) {
+                Ok(events) => events,
+                Err(_) => break,
+            };
+
+            let mut read = false;
+            for event in events {
+                read = true;
+
+                // The watch is gone along with the path.
+                if event.mask.contains(EventMask::IGNORED) {
+                    if let Some((_, watch_ids)) = self.watches.remove(&event.wd) {
+                        for watch_id in watch_ids {
+                            self.ids.remove(&watch_id);
+                        }
+                    }
+
+                    continue;
+                }
+
+                let kind = if event.mask.contains(EventMask::CLOSE_WRITE) {
+                    FileChangeKind::Modified
+                } else if event.mask.intersects(EventMask::CREATE | EventMask::MOVED_TO) {
+                    FileChangeKind::Created
+                } else if event.mask.intersects(
+                    EventMask::DELETE
+                        | EventMask::MOVED_FROM
+                        | EventMask::DELETE_SELF
+                        | EventMask::MOVE_SELF,
+                ) {
+                    FileChangeKind::Removed
+                } else {
+                    continue;
+                };
+
+                let (path, watch_ids) = match self.watches.get(&event.wd) {
+                    Some(watch) => watch,
+                    None => continue,
+                };
+
+                let path = match event.name {
+                    Some(name) => path.join(name),
+                    None => path.clone(),
+                };
+
+                for watch_id in watch_ids {
+                    changes.push((*watch_id, FileChange { path: path.clone(), kind }));
+                }
+            }
+
+            if !read {
+                break;
+            }
+        }
+
+        changes
+    }
+}
+
+impl<T: Application + 'static> Drop for FileWatcher<T> {
+    fn drop(&mut self) {
+        self.loop_handle.remove(self.registration);
+    }
+}
@@ -0,0 +1,97 @@
+//! Allocation of the ids handed to the application.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use sctk::reexports::client::backend::ObjectId;
+
+/// The allocator of the ids for the protocol objects.
+///
+/// The ids derived from the proxy pointers are reused along with the memory,
+/// so the events of the new object could be delivered with the id of the
+/// destroyed one. Instead, the ids are allocated from the slots with the
+/// generation bumped on each reuse, and mapped from the objects.
+///
+/// The generation is stored in the upper half of the id and starts with 1, so
+/// the allocated ids never collide with the ones derived from the pointers.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct IdAllocator {
+    inner: Arc<Mutex<IdAllocatorInner>>,
+}
+
+#[derive(Debug, Default)]
+struct IdAllocatorInner {
+    /// The current generation of each slot.
+    generations: Vec<u64>,
+
+    /// The slots of the released ids.
+    free: Vec<usize>,
+
+    /// The ids of the live objects.
+    objects: HashMap<ObjectId, u128>,
+}
+
+impl IdAllocator {
+    /// Allocate the id for the `object`.
+    pub(crate) fn allocate(&self, object: ObjectId) -> u128 {
+        let mut inner = self.inner.lock().unwrap();
+        debug_assert!(!inner.objects.contains_key(&object), "{object} already has an id");
+
+        let index = match inner.free.pop() {
+            Some(index) => index,
+            None => {
+                inner.generations.push(1);
+                inner.generations.len() - 1
+            },
+        };
+
+        let id = make_id(index, inner.generations[index]);
+        inner.objects.insert(object, id);
+        id
+    }
+
+    /// Get the id of the `object`.
+    pub(crate) fn get(&self, object: &ObjectId) -> Option<u128> {
+        self.inner.lock().unwrap().objects.get(object).copied()
+    }
+
+    /// Move the id over to the `new` object, so the id stays the same when the
+    /// object is recreated.
+    pub(crate) fn rebind(&self, old: &ObjectId, new: ObjectId) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(id) = inner.objects.remove(old) {
+            debug_assert!(!inner.objects.contains_key(&new), "{new} already has an id");
+            inner.objects.insert(new, id);
+        }
+    }
+
+    /// Whether the `id` is allocated and not yet released.
+    pub(crate) fn is_live(&self, id: u128) -> bool {
+        let inner = self.inner.lock().unwrap();
+        let (index, generation) = split_id(id);
+        inner.generations.get(index) == Some(&generation)
+            && inner.objects.values().any(|&live| live == id)
+    }
+
+    /// Release the `id`, so its slot could be reused with the next generation.
+    pub(crate) fn release(&self, id: u128) {
+        let mut inner = self.inner.lock().unwrap();
+        let (index, generation) = split_id(id);
+        if inner.generations.get(index) != Some(&generation) {
+            debug_assert!(false, "the id {id:#x} is released twice");
+            return;
+        }
+
+        inner.objects.retain(|_, &mut live| live != id);
+        inner.generations[index] += 1;
+        inner.free.push(index);
+    }
+}
+
+fn make_id(index: usize, generation: u64) -> u128 {
+    (generation as u128) << 64 | index as u128
+}
+
+fn split_id(id: u128) -> (usize, u64) {
+    ((id as u64) as usize, (id >> 64) as u64)
+}
@@ -2,39 +2,51 @@
 #![allow(dead_code)]
 #![allow(unused_variables)]
 
-use sctk::reexports::client::protocol::wl_output::WlOutput;
+use sctk::reexports::client::protocol::wl_seat::WlSeat;
 use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::reexports::client::Proxy;
 
-use winit_core::dpi::{LogicalSize, PhysicalSize};
-use winit_core::monitor::MonitorId;
+use winit_core::input::DeviceId;
 use winit_core::window::WindowId;
 
+pub mod clipboard;
+pub mod diagnostics;
 pub mod event_loop;
+pub mod explicit_sync;
+#[cfg(feature = "fswatch")]
+mod fswatch;
+mod ids;
 pub mod monitor;
+pub mod overlay;
+pub mod presenter;
+pub mod protocols;
+mod rate_limit;
+pub mod role;
+pub mod seat;
+#[cfg(feature = "single-instance")]
+pub mod single_instance;
 pub mod state;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "tokio")]
+pub mod tokio_runtime;
 pub mod window;
 
 /// Get the WindowId out of the surface.
+///
+/// Only used for the surfaces not created by the backend, use
+/// [`WinitState::window_id`] for the rest.
+///
+/// [`WinitState::window_id`]: crate::state::WinitState::window_id
 #[inline]
 pub(crate) fn make_wid(surface: &WlSurface) -> WindowId {
     WindowId(surface.id().as_ptr() as u128)
 }
 
-/// Get the WindowId out of the surface.
+/// Get the DeviceId out of the seat.
 #[inline]
-pub(crate) fn make_mid(output: &WlOutput) -> MonitorId {
-    MonitorId(output.id().as_ptr() as u128)
-}
-
-/// The default routine does floor, but we need round on Wayland.
-pub(crate) fn logical_to_physical_rounded(
-    size: LogicalSize<u32>,
-    scale_factor: f64,
-) -> PhysicalSize<u32> {
-    let width = size.width as f64 * scale_factor;
-    let height = size.height as f64 * scale_factor;
-    (width.round(), height.round()).into()
+pub(crate) fn make_did(seat: &WlSeat) -> DeviceId {
+    DeviceId(seat.id().as_ptr() as u128)
 }
 
 use winit_core::application::Application;
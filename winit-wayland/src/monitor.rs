@@ -1,29 +1,76 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use sctk::globals::GlobalData;
+use sctk::reexports::client::backend::ObjectId;
 use sctk::reexports::client::protocol::wl_output::WlOutput;
-use sctk::reexports::client::Proxy;
+use sctk::reexports::client::{event_created_child, Dispatch, Proxy, WEnum};
 
 use sctk::output::{OutputData, OutputHandler, OutputState};
 
+use smallvec::SmallVec;
 use wayland_client::{Connection, QueueHandle};
+use wayland_protocols_wlr::output_management::v1::client::zwlr_output_head_v1::{
+    self, AdaptiveSyncState, ZwlrOutputHeadV1,
+};
+use wayland_protocols_wlr::output_management::v1::client::zwlr_output_manager_v1::{
+    self, ZwlrOutputManagerV1,
+};
+use wayland_protocols_wlr::output_management::v1::client::zwlr_output_mode_v1::{
+    self, ZwlrOutputModeV1,
+};
+use wayland_protocols_wlr::output_power_management::v1::client::zwlr_output_power_manager_v1::ZwlrOutputPowerManagerV1;
+use wayland_protocols_wlr::output_power_management::v1::client::zwlr_output_power_v1::{
+    self, ZwlrOutputPowerV1,
+};
 use winit_core::application::Application;
 use winit_core::dpi::{LogicalPosition, PhysicalPosition, PhysicalSize};
-use winit_core::monitor::{Monitor as CoreMonitor, MonitorId};
+use winit_core::monitor::{AdaptiveSync, Monitor as CoreMonitor, MonitorId, PowerState};
 
 use crate::event_loop::RuntimeState;
+use crate::ids::IdAllocator;
+use crate::state::WinitState;
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct Monitor {
     pub(crate) output: WlOutput,
+    id: MonitorId,
+
+    /// The object tracking the power state, when wlr-output-power-management
+    /// is available.
+    power: Option<ZwlrOutputPowerV1>,
+
+    power_state: Option<PowerState>,
+
+    /// The state reported by the matching wlr-output-management head.
+    adaptive_sync: Option<AdaptiveSync>,
 }
 
 impl Monitor {
-    pub(crate) fn new(output: WlOutput) -> Self {
-        Self { output }
+    pub(crate) fn new<T: Application + 'static>(
+        output: WlOutput,
+        registry: &MonitorRegistry,
+        power_manager: Option<&ZwlrOutputPowerManagerV1>,
+        queue_handle: &QueueHandle<RuntimeState<T>>,
+    ) -> Self {
+        let id = registry.id(&output);
+        let power = power_manager
+            .map(|manager| manager.get_output_power(&output, queue_handle, output.clone()));
+        Self { output, id, power, power_state: None, adaptive_sync: None }
+    }
+}
+
+impl Drop for Monitor {
+    fn drop(&mut self) {
+        if let Some(power) = self.power.take() {
+            power.destroy();
+        }
     }
 }
 
 impl CoreMonitor for Monitor {
     fn id(&self) -> MonitorId {
-        crate::make_mid(&self.output)
+        self.id
     }
 
     fn name(&self) -> Option<String> {
@@ -31,6 +78,11 @@ impl CoreMonitor for Monitor {
         output_data.with_output_info(|info| info.name.clone())
     }
 
+    fn connection_name(&self) -> Option<String> {
+        let output_data = self.output.data::<OutputData>().unwrap();
+        output_data.with_output_info(|info| info.name.clone())
+    }
+
     fn size(&self) -> PhysicalSize<u32> {
         let output_data = self.output.data::<OutputData>().unwrap();
         let dimensions = output_data.with_output_info(|info| {
@@ -61,16 +113,88 @@ impl CoreMonitor for Monitor {
     }
 
     fn refresh_rate_millihertz(&self) -> Option<u32> {
-        let output_data = self.output.data::<OutputData>().unwrap();
-        output_data.with_output_info(|info| {
-            info.modes.iter().find_map(|mode| mode.current.then_some(mode.refresh_rate as u32))
-        })
+        refresh_rate_millihertz(&self.output)
     }
 
     fn scale_factor(&self) -> f64 {
         let output_data = self.output.data::<OutputData>().unwrap();
         output_data.scale_factor() as f64
     }
+
+    fn adaptive_sync(&self) -> Option<AdaptiveSync> {
+        self.adaptive_sync
+    }
+
+    fn power_state(&self) -> Option<PowerState> {
+        self.power_state
+    }
+}
+
+/// The refresh rate of the current mode of the `output`.
+pub(crate) fn refresh_rate_millihertz(output: &WlOutput) -> Option<u32> {
+    let output_data = output.data::<OutputData>()?;
+    output_data.with_output_info(|info| {
+        info.modes.iter().find_map(|mode| mode.current.then_some(mode.refresh_rate as u32))
+    })
+}
+
+/// The registry keeping the monitor ids stable across reconnects.
+///
+/// The ids are derived from the output proxies, so the same physical monitor
+/// would get a new id once reconnected. To prevent that the ids are mapped
+/// from the connector names of the outputs instead.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct MonitorRegistry {
+    inner: Arc<Mutex<MonitorRegistryInner>>,
+}
+
+#[derive(Debug, Default)]
+struct MonitorRegistryInner {
+    ids: HashMap<String, MonitorId>,
+    next_id: u128,
+
+    /// The ids of the outputs without the name.
+    unnamed: IdAllocator,
+}
+
+impl MonitorRegistry {
+    /// Get the stable id for the `output`.
+    pub(crate) fn id(&self, output: &WlOutput) -> MonitorId {
+        // Only the connector name is unique among the outputs, the description
+        // is the same for the identical monitors.
+        let name = output
+            .data::<OutputData>()
+            .and_then(|data| data.with_output_info(|info| info.name.clone()));
+
+        let mut inner = self.inner.lock().unwrap();
+
+        // Fallback to the id of the output object when it doesn't have a name.
+        let name = match name {
+            Some(name) => name,
+            None => {
+                let id = inner.unnamed.get(&output.id());
+                let id = id.unwrap_or_else(|| inner.unnamed.allocate(output.id()));
+                return MonitorId(id);
+            },
+        };
+
+        let next_id = inner.next_id;
+        let id = *inner.ids.entry(name).or_insert(MonitorId(next_id));
+        if id.0 == next_id {
+            inner.next_id += 1;
+        }
+
+        id
+    }
+
+    /// Release the id of the `output` without the name, the ids of the named
+    /// outputs are kept for when they're reconnected.
+    pub(crate) fn release(&self, output: &WlOutput) {
+        let inner = self.inner.lock().unwrap();
+        if let Some(id) = inner.unnamed.get(&output.id()) {
+            inner.unnamed.release(id);
+        }
+    }
 }
 
 impl<T: Application + 'static> OutputHandler for RuntimeState<T> {
@@ -78,18 +202,212 @@ impl<T: Application + 'static> OutputHandler for RuntimeState<T> {
         &mut self.winit.output_state
     }
 
-    fn new_output(&mut self, _: &Connection, _: &QueueHandle<Self>, output: WlOutput) {
-        self.winit.monitors.push(Monitor::new(output));
+    fn new_output(&mut self, _: &Connection, queue_handle: &QueueHandle<Self>, output: WlOutput) {
+        let winit = &mut self.winit;
+        let monitor = Monitor::new(
+            output,
+            &winit.monitor_registry,
+            winit.output_power_manager.as_ref(),
+            queue_handle,
+        );
+        winit.monitors.push(monitor);
+        winit.monitor_generation += 1;
+
+        // The head could be advertised before the output.
+        winit.apply_output_heads();
     }
 
     fn update_output(&mut self, _: &Connection, _: &QueueHandle<Self>, updated: WlOutput) {
-        // We dynamically load the output data from the proxy, thus we can
-        // ignore this implementation.
+        // We dynamically load the output data from the proxy, thus only
+        // notify about the change.
+        let monitor = self.winit.monitors.iter().find(|monitor| monitor.output == updated);
+        if let Some(monitor_id) = monitor.map(|monitor| monitor.id) {
+            self.winit.monitor_generation += 1;
+            let user = self.user.as_mut().unwrap();
+            user.monitor_changed(&mut self.winit, monitor_id);
+        }
     }
 
     fn output_destroyed(&mut self, _: &Connection, _: &QueueHandle<Self>, removed: WlOutput) {
+        self.winit.monitor_registry.release(&removed);
         self.winit.monitors.retain(|monitor| monitor.output != removed);
+        self.winit.monitor_generation += 1;
     }
 }
 
 sctk::delegate_output!(@<T: Application + 'static> RuntimeState<T>);
+
+/// The state of the wlr-output-management head.
+///
+/// The heads are matched with the monitors by the connector name.
+#[derive(Debug, Default)]
+pub(crate) struct OutputHead {
+    name: Option<String>,
+    adaptive_sync: Option<AdaptiveSync>,
+}
+
+impl<T: Application + 'static> WinitState<T> {
+    /// Apply the state of the heads to the monitors, returning the changed
+    /// ones.
+    pub(crate) fn apply_output_heads(&mut self) -> SmallVec<[MonitorId; 4]> {
+        let mut changed = SmallVec::new();
+        for monitor in &mut self.monitors {
+            let name = match monitor.connection_name() {
+                Some(name) => name,
+                None => continue,
+            };
+
+            let adaptive_sync = self
+                .output_heads
+                .values()
+                .find(|head| head.name.as_ref() == Some(&name))
+                .and_then(|head| head.adaptive_sync);
+
+            if monitor.adaptive_sync != adaptive_sync {
+                monitor.adaptive_sync = adaptive_sync;
+                changed.push(monitor.id);
+            }
+        }
+
+        if !changed.is_empty() {
+            self.monitor_generation += 1;
+        }
+
+        changed
+    }
+}
+
+impl<T: Application + 'static> Dispatch<ZwlrOutputPowerManagerV1, GlobalData> for RuntimeState<T> {
+    fn event(
+        _: &mut Self,
+        _: &ZwlrOutputPowerManagerV1,
+        _: <ZwlrOutputPowerManagerV1 as Proxy>::Event,
+        _: &GlobalData,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        // No events.
+    }
+}
+
+impl<T: Application + 'static> Dispatch<ZwlrOutputPowerV1, WlOutput> for RuntimeState<T> {
+    fn event(
+        state: &mut Self,
+        _: &ZwlrOutputPowerV1,
+        event: zwlr_output_power_v1::Event,
+        output: &WlOutput,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let power_state = match event {
+            zwlr_output_power_v1::Event::Mode { mode: WEnum::Value(mode) } => match mode {
+                zwlr_output_power_v1::Mode::On => Some(PowerState::On),
+                zwlr_output_power_v1::Mode::Off => Some(PowerState::Off),
+                _ => None,
+            },
+            // The power state can't be tracked anymore.
+            zwlr_output_power_v1::Event::Failed => None,
+            _ => return,
+        };
+
+        let monitor =
+            match state.winit.monitors.iter_mut().find(|monitor| monitor.output == *output) {
+                Some(monitor) => monitor,
+                None => return,
+            };
+
+        if monitor.power_state != power_state {
+            monitor.power_state = power_state;
+            let monitor_id = monitor.id;
+            state.winit.monitor_generation += 1;
+            let user = state.user.as_mut().unwrap();
+            user.monitor_changed(&mut state.winit, monitor_id);
+        }
+    }
+}
+
+impl<T: Application + 'static> Dispatch<ZwlrOutputManagerV1, GlobalData> for RuntimeState<T> {
+    fn event(
+        state: &mut Self,
+        _: &ZwlrOutputManagerV1,
+        event: zwlr_output_manager_v1::Event,
+        _: &GlobalData,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_output_manager_v1::Event::Head { head } => {
+                state.winit.output_heads.insert(head.id(), OutputHead::default());
+            },
+            zwlr_output_manager_v1::Event::Done { .. } => {
+                for monitor_id in state.winit.apply_output_heads() {
+                    let user = state.user.as_mut().unwrap();
+                    user.monitor_changed(&mut state.winit, monitor_id);
+                }
+            },
+            _ => (),
+        }
+    }
+
+    event_created_child!(RuntimeState<T>, ZwlrOutputManagerV1, [
+        zwlr_output_manager_v1::EVT_HEAD_OPCODE => (ZwlrOutputHeadV1, ()),
+    ]);
+}
+
+impl<T: Application + 'static> Dispatch<ZwlrOutputHeadV1, ()> for RuntimeState<T> {
+    fn event(
+        state: &mut Self,
+        head: &ZwlrOutputHeadV1,
+        event: zwlr_output_head_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let zwlr_output_head_v1::Event::Finished = event {
+            state.winit.output_heads.remove(&head.id());
+            if head.version() >= 3 {
+                head.release();
+            }
+            return;
+        }
+
+        let output_head = match state.winit.output_heads.get_mut(&head.id()) {
+            Some(output_head) => output_head,
+            None => return,
+        };
+
+        match event {
+            zwlr_output_head_v1::Event::Name { name } => output_head.name = Some(name),
+            zwlr_output_head_v1::Event::AdaptiveSync { state: WEnum::Value(adaptive_sync) } => {
+                output_head.adaptive_sync = match adaptive_sync {
+                    AdaptiveSyncState::Enabled => Some(AdaptiveSync::Enabled),
+                    AdaptiveSyncState::Disabled => Some(AdaptiveSync::Disabled),
+                    _ => None,
+                };
+            },
+            _ => (),
+        }
+    }
+
+    event_created_child!(RuntimeState<T>, ZwlrOutputHeadV1, [
+        zwlr_output_head_v1::EVT_MODE_OPCODE => (ZwlrOutputModeV1, ()),
+    ]);
+}
+
+impl<T: Application + 'static> Dispatch<ZwlrOutputModeV1, ()> for RuntimeState<T> {
+    fn event(
+        _: &mut Self,
+        mode: &ZwlrOutputModeV1,
+        event: zwlr_output_mode_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        // The modes are taken from the wl_output instead.
+        if let zwlr_output_mode_v1::Event::Finished = event {
+            if mode.version() >= 3 {
+                mode.release();
+            }
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! The overlay planes of the windows backed by the subsurfaces.
+
+use raw_window_handle::{HandleError, HasWindowHandle, WaylandWindowHandle, WindowHandle};
+use raw_window_handle_05::HasRawWindowHandle as HasRawWindowHandle05;
+
+use sctk::globals::GlobalData;
+use sctk::reexports::client::protocol::wl_subsurface::WlSubsurface;
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
+use sctk::reexports::client::{Connection, Dispatch, Proxy, QueueHandle};
+use sctk::reexports::protocols::wp::viewporter::client::wp_viewport::{self, WpViewport};
+use sctk::reexports::protocols::wp::viewporter::client::wp_viewporter::{self, WpViewporter};
+use sctk::subcompositor::SubcompositorState;
+
+use winit_core::application::Application;
+use winit_core::dpi::{LogicalPosition, LogicalSize, Position, Size};
+use winit_core::window::{OverlayPlane as CoreOverlayPlane, OverlayPlaneId};
+
+use crate::event_loop::RuntimeState;
+
+/// The overlay plane of the window.
+pub struct OverlayPlane {
+    id: OverlayPlaneId,
+
+    /// The viewport to scale the buffer, when wp-viewporter is available.
+    viewport: Option<WpViewport>,
+
+    subsurface: WlSubsurface,
+
+    surface: WlSurface,
+
+    /// The position relative to the parent surface.
+    position: Position,
+
+    /// The size to scale the buffer to.
+    size: Option<Size>,
+
+    /// The scale factor of the parent window.
+    scale_factor: f64,
+
+    z_index: i32,
+
+    /// Whether the planes of the window should be restacked.
+    restack: bool,
+}
+
+impl OverlayPlane {
+    pub(crate) fn new<T: Application + 'static>(
+        id: OverlayPlaneId,
+        parent: &WlSurface,
+        subcompositor: &SubcompositorState,
+        viewporter: Option<&WpViewporter>,
+        scale_factor: f64,
+        queue_handle: &QueueHandle<RuntimeState<T>>,
+    ) -> Self {
+        let (subsurface, surface) = subcompositor.create_subsurface(parent.clone(), queue_handle);
+
+        // Let the video frames be presented without committing the window.
+        subsurface.set_desync();
+
+        let viewport = viewporter
+            .map(|viewporter| viewporter.get_viewport(&surface, queue_handle, GlobalData));
+
+        Self {
+            id,
+            viewport,
+            subsurface,
+            surface,
+            position: LogicalPosition::new(0, 0).into(),
+            size: None,
+            scale_factor,
+            z_index: 0,
+            restack: true,
+        }
+    }
+
+    /// Update the scale factor of the parent window.
+    pub(crate) fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.scale_factor = scale_factor;
+        self.set_position(self.position);
+        self.set_size(self.size);
+    }
+}
+
+/// Apply the stacking order of the `planes` around their `parent`.
+pub(crate) fn restack(parent: &WlSurface, planes: &mut [OverlayPlane]) {
+    if !planes.iter().any(|plane| plane.restack) {
+        return;
+    }
+
+    let mut order: Vec<&OverlayPlane> = planes.iter().collect();
+    order.sort_by_key(|plane| plane.z_index);
+
+    // Stack the planes up from the parent, and down from it for the negative
+    // ones.
+    let mut sibling = parent;
+    for plane in order.iter().filter(|plane| plane.z_index >= 0) {
+        plane.subsurface.place_above(sibling);
+        sibling = &plane.surface;
+    }
+
+    let mut sibling = parent;
+    for plane in order.iter().rev().filter(|plane| plane.z_index < 0) {
+        plane.subsurface.place_below(sibling);
+        sibling = &plane.surface;
+    }
+
+    for plane in planes {
+        plane.restack = false;
+    }
+}
+
+impl CoreOverlayPlane for OverlayPlane {
+    fn id(&self) -> OverlayPlaneId {
+        self.id
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+        let position: LogicalPosition<i32> = position.to_logical(self.scale_factor);
+        self.subsurface.set_position(position.x, position.y);
+    }
+
+    fn set_size(&mut self, size: Option<Size>) {
+        self.size = size;
+        let viewport = match self.viewport.as_ref() {
+            Some(viewport) => viewport,
+            None => return,
+        };
+
+        match size {
+            Some(size) => {
+                let size: LogicalSize<i32> = size.to_logical(self.scale_factor);
+                viewport.set_destination(size.width.max(1), size.height.max(1));
+            },
+            // Unset the destination.
+            None => viewport.set_destination(-1, -1),
+        }
+    }
+
+    fn z_index(&self) -> i32 {
+        self.z_index
+    }
+
+    fn set_z_index(&mut self, z_index: i32) {
+        self.restack |= self.z_index != z_index;
+        self.z_index = z_index;
+    }
+}
+
+impl Drop for OverlayPlane {
+    fn drop(&mut self) {
+        if let Some(viewport) = self.viewport.take() {
+            viewport.destroy();
+        }
+
+        self.subsurface.destroy();
+        self.surface.destroy();
+    }
+}
+
+impl HasWindowHandle for OverlayPlane {
+    fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+        let ptr = self.surface.id().as_ptr();
+        let handle = WaylandWindowHandle::new({
+            std::ptr::NonNull::new(ptr as *mut _).expect("wl_surface will never be null")
+        });
+
+        unsafe { Ok(WindowHandle::borrow_raw(handle.into())) }
+    }
+}
+
+unsafe impl HasRawWindowHandle05 for OverlayPlane {
+    fn raw_window_handle(&self) -> raw_window_handle_05::RawWindowHandle {
+        let mut window_handle = raw_window_handle_05::WaylandWindowHandle::empty();
+        window_handle.surface = self.surface.id().as_ptr() as *mut _;
+        raw_window_handle_05::RawWindowHandle::Wayland(window_handle)
+    }
+}
+
+impl<T: Application + 'static> Dispatch<WpViewporter, GlobalData> for RuntimeState<T> {
+    fn event(
+        _: &mut Self,
+        _: &WpViewporter,
+        _: wp_viewporter::Event,
+        _: &GlobalData,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        // No events.
+    }
+}
+
+impl<T: Application + 'static> Dispatch<WpViewport, GlobalData> for RuntimeState<T> {
+    fn event(
+        _: &mut Self,
+        _: &WpViewport,
+        _: wp_viewport::Event,
+        _: &GlobalData,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        // No events.
+    }
+}
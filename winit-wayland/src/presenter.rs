@@ -0,0 +1,421 @@
+//! The double buffered presenter of the CPU rendered content.
+//!
+//! The presenter keeps two SHM buffers and only submits the damaged parts of
+//! the frame to the compositor. The buffer drawn into is brought up to date by
+//! copying the damage of the previous frame into it, instead of redrawing or
+//! copying the whole surface each frame.
+
+use std::error::Error;
+use std::hash::Hasher;
+use std::{fmt, io, mem};
+
+use sctk::reexports::client::protocol::wl_shm::Format;
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
+use sctk::shm::slot::{Buffer, SlotPool};
+use sctk::shm::{CreatePoolError, Shm};
+
+use winit_core::dpi::{PhysicalPosition, PhysicalSize, Rect};
+
+/// The size of the square tiles compared by the diffing.
+const TILE_SIZE: u32 = 64;
+
+/// The bytes per pixel of the [`Format::Argb8888`].
+const BYTES_PER_PIXEL: u32 = 4;
+
+/// The buffer along with the damage it misses compared to the last presented
+/// frame.
+struct PresenterBuffer {
+    buffer: Buffer,
+
+    /// `None` when the whole buffer is outdated.
+    missing: Option<Vec<Rect<u32>>>,
+}
+
+/// The double buffered, damage tracked presenter of the SHM content.
+///
+/// Create it with the [`WaylandEventLoopExt::create_presenter`], draw the
+/// frame with the [`ShmPresenter::begin`], reporting the damaged rectangles,
+/// and present it with the [`ShmPresenter::present`].
+///
+/// ```ignore
+/// if let Some(mut frame) = presenter.begin() {
+///     draw_cursor(frame.canvas(), frame.stride());
+///     frame.damage(cursor_rect);
+///     drop(frame);
+///     presenter.present(surface)?;
+/// }
+/// ```
+///
+/// [`WaylandEventLoopExt::create_presenter`]: crate::protocols::WaylandEventLoopExt::create_presenter
+pub struct ShmPresenter {
+    pool: SlotPool,
+    buffers: [Option<PresenterBuffer>; 2],
+
+    /// The index of the buffer to draw into.
+    back: usize,
+
+    size: PhysicalSize<u32>,
+
+    /// The damage of the frame being drawn.
+    damage: Vec<Rect<u32>>,
+
+    /// The tile hashes of the last presented frame, when diffing.
+    hashes: Option<Vec<u64>>,
+
+    diffing: bool,
+
+    /// The scratch to copy the damage between the buffers.
+    scratch: Vec<u8>,
+
+    /// Whether the frame was begun since the last present.
+    begun: bool,
+}
+
+impl ShmPresenter {
+    pub(crate) fn new(shm: &Shm, size: PhysicalSize<u32>) -> io::Result<Self> {
+        let len = buffer_len(size).max(1);
+        let pool = SlotPool::new(len * 2, shm).map_err(|err| match err {
+            CreatePoolError::Create(err) => err,
+            err => io::Error::other(err),
+        })?;
+        Ok(Self {
+            pool,
+            buffers: [None, None],
+            back: 0,
+            size,
+            damage: Vec::new(),
+            hashes: None,
+            diffing: false,
+            scratch: Vec::new(),
+            begun: false,
+        })
+    }
+
+    /// The size of the buffers.
+    pub fn size(&self) -> PhysicalSize<u32> {
+        self.size
+    }
+
+    /// Recreate the buffers with the new `size`, the next frame must be
+    /// drawn whole.
+    pub fn resize(&mut self, size: PhysicalSize<u32>) {
+        if self.size == size {
+            return;
+        }
+
+        self.size = size;
+        self.buffers = [None, None];
+        self.hashes = None;
+        self.begun = false;
+    }
+
+    /// Compute the damage by comparing the frame with the previous one,
+    /// instead of the damage reported with the [`ShmFrame::damage`].
+    ///
+    /// The whole frame must be drawn each time, the unchanged tiles are not
+    /// submitted to the compositor though.
+    pub fn set_diffing(&mut self, diffing: bool) {
+        self.diffing = diffing;
+        self.hashes = None;
+    }
+
+    /// Start drawing the next frame.
+    ///
+    /// The canvas holds the last presented frame, unless diffing, so only
+    /// the changed parts need to be drawn. Returns `None` when the compositor
+    /// still reads the buffer, try again after the next frame callback.
+    pub fn begin(&mut self) -> Option<ShmFrame<'_>> {
+        if self.size.width == 0 || self.size.height == 0 {
+            return None;
+        }
+
+        self.damage.clear();
+
+        if self.buffers[self.back].is_none() {
+            let stride = self.stride() as i32;
+            let (width, height) = (self.size.width as i32, self.size.height as i32);
+            let (buffer, _) =
+                self.pool.create_buffer(width, height, stride, Format::Argb8888).ok()?;
+            self.buffers[self.back] = Some(PresenterBuffer { buffer, missing: None });
+        }
+
+        let back = self.buffers[self.back].as_mut().unwrap();
+        back.buffer.canvas(&mut self.pool)?;
+
+        let missing = back.missing.take();
+        if !self.diffing {
+            self.repair(missing);
+        }
+
+        let stride = self.stride() as usize;
+        let size = self.size;
+        let back = self.buffers[self.back].as_ref().unwrap();
+        let canvas = back.buffer.canvas(&mut self.pool)?;
+        self.begun = true;
+        Some(ShmFrame { canvas, stride, size, damage: &mut self.damage })
+    }
+
+    /// Present the frame drawn since the [`ShmPresenter::begin`] on the
+    /// `surface`, submitting only its damage.
+    ///
+    /// Returns `Err` without committing when no frame was begun, like when
+    /// the [`ShmPresenter::begin`] returned `None`.
+    pub fn present(&mut self, surface: &WlSurface) -> Result<(), PresentError> {
+        if !mem::take(&mut self.begun) {
+            return Err(PresentError::NotBegun);
+        }
+
+        if self.diffing {
+            self.diff();
+        }
+
+        let back = self.buffers[self.back].as_ref().ok_or(PresentError::NotBegun)?;
+        back.buffer.attach_to(surface).map_err(|_| PresentError::BufferBusy)?;
+
+        for rect in &self.damage {
+            let (x, y) = (rect.origin.x as i32, rect.origin.y as i32);
+            surface.damage_buffer(x, y, rect.size.width as i32, rect.size.height as i32);
+        }
+
+        surface.commit();
+
+        // The other buffer holds the previous frame, so it only misses the
+        // damage of this one.
+        let front = self.back;
+        self.back ^= 1;
+        if let Some(buffer) = self.buffers[self.back].as_mut() {
+            if let Some(missing) = buffer.missing.as_mut() {
+                missing.clear();
+                missing.extend_from_slice(&self.damage);
+            }
+        }
+
+        if let Some(buffer) = self.buffers[front].as_mut() {
+            buffer.missing = Some(Vec::new());
+        }
+
+        Ok(())
+    }
+
+    fn stride(&self) -> u32 {
+        self.size.width * BYTES_PER_PIXEL
+    }
+
+    /// Bring the back buffer up to date with the last presented frame.
+    fn repair(&mut self, missing: Option<Vec<Rect<u32>>>) {
+        let full = Rect::new(PhysicalPosition::new(0, 0), self.size);
+        let front = match self.buffers[self.back ^ 1].as_ref() {
+            Some(front) => front,
+            None => {
+                // Nothing was presented yet, so the frame must be drawn whole.
+                if missing.is_none() {
+                    self.damage.push(full);
+                }
+                return;
+            },
+        };
+
+        let rects = missing.unwrap_or_else(|| vec![full]);
+        let stride = self.stride() as usize;
+
+        // Copy through the scratch, since the pool gives out a single canvas
+        // at a time.
+        let front_slot = front.buffer.slot();
+        self.scratch.clear();
+        let front_canvas = self.pool.raw_data_mut(&front_slot);
+        for rect in &rects {
+            for_each_row(rect, stride, |range| {
+                self.scratch.extend_from_slice(&front_canvas[range])
+            });
+        }
+
+        let back_slot = self.buffers[self.back].as_ref().unwrap().buffer.slot();
+        let back_canvas = self.pool.raw_data_mut(&back_slot);
+        let mut offset = 0;
+        for rect in &rects {
+            for_each_row(rect, stride, |range| {
+                let len = range.len();
+                back_canvas[range].copy_from_slice(&self.scratch[offset..offset + len]);
+                offset += len;
+            });
+        }
+    }
+
+    /// Damage the tiles which changed since the last presented frame.
+    fn diff(&mut self) {
+        let back = match self.buffers[self.back].as_ref() {
+            Some(back) => back,
+            None => return,
+        };
+
+        let stride = self.stride() as usize;
+        let canvas = self.pool.raw_data_mut(&back.buffer.slot());
+        let hashes = tile_hashes(canvas, self.size, stride);
+
+        self.damage.clear();
+        match self.hashes.as_ref().filter(|previous| previous.len() == hashes.len()) {
+            Some(previous) => {
+                let columns = self.size.width.div_ceil(TILE_SIZE) as usize;
+                for (row, (hashes, previous)) in
+                    hashes.chunks(columns).zip(previous.chunks(columns)).enumerate()
+                {
+                    damage_changed_tiles(row as u32, hashes, previous, self.size, &mut self.damage);
+                }
+            },
+            None => self.damage.push(Rect::new(PhysicalPosition::new(0, 0), self.size)),
+        }
+
+        self.hashes = Some(hashes);
+    }
+}
+
+/// The error of the [`ShmPresenter::present`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PresentError {
+    /// No frame was begun since the last present.
+    NotBegun,
+
+    /// The buffer is still read by the compositor.
+    BufferBusy,
+}
+
+impl fmt::Display for PresentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotBegun => f.write_str("no frame was begun since the last present"),
+            Self::BufferBusy => f.write_str("the buffer is still read by the compositor"),
+        }
+    }
+}
+
+impl Error for PresentError {}
+
+/// The frame being drawn, see [`ShmPresenter::begin`].
+pub struct ShmFrame<'a> {
+    canvas: &'a mut [u8],
+    stride: usize,
+    size: PhysicalSize<u32>,
+    damage: &'a mut Vec<Rect<u32>>,
+}
+
+impl ShmFrame<'_> {
+    /// The pixels in the [`Format::Argb8888`], row by row.
+    pub fn canvas(&mut self) -> &mut [u8] {
+        self.canvas
+    }
+
+    /// The length of the row in bytes.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    pub fn size(&self) -> PhysicalSize<u32> {
+        self.size
+    }
+
+    /// Mark the `rect` as changed, the parts outside of the frame are ignored.
+    pub fn damage(&mut self, rect: Rect<u32>) {
+        let x = rect.origin.x.min(self.size.width);
+        let y = rect.origin.y.min(self.size.height);
+        let width = rect.size.width.min(self.size.width - x);
+        let height = rect.size.height.min(self.size.height - y);
+        if width > 0 && height > 0 {
+            let size = PhysicalSize::new(width, height);
+            self.damage.push(Rect::new(PhysicalPosition::new(x, y), size));
+        }
+    }
+
+    /// Mark the whole frame as changed.
+    pub fn damage_all(&mut self) {
+        self.damage.clear();
+        self.damage.push(Rect::new(PhysicalPosition::new(0, 0), self.size));
+    }
+}
+
+fn buffer_len(size: PhysicalSize<u32>) -> usize {
+    size.width as usize * size.height as usize * BYTES_PER_PIXEL as usize
+}
+
+/// Call `f` with the byte range of each row of the `rect`.
+fn for_each_row<F: FnMut(std::ops::Range<usize>)>(rect: &Rect<u32>, stride: usize, mut f: F) {
+    let start = rect.origin.x as usize * BYTES_PER_PIXEL as usize;
+    let len = rect.size.width as usize * BYTES_PER_PIXEL as usize;
+    for y in rect.origin.y..rect.origin.y + rect.size.height {
+        let offset = y as usize * stride + start;
+        f(offset..offset + len);
+    }
+}
+
+/// Hash the tiles of the `canvas` row by row.
+fn tile_hashes(canvas: &[u8], size: PhysicalSize<u32>, stride: usize) -> Vec<u64> {
+    let columns = size.width.div_ceil(TILE_SIZE);
+    let rows = size.height.div_ceil(TILE_SIZE);
+    let mut hashes = Vec::with_capacity((columns * rows) as usize);
+
+    for row in 0..rows {
+        for column in 0..columns {
+            let tile = tile_rect(row, column, size);
+            let mut hasher = FnvHasher::default();
+            for_each_row(&tile, stride, |range| hasher.write(&canvas[range]));
+            hashes.push(hasher.finish());
+        }
+    }
+
+    hashes
+}
+
+/// Damage the runs of the changed tiles in the tile `row`.
+fn damage_changed_tiles(
+    row: u32,
+    hashes: &[u64],
+    previous: &[u64],
+    size: PhysicalSize<u32>,
+    damage: &mut Vec<Rect<u32>>,
+) {
+    let mut run: Option<Rect<u32>> = None;
+    for (column, (hash, previous)) in hashes.iter().zip(previous).enumerate() {
+        if hash == previous {
+            damage.extend(run.take());
+            continue;
+        }
+
+        let tile = tile_rect(row, column as u32, size);
+        match run.as_mut() {
+            Some(run) => run.size.width += tile.size.width,
+            None => run = Some(tile),
+        }
+    }
+
+    damage.extend(run);
+}
+
+/// The rectangle of the tile, clamped to the `size`.
+fn tile_rect(row: u32, column: u32, size: PhysicalSize<u32>) -> Rect<u32> {
+    let (x, y) = (column * TILE_SIZE, row * TILE_SIZE);
+    let width = TILE_SIZE.min(size.width - x);
+    let height = TILE_SIZE.min(size.height - y);
+    Rect::new(PhysicalPosition::new(x, y), PhysicalSize::new(width, height))
+}
+
+/// The FNV-1a hash, fast enough to hash the whole frame.
+struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
@@ -0,0 +1,190 @@
+//! Inspection of the protocols supported by the compositor.
+
+use std::io;
+use std::os::fd::BorrowedFd;
+
+use sctk::globals::GlobalData;
+
+use winit_core::application::Application;
+use winit_core::dpi::PhysicalSize;
+use winit_core::window::WindowId;
+
+use crate::event_loop::EventLoop;
+use crate::explicit_sync::{SyncError, SyncPoint, SyncTimeline};
+use crate::presenter::ShmPresenter;
+use crate::state::WinitState;
+
+/// The optional protocols used by the backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum WaylandProtocol {
+    /// Passing the focus between the windows.
+    XdgActivation,
+
+    /// Server side decorations.
+    XdgDecoration,
+
+    /// Fractional scaling of the windows.
+    FractionalScale,
+
+    /// Scaling and cropping of the surfaces, used by the overlay planes.
+    Viewporter,
+
+    /// Presentation feedback used to schedule the frames.
+    PresentationTime,
+
+    /// Setting the cursor icon by its name.
+    CursorShape,
+
+    /// Detection of the user inactivity.
+    IdleNotify,
+
+    /// Input method support.
+    TextInput,
+
+    /// Subsurfaces, used by the client side decorations and overlay planes.
+    Subcompositor,
+
+    /// The power state of the monitors.
+    OutputPowerManagement,
+
+    /// The adaptive sync state of the monitors.
+    OutputManagement,
+
+    /// Blurring the content behind the windows on KDE.
+    KdeBlur,
+
+    /// Explicit synchronization of the GPU rendered buffers.
+    ExplicitSync,
+}
+
+impl WaylandProtocol {
+    /// The interface of the protocol global.
+    pub const fn interface(self) -> &'static str {
+        match self {
+            WaylandProtocol::XdgActivation => "xdg_activation_v1",
+            WaylandProtocol::XdgDecoration => "zxdg_decoration_manager_v1",
+            WaylandProtocol::FractionalScale => "wp_fractional_scale_manager_v1",
+            WaylandProtocol::Viewporter => "wp_viewporter",
+            WaylandProtocol::PresentationTime => "wp_presentation",
+            WaylandProtocol::CursorShape => "wp_cursor_shape_manager_v1",
+            WaylandProtocol::IdleNotify => "ext_idle_notifier_v1",
+            WaylandProtocol::TextInput => "zwp_text_input_manager_v3",
+            WaylandProtocol::Subcompositor => "wl_subcompositor",
+            WaylandProtocol::OutputPowerManagement => "zwlr_output_power_manager_v1",
+            WaylandProtocol::OutputManagement => "zwlr_output_manager_v1",
+            WaylandProtocol::KdeBlur => "org_kde_kwin_blur_manager",
+            WaylandProtocol::ExplicitSync => "wp_linux_drm_syncobj_manager_v1",
+        }
+    }
+}
+
+/// Additional methods on the Wayland event loop.
+pub trait WaylandEventLoopExt {
+    /// Whether the compositor advertises the global with the `interface`
+    /// name of at least the `min_version`.
+    fn protocol_available(&self, interface: &str, min_version: u32) -> bool;
+
+    /// Whether the compositor supports the `protocol` known to the backend.
+    ///
+    /// Applications could use that to adapt their interface, for example to
+    /// hide the actions which would fail.
+    fn supports(&self, protocol: WaylandProtocol) -> bool {
+        self.protocol_available(protocol.interface(), 1)
+    }
+
+    /// Whether the compositor could draw the decorations of the windows.
+    ///
+    /// Without them, like on GNOME, the windows get the
+    /// [`DecorationMode::Client`] and winit draws its own frame. The actual
+    /// mode of each window is delivered with the
+    /// [`ApplicationWindow::decoration_mode_changed`].
+    ///
+    /// [`DecorationMode::Client`]: winit_core::window::DecorationMode::Client
+    /// [`ApplicationWindow::decoration_mode_changed`]: winit_core::application::ApplicationWindow::decoration_mode_changed
+    fn server_decorations_available(&self) -> bool {
+        self.supports(WaylandProtocol::XdgDecoration)
+    }
+
+    /// Whether the compositor supports the explicit synchronization, see
+    /// the [`explicit_sync`].
+    ///
+    /// [`explicit_sync`]: crate::explicit_sync
+    fn supports_explicit_sync(&self) -> bool {
+        self.supports(WaylandProtocol::ExplicitSync)
+    }
+
+    /// Import the DRM synchronization object `fd` as the timeline.
+    ///
+    /// Returns `Err` when the compositor doesn't support the explicit
+    /// synchronization.
+    fn import_timeline(&mut self, fd: BorrowedFd<'_>) -> Result<SyncTimeline, SyncError>;
+
+    /// Commit the buffer attached by the renderer to the window along with
+    /// its `acquire` and `release` points.
+    ///
+    /// Once used, each commit of the new buffer must go through this method,
+    /// the compositor treats the commit without the points as an error.
+    fn commit_with_sync(
+        &mut self,
+        window_id: WindowId,
+        acquire: SyncPoint<'_>,
+        release: SyncPoint<'_>,
+    ) -> Result<(), SyncError>;
+
+    /// Create the presenter of the CPU rendered content of the `size`.
+    ///
+    /// Returns `Err` when the shared memory for the buffers can't be allocated.
+    fn create_presenter(&self, size: PhysicalSize<u32>) -> io::Result<ShmPresenter>;
+}
+
+impl<T: Application + 'static> WaylandEventLoopExt for WinitState<T> {
+    fn protocol_available(&self, interface: &str, min_version: u32) -> bool {
+        self.registry_state
+            .globals()
+            .any(|global| global.interface == interface && global.version >= min_version)
+    }
+
+    fn import_timeline(&mut self, fd: BorrowedFd<'_>) -> Result<SyncTimeline, SyncError> {
+        let manager = self.syncobj_manager.as_ref().ok_or(SyncError::Unsupported)?;
+        Ok(SyncTimeline::new(manager.import_timeline(fd, &self.queue_handle, GlobalData)))
+    }
+
+    fn commit_with_sync(
+        &mut self,
+        window_id: WindowId,
+        acquire: SyncPoint<'_>,
+        release: SyncPoint<'_>,
+    ) -> Result<(), SyncError> {
+        let error = SyncError::Window(self.missing_window_error(window_id));
+        let window = self.windows.get_mut(&window_id).ok_or(error)?;
+        window.commit_with_sync(acquire, release)
+    }
+
+    fn create_presenter(&self, size: PhysicalSize<u32>) -> io::Result<ShmPresenter> {
+        ShmPresenter::new(&self.shm, size)
+    }
+}
+
+impl<T: Application + 'static> WaylandEventLoopExt for EventLoop<T> {
+    fn protocol_available(&self, interface: &str, min_version: u32) -> bool {
+        self.state.winit.protocol_available(interface, min_version)
+    }
+
+    fn import_timeline(&mut self, fd: BorrowedFd<'_>) -> Result<SyncTimeline, SyncError> {
+        self.state.winit.import_timeline(fd)
+    }
+
+    fn commit_with_sync(
+        &mut self,
+        window_id: WindowId,
+        acquire: SyncPoint<'_>,
+        release: SyncPoint<'_>,
+    ) -> Result<(), SyncError> {
+        self.state.winit.commit_with_sync(window_id, acquire, release)
+    }
+
+    fn create_presenter(&self, size: PhysicalSize<u32>) -> io::Result<ShmPresenter> {
+        self.state.winit.create_presenter(size)
+    }
+}
@@ -0,0 +1,91 @@
+//! Rate limiting of the logging of the high frequency events.
+//!
+//! The pointer motion and the configures during the interactive resize come
+//! at the refresh rate or faster, so logging each of them floods the output.
+//! Each kind of the event has the token bucket instead, the messages over the
+//! budget are dropped and their number is logged along with the next allowed
+//! message.
+
+use std::mem;
+use std::time::Instant;
+
+/// The number of messages logged in a row before the limiting kicks in.
+const BURST: f32 = 20.;
+
+/// The number of messages logged per second once the burst is spent.
+const RATE: f32 = 5.;
+
+/// The kind of the high frequency event, limited separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LogKind {
+    PointerMotion,
+    Configure,
+    FrameCallback,
+}
+
+impl LogKind {
+    const COUNT: usize = 3;
+}
+
+/// The token buckets of all the [`LogKind`]s.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    buckets: [Bucket; LogKind::COUNT],
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        let bucket = Bucket { tokens: BURST, refilled_at: Instant::now(), suppressed: 0 };
+        Self { buckets: [bucket; LogKind::COUNT] }
+    }
+}
+
+impl RateLimiter {
+    /// Take the token of the `kind`.
+    ///
+    /// Returns the number of the messages suppressed since the previous
+    /// allowed one, or `None` when this one should be suppressed as well.
+    pub(crate) fn check(&mut self, kind: LogKind, now: Instant) -> Option<u32> {
+        let bucket = &mut self.buckets[kind as usize];
+
+        let elapsed = now.saturating_duration_since(bucket.refilled_at).as_secs_f32();
+        bucket.tokens = (bucket.tokens + elapsed * RATE).min(BURST);
+        bucket.refilled_at = now;
+
+        if bucket.tokens < 1. {
+            bucket.suppressed = bucket.suppressed.saturating_add(1);
+            return None;
+        }
+
+        bucket.tokens -= 1.;
+        Some(mem::take(&mut bucket.suppressed))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f32,
+    refilled_at: Instant,
+
+    /// The number of the messages dropped since the last allowed one.
+    suppressed: u32,
+}
+
+/// Log the debug message of the `kind` within its rate limit.
+///
+/// The limiter is only touched when the debug logging is enabled.
+macro_rules! limited_debug {
+    ($limiter:expr, $kind:expr, $($arg:tt)+) => {
+        if log::log_enabled!(log::Level::Debug) {
+            if let Some(suppressed) = $limiter.check($kind, std::time::Instant::now()) {
+                if suppressed > 0 {
+                    log::debug!("{suppressed} {:?} messages suppressed", $kind);
+                }
+
+                log::debug!($($arg)+);
+            }
+        }
+    };
+}
+
+pub(crate) use limited_debug;
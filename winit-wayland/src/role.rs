@@ -0,0 +1,95 @@
+//! Surface roles implemented outside of the backend.
+//!
+//! The crate implementing the role registers its [`RoleHandler`] on the
+//! [`EventLoop`], and the surfaces it creates are managed like the rest of the
+//! windows, meaning that they are accessible with the
+//! [`EventLoopHandle::get_window`] and get the usual events.
+//!
+//! The events of the role protocol objects should be dispatched by
+//! implementing `Dispatch` for the [`RuntimeState`] and forwarded to the
+//! application with the [`RuntimeState::role_configured`] and
+//! [`RuntimeState::role_close_requested`].
+//!
+//! [`EventLoop`]: crate::event_loop::EventLoop
+//! [`EventLoopHandle::get_window`]: winit_core::event_loop::EventLoopHandle::get_window
+
+use sctk::compositor::CompositorState;
+use sctk::reexports::client::globals::{BindError, GlobalList};
+use sctk::reexports::client::QueueHandle;
+
+use winit_core::application::Application;
+use winit_core::dpi::PhysicalSize;
+use winit_core::window::{Surface, WindowId};
+
+use crate::event_loop::RuntimeState;
+
+/// The window with the custom surface role.
+///
+/// The window is not accessible by the application until the initial
+/// [`RuntimeState::role_configured`].
+pub trait SurfaceRole: Surface {
+    fn as_surface(&self) -> &dyn Surface;
+
+    fn as_surface_mut(&mut self) -> &mut dyn Surface;
+
+    /// Take the pending redraw requested with [`Surface::request_redraw`].
+    fn take_redraw(&mut self) -> bool;
+}
+
+/// The handler creating the surfaces with the custom role.
+pub trait RoleHandler<T: Application + 'static> {
+    /// Bind the protocol globals required by the role.
+    ///
+    /// Called once when the handler is registered.
+    fn bind(
+        &mut self,
+        globals: &GlobalList,
+        queue_handle: &QueueHandle<RuntimeState<T>>,
+    ) -> Result<(), BindError>;
+
+    /// Create the surfaces with the role requested since the last loop
+    /// iteration.
+    fn create_pending(
+        &mut self,
+        compositor: &CompositorState,
+        queue_handle: &QueueHandle<RuntimeState<T>>,
+    ) -> Vec<Box<dyn SurfaceRole>>;
+}
+
+impl<T: Application + 'static> RuntimeState<T> {
+    /// The surface with the custom role got configured to the new `size`.
+    pub fn role_configured(&mut self, window_id: WindowId, size: PhysicalSize<u32>) {
+        let winit = &mut self.winit;
+        let user = self.user.as_mut().unwrap();
+
+        let role = match winit.roles.get_mut(&window_id) {
+            Some(role) => role,
+            None => return,
+        };
+
+        if winit.configured_roles.insert(window_id) {
+            let scale_factor = role.scale_factor();
+            user.created(winit, window_id);
+            user.scale_factor_changed(winit, window_id, scale_factor);
+        }
+
+        user.resized(winit, window_id, size);
+    }
+
+    /// The compositor asked to close the surface with the custom role.
+    pub fn role_close_requested(&mut self, window_id: WindowId) {
+        let winit = &mut self.winit;
+        let user = self.user.as_mut().unwrap();
+
+        if !winit.roles.contains_key(&window_id) {
+            return;
+        }
+
+        if user.close_requested(winit, window_id) {
+            drop(winit.roles.remove(&window_id));
+            winit.configured_roles.remove(&window_id);
+            winit.tombstones.bury(window_id);
+            user.destroyed(winit, window_id);
+        }
+    }
+}
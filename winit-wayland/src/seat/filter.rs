@@ -0,0 +1,127 @@
+//! The delivery of the input events through the event filters.
+
+use std::mem;
+
+use winit_core::application::Application;
+use winit_core::input::filter::{EventFilter, EventFilterId, FilterAction, InputEvent};
+use winit_core::window::WindowId;
+
+use crate::state::WinitState;
+
+/// The filters added by the user.
+#[derive(Default)]
+pub(crate) struct EventFilters {
+    /// The filters in the order they were pushed.
+    filters: Vec<(EventFilterId, Box<dyn EventFilter>)>,
+
+    /// The filters removed while the filters were running.
+    removed: Vec<EventFilterId>,
+
+    /// Whether the filters are running.
+    running: bool,
+
+    next_id: u64,
+}
+
+impl EventFilters {
+    pub(crate) fn push(&mut self, filter: Box<dyn EventFilter>) -> EventFilterId {
+        let filter_id = EventFilterId(self.next_id);
+        self.next_id += 1;
+        self.filters.push((filter_id, filter));
+        filter_id
+    }
+
+    pub(crate) fn remove(&mut self, filter_id: EventFilterId) {
+        // The running filters are taken out, so remove them once they're back.
+        if self.running {
+            self.removed.push(filter_id);
+        }
+
+        self.filters.retain(|(id, _)| *id != filter_id);
+    }
+}
+
+impl<T: Application + 'static> WinitState<T> {
+    /// Pass the `event` through the filters and deliver it to the user.
+    pub(crate) fn deliver_input(&mut self, user: &mut T, window_id: WindowId, event: InputEvent) {
+        self.dispatch.record_event();
+
+        let event = match self.filter_input(window_id, event) {
+            Some(event) => event,
+            None => return,
+        };
+
+        match event {
+            InputEvent::PointerEntered { position, device_id } => {
+                if let Some(handler) = user.pointer_handler() {
+                    handler.pointer_entered(self, window_id, position, device_id);
+                }
+            },
+            InputEvent::PointerLeft { device_id } => {
+                if let Some(handler) = user.pointer_handler() {
+                    handler.pointer_left(self, window_id, device_id);
+                }
+            },
+            InputEvent::PointerMoved(motion) => {
+                if let Some(handler) = user.pointer_handler() {
+                    handler.pointer_moved(self, window_id, motion);
+                }
+            },
+            InputEvent::PointerScrolled(scroll) => {
+                if let Some(handler) = user.pointer_handler() {
+                    handler.pointer_scrolled(self, window_id, scroll);
+                }
+            },
+            InputEvent::PointerButton(event) => {
+                if let Some(handler) = user.pointer_handler() {
+                    handler.pointer_button(self, window_id, event);
+                }
+            },
+            InputEvent::Key(event) => {
+                if let Some(handler) = user.keyboard_handler() {
+                    handler.key_input(self, window_id, event);
+                }
+            },
+            InputEvent::Text(text) => {
+                if let Some(handler) = user.keyboard_handler() {
+                    handler.text_input(self, window_id, text);
+                }
+            },
+            // The `InputEvent` is non-exhaustive.
+            _ => {},
+        }
+    }
+
+    /// Run the filters, returning `None` when the event is consumed.
+    fn filter_input(&mut self, window_id: WindowId, mut event: InputEvent) -> Option<InputEvent> {
+        if self.event_filters.filters.is_empty() {
+            return Some(event);
+        }
+
+        // Take the filters out, so they could access the state.
+        let mut filters = mem::take(&mut self.event_filters.filters);
+        self.event_filters.running = true;
+
+        let mut action = FilterAction::Pass;
+        for (filter_id, filter) in filters.iter_mut().rev() {
+            if self.event_filters.removed.contains(filter_id) {
+                continue;
+            }
+
+            action = filter.filter(self, window_id, &mut event);
+            if action == FilterAction::Consume {
+                break;
+            }
+        }
+
+        // Put the filters back along with the ones pushed while running.
+        let event_filters = &mut self.event_filters;
+        event_filters.running = false;
+        let removed = mem::take(&mut event_filters.removed);
+        filters.retain(|(filter_id, _)| !removed.contains(filter_id));
+        filters.append(&mut event_filters.filters);
+        event_filters.filters = filters;
+
+        (action == FilterAction::Pass).then_some(event)
+    }
+}
@@ -0,0 +1,122 @@
+//! The user idle detection.
+
+use std::mem;
+use std::time::{Duration, Instant};
+
+use sctk::reexports::client::backend::ObjectId;
+use sctk::reexports::client::{Connection, Dispatch, Proxy, QueueHandle};
+use sctk::reexports::protocols::ext::idle_notify::v1::client::ext_idle_notification_v1::{
+    self, ExtIdleNotificationV1,
+};
+use sctk::reexports::protocols::ext::idle_notify::v1::client::ext_idle_notifier_v1::{
+    self, ExtIdleNotifierV1,
+};
+
+use sctk::globals::GlobalData;
+
+use winit_core::application::Application;
+
+use crate::event_loop::RuntimeState;
+use crate::state::WinitState;
+
+use super::WinitSeatState;
+
+impl WinitSeatState {
+    /// Replace the idle notification of the seat with the one for the
+    /// `threshold`.
+    pub(crate) fn set_idle_threshold<T: Application + 'static>(
+        &mut self,
+        notifier: Option<&ExtIdleNotifierV1>,
+        threshold: Option<Duration>,
+        queue_handle: &QueueHandle<RuntimeState<T>>,
+    ) {
+        if let Some(idle_notification) = self.idle_notification.take() {
+            idle_notification.destroy();
+        }
+        self.idle_since = None;
+
+        let (notifier, threshold, seat) = match (notifier, threshold, self.seat.as_ref()) {
+            (Some(notifier), Some(threshold), Some(seat)) => (notifier, threshold, seat),
+            _ => return,
+        };
+
+        let timeout = threshold.as_millis().min(u32::MAX as u128) as u32;
+        self.idle_notification =
+            Some(notifier.get_idle_notification(timeout, seat, queue_handle, seat.id()));
+    }
+}
+
+impl<T: Application + 'static> WinitState<T> {
+    /// The time the user became idle on all the seats.
+    pub(crate) fn idle_since_instant(&self) -> Option<Instant> {
+        let mut latest = None;
+        for seat_state in self.seats.values() {
+            if seat_state.idle_notification.is_none() {
+                continue;
+            }
+
+            // The user is still active on this seat.
+            let idle_since = seat_state.idle_since?;
+            latest = latest.max(Some(idle_since));
+        }
+
+        latest
+    }
+
+    /// Notify the user that they're no longer idle after the idle
+    /// notifications were replaced.
+    pub(crate) fn deliver_idle_reset(&mut self, user: &mut T) {
+        if mem::take(&mut self.idle_reset) {
+            user.user_idle(self, false);
+        }
+    }
+}
+
+impl<T: Application + 'static> Dispatch<ExtIdleNotifierV1, GlobalData> for RuntimeState<T> {
+    fn event(
+        _: &mut Self,
+        _: &ExtIdleNotifierV1,
+        _: ext_idle_notifier_v1::Event,
+        _: &GlobalData,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        // No events.
+    }
+}
+
+impl<T: Application + 'static> Dispatch<ExtIdleNotificationV1, ObjectId> for RuntimeState<T> {
+    fn event(
+        state: &mut Self,
+        _: &ExtIdleNotificationV1,
+        event: ext_idle_notification_v1::Event,
+        seat_id: &ObjectId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let was_idle = state.winit.idle_since_instant().is_some();
+
+        let seat_state = match state.winit.seats.get_mut(seat_id) {
+            Some(seat_state) => seat_state,
+            None => return,
+        };
+
+        match event {
+            ext_idle_notification_v1::Event::Idled => seat_state.idle_since = Some(Instant::now()),
+            ext_idle_notification_v1::Event::Resumed => seat_state.idle_since = None,
+            _ => return,
+        }
+
+        let idle = state.winit.idle_since_instant().is_some();
+
+        // The user wasn't told about the activity after the reset yet.
+        if idle && mem::take(&mut state.winit.idle_reset) {
+            return;
+        }
+
+        if idle != was_idle {
+            let user = state.user.as_mut().unwrap();
+            user.user_idle(&mut state.winit, idle);
+        }
+    }
+}
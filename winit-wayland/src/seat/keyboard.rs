@@ -0,0 +1,314 @@
+//! The keyboard input handling.
+
+use std::collections::HashMap;
+use std::env;
+use std::ffi::OsString;
+use std::fmt;
+
+use sctk::reexports::client::backend::ObjectId;
+use sctk::reexports::client::protocol::wl_keyboard::WlKeyboard;
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
+use sctk::reexports::client::{Connection, QueueHandle};
+
+use sctk::seat::keyboard::{KeyEvent as SctkKeyEvent, KeyboardHandler, Keymap, Keysym, Modifiers};
+
+use smol_str::SmolStr;
+use xkbcommon::xkb;
+use xkbcommon::xkb::compose::{FeedResult, Status};
+
+use winit_core::application::Application;
+use winit_core::input::filter::InputEvent;
+use winit_core::input::keyboard::{Key, KeyEvent};
+use winit_core::input::ElementState;
+use winit_core::window::WindowId;
+
+use crate::event_loop::RuntimeState;
+use crate::seat::WinitSeatState;
+
+/// The state of the keyboard on the seat.
+#[derive(Default)]
+pub struct KeyboardState {
+    /// The window the keyboard focus is on.
+    pub(crate) focus: Option<WindowId>,
+
+    /// The keymap to lookup the keys without the modifiers applied.
+    keymap: Option<xkb::Keymap>,
+
+    /// The state of the compose sequence, `None` when there's no compose
+    /// table for the current locale.
+    compose_state: Option<xkb::compose::State>,
+}
+
+impl fmt::Debug for KeyboardState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyboardState").field("focus", &self.focus).finish_non_exhaustive()
+    }
+}
+
+impl KeyboardState {
+    /// The key of the first layout without the modifiers applied.
+    fn key_without_modifiers(&self, raw_code: u32, keysym: Keysym) -> Key {
+        // NOTE: the xkb keycodes are offset by 8 from the evdev ones.
+        let keycode = xkb::Keycode::new(raw_code + 8);
+        let keysym = self
+            .keymap
+            .as_ref()
+            .and_then(|keymap| keymap.key_get_syms_by_level(keycode, 0, 0).first().copied())
+            .unwrap_or(keysym);
+        key_from_keysym(keysym)
+    }
+
+    /// Feed the pressed `keysym` into the compose sequence.
+    fn compose(&mut self, keysym: Keysym) -> Compose {
+        let compose_state = match self.compose_state.as_mut() {
+            Some(compose_state) => compose_state,
+            None => return Compose::None,
+        };
+
+        // The keys like modifiers are not part of the sequences.
+        if matches!(compose_state.feed(keysym), FeedResult::Ignored) {
+            return Compose::None;
+        }
+
+        match compose_state.status() {
+            Status::Composing => Compose::Composing,
+            Status::Composed => {
+                let text = compose_state.utf8();
+                compose_state.reset();
+                Compose::Composed(text)
+            },
+            Status::Cancelled => {
+                compose_state.reset();
+                Compose::Composing
+            },
+            Status::Nothing => Compose::None,
+        }
+    }
+}
+
+/// The result of feeding the key into the compose sequence.
+enum Compose {
+    /// The key is not part of the compose sequence.
+    None,
+    /// The key is consumed by the compose sequence.
+    Composing,
+    /// The compose sequence has finished with the given text.
+    Composed(Option<String>),
+}
+
+impl<T: Application + 'static> KeyboardHandler for RuntimeState<T> {
+    fn enter(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        keyboard: &WlKeyboard,
+        surface: &WlSurface,
+        _serial: u32,
+        _raw: &[u32],
+        _keysyms: &[Keysym],
+    ) {
+        let window_id = self.winit.window_id(surface);
+        if let Some(seat_state) = seat_for_keyboard(&mut self.winit.seats, keyboard) {
+            seat_state.keyboard_state.focus = Some(window_id);
+        }
+
+        if let Some(window) = self.winit.windows.get_mut(&window_id) {
+            window.has_focus = true;
+            let user = self.user.as_mut().unwrap();
+            user.focused(&mut self.winit, window_id, true);
+        }
+    }
+
+    fn leave(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        keyboard: &WlKeyboard,
+        surface: &WlSurface,
+        _serial: u32,
+    ) {
+        let window_id = self.winit.window_id(surface);
+        if let Some(seat_state) = seat_for_keyboard(&mut self.winit.seats, keyboard) {
+            seat_state.keyboard_state.focus = None;
+        }
+
+        if let Some(window) = self.winit.windows.get_mut(&window_id) {
+            window.has_focus = false;
+            let user = self.user.as_mut().unwrap();
+            user.focused(&mut self.winit, window_id, false);
+        }
+    }
+
+    fn press_key(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        keyboard: &WlKeyboard,
+        serial: u32,
+        event: SctkKeyEvent,
+    ) {
+        let seat = seat_for_keyboard(&mut self.winit.seats, keyboard)
+            .and_then(|seat_state| seat_state.seat.clone());
+        if let Some(seat) = seat {
+            self.winit.latest_serial = Some((seat, serial));
+        }
+
+        self.key_input(keyboard, event, ElementState::Pressed, false);
+    }
+
+    fn release_key(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        keyboard: &WlKeyboard,
+        _serial: u32,
+        event: SctkKeyEvent,
+    ) {
+        self.key_input(keyboard, event, ElementState::Released, false);
+    }
+
+    fn update_modifiers(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _keyboard: &WlKeyboard,
+        _serial: u32,
+        _modifiers: Modifiers,
+        _layout: u32,
+    ) {
+    }
+
+    fn update_keymap(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        keyboard: &WlKeyboard,
+        keymap: Keymap<'_>,
+    ) {
+        let seat_state = match seat_for_keyboard(&mut self.winit.seats, keyboard) {
+            Some(seat_state) => seat_state,
+            None => return,
+        };
+
+        // Compile our own copy of the keymap, since sctk doesn't let us lookup the keys
+        // by level.
+        let context = xkb::Context::new(xkb::CONTEXT_NO_FLAGS);
+        seat_state.keyboard_state.keymap = xkb::Keymap::new_from_string(
+            &context,
+            keymap.as_string(),
+            xkb::KEYMAP_FORMAT_TEXT_V1,
+            xkb::KEYMAP_COMPILE_NO_FLAGS,
+        );
+
+        // The compose table depends only on the locale, so load it once.
+        if seat_state.keyboard_state.compose_state.is_none() {
+            seat_state.keyboard_state.compose_state = xkb::compose::Table::new_from_locale(
+                &context,
+                &locale(),
+                xkb::compose::COMPILE_NO_FLAGS,
+            )
+            .ok()
+            .map(|table| xkb::compose::State::new(&table, xkb::compose::STATE_NO_FLAGS));
+        }
+    }
+}
+
+impl<T: Application + 'static> RuntimeState<T> {
+    /// Deliver the key repeated by the sctk.
+    pub(crate) fn key_repeat(&mut self, keyboard: &WlKeyboard, event: SctkKeyEvent) {
+        self.key_input(keyboard, event, ElementState::Pressed, true);
+    }
+
+    fn key_input(
+        &mut self,
+        keyboard: &WlKeyboard,
+        event: SctkKeyEvent,
+        state: ElementState,
+        repeat: bool,
+    ) {
+        let seat_state = match seat_for_keyboard(&mut self.winit.seats, keyboard) {
+            Some(seat_state) => seat_state,
+            None => return,
+        };
+
+        let window_id = match seat_state.keyboard_state.focus {
+            Some(window_id) => window_id,
+            None => return,
+        };
+
+        let key_without_modifiers =
+            seat_state.keyboard_state.key_without_modifiers(event.raw_code, event.keysym);
+        let logical_key = key_from_keysym(event.keysym);
+
+        // The repeated keys are not fed into the compose sequence again.
+        let compose = match state {
+            ElementState::Pressed if !repeat => seat_state.keyboard_state.compose(event.keysym),
+            _ => Compose::None,
+        };
+
+        let (text, text_with_all_modifiers) = match state {
+            ElementState::Pressed => match compose {
+                Compose::None => {
+                    let text = match &logical_key {
+                        Key::Character(text) => Some(text.clone()),
+                        _ => None,
+                    };
+                    (text, event.utf8)
+                },
+                Compose::Composing => (None, None),
+                Compose::Composed(text) => (text.clone(), text),
+            },
+            ElementState::Released => (None, None),
+        };
+
+        // The text with the control characters, like from `Ctrl+A`, is not an input.
+        let committed_text = text_with_all_modifiers
+            .as_deref()
+            .filter(|text| !text.chars().any(char::is_control))
+            .map(SmolStr::new);
+
+        let event = KeyEvent {
+            physical_key: event.raw_code,
+            logical_key,
+            text,
+            text_with_all_modifiers,
+            key_without_modifiers,
+            state,
+            repeat,
+        };
+
+        let user = self.user.as_mut().unwrap();
+        self.winit.deliver_input(user, window_id, InputEvent::Key(event));
+
+        if let Some(text) = committed_text {
+            self.winit.deliver_input(user, window_id, InputEvent::Text(text));
+        }
+    }
+}
+
+/// Find the seat the `keyboard` belongs to.
+// The hash of the `ObjectId` doesn't depend on its interior mutability.
+#[allow(clippy::mutable_key_type)]
+fn seat_for_keyboard<'a>(
+    seats: &'a mut HashMap<ObjectId, WinitSeatState>,
+    keyboard: &WlKeyboard,
+) -> Option<&'a mut WinitSeatState> {
+    seats.values_mut().find(|seat_state| seat_state.keyboard.as_ref() == Some(keyboard))
+}
+
+/// The locale used to lookup the compose table.
+fn locale() -> OsString {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .into_iter()
+        .find_map(|var| env::var_os(var).filter(|locale| !locale.is_empty()))
+        .unwrap_or_else(|| "C".into())
+}
+
+fn key_from_keysym(keysym: Keysym) -> Key {
+    match keysym.key_char().filter(|ch| !ch.is_control()) {
+        Some(ch) => Key::Character(ch.to_string()),
+        None => Key::Unidentified(keysym.raw()),
+    }
+}
+
+sctk::delegate_keyboard!(@<T: Application + 'static> RuntimeState<T>);
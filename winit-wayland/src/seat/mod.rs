@@ -0,0 +1,204 @@
+//! Seat and input handling.
+
+use std::time::Instant;
+
+use sctk::reexports::client::protocol::wl_keyboard::WlKeyboard;
+use sctk::reexports::client::protocol::wl_seat::WlSeat;
+use sctk::reexports::client::{Connection, Proxy, QueueHandle};
+
+use sctk::data_device_manager::data_device::DataDevice;
+use sctk::reexports::protocols::ext::idle_notify::v1::client::ext_idle_notification_v1::ExtIdleNotificationV1;
+use sctk::seat::pointer::{ThemeSpec, ThemedPointer};
+use sctk::seat::{Capability as SeatCapability, SeatHandler, SeatState};
+use wayland_protocols::wp::cursor_shape::v1::client::wp_cursor_shape_device_v1::WpCursorShapeDeviceV1;
+
+use winit_core::application::Application;
+use winit_core::cursor::CursorIcon;
+use winit_core::window::WindowId;
+
+use crate::clipboard::Clipboard;
+use crate::event_loop::RuntimeState;
+
+mod filter;
+mod idle;
+mod keyboard;
+mod pointer;
+
+pub(crate) use filter::EventFilters;
+pub use keyboard::KeyboardState;
+pub use pointer::{PendingMotion, WheelAxis};
+
+/// The state of the particular seat.
+#[derive(Debug, Default)]
+pub struct WinitSeatState {
+    /// The seat itself.
+    pub(crate) seat: Option<WlSeat>,
+
+    /// The keyboard bound on the seat.
+    pub(crate) keyboard: Option<WlKeyboard>,
+
+    /// The state of the keyboard.
+    pub(crate) keyboard_state: KeyboardState,
+
+    /// The pointer bound on the seat, with its own cursor surface.
+    pub(crate) pointer: Option<ThemedPointer>,
+
+    /// The device to set the cursor by its name.
+    pub(crate) cursor_shape_device: Option<WpCursorShapeDeviceV1>,
+
+    /// The window the pointer is over.
+    pub(crate) pointer_focus: Option<WindowId>,
+
+    /// The cursor shown since the pointer entered the window.
+    pub(crate) cursor: Option<CursorIcon>,
+
+    /// The fractions of the wheel clicks not delivered as the whole clicks
+    /// yet, in the 120ths of the click.
+    pub(crate) wheel_remainder: (i32, i32),
+
+    /// The wheel scroll along the horizontal and the vertical axes.
+    pub(crate) wheel_axes: (WheelAxis, WheelAxis),
+
+    /// The notification about the user inactivity on the seat.
+    pub(crate) idle_notification: Option<ExtIdleNotificationV1>,
+
+    /// The time the user became idle on the seat.
+    pub(crate) idle_since: Option<Instant>,
+
+    /// The data device to access the clipboard of the seat.
+    pub(crate) data_device: Option<DataDevice>,
+}
+
+impl WinitSeatState {
+    pub(crate) fn new(seat: WlSeat) -> Self {
+        Self { seat: Some(seat), ..Default::default() }
+    }
+}
+
+impl<T: Application + 'static> SeatHandler for RuntimeState<T> {
+    fn seat_state(&mut self) -> &mut SeatState {
+        &mut self.winit.seat_state
+    }
+
+    fn new_capability(
+        &mut self,
+        _: &Connection,
+        queue_handle: &QueueHandle<Self>,
+        seat: WlSeat,
+        capability: SeatCapability,
+    ) {
+        let seat_state = self.winit.seats.entry(seat.id()).or_default();
+        seat_state.seat = Some(seat.clone());
+        match capability {
+            SeatCapability::Keyboard if seat_state.keyboard.is_none() => {
+                // The repeat follows the rate and the delay from the
+                // `wl_keyboard.repeat_info`.
+                let keyboard = self.winit.seat_state.get_keyboard_with_repeat(
+                    queue_handle,
+                    &seat,
+                    None,
+                    self.winit.loop_handle.clone(),
+                    Box::new(|state, keyboard, event| state.key_repeat(keyboard, event)),
+                );
+                seat_state.keyboard = keyboard.ok();
+            },
+            SeatCapability::Pointer if seat_state.pointer.is_none() => {
+                // Each seat gets its own cursor surface, so the cursors of the
+                // seats don't affect each other.
+                let surface = self.winit.compositor.create_surface(queue_handle);
+                let pointer = self.winit.seat_state.get_pointer_with_theme(
+                    queue_handle,
+                    &seat,
+                    self.winit.shm.wl_shm(),
+                    surface,
+                    ThemeSpec::default(),
+                );
+                seat_state.pointer = pointer.ok();
+
+                let manager = self.winit.cursor_shape_manager.as_ref();
+                seat_state.cursor_shape_device =
+                    seat_state.pointer.as_ref().zip(manager).map(|(pointer, manager)| {
+                        manager.get_shape_device(pointer.pointer(), queue_handle)
+                    });
+            },
+            _ => (),
+        }
+    }
+
+    fn remove_capability(
+        &mut self,
+        _: &Connection,
+        _queue_handle: &QueueHandle<Self>,
+        seat: WlSeat,
+        capability: SeatCapability,
+    ) {
+        let seat_state = match self.winit.seats.get_mut(&seat.id()) {
+            Some(seat_state) => seat_state,
+            None => return,
+        };
+
+        match capability {
+            SeatCapability::Keyboard => {
+                if let Some(keyboard) = seat_state.keyboard.take() {
+                    if keyboard.version() >= 3 {
+                        keyboard.release();
+                    }
+                }
+                seat_state.keyboard_state = Default::default();
+            },
+            SeatCapability::Pointer => {
+                if let Some(cursor_shape_device) = seat_state.cursor_shape_device.take() {
+                    cursor_shape_device.destroy();
+                }
+
+                // Releases the pointer along with its cursor surface.
+                drop(seat_state.pointer.take());
+                seat_state.pointer_focus = None;
+                seat_state.cursor = None;
+            },
+            _ => (),
+        }
+    }
+
+    fn new_seat(
+        &mut self,
+        _connection: &Connection,
+        queue_handle: &QueueHandle<Self>,
+        seat: WlSeat,
+    ) {
+        let winit = &mut self.winit;
+        let mut seat_state = WinitSeatState::new(seat.clone());
+        seat_state.data_device =
+            Clipboard::data_device(winit.data_device_manager.as_ref(), &seat, queue_handle);
+        seat_state.set_idle_threshold(
+            winit.idle_notifier.as_ref(),
+            winit.idle_threshold,
+            queue_handle,
+        );
+        winit.seats.insert(seat.id(), seat_state);
+    }
+
+    fn remove_seat(
+        &mut self,
+        _connection: &Connection,
+        _queue_handle: &QueueHandle<Self>,
+        seat: WlSeat,
+    ) {
+        let seat_state = match self.winit.seats.remove(&seat.id()) {
+            Some(seat_state) => seat_state,
+            None => return,
+        };
+
+        if let Some(idle_notification) = seat_state.idle_notification {
+            idle_notification.destroy();
+        }
+
+        if let Some(data_device) = seat_state.data_device {
+            if data_device.inner().version() >= 2 {
+                data_device.inner().release();
+            }
+        }
+    }
+}
+
+sctk::delegate_seat!(@<T: Application + 'static> RuntimeState<T>);
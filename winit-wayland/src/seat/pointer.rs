@@ -0,0 +1,478 @@
+//! The pointer input handling.
+
+use std::mem;
+use std::time::Duration;
+
+use sctk::reexports::client::protocol::wl_pointer::{AxisSource, WlPointer};
+use sctk::reexports::client::protocol::wl_seat::WlSeat;
+use sctk::reexports::client::{Connection, Proxy, QueueHandle};
+use sctk::reexports::csd_frame::FrameClick;
+
+use sctk::seat::pointer::{
+    AxisScroll, PointerData, PointerEvent, PointerEventKind, PointerHandler,
+};
+
+use smallvec::SmallVec;
+use wayland_protocols::wp::cursor_shape::v1::client::wp_cursor_shape_device_v1::Shape;
+
+use winit_core::application::Application;
+use winit_core::cursor::CursorIcon;
+use winit_core::dpi::{LogicalPosition, PhysicalPosition};
+use winit_core::input::filter::InputEvent;
+use winit_core::input::pointer::{
+    MotionCompression, PointerButton, PointerButtonEvent, PointerMotion, PointerScroll, ScrollDelta,
+};
+use winit_core::input::ElementState;
+use winit_core::window::WindowId;
+
+use crate::event_loop::RuntimeState;
+use crate::rate_limit::{limited_debug, LogKind};
+use crate::seat::WinitSeatState;
+use crate::state::WinitState;
+
+// The buttons from the `linux/input-event-codes.h`.
+const BTN_LEFT: u32 = 0x110;
+const BTN_RIGHT: u32 = 0x111;
+const BTN_MIDDLE: u32 = 0x112;
+const BTN_SIDE: u32 = 0x113;
+const BTN_EXTRA: u32 = 0x114;
+
+/// The [`PointerButton`] of the evdev `button` code.
+fn pointer_button(button: u32) -> PointerButton {
+    match button {
+        BTN_LEFT => PointerButton::Left,
+        BTN_RIGHT => PointerButton::Right,
+        BTN_MIDDLE => PointerButton::Middle,
+        BTN_SIDE => PointerButton::Back,
+        BTN_EXTRA => PointerButton::Forward,
+        button => PointerButton::Other(button as u16),
+    }
+}
+
+/// The shape of the cursor-shape protocol for the `icon`.
+pub(crate) fn cursor_shape(icon: CursorIcon) -> Shape {
+    match icon {
+        CursorIcon::Default => Shape::Default,
+        CursorIcon::ContextMenu => Shape::ContextMenu,
+        CursorIcon::Help => Shape::Help,
+        CursorIcon::Pointer => Shape::Pointer,
+        CursorIcon::Progress => Shape::Progress,
+        CursorIcon::Wait => Shape::Wait,
+        CursorIcon::Cell => Shape::Cell,
+        CursorIcon::Crosshair => Shape::Crosshair,
+        CursorIcon::Text => Shape::Text,
+        CursorIcon::VerticalText => Shape::VerticalText,
+        CursorIcon::Alias => Shape::Alias,
+        CursorIcon::Copy => Shape::Copy,
+        CursorIcon::Move => Shape::Move,
+        CursorIcon::NoDrop => Shape::NoDrop,
+        CursorIcon::NotAllowed => Shape::NotAllowed,
+        CursorIcon::Grab => Shape::Grab,
+        CursorIcon::Grabbing => Shape::Grabbing,
+        CursorIcon::EResize => Shape::EResize,
+        CursorIcon::NResize => Shape::NResize,
+        CursorIcon::NeResize => Shape::NeResize,
+        CursorIcon::NwResize => Shape::NwResize,
+        CursorIcon::SResize => Shape::SResize,
+        CursorIcon::SeResize => Shape::SeResize,
+        CursorIcon::SwResize => Shape::SwResize,
+        CursorIcon::WResize => Shape::WResize,
+        CursorIcon::EwResize => Shape::EwResize,
+        CursorIcon::NsResize => Shape::NsResize,
+        CursorIcon::NeswResize => Shape::NeswResize,
+        CursorIcon::NwseResize => Shape::NwseResize,
+        CursorIcon::ColResize => Shape::ColResize,
+        CursorIcon::RowResize => Shape::RowResize,
+        CursorIcon::AllScroll => Shape::AllScroll,
+        CursorIcon::ZoomIn => Shape::ZoomIn,
+        CursorIcon::ZoomOut => Shape::ZoomOut,
+        // The icons added to the `cursor-icon` after the protocol.
+        _ => Shape::Default,
+    }
+}
+
+/// The distance of the wheel click until one is reported, the one of the
+/// libinput based compositors.
+const DEFAULT_CLICK_DISTANCE: f64 = 10.;
+
+/// The wheel scroll along one axis.
+///
+/// The `wl_pointer.axis_value120` is only sent on the `wl_seat` v8, which sctk
+/// doesn't bind, so the high resolution scroll is estimated from the distance
+/// of the axis events, in the clicks of the distance reported along the
+/// `wl_pointer.axis_discrete`. The high resolution wheels only send the
+/// discrete event once the whole click is scrolled, so the estimate is
+/// corrected to sum up to the click then.
+#[derive(Debug, Clone, Copy)]
+pub struct WheelAxis {
+    /// The distance of one click.
+    click_distance: f64,
+
+    /// The distance scrolled since the last discrete event.
+    pending_distance: f64,
+
+    /// The 120ths reported since the last discrete event.
+    pending120: i32,
+}
+
+impl Default for WheelAxis {
+    fn default() -> Self {
+        Self { click_distance: DEFAULT_CLICK_DISTANCE, pending_distance: 0., pending120: 0 }
+    }
+}
+
+impl WheelAxis {
+    /// The scroll of the `axis` in the 120ths of the click, the `wheel` tells
+    /// whether the source of the axis event is the wheel.
+    fn value120(&mut self, axis: AxisScroll, wheel: bool) -> i32 {
+        if axis.discrete != 0 {
+            let distance = self.pending_distance + axis.absolute;
+            if distance != 0. && distance.signum() == f64::from(axis.discrete).signum() {
+                self.click_distance = (distance / f64::from(axis.discrete)).abs();
+            }
+
+            // Complete the clicks the estimate was reported for.
+            let value120 = axis.discrete * 120 - self.pending120;
+            self.pending_distance = 0.;
+            self.pending120 = 0;
+            return value120;
+        }
+
+        if !wheel || axis.absolute == 0. {
+            return 0;
+        }
+
+        // Stay below the click until the discrete event reports it.
+        let estimate = (axis.absolute / self.click_distance * 120.).round() as i32;
+        let pending120 = (self.pending120 + estimate).clamp(-119, 119);
+        let value120 = pending120 - self.pending120;
+        self.pending_distance += axis.absolute;
+        self.pending120 = pending120;
+        value120
+    }
+}
+
+/// The motion of the pointer over the window waiting to be delivered.
+#[derive(Debug, Default)]
+pub struct PendingMotion {
+    /// The last position delivered to the user.
+    last_position: Option<PhysicalPosition<f64>>,
+
+    /// The positions since the last delivery.
+    positions: Vec<PhysicalPosition<f64>>,
+}
+
+impl<T: Application + 'static> PointerHandler for RuntimeState<T> {
+    fn pointer_frame(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        pointer: &WlPointer,
+        events: &[PointerEvent],
+    ) {
+        let seat = match pointer.data::<PointerData>() {
+            Some(data) => data.seat().clone(),
+            None => return,
+        };
+        let device_id = crate::make_did(&seat);
+
+        for event in events {
+            if let PointerEventKind::Press { serial, .. } = event.kind {
+                self.winit.latest_serial = Some((seat.clone(), serial));
+            }
+
+            let window_id = self.winit.window_id(&event.surface);
+            let scale_factor = match self.winit.windows.get(&window_id) {
+                Some(window) => window.scale_factor,
+                // The events on the decorations are handled by the frame.
+                None => {
+                    self.frame_pointer_event(&seat, event);
+                    continue;
+                },
+            };
+
+            let winit = &mut self.winit;
+            let user = self.user.as_mut().unwrap();
+
+            let position = LogicalPosition::<f64>::from(event.position).to_physical(scale_factor);
+
+            // The position is tracked on every event, so it's current even with
+            // the motion compressed.
+            if let Some(window) = winit.windows.get_mut(&window_id) {
+                let position = match event.kind {
+                    PointerEventKind::Leave { .. } => None,
+                    _ => Some(position),
+                };
+                window.set_pointer_position(device_id, position);
+            }
+
+            match event.kind {
+                PointerEventKind::Enter { .. } => {
+                    winit.pointer_entered(&seat, window_id);
+                    winit.pending_motion.insert(window_id, PendingMotion::default());
+                    let event = InputEvent::PointerEntered { position, device_id };
+                    winit.deliver_input(user, window_id, event);
+                },
+                PointerEventKind::Leave { .. } => {
+                    if let Some(seat_state) = winit.seats.get_mut(&seat.id()) {
+                        seat_state.pointer_focus = None;
+                    }
+
+                    // Deliver the motion before leaving to preserve the order.
+                    winit.flush_pointer_motion(user, window_id);
+                    winit.pending_motion.remove(&window_id);
+                    winit.deliver_input(user, window_id, InputEvent::PointerLeft { device_id });
+                    continue;
+                },
+                PointerEventKind::Axis { horizontal, vertical, source, .. } => {
+                    let delta = winit.seats.get_mut(&seat.id()).and_then(|seat_state| {
+                        seat_state.scroll_delta(horizontal, vertical, source, scale_factor)
+                    });
+
+                    if let Some(delta) = delta {
+                        // Deliver the motion before the scroll to preserve the order.
+                        winit.flush_pointer_motion(user, window_id);
+                        let event = InputEvent::PointerScrolled(PointerScroll { delta, device_id });
+                        winit.deliver_input(user, window_id, event);
+                    }
+
+                    continue;
+                },
+                PointerEventKind::Press { button, .. }
+                | PointerEventKind::Release { button, .. } => {
+                    let state = match event.kind {
+                        PointerEventKind::Press { .. } => ElementState::Pressed,
+                        _ => ElementState::Released,
+                    };
+
+                    // Deliver the motion before the button to preserve the order.
+                    winit.flush_pointer_motion(user, window_id);
+                    let button = pointer_button(button);
+                    let event = PointerButtonEvent { button, state, position, device_id };
+                    winit.deliver_input(user, window_id, InputEvent::PointerButton(event));
+                    continue;
+                },
+                PointerEventKind::Motion { .. } => {
+                    limited_debug!(
+                        winit.log_limiter,
+                        LogKind::PointerMotion,
+                        "Pointer motion over {window_id:?} at {position:?}"
+                    );
+                },
+            }
+
+            if let Some(pending) = winit.pending_motion.get_mut(&window_id) {
+                pending.positions.push(position);
+            }
+
+            if winit.motion_compression == MotionCompression::None {
+                // Over the budget the motion is compressed until the next iteration.
+                if winit.dispatch.try_continue() {
+                    winit.flush_pointer_motion(user, window_id);
+                } else {
+                    winit.dispatch.record_deferred();
+                }
+            }
+        }
+    }
+}
+
+impl<T: Application + 'static> RuntimeState<T> {
+    /// Route the pointer event to the frame the `event.surface` belongs to.
+    fn frame_pointer_event(&mut self, seat: &WlSeat, event: &PointerEvent) {
+        let time = match event.kind {
+            PointerEventKind::Motion { time }
+            | PointerEventKind::Press { time, .. }
+            | PointerEventKind::Release { time, .. } => time,
+            _ => 0,
+        };
+        let timestamp = Duration::from_millis(time as u64);
+        let (x, y) = event.position;
+
+        let window = self.winit.windows.iter_mut().find_map(|(window_id, window)| {
+            window
+                .frame_point_moved(&event.surface, timestamp, x, y)
+                .then_some((*window_id, window))
+        });
+        let (window_id, window) = match window {
+            Some(window) => window,
+            None => return,
+        };
+
+        let (button, serial, pressed) = match event.kind {
+            PointerEventKind::Leave { .. } => {
+                window.frame_point_left();
+                return;
+            },
+            PointerEventKind::Press { button, serial, .. } => (button, serial, true),
+            PointerEventKind::Release { button, serial, .. } => (button, serial, false),
+            _ => return,
+        };
+
+        let click = match button {
+            BTN_LEFT => FrameClick::Normal,
+            BTN_RIGHT => FrameClick::Alternate,
+            _ => return,
+        };
+
+        let action = match window.frame_click(seat, serial, timestamp, click, pressed) {
+            Some(action) => action,
+            None => return,
+        };
+
+        let user = self.user.as_mut().unwrap();
+        if user.decoration_action(&mut self.winit, window_id, action) {
+            self.decoration_action(window_id, action);
+        }
+    }
+}
+
+impl WinitSeatState {
+    /// The scroll of the axis event, `None` when there's nothing to scroll,
+    /// like for the end of the touchpad scroll.
+    fn scroll_delta(
+        &mut self,
+        horizontal: AxisScroll,
+        vertical: AxisScroll,
+        source: Option<AxisSource>,
+        scale_factor: f64,
+    ) -> Option<ScrollDelta> {
+        let wheel = matches!(source, Some(AxisSource::Wheel | AxisSource::WheelTilt));
+        let (x_axis, y_axis) = &mut self.wheel_axes;
+        let (x120, y120) = (x_axis.value120(horizontal, wheel), y_axis.value120(vertical, wheel));
+
+        if x120 != 0 || y120 != 0 {
+            let (x, y) = &mut self.wheel_remainder;
+            *x += x120;
+            *y += y120;
+
+            let clicks = PhysicalPosition::new(*x / 120, *y / 120);
+            *x %= 120;
+            *y %= 120;
+
+            let value120 = PhysicalPosition::new(x120, y120);
+            return Some(ScrollDelta::Wheel { clicks, value120 });
+        }
+
+        if wheel || (horizontal.absolute == 0. && vertical.absolute == 0.) {
+            return None;
+        }
+
+        let delta = LogicalPosition::new(horizontal.absolute, vertical.absolute);
+        Some(ScrollDelta::Pixels(delta.to_physical(scale_factor)))
+    }
+
+    /// Show the `cursor` on the pointer of the seat.
+    pub(crate) fn set_cursor(&mut self, connection: &Connection, cursor: CursorIcon) {
+        let pointer = match self.pointer.as_ref() {
+            Some(pointer) if self.cursor != Some(cursor) => pointer,
+            _ => return,
+        };
+
+        match self.cursor_shape_device.as_ref() {
+            Some(cursor_shape_device) => {
+                let data = pointer.pointer().data::<PointerData>();
+                let Some(serial) = data.and_then(PointerData::latest_enter_serial) else {
+                    return;
+                };
+                cursor_shape_device.set_shape(serial, cursor_shape(cursor));
+            },
+            // Draw the cursor from the theme when the compositor can't do it.
+            None => {
+                if let Err(err) = pointer.set_cursor(connection, cursor) {
+                    log::warn!("Failed to set the cursor {cursor:?}: {err}");
+                    return;
+                }
+            },
+        }
+
+        self.cursor = Some(cursor);
+    }
+}
+
+impl<T: Application + 'static> WinitState<T> {
+    /// Track the window the pointer of the `seat` entered and show its cursor.
+    fn pointer_entered(&mut self, seat: &WlSeat, window_id: WindowId) {
+        let cursor = match self.windows.get(&window_id) {
+            Some(window) => window.cursor(crate::make_did(seat)),
+            None => return,
+        };
+
+        let seat_state = match self.seats.get_mut(&seat.id()) {
+            Some(seat_state) => seat_state,
+            None => return,
+        };
+
+        // The cursor has to be set again on each enter.
+        seat_state.pointer_focus = Some(window_id);
+        seat_state.cursor = None;
+        seat_state.set_cursor(&self.connection, cursor);
+    }
+
+    /// Show the cursors changed by the user on the seats over the windows.
+    pub(crate) fn update_cursors(&mut self) {
+        for (window_id, window) in &mut self.windows {
+            if !mem::take(&mut window.cursor_changed) {
+                continue;
+            }
+
+            for seat_state in self.seats.values_mut() {
+                let seat = match seat_state.seat.as_ref() {
+                    Some(seat) if seat_state.pointer_focus == Some(*window_id) => seat,
+                    _ => continue,
+                };
+
+                let cursor = window.cursor(crate::make_did(seat));
+                seat_state.set_cursor(&self.connection, cursor);
+            }
+        }
+    }
+
+    /// Deliver the pending motion for all the windows.
+    pub(crate) fn flush_all_pointer_motion(&mut self, user: &mut T) {
+        // The pointer is usually over a single window, so don't allocate.
+        let window_ids: SmallVec<[WindowId; 4]> = self
+            .pending_motion
+            .iter()
+            .filter_map(|(window_id, pending)| {
+                (!pending.positions.is_empty()).then_some(*window_id)
+            })
+            .collect();
+
+        for window_id in window_ids {
+            self.flush_pointer_motion(user, window_id);
+        }
+    }
+
+    /// Deliver the pending motion for the given window.
+    pub(crate) fn flush_pointer_motion(&mut self, user: &mut T, window_id: WindowId) {
+        let pending = match self.pending_motion.get_mut(&window_id) {
+            Some(pending) => pending,
+            None => return,
+        };
+
+        let position = match pending.positions.last() {
+            Some(position) => *position,
+            None => return,
+        };
+
+        let delta = match pending.last_position.replace(position) {
+            Some(last) => PhysicalPosition::new(position.x - last.x, position.y - last.y),
+            None => PhysicalPosition::new(0., 0.),
+        };
+
+        // Expose the positions to the user for the duration of the callback, the
+        // allocations are swapped back to be reused.
+        mem::swap(&mut self.coalesced_positions, &mut pending.positions);
+
+        let motion = PointerMotion { position, delta };
+        self.deliver_input(user, window_id, InputEvent::PointerMoved(motion));
+
+        self.coalesced_positions.clear();
+        if let Some(pending) = self.pending_motion.get_mut(&window_id) {
+            mem::swap(&mut self.coalesced_positions, &mut pending.positions);
+        }
+    }
+}
+
+sctk::delegate_pointer!(@<T: Application + 'static> RuntimeState<T>);
@@ -0,0 +1,145 @@
+//! Keeping the application single-instance over the DBus session bus.
+
+use std::error::Error;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::{env, fmt, io};
+
+use calloop::channel::Sender;
+use zbus::blocking::{Connection, ConnectionBuilder};
+use zbus::{dbus_interface, Interface};
+
+use winit_core::event_loop::ActivationRequest;
+
+/// The object the running instance serves the activation requests at.
+const OBJECT_PATH: &str = "/org/rust_windowing/winit/SingleInstance";
+
+/// The outcome of [`EventLoop::claim_single_instance`].
+///
+/// [`EventLoop::claim_single_instance`]: crate::event_loop::EventLoop::claim_single_instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SingleInstance {
+    /// This process owns the name and receives the activation requests.
+    Primary,
+
+    /// The request was forwarded to the running instance, this process
+    /// should exit.
+    Forwarded,
+}
+
+/// The error of [`EventLoop::claim_single_instance`].
+///
+/// [`EventLoop::claim_single_instance`]: crate::event_loop::EventLoop::claim_single_instance
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SingleInstanceError {
+    /// The session bus is not reachable.
+    NoSessionBus(zbus::Error),
+
+    /// The name is owned by the other instance, which didn't take the
+    /// forwarded request.
+    ForwardFailed(zbus::Error),
+
+    /// The session bus refused to give the name.
+    NameRefused(zbus::Error),
+
+    /// The requests of the other instances can't be received on the event
+    /// loop.
+    Io(io::Error),
+}
+
+impl fmt::Display for SingleInstanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSessionBus(err) => write!(f, "failed to connect to the session bus: {err}"),
+            Self::ForwardFailed(err) => write!(f, "failed to forward the request: {err}"),
+            Self::NameRefused(err) => write!(f, "failed to claim the name: {err}"),
+            Self::Io(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for SingleInstanceError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::NoSessionBus(err) | Self::ForwardFailed(err) | Self::NameRefused(err) => {
+                Some(err)
+            },
+            Self::Io(err) => Some(err),
+        }
+    }
+}
+
+/// The interface served at the [`OBJECT_PATH`].
+struct ActivationService {
+    sender: Mutex<Sender<ActivationRequest>>,
+}
+
+#[dbus_interface(name = "org.rust_windowing.winit.SingleInstance")]
+impl ActivationService {
+    fn activate(
+        &self,
+        arguments: Vec<String>,
+        working_directory: String,
+        activation_token: String,
+    ) {
+        let request = ActivationRequest {
+            arguments,
+            working_directory: (!working_directory.is_empty())
+                .then(|| PathBuf::from(working_directory)),
+            activation_token: (!activation_token.is_empty()).then_some(activation_token),
+        };
+
+        let _ = self.sender.lock().unwrap().send(request);
+    }
+}
+
+/// Claim the `app_id` on the session bus and serve the activation requests.
+///
+/// Returns `None` when the name is already owned by the other instance.
+pub(crate) fn serve(
+    app_id: &str,
+    sender: Sender<ActivationRequest>,
+) -> Result<Option<Connection>, SingleInstanceError> {
+    let service = ActivationService { sender: Mutex::new(sender) };
+    let connection = ConnectionBuilder::session()
+        .and_then(|builder| builder.serve_at(OBJECT_PATH, service))
+        .and_then(|builder| builder.build())
+        .map_err(SingleInstanceError::NoSessionBus)?;
+
+    // The name is claimed once connected, to tell the missing bus apart from
+    // the running instance.
+    match connection.request_name(app_id) {
+        Ok(()) => Ok(Some(connection)),
+        Err(zbus::Error::NameTaken) => Ok(None),
+        Err(err) => Err(SingleInstanceError::NameRefused(err)),
+    }
+}
+
+/// Forward the command line of this process to the instance owning `app_id`.
+pub(crate) fn forward(
+    app_id: &str,
+    activation_token: Option<String>,
+) -> Result<(), SingleInstanceError> {
+    // The arguments are sent as strings over the bus.
+    let arguments: Vec<String> =
+        env::args_os().skip(1).map(|argument| argument.to_string_lossy().into_owned()).collect();
+    let working_directory = env::current_dir()
+        .ok()
+        .and_then(|dir| dir.into_os_string().into_string().ok())
+        .unwrap_or_default();
+    let activation_token = activation_token.unwrap_or_default();
+
+    let connection = Connection::session().map_err(SingleInstanceError::NoSessionBus)?;
+    connection
+        .call_method(
+            Some(app_id),
+            OBJECT_PATH,
+            Some(ActivationService::name()),
+            "Activate",
+            &(arguments, working_directory, activation_token),
+        )
+        .map_err(SingleInstanceError::ForwardFailed)?;
+
+    Ok(())
+}
@@ -1,21 +1,35 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::io;
+use std::os::fd::{BorrowedFd, RawFd};
+use std::path::Path;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use calloop::ping::Ping;
 use raw_window_handle::{DisplayHandle, HandleError, HasDisplayHandle};
 use raw_window_handle_05::HasRawDisplayHandle as HasRawDisplayHandle05;
 
-use sctk::reexports::calloop::LoopHandle;
+use sctk::reexports::calloop::generic::Generic;
+use sctk::reexports::calloop::timer::{TimeoutAction, Timer};
+use sctk::reexports::calloop::{
+    Interest as CalloopInterest, LoopHandle, Mode, PostAction, RegistrationToken,
+};
 use sctk::reexports::client::backend::ObjectId;
 use sctk::reexports::client::globals::GlobalList;
+use sctk::reexports::client::protocol::wl_callback::{self, WlCallback};
 use sctk::reexports::client::protocol::wl_output::{self, WlOutput};
 use sctk::reexports::client::protocol::wl_seat::WlSeat;
 use sctk::reexports::client::protocol::wl_surface::WlSurface;
-use sctk::reexports::client::{Connection, Proxy, QueueHandle};
+use sctk::reexports::client::{Connection, Dispatch, Proxy, QueueHandle};
 
+use sctk::activation::{ActivationHandler, ActivationState, RequestData};
 use sctk::compositor::{CompositorHandler, CompositorState};
-use sctk::output::{OutputHandler, OutputState};
+use sctk::data_device_manager::DataDeviceManagerState;
+use sctk::globals::GlobalData;
+use sctk::output::{OutputData, OutputHandler, OutputState};
 use sctk::registry::{ProvidesRegistryState, RegistryState};
+use sctk::seat::pointer::cursor_shape::CursorShapeManager;
 use sctk::seat::pointer::ThemedPointer;
 use sctk::seat::{Capability as SeatCapability, SeatHandler, SeatState};
 use sctk::shell::xdg::window::{Window as XdgWindow, WindowConfigure, WindowHandler};
@@ -25,13 +39,52 @@ use sctk::shm::slot::SlotPool;
 use sctk::shm::{Shm, ShmHandler};
 use sctk::subcompositor::SubcompositorState;
 
+use sctk::reexports::protocols::ext::idle_notify::v1::client::ext_idle_notifier_v1::ExtIdleNotifierV1;
+use sctk::reexports::protocols::wp::fractional_scale::v1::client::wp_fractional_scale_manager_v1::{
+    self, WpFractionalScaleManagerV1,
+};
+use sctk::reexports::protocols::wp::fractional_scale::v1::client::wp_fractional_scale_v1::{
+    self, WpFractionalScaleV1,
+};
+use sctk::reexports::protocols::wp::presentation_time::client::wp_presentation::{
+    self, WpPresentation,
+};
+use sctk::reexports::protocols::wp::presentation_time::client::wp_presentation_feedback::{
+    self, WpPresentationFeedback,
+};
+use sctk::reexports::protocols::wp::viewporter::client::wp_viewporter::WpViewporter;
+
+use smallvec::SmallVec;
+use wayland_protocols::wp::linux_drm_syncobj::v1::client::wp_linux_drm_syncobj_manager_v1::WpLinuxDrmSyncobjManagerV1;
+use wayland_protocols_wlr::output_management::v1::client::zwlr_output_manager_v1::ZwlrOutputManagerV1;
+use wayland_protocols_wlr::output_power_management::v1::client::zwlr_output_power_manager_v1::ZwlrOutputPowerManagerV1;
+
 use winit_core::application::Application;
+use winit_core::clipboard::Clipboard as CoreClipboard;
+use winit_core::dpi::{PhysicalPosition, Rounding, RoundingPolicy};
+use winit_core::event_loop::budget::{DispatchBudget, DispatchMeter, DispatchStats};
 use winit_core::event_loop::proxy::EventLoopProxy as CoreEventLoopProxy;
-use winit_core::event_loop::EventLoopHandle;
+use winit_core::event_loop::{
+    EventLoopHandle, FdToken, Interest, Query, QueryResult, Readiness, RequestId, StartupTimings,
+    TimerId, WatchId,
+};
+use winit_core::input::filter::{EventFilter, EventFilterId};
+use winit_core::input::pointer::MotionCompression;
+use winit_core::input::{DeviceCapabilities, DeviceId, DeviceInfo};
 use winit_core::monitor::{Monitor as CoreMonitor, MonitorId};
-use winit_core::window::{Window as CoreWindow, WindowAttributes, WindowId};
+use winit_core::window::{
+    CreateWindowError, GroupId, RoleAttributes, Surface as CoreSurface, Tombstones, WindowError,
+    WindowId,
+};
 
-use crate::monitor::Monitor;
+use crate::clipboard::Clipboard;
+#[cfg(feature = "fswatch")]
+use crate::fswatch::FileWatcher;
+use crate::ids::IdAllocator;
+use crate::monitor::{Monitor, MonitorRegistry, OutputHead};
+use crate::rate_limit::{limited_debug, LogKind, RateLimiter};
+use crate::role::SurfaceRole;
+use crate::seat::{EventFilters, PendingMotion, WinitSeatState};
 use crate::window::Window;
 
 use crate::event_loop::{EventLoopProxy, RuntimeState};
@@ -41,40 +94,359 @@ impl<T: Application + 'static> EventLoopHandle for WinitState<T> {
         self.proxy.clone()
     }
 
-    fn create_window(&mut self, attributes: &WindowAttributes) -> Result<(), ()> {
+    fn create_window(&mut self, attributes: RoleAttributes) -> Result<(), CreateWindowError> {
+        let attributes = winit_core::env::overrides().apply(attributes);
+        let lints = attributes.validate().map_err(CreateWindowError::InvalidAttributes)?;
+        for lint in lints {
+            log::warn!("Suspicious window attributes: {lint:?}");
+        }
+
+        let attributes = match attributes {
+            RoleAttributes::Toplevel(attributes) => attributes,
+            // TODO: popups and subsurfaces.
+            RoleAttributes::Popup(_) | RoleAttributes::Subsurface(_) => {
+                return Err(CreateWindowError::Unsupported)
+            },
+        };
+
         let window = Window::new(self, attributes);
         let window_id = window.id();
+        debug_assert!(!self.roles.contains_key(&window_id), "{window_id:?} is already in use");
+        self.tombstones.revive(window_id);
         self.windows.insert(window_id, window);
         Ok(())
     }
 
     fn num_windows(&self) -> usize {
-        self.windows.len()
+        self.windows.len() + self.roles.len()
     }
 
-    fn get_window(&self, window_id: WindowId) -> Option<&dyn CoreWindow> {
+    fn get_window(&self, window_id: WindowId) -> Option<&dyn CoreSurface> {
+        self.tombstones.debug_assert_alive(window_id);
+
+        if self.configured_roles.contains(&window_id) {
+            return self.roles.get(&window_id).map(|role| role.as_surface());
+        }
+
         let window = self.windows.get(&window_id)?;
 
         if window.last_configure.is_none() {
             return None;
         } else {
-            Some(window as &dyn CoreWindow)
+            Some(window as &dyn CoreSurface)
         }
     }
 
-    fn get_window_mut(&mut self, window_id: WindowId) -> Option<&mut dyn CoreWindow> {
+    fn get_window_mut(&mut self, window_id: WindowId) -> Option<&mut dyn CoreSurface> {
+        self.tombstones.debug_assert_alive(window_id);
+
+        if self.configured_roles.contains(&window_id) {
+            return self.roles.get_mut(&window_id).map(|role| role.as_surface_mut());
+        }
+
         let window = self.windows.get_mut(&window_id)?;
         if window.last_configure.is_none() {
             return None;
         } else {
-            Some(window as &mut dyn CoreWindow)
+            Some(window as &mut dyn CoreSurface)
+        }
+    }
+
+    fn try_get_window(&self, window_id: WindowId) -> Result<&dyn CoreSurface, WindowError> {
+        if self.configured_roles.contains(&window_id) {
+            let role = self.roles.get(&window_id).ok_or(WindowError::Unknown)?;
+            return Ok(role.as_surface());
+        }
+
+        match self.windows.get(&window_id) {
+            Some(window) if window.last_configure.is_some() => Ok(window as &dyn CoreSurface),
+            Some(_) => Err(WindowError::NotConfigured),
+            None if self.roles.contains_key(&window_id) => Err(WindowError::NotConfigured),
+            None => Err(self.missing_window_error(window_id)),
+        }
+    }
+
+    fn try_get_window_mut(
+        &mut self,
+        window_id: WindowId,
+    ) -> Result<&mut dyn CoreSurface, WindowError> {
+        if self.configured_roles.contains(&window_id) {
+            let role = self.roles.get_mut(&window_id).ok_or(WindowError::Unknown)?;
+            return Ok(role.as_surface_mut());
+        }
+
+        if self.roles.contains_key(&window_id) {
+            return Err(WindowError::NotConfigured);
+        }
+
+        let error = self.missing_window_error(window_id);
+        match self.windows.get_mut(&window_id) {
+            Some(window) if window.last_configure.is_some() => Ok(window as &mut dyn CoreSurface),
+            Some(_) => Err(WindowError::NotConfigured),
+            None => Err(error),
+        }
+    }
+
+    fn set_timer(&mut self, interval: Duration, timer_id: TimerId) {
+        self.cancel_timer(timer_id);
+
+        let timer = Timer::from_duration(interval);
+        let token = self.loop_handle.insert_source(timer, move |_, _, state| {
+            // The timer could be cancelled by the previous callback.
+            if !state.winit.timers.contains_key(&timer_id) {
+                return TimeoutAction::Drop;
+            }
+
+            let winit = &mut state.winit;
+            let user = state.user.as_mut().unwrap();
+            user.timer_fired(winit, timer_id);
+
+            TimeoutAction::ToDuration(interval)
+        });
+
+        match token {
+            Ok(token) => {
+                self.timers.insert(timer_id, token);
+            },
+            Err(err) => {
+                log::warn!("Failed to insert timer: {err}");
+            },
+        }
+    }
+
+    fn cancel_timer(&mut self, timer_id: TimerId) {
+        if let Some(token) = self.timers.remove(&timer_id) {
+            self.loop_handle.remove(token);
+        }
+    }
+
+    unsafe fn register_fd(
+        &mut self,
+        fd: RawFd,
+        interest: Interest,
+        token: FdToken,
+    ) -> io::Result<()> {
+        self.unregister_fd(token);
+
+        let interest = CalloopInterest {
+            readable: interest.contains(Interest::READABLE),
+            writable: interest.contains(Interest::WRITABLE),
+        };
+
+        // SAFETY: the user guarantees that the fd is open until unregistered.
+        let fd = unsafe { BorrowedFd::borrow_raw(fd) };
+        let source = Generic::new(fd, interest, Mode::Level);
+        let registration = self.loop_handle.insert_source(source, move |readiness, _, state| {
+            // The fd could be unregistered by the previous callback.
+            if !state.winit.fds.contains_key(&token) {
+                return Ok(PostAction::Remove);
+            }
+
+            let mut ready = Readiness::empty();
+            ready.set(Readiness::READABLE, readiness.readable);
+            ready.set(Readiness::WRITABLE, readiness.writable);
+            ready.set(Readiness::ERROR, readiness.error);
+
+            let winit = &mut state.winit;
+            let user = state.user.as_mut().unwrap();
+            user.fd_ready(winit, token, ready);
+
+            Ok(PostAction::Continue)
+        });
+
+        let registration_token = registration.map_err(|err| io::Error::from(err.error))?;
+        self.fds.insert(token, registration_token);
+        Ok(())
+    }
+
+    fn unregister_fd(&mut self, token: FdToken) {
+        if let Some(registration_token) = self.fds.remove(&token) {
+            self.loop_handle.remove(registration_token);
+        }
+    }
+
+    #[cfg(feature = "fswatch")]
+    fn watch_path(&mut self, path: &Path, watch_id: WatchId) -> io::Result<()> {
+        self.unwatch_path(watch_id);
+
+        if self.file_watcher.is_none() {
+            self.file_watcher = Some(FileWatcher::new(&self.loop_handle)?);
+        }
+
+        self.file_watcher.as_mut().unwrap().watch(path, watch_id)
+    }
+
+    #[cfg(feature = "fswatch")]
+    fn unwatch_path(&mut self, watch_id: WatchId) {
+        if let Some(file_watcher) = self.file_watcher.as_mut() {
+            file_watcher.unwatch(watch_id);
         }
     }
 
+    fn request(&mut self, query: Query) -> RequestId {
+        let request_id = RequestId(self.next_request_id);
+        self.next_request_id += 1;
+
+        // The sync callback is done once the compositor has processed all the
+        // previous requests, so the state is up to date by then.
+        let data = QueryData { request_id, query };
+        self.connection.display().sync(&self.queue_handle, data);
+
+        request_id
+    }
+
+    fn clipboard(&mut self) -> Option<&mut dyn CoreClipboard> {
+        self.data_device_manager.as_ref()?;
+        Some(&mut self.clipboard)
+    }
+
+    fn set_motion_compression(&mut self, compression: MotionCompression) {
+        self.motion_compression = compression;
+    }
+
+    fn set_dispatch_budget(&mut self, budget: DispatchBudget) {
+        self.dispatch.set_budget(budget);
+    }
+
+    fn dispatch_stats(&self) -> DispatchStats {
+        self.dispatch.stats()
+    }
+
+    fn push_event_filter(&mut self, filter: Box<dyn EventFilter>) -> EventFilterId {
+        self.event_filters.push(filter)
+    }
+
+    fn remove_event_filter(&mut self, filter_id: EventFilterId) {
+        self.event_filters.remove(filter_id);
+    }
+
+    fn idle_since(&self) -> Option<Duration> {
+        let idle_since = self.idle_since_instant()?;
+        Some(idle_since.elapsed() + self.idle_threshold?)
+    }
+
+    fn set_idle_threshold(&mut self, threshold: Option<Duration>) {
+        // The new notifications start from the active state.
+        self.idle_reset |= self.idle_since_instant().is_some();
+        self.idle_threshold = threshold;
+        for seat_state in self.seats.values_mut() {
+            seat_state.set_idle_threshold(
+                self.idle_notifier.as_ref(),
+                threshold,
+                &self.queue_handle,
+            );
+        }
+    }
+
+    fn startup_timings(&self) -> StartupTimings {
+        self.startup.timings
+    }
+
+    fn rounding_policy(&self) -> RoundingPolicy {
+        self.rounding_policy
+    }
+
+    fn set_rounding_policy(&mut self, policy: RoundingPolicy) {
+        self.rounding_policy = policy;
+        for window in self.windows.values_mut() {
+            window.rounding_policy = policy;
+        }
+    }
+
+    fn coalesced_pointer_positions(&self) -> &[PhysicalPosition<f64>] {
+        &self.coalesced_positions
+    }
+
+    fn dump_state(&self, writer: &mut dyn io::Write) -> io::Result<()> {
+        let globals: Vec<_> = self
+            .registry_state
+            .globals()
+            .map(|global| {
+                serde_json::json!({
+                    "name": global.name,
+                    "interface": global.interface,
+                    "version": global.version,
+                })
+            })
+            .collect();
+
+        let monitors: Vec<_> = self
+            .monitors
+            .iter()
+            .map(|monitor| {
+                let size = monitor.size();
+                let position = monitor.position();
+                serde_json::json!({
+                    "id": monitor.id().0,
+                    "connection_name": monitor.connection_name(),
+                    "size": (size.width, size.height),
+                    "position": (position.x, position.y),
+                    "refresh_rate_millihertz": monitor.refresh_rate_millihertz(),
+                    "scale_factor": monitor.scale_factor(),
+                })
+            })
+            .collect();
+
+        let seats: Vec<_> = self
+            .seats
+            .values()
+            .map(|seat| {
+                serde_json::json!({
+                    "keyboard": seat.keyboard.as_ref().map(|keyboard| keyboard.version()),
+                    "keyboard_focus": seat.keyboard_state.focus.map(|window_id| window_id.0),
+                    "pointer": seat.pointer.as_ref().map(|pointer| pointer.pointer().version()),
+                })
+            })
+            .collect();
+
+        let windows: Vec<_> = self.windows.values().map(|window| window.dump()).collect();
+
+        let roles: Vec<_> = self
+            .roles
+            .keys()
+            .map(|window_id| {
+                serde_json::json!({
+                    "id": window_id.0,
+                    "configured": self.configured_roles.contains(window_id),
+                })
+            })
+            .collect();
+
+        let state = serde_json::json!({
+            "backend": "wayland",
+            "globals": globals,
+            "monitors": monitors,
+            "seats": seats,
+            "windows": windows,
+            "roles": roles,
+            "timers": self.timers.len(),
+            "motion_compression": format!("{:?}", self.motion_compression),
+            "pending_motion": self.pending_motion.len(),
+            "dispatch_stats": format!("{:?}", self.dispatch.stats()),
+        });
+
+        serde_json::to_writer_pretty(writer, &state).map_err(io::Error::from)
+    }
+
     fn exit(&mut self) {
         self.exit = true;
     }
 
+    fn group_members(&self, group: GroupId) -> SmallVec<[WindowId; 4]> {
+        let mut members: SmallVec<[(Instant, WindowId); 4]> = self
+            .windows
+            .iter()
+            .filter_map(|(window_id, window)| match window.group {
+                Some((window_group, joined_at)) if window_group == group => {
+                    Some((joined_at, *window_id))
+                },
+                _ => None,
+            })
+            .collect();
+        members.sort_unstable_by_key(|&(joined_at, window_id)| (joined_at, window_id.0));
+        members.into_iter().map(|(_, window_id)| window_id).collect()
+    }
+
     fn get_monitor(&self, monitor_id: MonitorId) -> Option<&dyn CoreMonitor> {
         self.monitors
             .iter()
@@ -82,9 +454,27 @@ impl<T: Application + 'static> EventLoopHandle for WinitState<T> {
             .map(|monitor| monitor as &dyn CoreMonitor)
     }
 
-    fn monitors(&self) -> Vec<&dyn CoreMonitor> {
+    fn monitors(&self) -> SmallVec<[&dyn CoreMonitor; 4]> {
         self.monitors.iter().map(|monitor| monitor as &dyn CoreMonitor).collect()
     }
+
+    fn monitor_generation(&self) -> u64 {
+        self.monitor_generation
+    }
+
+    fn device_info(&self, device_id: DeviceId) -> Option<DeviceInfo> {
+        let seat = self.seat_state.seats().find(|seat| crate::make_did(seat) == device_id)?;
+        let info = self.seat_state.info(&seat)?;
+
+        let mut capabilities = DeviceCapabilities::empty();
+        capabilities.set(DeviceCapabilities::POINTER, info.has_pointer);
+        capabilities.set(DeviceCapabilities::KEYBOARD, info.has_keyboard);
+        capabilities.set(DeviceCapabilities::TOUCH, info.has_touch);
+
+        // NOTE: Wayland doesn't expose the underlying hardware, the seat is the
+        // closest thing to the device we have.
+        Some(DeviceInfo { name: info.name, vendor_id: None, product_id: None, capabilities })
+    }
 }
 
 impl<T: Application + 'static> HasDisplayHandle for WinitState<T> {
@@ -127,15 +517,125 @@ pub struct WinitState<T: Application + 'static> {
     /// The XDG shell that is used for widnows.
     pub xdg_shell: XdgShell,
 
+    /// The xdg-activation to pass the focus between the windows.
+    pub(crate) xdg_activation: Option<ActivationState>,
+
+    /// The activation token the application was launched with.
+    pub(crate) activation_token: Option<String>,
+
+    /// The seat and serial of the latest user interaction, used to request
+    /// the activation tokens.
+    pub(crate) latest_serial: Option<(WlSeat, u32)>,
+
+    /// The presentation-time to schedule the frames of each window.
+    pub(crate) presentation: Option<WpPresentation>,
+
+    /// The viewporter to scale the overlay planes and the windows with the
+    /// fractional scale.
+    pub(crate) viewporter: Option<WpViewporter>,
+
+    /// The fractional-scale manager to get the preferred scale of the windows.
+    pub(crate) fractional_scale_manager: Option<WpFractionalScaleManagerV1>,
+
+    /// The linux-drm-syncobj manager for the explicit synchronization.
+    pub(crate) syncobj_manager: Option<WpLinuxDrmSyncobjManagerV1>,
+
     /// Currently handled seats.
-    pub seats: HashMap<ObjectId, ()>,
+    pub seats: HashMap<ObjectId, WinitSeatState>,
 
     pub windows: HashMap<WindowId, Window<T>>,
 
+    /// The allocator of the ids of the windows.
+    pub(crate) window_ids: IdAllocator,
+
+    /// The windows with the custom surface roles.
+    pub roles: HashMap<WindowId, Box<dyn SurfaceRole>>,
+
+    /// The custom roles which got their initial configure.
+    pub(crate) configured_roles: HashSet<WindowId>,
+
+    /// The recently destroyed windows and custom roles.
+    pub(crate) tombstones: Tombstones,
+
     pub monitors: Vec<Monitor>,
 
+    /// The counter of the changes to the monitors.
+    pub(crate) monitor_generation: u64,
+
+    /// The registry of the stable monitor ids.
+    pub(crate) monitor_registry: MonitorRegistry,
+
+    /// The wlr-output-power-management to track the power of the monitors.
+    pub(crate) output_power_manager: Option<ZwlrOutputPowerManagerV1>,
+
+    /// The wlr-output-management to track the adaptive sync of the monitors.
+    output_manager: Option<ZwlrOutputManagerV1>,
+
+    /// The heads advertised by the wlr-output-management.
+    pub(crate) output_heads: HashMap<ObjectId, OutputHead>,
+
     pub(crate) queue_handle: QueueHandle<RuntimeState<T>>,
 
+    /// The handle to the calloop event loop.
+    pub(crate) loop_handle: LoopHandle<'static, RuntimeState<T>>,
+
+    /// The timers registered by the user.
+    pub(crate) timers: HashMap<TimerId, RegistrationToken>,
+
+    /// The file descriptors registered by the user.
+    pub(crate) fds: HashMap<FdToken, RegistrationToken>,
+
+    /// The paths watched by the user, created on the first watch.
+    #[cfg(feature = "fswatch")]
+    pub(crate) file_watcher: Option<FileWatcher<T>>,
+
+    /// The id of the next query issued by the user.
+    next_request_id: u64,
+
+    /// How the pointer motion is delivered.
+    pub(crate) motion_compression: MotionCompression,
+
+    /// The budget of the event delivery per iteration.
+    pub(crate) dispatch: DispatchMeter,
+
+    /// The rate limits of the logging of the high frequency events.
+    pub(crate) log_limiter: RateLimiter,
+
+    /// The rounding of the window sizes.
+    pub(crate) rounding_policy: RoundingPolicy,
+
+    /// The startup milestones reached so far.
+    pub(crate) startup: Startup,
+
+    /// The ext-idle-notify to detect the user inactivity.
+    pub(crate) idle_notifier: Option<ExtIdleNotifierV1>,
+
+    /// The inactivity after which the user is considered idle.
+    pub(crate) idle_threshold: Option<Duration>,
+
+    /// Whether the user was idle when the idle notifications were replaced,
+    /// so the activity is reported on the next loop iteration.
+    pub(crate) idle_reset: bool,
+
+    /// The pointer motion waiting to be delivered for the windows under the
+    /// pointer.
+    pub(crate) pending_motion: HashMap<WindowId, PendingMotion>,
+
+    /// The positions of the motion being delivered.
+    pub(crate) coalesced_positions: Vec<PhysicalPosition<f64>>,
+
+    /// The filters the input events pass through.
+    pub(crate) event_filters: EventFilters,
+
+    /// The wl_data_device_manager to access the clipboard.
+    pub(crate) data_device_manager: Option<DataDeviceManagerState>,
+
+    /// The clipboard of the seats.
+    pub(crate) clipboard: Clipboard,
+
+    /// The cursor-shape manager to set the cursors by their names.
+    pub(crate) cursor_shape_manager: Option<CursorShapeManager>,
+
     pub proxy: Arc<EventLoopProxy>,
 
     pub exit: bool,
@@ -146,15 +646,20 @@ impl<T: Application + 'static> WinitState<T> {
         connection: Connection,
         globals: &GlobalList,
         queue_handle: &QueueHandle<RuntimeState<T>>,
+        loop_handle: LoopHandle<'static, RuntimeState<T>>,
         proxy: EventLoopProxy,
     ) -> Result<Self, ()> {
         let registry_state = RegistryState::new(globals);
         let output_state = OutputState::new(globals, queue_handle);
 
         let seat_state = SeatState::new(globals, queue_handle);
+        let data_device_manager = DataDeviceManagerState::bind(globals, queue_handle).ok();
         let mut seats = HashMap::default();
         for seat in seat_state.seats() {
-            seats.insert(seat.id(), ());
+            let mut winit_seat = WinitSeatState::new(seat.clone());
+            winit_seat.data_device =
+                Clipboard::data_device(data_device_manager.as_ref(), &seat, queue_handle);
+            seats.insert(seat.id(), winit_seat);
         }
 
         let compositor_state = Arc::new(CompositorState::bind(globals, queue_handle).unwrap());
@@ -171,11 +676,33 @@ impl<T: Application + 'static> WinitState<T> {
         };
 
         let shm = Shm::bind(globals, queue_handle).unwrap();
-        let monitors = output_state.outputs().map(Monitor::new).collect();
+        let output_power_manager: Option<ZwlrOutputPowerManagerV1> =
+            globals.bind(queue_handle, 1..=1, GlobalData).ok();
+        let output_manager: Option<ZwlrOutputManagerV1> =
+            globals.bind(queue_handle, 1..=4, GlobalData).ok();
+        let monitor_registry = MonitorRegistry::default();
+        let monitors = output_state
+            .outputs()
+            .map(|output| {
+                Monitor::new(output, &monitor_registry, output_power_manager.as_ref(), queue_handle)
+            })
+            .collect();
+
+        // The token is for our first window, so don't leak it to the children.
+        let activation_token = env::var("XDG_ACTIVATION_TOKEN").ok();
+        env::remove_var("XDG_ACTIVATION_TOKEN");
 
         Ok(Self {
             xdg_shell: XdgShell::bind(globals, queue_handle).unwrap(),
+            xdg_activation: ActivationState::bind(globals, queue_handle).ok(),
+            activation_token,
+            latest_serial: None,
+            presentation: globals.bind(queue_handle, 1..=1, GlobalData).ok(),
+            viewporter: globals.bind(queue_handle, 1..=1, GlobalData).ok(),
+            fractional_scale_manager: globals.bind(queue_handle, 1..=1, GlobalData).ok(),
+            syncobj_manager: globals.bind(queue_handle, 1..=1, GlobalData).ok(),
             queue_handle: queue_handle.clone(),
+            loop_handle,
             subcompositor: subcompositor_state,
             compositor: compositor_state,
             proxy: Arc::new(proxy),
@@ -183,14 +710,60 @@ impl<T: Application + 'static> WinitState<T> {
             output_state,
             seat_state,
             connection,
+            monitor_registry,
             monitors,
+            monitor_generation: 0,
+            output_power_manager,
+            output_manager,
+            output_heads: Default::default(),
             seats,
             shm,
             windows: Default::default(),
+            window_ids: Default::default(),
+            roles: Default::default(),
+            configured_roles: Default::default(),
+            tombstones: Default::default(),
+            timers: Default::default(),
+            fds: Default::default(),
+            #[cfg(feature = "fswatch")]
+            file_watcher: None,
+            next_request_id: 0,
+            motion_compression: Default::default(),
+            dispatch: Default::default(),
+            log_limiter: Default::default(),
+            rounding_policy: RoundingPolicy::uniform(Rounding::Round),
+            startup: Startup::default(),
+            idle_notifier: globals.bind(queue_handle, 1..=1, GlobalData).ok(),
+            idle_threshold: None,
+            idle_reset: false,
+            pending_motion: Default::default(),
+            coalesced_positions: Default::default(),
+            event_filters: Default::default(),
+            data_device_manager,
+            clipboard: Default::default(),
+            cursor_shape_manager: CursorShapeManager::bind(globals, queue_handle).ok(),
             exit: Default::default(),
         })
     }
 
+    /// Why the window with the `window_id` is not in the state.
+    pub(crate) fn missing_window_error(&self, window_id: WindowId) -> WindowError {
+        if self.tombstones.is_destroyed(window_id) {
+            WindowError::Destroyed
+        } else {
+            WindowError::Unknown
+        }
+    }
+
+    /// Get the WindowId of the `surface`.
+    pub(crate) fn window_id(&self, surface: &WlSurface) -> WindowId {
+        match self.window_ids.get(&surface.id()) {
+            Some(id) => WindowId(id),
+            // The surfaces with the custom roles have their own ids.
+            None => crate::make_wid(surface),
+        }
+    }
+
     pub(crate) fn scale_factor_changed(
         state: &mut RuntimeState<T>,
         surface: &WlSurface,
@@ -198,12 +771,27 @@ impl<T: Application + 'static> WinitState<T> {
         legacy: bool,
     ) {
         let winit = &mut state.winit;
-        let window_id = crate::make_wid(surface);
+        let window_id = winit.window_id(surface);
         let window = match winit.windows.get_mut(&window_id) {
             Some(window) => window,
             None => return,
         };
 
+        // The fractional scale takes priority over the integer scale factor.
+        if legacy && window.fractional_scale.is_some() {
+            return;
+        }
+
+        #[cfg(feature = "testing")]
+        if window.pinned_content.is_some() {
+            return;
+        }
+
+        // The override replaces whatever the compositor prefers, the integer
+        // scale can't be fractional though.
+        let scale_factor = winit_core::env::overrides().window_scale_factor(scale_factor);
+        let scale_factor = if legacy { scale_factor.round().max(1.) } else { scale_factor };
+
         window.set_scale_factor(scale_factor);
 
         let user_state = &mut state.user.as_mut().unwrap();
@@ -223,69 +811,154 @@ impl<T: Application + 'static> ProvidesRegistryState for RuntimeState<T> {
     }
 }
 
-impl<T: Application + 'static> SeatHandler for RuntimeState<T> {
-    fn seat_state(&mut self) -> &mut SeatState {
-        &mut self.winit.seat_state
+impl<T: Application + 'static> CompositorHandler for RuntimeState<T> {
+    fn scale_factor_changed(
+        &mut self,
+        conn: &Connection,
+        qh: &QueueHandle<Self>,
+        surface: &WlSurface,
+        scale_factor: i32,
+    ) {
+        // NOTE: sctk delivers both `wl_surface::preferred_buffer_scale` from
+        // wl_compositor@v6 and the scale computed from the entered outputs on older
+        // compositors through this callback, both are integer scales.
+        WinitState::scale_factor_changed(self, surface, scale_factor as f64, true)
     }
 
-    fn new_capability(
+    fn transform_changed(
         &mut self,
-        _: &Connection,
-        queue_handle: &QueueHandle<Self>,
-        seat: WlSeat,
-        capability: SeatCapability,
+        conn: &Connection,
+        qh: &QueueHandle<Self>,
+        surface: &WlSurface,
+        new_transform: wl_output::Transform,
     ) {
+        // TODO(kchibisov) we need to expose it somehow in winit.
     }
 
-    fn remove_capability(
+    fn surface_enter(
         &mut self,
         _: &Connection,
-        _queue_handle: &QueueHandle<Self>,
-        seat: WlSeat,
-        capability: SeatCapability,
+        _: &QueueHandle<Self>,
+        _: &WlSurface,
+        _: &wl_output::WlOutput,
     ) {
+        // The scale comes from the preferred scale events instead.
     }
 
-    fn new_seat(
+    fn surface_leave(
         &mut self,
-        _connection: &Connection,
-        _queue_handle: &QueueHandle<Self>,
-        seat: WlSeat,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &WlSurface,
+        _: &wl_output::WlOutput,
     ) {
     }
 
-    fn remove_seat(
-        &mut self,
-        _connection: &Connection,
-        _queue_handle: &QueueHandle<Self>,
-        seat: WlSeat,
+    fn frame(&mut self, conn: &Connection, qh: &QueueHandle<Self>, surface: &WlSurface, time: u32) {
+        let winit = &mut self.winit;
+        let window_id = winit.window_id(surface);
+        let window = match winit.windows.get_mut(&window_id) {
+            Some(window) => window,
+            None => return,
+        };
+
+        limited_debug!(
+            winit.log_limiter,
+            LogKind::FrameCallback,
+            "Frame callback of {window_id:?}"
+        );
+
+        let startup = &mut winit.startup;
+        if startup.timings.first_frame_presented.is_none() {
+            startup.timings.first_frame_presented = Some(startup.started_at.elapsed());
+        }
+
+        if window.frame_presented() {
+            self.user.as_mut().unwrap().frame_missed(winit, window_id);
+        }
+    }
+}
+
+impl<T: Application + 'static> Dispatch<WpFractionalScaleManagerV1, GlobalData>
+    for RuntimeState<T>
+{
+    fn event(
+        _: &mut Self,
+        _: &WpFractionalScaleManagerV1,
+        _: wp_fractional_scale_manager_v1::Event,
+        _: &GlobalData,
+        _: &Connection,
+        _: &QueueHandle<Self>,
     ) {
+        // No events.
     }
 }
 
-impl<T: Application + 'static> CompositorHandler for RuntimeState<T> {
-    fn scale_factor_changed(
-        &mut self,
-        conn: &Connection,
-        qh: &QueueHandle<Self>,
+impl<T: Application + 'static> Dispatch<WpFractionalScaleV1, WlSurface> for RuntimeState<T> {
+    fn event(
+        state: &mut Self,
+        _: &WpFractionalScaleV1,
+        event: wp_fractional_scale_v1::Event,
         surface: &WlSurface,
-        scale_factor: i32,
+        _: &Connection,
+        _: &QueueHandle<Self>,
     ) {
-        WinitState::scale_factor_changed(self, surface, scale_factor as f64, true)
+        // The scale is sent in the 120ths.
+        if let wp_fractional_scale_v1::Event::PreferredScale { scale } = event {
+            WinitState::scale_factor_changed(state, surface, scale as f64 / 120., false);
+        }
     }
+}
 
-    fn transform_changed(
-        &mut self,
-        conn: &Connection,
-        qh: &QueueHandle<Self>,
-        surface: &WlSurface,
-        new_transform: wl_output::Transform,
+impl<T: Application + 'static> Dispatch<WpPresentation, GlobalData> for RuntimeState<T> {
+    fn event(
+        _: &mut Self,
+        _: &WpPresentation,
+        _: wp_presentation::Event,
+        _: &GlobalData,
+        _: &Connection,
+        _: &QueueHandle<Self>,
     ) {
-        // TODO(kchibisov) we need to expose it somehow in winit.
+        // The clock is not used, since only the refresh is taken.
     }
+}
 
-    fn frame(&mut self, conn: &Connection, qh: &QueueHandle<Self>, surface: &WlSurface, time: u32) {
-        todo!()
+impl<T: Application + 'static> Dispatch<WpPresentationFeedback, WindowId> for RuntimeState<T> {
+    fn event(
+        state: &mut Self,
+        _: &WpPresentationFeedback,
+        event: wp_presentation_feedback::Event,
+        window_id: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let refresh = match event {
+            wp_presentation_feedback::Event::Presented { refresh, .. } => refresh,
+            _ => return,
+        };
+
+        // The refresh is zero when the output has a variable refresh rate.
+        if let Some(window) = state.winit.windows.get_mut(window_id) {
+            window.presentation_refresh =
+                (refresh != 0).then(|| Duration::from_nanos(refresh as u64));
+        }
+    }
+}
+
+impl<T: Application + 'static> ActivationHandler for RuntimeState<T> {
+    type RequestData = RequestData;
+
+    fn new_token(&mut self, token: String, data: &Self::RequestData) {
+        let surface = match data.surface.as_ref() {
+            Some(surface) => surface,
+            None => return,
+        };
+
+        if let Some(xdg_activation) = self.winit.xdg_activation.as_ref() {
+            xdg_activation.activate::<Self>(surface, token);
+            let window_id = self.winit.window_id(surface);
+            self.winit.watch_activation(window_id);
+        }
     }
 }
 
@@ -295,10 +968,65 @@ impl<T: Application + 'static> ShmHandler for RuntimeState<T> {
     }
 }
 
+/// The tracking of the startup milestones.
+#[derive(Debug)]
+pub(crate) struct Startup {
+    /// The time the event loop creation started at.
+    pub(crate) started_at: Instant,
+
+    pub(crate) timings: StartupTimings,
+}
+
+impl Default for Startup {
+    fn default() -> Self {
+        Self { started_at: Instant::now(), timings: Default::default() }
+    }
+}
+
+/// The query waiting for the roundtrip.
+pub(crate) struct QueryData {
+    request_id: RequestId,
+    query: Query,
+}
+
+impl<T: Application + 'static> WinitState<T> {
+    fn resolve_query(&self, query: &Query) -> QueryResult {
+        match query {
+            Query::MonitorDescription(monitor_id) => {
+                let monitor = self.monitors.iter().find(|monitor| monitor.id() == *monitor_id);
+                let description = monitor.and_then(|monitor| {
+                    let output_data = monitor.output.data::<OutputData>()?;
+                    output_data.with_output_info(|info| info.description.clone())
+                });
+                QueryResult::MonitorDescription(description)
+            },
+            // TODO: query the settings portal.
+            _ => QueryResult::Unsupported,
+        }
+    }
+}
+
+impl<T: Application + 'static> Dispatch<WlCallback, QueryData> for RuntimeState<T> {
+    fn event(
+        state: &mut Self,
+        _: &WlCallback,
+        event: wl_callback::Event,
+        data: &QueryData,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let wl_callback::Event::Done { .. } = event {
+            let result = state.winit.resolve_query(&data.query);
+            let user = state.user.as_mut().unwrap();
+            user.query_complete(&mut state.winit, data.request_id, result);
+        }
+    }
+}
+
 sctk::delegate_registry!(@<T: Application + 'static> RuntimeState<T>);
-sctk::delegate_seat!(@<T: Application + 'static> RuntimeState<T>);
 sctk::delegate_subcompositor!(@<T: Application + 'static> RuntimeState<T>);
 sctk::delegate_shm!(@<T: Application + 'static> RuntimeState<T>);
+sctk::delegate_activation!(@<T: Application + 'static> RuntimeState<T>);
 sctk::delegate_compositor!(@<T: Application + 'static> RuntimeState<T>);
 sctk::delegate_xdg_shell!(@<T: Application + 'static> RuntimeState<T>);
 sctk::delegate_xdg_window!(@<T: Application + 'static> RuntimeState<T>);
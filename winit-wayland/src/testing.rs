@@ -0,0 +1,93 @@
+//! The overrides for the tests, to get the same window content on every
+//! machine, like for the screenshot comparisons.
+
+use winit_core::application::Application;
+use winit_core::dpi::LogicalSize;
+use winit_core::window::{Surface as CoreSurface, WindowError, WindowId};
+
+use crate::event_loop::EventLoop;
+use crate::state::WinitState;
+use crate::window::Window;
+
+/// Additional methods on the Wayland event loop for the tests.
+pub trait WaylandEventLoopTestingExt {
+    /// Pin the logical size and the scale factor of the window.
+    ///
+    /// The sizes suggested by the configures and the scales preferred by the
+    /// compositor are ignored from then on. The [`resized`] and the
+    /// [`scale_factor_changed`] are delivered with the pinned values instead.
+    ///
+    /// Without the fractional scaling the compositor only supports the
+    /// integer scales, so the fractional `scale_factor` is truncated for the
+    /// buffer scale.
+    ///
+    /// [`resized`]: winit_core::application::ApplicationWindow::resized
+    /// [`scale_factor_changed`]: winit_core::application::ApplicationWindow::scale_factor_changed
+    fn pin_content(
+        &mut self,
+        window_id: WindowId,
+        size: LogicalSize<u32>,
+        scale_factor: f64,
+    ) -> Result<(), WindowError>;
+
+    /// Follow the compositor again, starting with the next configure.
+    fn unpin_content(&mut self, window_id: WindowId) -> Result<(), WindowError>;
+}
+
+impl<T: Application + 'static> WaylandEventLoopTestingExt for WinitState<T> {
+    fn pin_content(
+        &mut self,
+        window_id: WindowId,
+        size: LogicalSize<u32>,
+        scale_factor: f64,
+    ) -> Result<(), WindowError> {
+        let Some(window) = self.windows.get_mut(&window_id) else {
+            return Err(self.missing_window_error(window_id));
+        };
+
+        window.pin_content(size, scale_factor);
+        Ok(())
+    }
+
+    fn unpin_content(&mut self, window_id: WindowId) -> Result<(), WindowError> {
+        let Some(window) = self.windows.get_mut(&window_id) else {
+            return Err(self.missing_window_error(window_id));
+        };
+
+        window.pinned_content = None;
+        Ok(())
+    }
+}
+
+impl<T: Application + 'static> WaylandEventLoopTestingExt for EventLoop<T> {
+    fn pin_content(
+        &mut self,
+        window_id: WindowId,
+        size: LogicalSize<u32>,
+        scale_factor: f64,
+    ) -> Result<(), WindowError> {
+        self.state.winit.pin_content(window_id, size, scale_factor)
+    }
+
+    fn unpin_content(&mut self, window_id: WindowId) -> Result<(), WindowError> {
+        self.state.winit.unpin_content(window_id)
+    }
+}
+
+impl<T: Application + 'static> Window<T> {
+    fn pin_content(&mut self, size: LogicalSize<u32>, scale_factor: f64) {
+        self.pinned_content = Some((size, scale_factor));
+
+        if self.scale_factor != scale_factor {
+            self.set_scale_factor(scale_factor);
+            self.scale_changed = true;
+        }
+
+        // The initial configure picks the pinned size on its own.
+        if self.configured() {
+            self.resize(size);
+            self.size_changed = true;
+            self.request_redraw();
+        }
+    }
+}
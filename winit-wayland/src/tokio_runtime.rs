@@ -0,0 +1,204 @@
+//! Running the tokio futures on the event loop thread.
+//!
+//! The runtime behind the [`Handle`] is entered for the whole run of the event
+//! loop, so the callbacks could spawn onto it and use the libraries built on
+//! top of it, like `reqwest` or `zbus` with the `tokio` feature. The futures
+//! which must stay on the event loop thread are spawned with the
+//! [`LocalSpawner`] and polled between the dispatches of the event loop, their
+//! wakers wake the loop up.
+//!
+//! The IO and the timers are driven by the runtime itself, so it must have
+//! threads of its own, like the multi-thread runtime does. The current-thread
+//! runtime can't be driven through the [`Handle`], so it's rejected.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+
+use calloop::ping::Ping;
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+use winit_core::application::Application;
+
+use crate::event_loop::{EventLoop, RuntimeState};
+
+/// The number of futures polled in a single iteration of the event loop, so
+/// the busy futures don't starve the input.
+const POLL_BUDGET: usize = 64;
+
+/// Additional methods on the event loop running the tokio futures.
+pub trait EventLoopExt: Sized {
+    /// Enter the runtime of the `handle` while running and poll the futures
+    /// spawned with the [`LocalSpawner`].
+    ///
+    /// The handle of the current-thread runtime is ignored, since nothing
+    /// drives its IO and timers while the event loop runs.
+    fn with_tokio(self, handle: Handle) -> Self;
+
+    /// The spawner of the futures polled on the event loop thread, `None`
+    /// when the [`EventLoopExt::with_tokio`] wasn't used.
+    fn local_spawner(&self) -> Option<LocalSpawner>;
+}
+
+impl<T: Application + 'static> EventLoopExt for EventLoop<T> {
+    fn with_tokio(mut self, handle: Handle) -> Self {
+        if handle.runtime_flavor() == RuntimeFlavor::CurrentThread {
+            log::warn!("The current-thread tokio runtime can't be driven by the event loop");
+            return self;
+        }
+
+        let (ping, ping_source) = match calloop::ping::make_ping() {
+            Ok(ping) => ping,
+            Err(err) => {
+                log::warn!("Failed to create the ping of the tokio wakers: {err}");
+                return self;
+            },
+        };
+
+        // The futures are polled by the loop itself, the source only wakes it up.
+        let inserted =
+            self.event_loop.handle().insert_source(ping_source, |_, _, _: &mut RuntimeState<T>| {});
+        if inserted.is_err() {
+            return self;
+        }
+
+        self.tokio = Some(TokioState::new(handle, ping));
+        self
+    }
+
+    fn local_spawner(&self) -> Option<LocalSpawner> {
+        self.tokio.as_ref().map(TokioState::spawner)
+    }
+}
+
+/// The spawner of the futures polled on the event loop thread.
+#[derive(Clone)]
+pub struct LocalSpawner {
+    tasks: Rc<RefCell<Tasks>>,
+}
+
+impl LocalSpawner {
+    /// Spawn the `future`, which is first polled in the next iteration of the
+    /// event loop.
+    pub fn spawn<F: Future<Output = ()> + 'static>(&self, future: F) {
+        let mut tasks = self.tasks.borrow_mut();
+        let id = tasks.free.pop().unwrap_or_else(|| {
+            tasks.slots.push(None);
+            tasks.slots.len() - 1
+        });
+
+        let waker = Arc::new(TaskWaker::new(id, tasks.woken.clone(), tasks.ping.clone()));
+        waker.wake_by_ref();
+        tasks.slots[id] = Some(Task { future: Box::pin(future), waker });
+    }
+}
+
+/// The tokio runtime state of the event loop.
+pub(crate) struct TokioState {
+    handle: Handle,
+    tasks: Rc<RefCell<Tasks>>,
+}
+
+impl TokioState {
+    fn new(handle: Handle, ping: Ping) -> Self {
+        let tasks = Tasks { slots: Vec::new(), free: Vec::new(), woken: Default::default(), ping };
+        Self { handle, tasks: Rc::new(RefCell::new(tasks)) }
+    }
+
+    /// The handle of the runtime entered while running.
+    pub(crate) fn handle(&self) -> &Handle {
+        &self.handle
+    }
+
+    fn spawner(&self) -> LocalSpawner {
+        LocalSpawner { tasks: self.tasks.clone() }
+    }
+
+    /// Poll the woken futures, up to the [`POLL_BUDGET`].
+    ///
+    /// Returns `true` when some futures are still woken, so the loop shouldn't
+    /// block.
+    pub(crate) fn poll(&self) -> bool {
+        for _ in 0..POLL_BUDGET {
+            let woken = self.tasks.borrow().woken.lock().unwrap().pop_front();
+            let id = match woken {
+                Some(id) => id,
+                None => return false,
+            };
+
+            // Take the future out, so it could spawn the other ones.
+            let task = match self.tasks.borrow_mut().slots.get_mut(id).and_then(Option::take) {
+                Some(task) => task,
+                None => continue,
+            };
+
+            let Task { mut future, waker } = task;
+            waker.queued.store(false, Ordering::Release);
+            let task_waker = Waker::from(waker.clone());
+            let mut cx = Context::from_waker(&task_waker);
+
+            let poll = future.as_mut().poll(&mut cx);
+            let mut tasks = self.tasks.borrow_mut();
+            match poll {
+                Poll::Pending => tasks.slots[id] = Some(Task { future, waker }),
+                Poll::Ready(()) => tasks.free.push(id),
+            }
+        }
+
+        !self.tasks.borrow().woken.lock().unwrap().is_empty()
+    }
+}
+
+/// The futures spawned on the event loop thread.
+struct Tasks {
+    slots: Vec<Option<Task>>,
+
+    /// The slots of the finished futures.
+    free: Vec<usize>,
+
+    /// The ids of the futures to poll.
+    woken: Arc<Mutex<VecDeque<usize>>>,
+
+    /// The ping waking the event loop up.
+    ping: Ping,
+}
+
+struct Task {
+    future: Pin<Box<dyn Future<Output = ()>>>,
+    waker: Arc<TaskWaker>,
+}
+
+/// The waker of the future, which could be woken from the runtime threads.
+struct TaskWaker {
+    id: usize,
+
+    /// Whether the future is already queued to be polled.
+    queued: AtomicBool,
+
+    woken: Arc<Mutex<VecDeque<usize>>>,
+    ping: Ping,
+}
+
+impl TaskWaker {
+    fn new(id: usize, woken: Arc<Mutex<VecDeque<usize>>>, ping: Ping) -> Self {
+        Self { id, queued: AtomicBool::new(false), woken, ping }
+    }
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        if !self.queued.swap(true, Ordering::AcqRel) {
+            self.woken.lock().unwrap().push_back(self.id);
+            self.ping.ping();
+        }
+    }
+}
@@ -1,49 +1,101 @@
+use std::collections::HashMap;
+use std::mem;
 use std::num::NonZeroU32;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use raw_window_handle::{HandleError, HasWindowHandle, WaylandWindowHandle, WindowHandle};
 use raw_window_handle_05::HasRawWindowHandle as HasRawWindowHandle05;
 
+use sctk::activation::RequestData;
 use sctk::compositor::{CompositorHandler, CompositorState, Region, SurfaceData};
+use sctk::globals::GlobalData;
+use sctk::reexports::calloop::timer::{TimeoutAction, Timer};
+use sctk::reexports::client::protocol::wl_seat::WlSeat;
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::reexports::client::Proxy;
 use sctk::reexports::csd_frame::{
     DecorationsFrame, FrameAction, FrameClick, ResizeEdge, WindowState as XdgWindowState,
 };
 use sctk::reexports::protocols::wp::fractional_scale::v1::client::wp_fractional_scale_v1::WpFractionalScaleV1;
+use sctk::reexports::protocols::wp::presentation_time::client::wp_presentation::WpPresentation;
 use sctk::reexports::protocols::wp::text_input::zv3::client::zwp_text_input_v3::ZwpTextInputV3;
 use sctk::reexports::protocols::wp::viewporter::client::wp_viewport::WpViewport;
+use sctk::reexports::protocols::wp::viewporter::client::wp_viewporter::WpViewporter;
 use sctk::reexports::protocols::xdg::shell::client::xdg_toplevel::ResizeEdge as XdgResizeEdge;
 use sctk::shell::xdg::window::{
     DecorationMode, Window as XdgWindow, WindowConfigure, WindowDecorations, WindowHandler,
 };
 use sctk::shell::xdg::XdgSurface;
 use sctk::shell::WaylandSurface;
+use sctk::subcompositor::SubcompositorState;
 
+use smallvec::SmallVec;
 use wayland_client::{Connection, QueueHandle};
+use wayland_protocols::wp::linux_drm_syncobj::v1::client::wp_linux_drm_syncobj_manager_v1::WpLinuxDrmSyncobjManagerV1;
+use wayland_protocols::wp::linux_drm_syncobj::v1::client::wp_linux_drm_syncobj_surface_v1::WpLinuxDrmSyncobjSurfaceV1;
 use winit_core::application::Application;
-use winit_core::dpi::{LogicalSize, PhysicalSize, Size};
-use winit_core::monitor::MonitorId;
-use winit_core::window::{Theme, Window as CoreWindow, WindowAttributes, WindowId};
+use winit_core::cursor::CursorIcon;
+use winit_core::dpi::{
+    Insets, LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, Position, Rect,
+    RoundingPolicy, Size,
+};
+use winit_core::event_loop::EventLoopHandle;
+use winit_core::input::DeviceId;
+use winit_core::monitor::{Monitor as CoreMonitor, MonitorId};
+use winit_core::window::{
+    CloseBehavior, ConfigurePolicy, DecorationAction, DecorationMode as CoreDecorationMode,
+    FrameStats, GroupId, OverlayPlane as CoreOverlayPlane, OverlayPlaneError, OverlayPlaneId,
+    RegionKind, ResizeDelivery, SizePolicy, Surface as CoreSurface, Theme, TiledEdges, Title,
+    TitlePolicy, Toplevel, ToplevelAttributes, WindowId,
+};
 
 use crate::event_loop::RuntimeState;
-use crate::logical_to_physical_rounded;
-use crate::monitor::Monitor;
+use crate::ids::IdAllocator;
+use crate::monitor::{self, MonitorRegistry};
+use crate::overlay::{self, OverlayPlane};
+use crate::rate_limit::{limited_debug, LogKind};
 use crate::state::WinitState;
 
 // Minimum window inner size.
 const MIN_WINDOW_SIZE: LogicalSize<u32> = LogicalSize::new(2, 1);
 
+// The refresh rate assumed when the monitor doesn't provide one.
+const FALLBACK_REFRESH_RATE_MILLIHERTZ: u32 = 60_000;
+
+// The time the compositor has to focus the window after the activation.
+const ACTIVATION_TIMEOUT: Duration = Duration::from_millis(500);
+
+// The time without the frame callback after which the window is considered
+// hidden by the compositor.
+const THROTTLE_TIMEOUT: Duration = Duration::from_secs(1);
+
+// The time the application has to redraw with the staged size.
+const STAGED_CONFIGURE_TIMEOUT: Duration = Duration::from_millis(100);
+
 #[cfg(feature = "sctk-adwaita")]
 type WinitFrame<T> = sctk_adwaita::AdwaitaFrame<RuntimeState<T>>;
 #[cfg(not(feature = "sctk-adwaita"))]
 type WinitFrame = sctk::shell::xdg::fallback_frame::FallbackFrame<RuntimeState>;
 
+/// The request deferred until the initial configure of the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PendingRequest {
+    Maximized(bool),
+    Minimized,
+}
+
 pub struct Window<T: Application + 'static> {
+    id: WindowId,
+
+    /// The allocator to release the id to.
+    ids: IdAllocator,
+
     /// The last received configure.
     pub last_configure: Option<WindowConfigure>,
 
     pub viewport: Option<WpViewport>,
-    fractional_scale: Option<WpFractionalScaleV1>,
+    pub(crate) fractional_scale: Option<WpFractionalScaleV1>,
 
     /// The window frame, which is created from the configure request.
     frame: Option<WinitFrame<T>>,
@@ -54,8 +106,11 @@ pub struct Window<T: Application + 'static> {
     /// The latest requested window title.
     title: String,
 
+    /// The policy applied to the requested titles.
+    title_policy: TitlePolicy,
+
     /// Whether the window has focus.
-    has_focus: bool,
+    pub(crate) has_focus: bool,
 
     /// Whether the frame is resizable.
     resizable: bool,
@@ -73,8 +128,14 @@ pub struct Window<T: Application + 'static> {
     /// configure.
     initial_size: Option<Size>,
 
+    /// How the initial size is picked.
+    size_policy: SizePolicy,
+
     compositor: Arc<CompositorState>,
 
+    /// The registry to map the outputs to the monitor ids.
+    monitor_registry: MonitorRegistry,
+
     /// Theme varaint.
     theme: Option<Theme>,
 
@@ -85,8 +146,15 @@ pub struct Window<T: Application + 'static> {
     /// Whether we should decorate the frame.
     decorate: bool,
 
-    /// Whether the window is transparent.
-    transparent: bool,
+    /// The opaque region of the window, `None` when the window is
+    /// transparent.
+    opaque_region: Option<Vec<Rect<u32>>>,
+
+    /// The region accepting the input, `None` for the whole window.
+    input_region: Option<Vec<Rect<u32>>>,
+
+    /// The rounding of the logical size into the physical one.
+    pub(crate) rounding_policy: RoundingPolicy,
 
     /// Whether the CSD fail to create, so we don't try to create them on each
     /// iteration.
@@ -94,14 +162,130 @@ pub struct Window<T: Application + 'static> {
 
     pub redraw: bool,
 
+    /// What happens when the user allows closing the window.
+    close_behavior: CloseBehavior,
+
+    /// When the size from the configure is applied.
+    configure_policy: ConfigurePolicy,
+
+    /// The size waiting for the redraw to be applied, along with the time
+    /// it's applied anyway.
+    pending_resize: Option<(LogicalSize<u32>, Instant)>,
+
+    /// Which of the configured sizes are delivered.
+    resize_delivery: ResizeDelivery,
+
+    /// The latest configure, waiting to be applied along with the ones
+    /// received with it.
+    pending_configure: Option<WindowConfigure>,
+
+    /// Whether the user asked to focus the window.
+    pub(crate) activation_requested: bool,
+
+    /// Whether the window could take the keyboard focus.
+    focusable: bool,
+
+    /// The cursor of the seats without their own one.
+    cursor: CursorIcon,
+
+    /// The cursors of the particular seats.
+    seat_cursors: SmallVec<[(DeviceId, CursorIcon); 2]>,
+
+    /// Whether the cursors changed since they were applied to the seats.
+    pub(crate) cursor_changed: bool,
+
+    /// The positions of the pointers over the window, the most recently moved
+    /// one is the last.
+    pointer_positions: SmallVec<[(DeviceId, PhysicalPosition<f64>); 1]>,
+
+    /// Whether the content size changed without the configure, so the user
+    /// wasn't notified yet.
+    pub(crate) size_changed: bool,
+
+    /// Whether the scale factor changed without the compositor, so the user
+    /// wasn't notified yet.
+    pub(crate) scale_changed: bool,
+
+    /// The logical size and the scale factor pinned by the tests.
+    #[cfg(feature = "testing")]
+    pub(crate) pinned_content: Option<(LogicalSize<u32>, f64)>,
+
+    /// The margins of the content outside of the window geometry.
+    content_insets: Insets<u32>,
+
+    /// The requests replayed on the initial configure.
+    pending_requests: SmallVec<[PendingRequest; 2]>,
+
+    /// The group of the window along with the time it joined the group.
+    pub(crate) group: Option<(GroupId, Instant)>,
+
+    /// The window set as the parent of the window to keep it above the main
+    /// window of its group.
+    parent: Option<WindowId>,
+
+    /// Whether the group of the window changed since the parents were last
+    /// updated.
+    group_changed: bool,
+
+    /// Whether the window was unmapped by the [`CloseBehavior::HideInstead`]
+    /// or the [`Toplevel::set_visible`].
+    pub(crate) hidden: bool,
+
+    /// Whether the hidden window waits for the configure to be mapped again.
+    remapping: bool,
+
+    /// The presentation statistics of the window.
+    frame_stats: FrameStats,
+
+    /// The time the pending frame callback was requested at.
+    frame_requested_at: Option<Instant>,
+
+    /// The time of the last redraw delivered while the frame callback was
+    /// still pending.
+    fallback_redraw_at: Option<Instant>,
+
+    /// Whether to suppress the redraws while the window is hidden.
+    throttling: bool,
+
+    /// The throttle state last reported to the user.
+    pub(crate) throttled: bool,
+
+    /// The presentation-time to get the refresh of the output the window is
+    /// presented on.
+    presentation: Option<WpPresentation>,
+
+    /// The refresh interval reported by the last presentation feedback.
+    pub(crate) presentation_refresh: Option<Duration>,
+
+    /// The overlay planes attached to the window.
+    overlay_planes: Vec<OverlayPlane>,
+
+    /// The id of the next overlay plane.
+    next_overlay_plane_id: u64,
+
+    subcompositor: Option<Arc<SubcompositorState>>,
+
+    viewporter: Option<WpViewporter>,
+
+    /// The linux-drm-syncobj manager for the explicit synchronization.
+    pub(crate) syncobj_manager: Option<WpLinuxDrmSyncobjManagerV1>,
+
+    /// The explicit synchronization of the surface, created on the first
+    /// commit with the sync points.
+    pub(crate) syncobj_surface: Option<WpLinuxDrmSyncobjSurfaceV1>,
+
+    pub(crate) queue_handle: QueueHandle<RuntimeState<T>>,
+
     // Note, should be the last since it drops the surface.
     pub window: XdgWindow,
 }
 
 impl<T: Application + 'static> Window<T> {
-    pub fn new(winit: &mut WinitState<T>, attributes: &WindowAttributes) -> Self {
+    pub fn new(winit: &mut WinitState<T>, attributes: ToplevelAttributes) -> Self {
         let compositor = winit.compositor.clone();
+        let monitor_registry = winit.monitor_registry.clone();
         let surface = compositor.create_surface(&winit.queue_handle);
+        let id = WindowId(winit.window_ids.allocate(surface.id()));
 
         // We prefer server side decorations, however to not have decorations we ask for
         // client side decorations instead.
@@ -113,39 +297,99 @@ impl<T: Application + 'static> Window<T> {
 
         let window = winit.xdg_shell.create_window(surface.clone(), decorate, &winit.queue_handle);
 
-        let viewport = None;
-        let fractional_scale = None;
+        // The fractional scale needs the viewport to scale the buffer down.
+        let (viewport, fractional_scale) =
+            match (winit.viewporter.as_ref(), winit.fractional_scale_manager.as_ref()) {
+                (Some(viewporter), Some(manager)) => (
+                    Some(viewporter.get_viewport(&surface, &winit.queue_handle, GlobalData)),
+                    Some(manager.get_fractional_scale(
+                        &surface,
+                        &winit.queue_handle,
+                        surface.clone(),
+                    )),
+                ),
+                _ => (None, None),
+            };
 
         let size = attributes.inner_size();
 
         // Create the window with some defaults.
         let mut window = Self {
+            id,
+            ids: winit.window_ids.clone(),
             decorate: attributes.decorations(),
             min_inner_size: MIN_WINDOW_SIZE,
             stateless_size: size.to_logical(1.),
             size: size.to_logical(1.),
             initial_size: Some(size),
+            size_policy: attributes.size_policy(),
             max_inner_size: None,
             last_configure: None,
-            transparent: true,
+            opaque_region: None,
+            input_region: None,
             fractional_scale,
             scale_factor: 1.,
             has_focus: false,
             csd_fails: false,
             resizable: true,
             redraw: false,
+            close_behavior: attributes.close_behavior(),
+            configure_policy: attributes.configure_policy(),
+            pending_resize: None,
+            resize_delivery: attributes.resize_delivery(),
+            pending_configure: None,
+            activation_requested: false,
+            focusable: true,
+            cursor: Default::default(),
+            seat_cursors: Default::default(),
+            cursor_changed: false,
+            pointer_positions: Default::default(),
+            size_changed: false,
+            scale_changed: false,
+            #[cfg(feature = "testing")]
+            pinned_content: None,
+            content_insets: Default::default(),
+            pending_requests: Default::default(),
+            group: None,
+            parent: None,
+            group_changed: false,
+            rounding_policy: winit.rounding_policy,
+            hidden: false,
+            remapping: false,
+            frame_stats: FrameStats::default(),
+            frame_requested_at: None,
+            fallback_redraw_at: None,
+            throttling: true,
+            throttled: false,
+            presentation: winit.presentation.clone(),
+            presentation_refresh: None,
+            overlay_planes: Vec::new(),
+            next_overlay_plane_id: 0,
+            subcompositor: winit.subcompositor.clone(),
+            viewporter: winit.viewporter.clone(),
+            syncobj_manager: winit.syncobj_manager.clone(),
+            syncobj_surface: None,
+            queue_handle: winit.queue_handle.clone(),
             frame: None,
             theme: None,
+            monitor_registry,
             compositor,
             viewport,
             window,
             title: Default::default(),
+            title_policy: attributes.title_policy(),
         };
 
-        window.set_title(attributes.title());
+        // Apply the scale override even when the compositor never suggests the
+        // scale, only the integer one is supported without the fractional scale.
+        if let Some(scale_factor) = winit_core::env::overrides().scale_factor {
+            window.set_scale_factor(scale_factor.round().max(1.));
+        }
+
+        window.set_title(attributes.title().into());
 
         // Set transparency hint.
-        window.set_transparent(attributes.transparent);
+        window.set_transparent(attributes.transparent());
 
         window.set_min_inner_size(attributes.min_inner_size());
         window.set_max_inner_size(attributes.max_inner_size());
@@ -154,8 +398,20 @@ impl<T: Application + 'static> Window<T> {
 
         // window.set_
 
+        // The maximized and fullscreen states are part of the initial configure.
         if attributes.maximized() {
-            window.set_maximized(true);
+            window.window.set_maximized();
+        }
+
+        if let Some(connection_name) = attributes.fullscreen_monitor() {
+            let output = winit
+                .monitors
+                .iter()
+                .find(|monitor| monitor.connection_name().as_deref() == Some(connection_name))
+                .map(|monitor| &monitor.output);
+
+            // Let the compositor pick the output when the monitor is gone.
+            window.window.set_fullscreen(output);
         }
 
         // TODO: platform attributes.
@@ -163,6 +419,16 @@ impl<T: Application + 'static> Window<T> {
         // NOTE: initial commit for the window.
         window.window.commit();
 
+        // Take the focus with the token the application was launched with, the
+        // inactive windows leave it for the next window.
+        if attributes.active() {
+            if let (Some(xdg_activation), Some(token)) =
+                (winit.xdg_activation.as_ref(), winit.activation_token.take())
+            {
+                xdg_activation.activate::<RuntimeState<T>>(window.window.wl_surface(), token);
+            }
+        }
+
         window
     }
 
@@ -189,37 +455,37 @@ impl<T: Application + 'static> Window<T> {
         }
     }
 
-    pub fn set_transparent(&mut self, transparent: bool) {
-        self.transparent = transparent;
-        self.reload_transparency_hint();
-    }
-
     pub fn set_min_inner_size(&mut self, size: Option<Size>) {
         let mut size =
             size.map(|size| size.to_logical(self.scale_factor)).unwrap_or(MIN_WINDOW_SIZE);
         size.height = size.width.max(MIN_WINDOW_SIZE.width);
         size.width = size.width.max(MIN_WINDOW_SIZE.width);
         // TODO borders
-        self.window.set_min_size(Some(size.into()));
+        self.window.set_min_size(Some(self.geometry_size(size).into()));
         self.min_inner_size = size;
     }
 
     pub fn set_max_inner_size(&mut self, size: Option<Size>) {
         let size = size.map(|size| size.to_logical(self.scale_factor));
-        self.window.set_max_size(size.map(Into::into));
+        self.window.set_max_size(size.map(|size| self.geometry_size(size).into()));
         self.max_inner_size = size;
     }
 
     pub(crate) fn resize(&mut self, new_size: LogicalSize<u32>) {
         self.size = new_size;
+        self.pending_resize = None;
 
         // Update the stateless size.
         if Some(true) == self.last_configure.as_ref().map(Self::is_stateless) {
             self.stateless_size = self.size;
         }
 
-        // Update the inner frame.
-        let ((x, y), outer_size) = if let Some(frame) = self.frame.as_mut() {
+        // Update the inner frame, the borders follow the state the size is for.
+        if let Some(frame) = self.frame.as_mut() {
+            if let Some(configure) = self.last_configure.as_ref() {
+                frame.update_state(configure.state);
+            }
+
             // Resize only visible frame.
             if !frame.is_hidden() {
                 frame.resize(
@@ -227,22 +493,13 @@ impl<T: Application + 'static> Window<T> {
                     NonZeroU32::new(self.size.height).unwrap(),
                 );
             }
-
-            (frame.location(), frame.add_borders(self.size.width, self.size.height).into())
-        } else {
-            ((0, 0), self.size)
-        };
+        }
 
         // Reload the hint.
         self.reload_transparency_hint();
+        self.reload_input_region();
 
-        // Set the window geometry.
-        self.window.xdg_surface().set_window_geometry(
-            x,
-            y,
-            outer_size.width as i32,
-            outer_size.height as i32,
-        );
+        self.update_window_geometry();
 
         // Update the target viewport, this is used if and only if fractional scaling is
         // in use.
@@ -252,6 +509,134 @@ impl<T: Application + 'static> Window<T> {
         }
     }
 
+    /// Set the window geometry to the content with the frame around it, without
+    /// the margins drawn by the user.
+    fn update_window_geometry(&self) {
+        let ((x, y), outer_size) = match self.frame.as_ref() {
+            Some(frame) => {
+                (frame.location(), frame.add_borders(self.size.width, self.size.height).into())
+            },
+            None => ((0, 0), self.size),
+        };
+
+        let insets = self.logical_content_insets();
+        self.window.xdg_surface().set_window_geometry(
+            x + insets.left,
+            y + insets.top,
+            (outer_size.width as i32 - insets.left - insets.right).max(1),
+            (outer_size.height as i32 - insets.top - insets.bottom).max(1),
+        );
+    }
+
+    /// Replay the requests deferred until the initial configure, in the order
+    /// they were issued.
+    fn replay_pending_requests(&mut self) {
+        for request in mem::take(&mut self.pending_requests) {
+            match request {
+                PendingRequest::Maximized(true) => self.window.set_maximized(),
+                PendingRequest::Maximized(false) => self.window.unset_maximized(),
+                PendingRequest::Minimized => self.window.set_minimized(),
+            }
+        }
+    }
+
+    /// The [`Toplevel::set_content_insets`] in the surface coordinates.
+    ///
+    /// The insets are ignored while the frame is drawn around the content,
+    /// since they'd cut into the frame.
+    fn logical_content_insets(&self) -> Insets<i32> {
+        if self.frame.as_ref().is_some_and(|frame| !frame.is_hidden()) {
+            return Insets::default();
+        }
+
+        let to_logical = |inset: u32| (inset as f64 / self.scale_factor).round() as i32;
+        let insets = self.content_insets;
+        Insets::new(
+            to_logical(insets.top),
+            to_logical(insets.left),
+            to_logical(insets.bottom),
+            to_logical(insets.right),
+        )
+    }
+
+    /// The size of the window geometry for the surface of the `size`,
+    /// without the [`Toplevel::set_content_insets`].
+    fn geometry_size(&self, size: LogicalSize<u32>) -> LogicalSize<u32> {
+        let insets = self.logical_content_insets();
+        let width = size.width as i32 - insets.left - insets.right;
+        let height = size.height as i32 - insets.top - insets.bottom;
+        LogicalSize::new(width.max(1) as u32, height.max(1) as u32)
+    }
+
+    /// Resize the window according to its [`ConfigurePolicy`].
+    pub(crate) fn stage_resize(&mut self, new_size: LogicalSize<u32>, initial_configure: bool) {
+        match self.configure_policy {
+            // Nothing is drawn before the initial configure, or while hidden.
+            ConfigurePolicy::Staged if !initial_configure && !self.hidden => {
+                let deadline = Instant::now() + STAGED_CONFIGURE_TIMEOUT;
+                self.pending_resize = Some((new_size, deadline));
+                self.redraw = true;
+            },
+            _ => self.resize(new_size),
+        }
+    }
+
+    /// Apply the staged size, right before the content with it is drawn.
+    pub(crate) fn apply_pending_resize(&mut self) {
+        if let Some((new_size, _)) = self.pending_resize.take() {
+            self.resize(new_size);
+        }
+    }
+
+    /// The time the staged size is applied without the redraw.
+    pub(crate) fn pending_resize_deadline(&self) -> Option<Instant> {
+        self.pending_resize.map(|(_, deadline)| deadline)
+    }
+
+    /// The cursor of the `seat` over the window.
+    pub(crate) fn cursor(&self, seat: DeviceId) -> CursorIcon {
+        self.seat_cursors
+            .iter()
+            .find(|(device_id, _)| *device_id == seat)
+            .map_or(self.cursor, |(_, cursor)| *cursor)
+    }
+
+    /// Track the position of the pointer of the `seat`, `None` when it has
+    /// left the window.
+    pub(crate) fn set_pointer_position(
+        &mut self,
+        seat: DeviceId,
+        position: Option<PhysicalPosition<f64>>,
+    ) {
+        self.pointer_positions.retain(|(device_id, _)| *device_id != seat);
+        if let Some(position) = position {
+            self.pointer_positions.push((seat, position));
+        }
+    }
+
+    /// The time the coalesced configure is applied.
+    ///
+    /// With the [`ResizeDelivery::PerFrame`] the configure waits for the frame
+    /// callback of the previous frame, bounded by the refresh interval.
+    pub(crate) fn pending_configure_deadline(&self) -> Option<Instant> {
+        self.pending_configure.as_ref()?;
+        match (self.resize_delivery, self.frame_requested_at) {
+            (ResizeDelivery::PerFrame, Some(requested_at)) => {
+                Some(requested_at + self.refresh_interval())
+            },
+            _ => Some(Instant::now()),
+        }
+    }
+
+    /// Take the coalesced configure, when it's due.
+    pub(crate) fn take_pending_configure(&mut self, now: Instant) -> Option<WindowConfigure> {
+        if self.pending_configure_deadline()? <= now {
+            self.pending_configure.take()
+        } else {
+            None
+        }
+    }
+
     #[inline]
     pub(crate) fn is_stateless(configure: &WindowConfigure) -> bool {
         !(configure.is_maximized() || configure.is_fullscreen() || configure.is_tiled())
@@ -268,20 +653,96 @@ impl<T: Application + 'static> Window<T> {
         if let Some(frame) = self.frame.as_mut() {
             frame.set_scaling_factor(scale_factor);
         }
+
+        for plane in &mut self.overlay_planes {
+            plane.set_scale_factor(scale_factor);
+        }
+    }
+
+    /// Apply the stacking order of the overlay planes changed by the user.
+    pub(crate) fn restack_overlay_planes(&mut self) {
+        overlay::restack(self.window.wl_surface(), &mut self.overlay_planes);
     }
 
     /// Reissue the transparency hint to the compositor.
     pub(crate) fn reload_transparency_hint(&self) {
         let surface = self.window.wl_surface();
 
-        if self.transparent {
-            surface.set_opaque_region(None);
-        } else if let Ok(region) = Region::new(&*self.compositor) {
-            region.add(0, 0, i32::MAX, i32::MAX);
-            surface.set_opaque_region(Some(region.wl_region()));
-        } else {
-            // warn!("Failed to mark window opaque.");
+        let rects = match self.opaque_region.as_ref() {
+            Some(rects) => rects,
+            None => {
+                surface.set_opaque_region(None);
+                return;
+            },
+        };
+
+        let region = match Region::new(&*self.compositor) {
+            Ok(region) => region,
+            Err(err) => {
+                log::warn!("Failed to mark window opaque: {err}");
+                return;
+            },
+        };
+
+        for rect in rects {
+            // Round inwards to not mark the transparent pixels as opaque.
+            let left = (rect.origin.x as f64 / self.scale_factor).ceil();
+            let top = (rect.origin.y as f64 / self.scale_factor).ceil();
+            let right =
+                ((rect.origin.x as f64 + rect.size.width as f64) / self.scale_factor).floor();
+            let bottom =
+                ((rect.origin.y as f64 + rect.size.height as f64) / self.scale_factor).floor();
+
+            let clamp = |value: f64| value.min(i32::MAX as f64) as i32;
+            let (left, top) = (clamp(left), clamp(top));
+            let (width, height) = (clamp(right) - left, clamp(bottom) - top);
+            if width > 0 && height > 0 {
+                region.add(left, top, width, height);
+            }
         }
+
+        surface.set_opaque_region(Some(region.wl_region()));
+    }
+
+    /// Apply the input region in the surface coordinates.
+    pub(crate) fn reload_input_region(&self) {
+        let surface = self.window.wl_surface();
+
+        let rects = match self.input_region.as_ref() {
+            Some(rects) => rects,
+            None => {
+                surface.set_input_region(None);
+                return;
+            },
+        };
+
+        let region = match Region::new(&*self.compositor) {
+            Ok(region) => region,
+            Err(err) => {
+                log::warn!("Failed to set the input region: {err}");
+                return;
+            },
+        };
+
+        for rect in rects {
+            // Round outwards to keep the partially covered pixels interactive.
+            let left = (rect.origin.x as f64 / self.scale_factor).floor();
+            let top = (rect.origin.y as f64 / self.scale_factor).floor();
+            let right =
+                ((rect.origin.x as f64 + rect.size.width as f64) / self.scale_factor).ceil();
+            let bottom =
+                ((rect.origin.y as f64 + rect.size.height as f64) / self.scale_factor).ceil();
+
+            let clamp = |value: f64| value.min(i32::MAX as f64) as i32;
+            let (left, top) = (clamp(left), clamp(top));
+            let (width, height) = (clamp(right) - left, clamp(bottom) - top);
+            if width > 0 && height > 0 {
+                region.add(left, top, width, height);
+            }
+        }
+
+        // The empty region makes the whole surface pass the input through.
+        surface.set_input_region(Some(region.wl_region()));
     }
 
     /// Reload the hints for minimum and maximum sizes.
@@ -293,21 +754,386 @@ impl<T: Application + 'static> Window<T> {
     pub(crate) fn configured(&self) -> bool {
         self.last_configure.is_some()
     }
+
+    /// The state of the window for the bug reports.
+    pub(crate) fn dump(&self) -> serde_json::Value {
+        let last_configure = self.last_configure.as_ref().map(|configure| {
+            let new_size = match configure.new_size {
+                (Some(width), Some(height)) => Some((width.get(), height.get())),
+                _ => None,
+            };
+
+            serde_json::json!({
+                "new_size": new_size,
+                "suggested_bounds": configure.suggested_bounds,
+                "state": format!("{:?}", configure.state),
+                "decoration_mode": format!("{:?}", configure.decoration_mode),
+            })
+        });
+
+        serde_json::json!({
+            "id": self.id().0,
+            // The title could contain the user data.
+            "title_len": self.title.chars().count(),
+            "size": (self.size.width, self.size.height),
+            "stateless_size": (self.stateless_size.width, self.stateless_size.height),
+            "pending_size": self.pending_resize.map(|(size, _)| (size.width, size.height)),
+            "scale_factor": self.scale_factor,
+            "fractional_scale": self.fractional_scale.is_some(),
+            "viewport": self.viewport.is_some(),
+            "csd": self.frame.is_some(),
+            "csd_fails": self.csd_fails,
+            "has_focus": self.has_focus,
+            "resizable": self.resizable,
+            "decorate": self.decorate,
+            "opaque_rects": self.opaque_region.as_ref().map(|rects| rects.len()),
+            "input_rects": self.input_region.as_ref().map(|rects| rects.len()),
+            "redraw": self.redraw,
+            "frame_callback_pending": self.frame_requested_at.is_some(),
+            "frame_stats": {
+                "requested": self.frame_stats.requested,
+                "presented": self.frame_stats.presented,
+                "missed": self.frame_stats.missed,
+            },
+            "last_configure": last_configure,
+        })
+    }
+
+    /// Unmap the window, it's not redrawn until shown again.
+    pub(crate) fn hide(&mut self) {
+        self.hidden = true;
+        self.remapping = false;
+        self.redraw = false;
+
+        // The configure is acked along with this commit.
+        self.apply_pending_resize();
+
+        let surface = self.window.wl_surface();
+        surface.attach(None, 0, 0);
+        surface.commit();
+    }
+
+    /// Map the hidden window again, it's redrawn once configured.
+    pub(crate) fn show(&mut self) {
+        if !self.hidden || self.remapping {
+            return;
+        }
+
+        // The unmapped window gets the configure after the commit without the
+        // buffer, like on the creation.
+        self.remapping = true;
+        self.window.commit();
+    }
+
+    /// Finish mapping the window after [`Window::show`], once configured.
+    fn remapped(&mut self) {
+        if !mem::take(&mut self.remapping) {
+            return;
+        }
+
+        // The unmapped surface never gets the callback of its last frame.
+        self.hidden = false;
+        self.frame_requested_at = None;
+        self.fallback_redraw_at = None;
+        self.redraw = true;
+    }
+
+    /// Move the pointer over the frame.
+    ///
+    /// Returns `false` when the `surface` is not part of the frame.
+    pub(crate) fn frame_point_moved(
+        &mut self,
+        surface: &WlSurface,
+        timestamp: Duration,
+        x: f64,
+        y: f64,
+    ) -> bool {
+        match self.frame.as_mut().filter(|frame| !frame.is_hidden()) {
+            Some(frame) => frame.click_point_moved(timestamp, &surface.id(), x, y).is_some(),
+            None => false,
+        }
+    }
+
+    /// The pointer has left the frame.
+    pub(crate) fn frame_point_left(&mut self) {
+        if let Some(frame) = self.frame.as_mut() {
+            frame.click_point_left();
+        }
+    }
+
+    /// Click on the frame.
+    ///
+    /// The interactive actions are performed right away, while the rest is
+    /// returned to be confirmed by the user.
+    pub(crate) fn frame_click(
+        &mut self,
+        seat: &WlSeat,
+        serial: u32,
+        timestamp: Duration,
+        click: FrameClick,
+        pressed: bool,
+    ) -> Option<DecorationAction> {
+        match self.frame.as_mut()?.on_click(timestamp, click, pressed)? {
+            FrameAction::Minimize => Some(DecorationAction::Minimize),
+            FrameAction::Maximize => Some(DecorationAction::Maximize),
+            FrameAction::UnMaximize => Some(DecorationAction::Unmaximize),
+            FrameAction::Close => Some(DecorationAction::Close),
+            FrameAction::Move => {
+                self.window.move_(seat, serial);
+                None
+            },
+            FrameAction::Resize(edge) => {
+                let edge = match edge {
+                    ResizeEdge::None => XdgResizeEdge::None,
+                    ResizeEdge::Top => XdgResizeEdge::Top,
+                    ResizeEdge::Bottom => XdgResizeEdge::Bottom,
+                    ResizeEdge::Left => XdgResizeEdge::Left,
+                    ResizeEdge::TopLeft => XdgResizeEdge::TopLeft,
+                    ResizeEdge::BottomLeft => XdgResizeEdge::BottomLeft,
+                    ResizeEdge::Right => XdgResizeEdge::Right,
+                    ResizeEdge::TopRight => XdgResizeEdge::TopRight,
+                    ResizeEdge::BottomRight => XdgResizeEdge::BottomRight,
+                    _ => return None,
+                };
+                self.window.resize(seat, serial, edge);
+                None
+            },
+            FrameAction::ShowMenu(x, y) => {
+                self.window.show_window_menu(seat, serial, (x, y));
+                None
+            },
+            _ => None,
+        }
+    }
+
+    /// Request the frame callback for the next redraw, the callback is
+    /// committed by the user along with the contents of the frame.
+    pub(crate) fn request_frame_callback(&mut self, queue_handle: &QueueHandle<RuntimeState<T>>) {
+        // The callback for the previous frame is still pending, so the next
+        // fallback redraw is one interval after this one.
+        if self.frame_requested_at.is_some() {
+            self.fallback_redraw_at = Some(Instant::now());
+            return;
+        }
+
+        let surface = self.window.wl_surface();
+        surface.frame(queue_handle, surface.clone());
+        if let Some(presentation) = self.presentation.as_ref() {
+            presentation.feedback(surface, queue_handle, self.id);
+        }
+
+        self.frame_stats.requested += 1;
+        self.frame_requested_at = Some(Instant::now());
+    }
+
+    /// The time the pending redraw should be delivered at.
+    ///
+    /// The redraw waits for the frame callback of the previous frame, so each
+    /// window is drawn at the refresh rate of its own monitor. The refresh
+    /// interval bounds the wait, in case the compositor throttles the window.
+    ///
+    /// The throttled window is redrawn once per [`THROTTLE_TIMEOUT`] instead,
+    /// since the callback is also missing when the user skipped the previous
+    /// redraw without committing it.
+    pub(crate) fn frame_deadline(&self) -> Instant {
+        let requested_at = match self.frame_requested_at {
+            Some(requested_at) => requested_at,
+            None => return Instant::now(),
+        };
+
+        let interval = if self.throttling && self.is_throttled() {
+            THROTTLE_TIMEOUT
+        } else {
+            self.refresh_interval()
+        };
+
+        self.fallback_redraw_at.unwrap_or(requested_at) + interval
+    }
+
+    /// The time the window becomes throttled at, unless the frame callback
+    /// arrives before it.
+    pub(crate) fn throttle_deadline(&self) -> Option<Instant> {
+        let requested_at = self.frame_requested_at.filter(|_| !self.throttled)?;
+        Some(requested_at + THROTTLE_TIMEOUT)
+    }
+
+    /// Account the presented frame.
+    ///
+    /// Returns `true` when the frame wasn't presented within the refresh
+    /// interval.
+    pub(crate) fn frame_presented(&mut self) -> bool {
+        let requested_at = match self.frame_requested_at.take() {
+            Some(requested_at) => requested_at,
+            None => return false,
+        };
+        self.fallback_redraw_at = None;
+
+        self.frame_stats.presented += 1;
+
+        let missed = requested_at.elapsed() > self.refresh_interval();
+        if missed {
+            self.frame_stats.missed += 1;
+        }
+
+        missed
+    }
+
+    /// The refresh interval of the monitor the window is on.
+    fn refresh_interval(&self) -> Duration {
+        if let Some(refresh) = self.presentation_refresh {
+            return refresh;
+        }
+
+        let refresh_rate = self
+            .window
+            .wl_surface()
+            .data::<SurfaceData>()
+            .and_then(|data| data.outputs().next())
+            .and_then(|output| monitor::refresh_rate_millihertz(&output))
+            .filter(|refresh_rate| *refresh_rate != 0)
+            .unwrap_or(FALLBACK_REFRESH_RATE_MILLIHERTZ);
+
+        Duration::from_nanos(1_000_000_000_000 / refresh_rate as u64)
+    }
 }
 
-impl<T: Application + 'static> CoreWindow for Window<T> {
+impl<T: Application + 'static> CoreSurface for Window<T> {
     fn id(&self) -> WindowId {
-        crate::make_wid(&self.window.wl_surface())
+        self.id
     }
 
     fn request_redraw(&mut self) {
-        self.redraw = true;
+        // Attaching the buffer would map the hidden window.
+        if !self.hidden {
+            self.redraw = true;
+        }
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    fn inner_size(&self) -> PhysicalSize<u32> {
+        let size = self.pending_resize.map_or(self.size, |(size, _)| size);
+        self.rounding_policy.to_physical_size(size, self.scale_factor)
+    }
+
+    fn surface_geometry(&self) -> Rect<u32> {
+        // The frame is drawn around the main surface, so the content is offset by
+        // the frame location.
+        let (x, y) = self.frame.as_ref().map(|frame| frame.location()).unwrap_or((0, 0));
+        let origin = LogicalPosition::new(-x, -y).to_physical(self.scale_factor);
+        Rect::new(origin, self.inner_size())
+    }
+
+    fn current_monitor(&self) -> Option<MonitorId> {
+        let data = self.window.wl_surface().data::<SurfaceData>()?;
+        data.outputs().next().map(|output| self.monitor_registry.id(&output))
+    }
+
+    fn primary_monitor(&self) -> Option<MonitorId> {
+        None
+    }
+
+    fn set_opaque_region(&mut self, region: Option<&[Rect<u32>]>) {
+        self.opaque_region = region.map(|rects| rects.to_vec());
+        self.reload_transparency_hint();
     }
 
+    fn set_input_region(&mut self, region: Option<&[Rect<u32>]>) {
+        self.input_region = region.map(|rects| rects.to_vec());
+        self.reload_input_region();
+    }
+
+    fn frame_stats(&self) -> FrameStats {
+        self.frame_stats
+    }
+
+    fn is_throttled(&self) -> bool {
+        // The compositor stops sending the frame callbacks once the window is
+        // not visible, since it's the only hint Wayland gives about that.
+        self.hidden
+            || self
+                .frame_requested_at
+                .is_some_and(|requested_at| requested_at.elapsed() >= THROTTLE_TIMEOUT)
+    }
+
+    fn set_throttling(&mut self, throttling: bool) {
+        self.throttling = throttling;
+    }
+
+    fn is_focusable(&self) -> bool {
+        self.focusable
+    }
+
+    fn set_focusable(&mut self, focusable: bool) {
+        // Wayland has no way to refuse the keyboard focus, so only stop asking
+        // for it.
+        self.focusable = focusable;
+        if !focusable {
+            self.activation_requested = false;
+        }
+    }
+
+    fn set_cursor(&mut self, cursor: CursorIcon, seat: Option<DeviceId>) {
+        match seat {
+            Some(seat) => {
+                self.seat_cursors.retain(|(device_id, _)| *device_id != seat);
+                self.seat_cursors.push((seat, cursor));
+            },
+            None => {
+                self.cursor = cursor;
+                self.seat_cursors.clear();
+            },
+        }
+
+        // Applied to the seats over the window by the event loop.
+        self.cursor_changed = true;
+    }
+
+    fn cursor_position(&self, seat: Option<DeviceId>) -> Option<PhysicalPosition<f64>> {
+        let mut positions = self.pointer_positions.iter().rev();
+        match seat {
+            Some(seat) => positions.find(|(device_id, _)| *device_id == seat),
+            None => positions.next(),
+        }
+        .map(|(_, position)| *position)
+    }
+
+    fn as_toplevel(&self) -> Option<&dyn Toplevel> {
+        Some(self)
+    }
+
+    fn as_toplevel_mut(&mut self) -> Option<&mut dyn Toplevel> {
+        Some(self)
+    }
+}
+
+impl<T: Application + 'static> Toplevel for Window<T> {
     fn title(&self) -> &str {
         &self.title
     }
 
+    fn set_title(&mut self, title: Title) -> Title {
+        let title = self.title_policy.apply(title.as_str());
+        if title.is_truncated() {
+            log::debug!("Window title truncated to {} bytes", self.title_policy.max_len);
+        }
+
+        // Update the CSD title.
+        if let Some(frame) = self.frame.as_mut() {
+            frame.set_title(title.as_str());
+        }
+
+        self.window.set_title(title.as_str());
+        self.title = title.as_str().to_owned();
+        title
+    }
+
+    fn title_policy(&self) -> TitlePolicy {
+        self.title_policy
+    }
+
     fn theme(&self) -> Option<Theme> {
         self.theme
     }
@@ -320,56 +1146,258 @@ impl<T: Application + 'static> CoreWindow for Window<T> {
         }
     }
 
-    fn set_title(&mut self, title: &str) {
-        let mut title = title.to_owned();
-        // Truncate the title to at most 1024 bytes, so that it does not blow up the
-        // protocol messages
-        if title.len() > 1024 {
-            let mut new_len = 1024;
-            while !title.is_char_boundary(new_len) {
-                new_len -= 1;
-            }
-            title.truncate(new_len);
+    fn is_decorated(&self) -> bool {
+        self.decorate
+    }
+
+    fn set_decorations(&mut self, decorate: bool) {
+        if self.decorate == decorate {
+            return;
         }
 
-        // Update the CSD title.
-        if let Some(frame) = self.frame.as_mut() {
-            frame.set_title(&title);
+        self.decorate = decorate;
+
+        // Same as on creation, the server side decorations can't be hidden, so
+        // switch to the client side ones for that.
+        match self.last_configure.as_ref().map(|configure| configure.decoration_mode) {
+            Some(DecorationMode::Server) if !decorate => {
+                self.window.request_decoration_mode(Some(DecorationMode::Client))
+            },
+            _ if decorate => self.window.request_decoration_mode(Some(DecorationMode::Server)),
+            _ => (),
         }
 
-        self.window.set_title(&title);
-        self.title = title;
+        // The server side decorations are resized by the compositor along with
+        // the configure.
+        let frame = match self.frame.as_mut() {
+            Some(frame) => frame,
+            None => return,
+        };
+
+        // Keep the outer size, so the content takes over the space of the frame.
+        let (width, height) = frame.add_borders(self.size.width, self.size.height);
+        frame.set_hidden(!decorate);
+        let (width, height) = match (NonZeroU32::new(width), NonZeroU32::new(height)) {
+            (Some(width), Some(height)) => frame.subtract_borders(width, height),
+            _ => return,
+        };
+
+        let new_size = LogicalSize::new(
+            width.map_or(self.min_inner_size.width, NonZeroU32::get),
+            height.map_or(self.min_inner_size.height, NonZeroU32::get),
+        );
+        self.resize(new_size);
+        self.size_changed = true;
+        self.request_redraw();
     }
 
-    fn scale_factor(&self) -> f64 {
-        self.scale_factor
+    fn set_content_insets(&mut self, insets: Insets<u32>) {
+        if self.content_insets == insets {
+            return;
+        }
+
+        self.content_insets = insets;
+
+        // The size hints are of the window geometry as well.
+        if self.resizable {
+            self.reload_min_max_hints();
+        } else {
+            self.set_min_inner_size(Some(self.size.into()));
+            self.set_max_inner_size(Some(self.size.into()));
+        }
+
+        // Applied along with the next commit of the user.
+        if self.last_configure.is_some() {
+            self.update_window_geometry();
+        }
     }
 
-    fn inner_size(&self) -> PhysicalSize<u32> {
-        crate::logical_to_physical_rounded(self.size, self.scale_factor)
+    fn request_inner_size(&mut self, size: Size) -> Option<PhysicalSize<u32>> {
+        // The window is not yet configured, thus replace the initial size.
+        if self.last_configure.is_none() {
+            self.initial_size = Some(size);
+            self.size_policy = SizePolicy::Fixed;
+            return None;
+        }
+
+        let mut new_size: LogicalSize<u32> = size.to_logical(self.scale_factor);
+        new_size.width = new_size.width.max(self.min_inner_size.width);
+        new_size.height = new_size.height.max(self.min_inner_size.height);
+        if let Some(max_inner_size) = self.max_inner_size {
+            new_size.width = new_size.width.min(max_inner_size.width);
+            new_size.height = new_size.height.min(max_inner_size.height);
+        }
+
+        // The compositor is in charge of the size when the window is maximized or
+        // tiled, so restore to the requested size once it's floating again.
+        if !self.last_configure.as_ref().is_none_or(Self::is_stateless) {
+            self.stateless_size = new_size;
+            return None;
+        }
+
+        // The floating window could pick any size, thus apply it right away. The
+        // frame borders are added around the new size.
+        self.resize(new_size);
+        self.request_redraw();
+
+        Some(self.inner_size())
     }
 
     fn set_minimized(&mut self, minimize: bool) {
-        if minimize {
+        if !minimize {
+            return;
+        }
+
+        if self.last_configure.is_none() {
+            self.pending_requests.push(PendingRequest::Minimized);
+        } else {
             self.window.set_minimized();
         }
     }
 
     fn set_maximized(&mut self, maximized: bool) {
-        if maximized {
+        if self.last_configure.is_none() {
+            self.pending_requests.push(PendingRequest::Maximized(maximized));
+        } else if maximized {
             self.window.set_maximized();
         } else {
             self.window.unset_maximized();
         }
     }
 
-    fn current_monitor(&self) -> Option<MonitorId> {
-        let data = self.window.wl_surface().data::<SurfaceData>()?;
-        data.outputs().next().as_ref().map(crate::make_mid)
+    fn is_maximized(&self) -> bool {
+        self.last_configure.as_ref().is_some_and(|configure| configure.is_maximized())
     }
 
-    fn primary_monitor(&self) -> Option<MonitorId> {
-        None
+    fn create_overlay_plane(&mut self) -> Result<OverlayPlaneId, OverlayPlaneError> {
+        let subcompositor = self
+            .subcompositor
+            .as_ref()
+            .ok_or(OverlayPlaneError::MissingGlobal("wl_subcompositor"))?;
+        let plane_id = OverlayPlaneId(self.next_overlay_plane_id);
+        self.next_overlay_plane_id += 1;
+
+        let plane = OverlayPlane::new(
+            plane_id,
+            self.window.wl_surface(),
+            subcompositor,
+            self.viewporter.as_ref(),
+            self.scale_factor,
+            &self.queue_handle,
+        );
+        self.overlay_planes.push(plane);
+
+        Ok(plane_id)
+    }
+
+    fn overlay_plane(&self, plane_id: OverlayPlaneId) -> Option<&dyn CoreOverlayPlane> {
+        self.overlay_planes
+            .iter()
+            .find(|plane| plane.id() == plane_id)
+            .map(|plane| plane as &dyn CoreOverlayPlane)
+    }
+
+    fn overlay_plane_mut(&mut self, plane_id: OverlayPlaneId) -> Option<&mut dyn CoreOverlayPlane> {
+        self.overlay_planes
+            .iter_mut()
+            .find(|plane| plane.id() == plane_id)
+            .map(|plane| plane as &mut dyn CoreOverlayPlane)
+    }
+
+    fn destroy_overlay_plane(&mut self, plane_id: OverlayPlaneId) {
+        self.overlay_planes.retain(|plane| plane.id() != plane_id);
+    }
+
+    fn request_activation(&mut self) {
+        if !self.focusable {
+            return;
+        }
+
+        // The token is requested on the next loop iteration, since it needs
+        // the latest input serial.
+        self.activation_requested = true;
+    }
+
+    fn is_fullscreen(&self) -> bool {
+        self.last_configure.as_ref().is_some_and(|configure| configure.is_fullscreen())
+    }
+
+    fn close_behavior(&self) -> CloseBehavior {
+        self.close_behavior
+    }
+
+    fn set_close_behavior(&mut self, close_behavior: CloseBehavior) {
+        self.close_behavior = close_behavior;
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        if visible {
+            self.show();
+        } else if !self.hidden {
+            self.hide();
+        }
+    }
+
+    fn hit_test(&self, position: Position) -> RegionKind {
+        let position: PhysicalPosition<f64> = position.to_physical(self.scale_factor);
+        if self.surface_geometry().contains(position) {
+            return RegionKind::Content;
+        }
+
+        let frame = match self.frame.as_ref().filter(|frame| !frame.is_hidden()) {
+            Some(frame) => frame,
+            None => return RegionKind::Outside,
+        };
+
+        let (width, height) = frame.add_borders(self.size.width, self.size.height);
+        let size = LogicalSize::new(width, height);
+        let size = self.rounding_policy.to_physical_size(size, self.scale_factor);
+        let whole = Rect::new(PhysicalPosition::new(0, 0), size);
+
+        // The side borders of the frame are used for resizing, while the top one
+        // also has the titlebar, so use the left one for all the edges.
+        let (x, _) = frame.location();
+        let border = (-x as f64 * self.scale_factor).round() as u32;
+
+        match whole.border_at(position, Insets::uniform(border)) {
+            Some(border) => RegionKind::ResizeBorder(border),
+            None if whole.contains(position) => RegionKind::Frame,
+            None => RegionKind::Outside,
+        }
+    }
+
+    fn recommended_bounds(&self) -> Option<LogicalSize<u32>> {
+        let configure = self.last_configure.as_ref()?;
+        configure.suggested_bounds.map(Into::into)
+    }
+
+    fn group(&self) -> Option<GroupId> {
+        self.group.map(|(group, _)| group)
+    }
+
+    fn set_group(&mut self, group: Option<GroupId>) {
+        if self.group() == group {
+            return;
+        }
+
+        // The parents are updated on the next loop iteration, since they need
+        // the rest of the group.
+        self.group = group.map(|group| (group, Instant::now()));
+        self.group_changed = true;
+    }
+
+    fn tiled_edges(&self) -> TiledEdges {
+        let state = match self.last_configure.as_ref() {
+            Some(configure) => configure.state,
+            None => return TiledEdges::empty(),
+        };
+
+        let mut tiled_edges = TiledEdges::empty();
+        tiled_edges.set(TiledEdges::LEFT, state.contains(XdgWindowState::TILED_LEFT));
+        tiled_edges.set(TiledEdges::RIGHT, state.contains(XdgWindowState::TILED_RIGHT));
+        tiled_edges.set(TiledEdges::TOP, state.contains(XdgWindowState::TILED_TOP));
+        tiled_edges.set(TiledEdges::BOTTOM, state.contains(XdgWindowState::TILED_BOTTOM));
+        tiled_edges
     }
 }
 
@@ -384,27 +1412,220 @@ impl<T: Application + 'static> HasWindowHandle for Window<T> {
     }
 }
 
-impl<T: Application + 'static> WindowHandler for RuntimeState<T> {
-    fn request_close(&mut self, _: &Connection, _: &QueueHandle<Self>, window: &XdgWindow) {
-        let window_id = crate::make_wid(window.wl_surface());
+impl<T: Application + 'static> RuntimeState<T> {
+    /// Apply the configures coalesced during the dispatch, which are due.
+    pub(crate) fn apply_pending_configures(&mut self) {
+        let now = Instant::now();
+        let configures: SmallVec<[(WindowId, WindowConfigure); 2]> = self
+            .winit
+            .windows
+            .iter_mut()
+            .filter_map(|(window_id, window)| {
+                Some((*window_id, window.take_pending_configure(now)?))
+            })
+            .collect();
+
+        for (window_id, configure) in configures {
+            self.apply_configure(window_id, configure);
+        }
+    }
+
+    /// Ask the user to close the window.
+    pub(crate) fn request_window_close(&mut self, window_id: WindowId) {
+        if !self.winit.windows.contains_key(&window_id) {
+            return;
+        }
+
         let user_state = self.user.as_mut().unwrap();
-        if user_state.close_requested(&mut self.winit, window_id) {
-            // Instantly drop the window.
-            drop(self.winit.windows.remove(&window_id));
-            user_state.destroyed(&mut self.winit, window_id);
+        if !user_state.close_requested(&mut self.winit, window_id) {
+            return;
+        }
+
+        // The user could change the behavior while asked.
+        let close_behavior = match self.winit.windows.get(&window_id) {
+            Some(window) => window.close_behavior,
+            None => return,
+        };
+
+        match close_behavior {
+            CloseBehavior::NotifyOnly => (),
+            CloseBehavior::DestroyImmediately => {
+                drop(self.winit.windows.remove(&window_id));
+                self.winit.pending_motion.remove(&window_id);
+                self.winit.tombstones.bury(window_id);
+                user_state.destroyed(&mut self.winit, window_id);
+            },
+            CloseBehavior::HideInstead => {
+                if let Some(window) = self.winit.windows.get_mut(&window_id) {
+                    window.hide();
+                }
+            },
+        }
+    }
+
+    /// Perform the decoration action confirmed by the user.
+    pub(crate) fn decoration_action(&mut self, window_id: WindowId, action: DecorationAction) {
+        if action == DecorationAction::Close {
+            self.request_window_close(window_id);
+            return;
+        }
+
+        let window = match self.winit.windows.get_mut(&window_id) {
+            Some(window) => window,
+            None => return,
+        };
+
+        match action {
+            DecorationAction::Minimize => window.set_minimized(true),
+            DecorationAction::Maximize => window.set_maximized(true),
+            DecorationAction::Unmaximize => window.set_maximized(false),
+            DecorationAction::Close => unreachable!(),
+        }
+    }
+}
+
+impl<T: Application + 'static> WinitState<T> {
+    /// Request the activation tokens for the windows asked to be focused.
+    pub(crate) fn request_activations(&mut self, user: &mut T) {
+        let mut denied = SmallVec::<[WindowId; 2]>::new();
+        for (window_id, window) in &mut self.windows {
+            if !mem::take(&mut window.activation_requested) || window.has_focus {
+                continue;
+            }
+
+            let xdg_activation = match self.xdg_activation.as_ref() {
+                Some(xdg_activation) => xdg_activation,
+                None => {
+                    denied.push(*window_id);
+                    continue;
+                },
+            };
+
+            let data = RequestData {
+                app_id: None,
+                seat_and_serial: self.latest_serial.clone(),
+                surface: Some(window.window.wl_surface().clone()),
+            };
+            xdg_activation.request_token(&self.queue_handle, data);
+        }
+
+        for window_id in denied {
+            user.activation_denied(self, window_id);
+        }
+    }
+
+    /// Parent the windows to the main window of their group.
+    ///
+    /// The parents only change when a window joins or leaves a group, or when
+    /// the parent of a window is destroyed.
+    pub(crate) fn update_window_groups(&mut self) {
+        let dirty = self.windows.values().any(|window| {
+            window.group_changed
+                || window.parent.is_some_and(|parent| !self.windows.contains_key(&parent))
+        });
+        if !dirty {
+            return;
+        }
+
+        // The main window of a group is the one which joined it first.
+        let mut leaders = HashMap::<GroupId, (Instant, WindowId)>::new();
+        for (window_id, window) in &self.windows {
+            let Some((group, joined_at)) = window.group else { continue };
+            let candidate = (joined_at, *window_id);
+            leaders
+                .entry(group)
+                .and_modify(|leader| {
+                    if (candidate.0, candidate.1 .0) < (leader.0, leader.1 .0) {
+                        *leader = candidate;
+                    }
+                })
+                .or_insert(candidate);
+        }
+
+        let mut reparent = SmallVec::<[(WindowId, Option<WindowId>); 4]>::new();
+        for (window_id, window) in &mut self.windows {
+            window.group_changed = false;
+            let parent = window
+                .group
+                .and_then(|(group, _)| leaders.get(&group).map(|&(_, leader)| leader))
+                .filter(|leader| leader != window_id);
+            if parent != window.parent {
+                reparent.push((*window_id, parent));
+            }
         }
+
+        for (window_id, parent_id) in reparent {
+            let parent = parent_id.and_then(|parent_id| self.windows.get(&parent_id));
+            let parent = parent.map(|parent| parent.window.clone());
+            let window = self.windows.get_mut(&window_id).unwrap();
+            window.window.set_parent(parent.as_ref());
+            window.parent = parent_id;
+        }
+    }
+
+    /// Notify the user when the window didn't get the focus after the
+    /// activation.
+    ///
+    /// The compositor silently ignores the activation it refuses, so the
+    /// focus is checked after the timeout.
+    pub(crate) fn watch_activation(&mut self, window_id: WindowId) {
+        let timer = Timer::from_duration(ACTIVATION_TIMEOUT);
+        let _ = self.loop_handle.insert_source(timer, move |_, _, state| {
+            let focused = state.winit.windows.get(&window_id).map(|window| window.has_focus);
+            if focused == Some(false) {
+                let user = state.user.as_mut().unwrap();
+                user.activation_denied(&mut state.winit, window_id);
+            }
+
+            TimeoutAction::Drop
+        });
+    }
+}
+
+impl<T: Application + 'static> WindowHandler for RuntimeState<T> {
+    fn request_close(&mut self, _: &Connection, _: &QueueHandle<Self>, window: &XdgWindow) {
+        let window_id = self.winit.window_id(window.wl_surface());
+        self.request_window_close(window_id);
     }
 
     fn configure(
         &mut self,
         _: &Connection,
-        queue_handle: &QueueHandle<Self>,
+        _: &QueueHandle<Self>,
         window: &XdgWindow,
         configure: WindowConfigure,
         _serial: u32,
     ) {
+        let window_id = self.winit.window_id(window.wl_surface());
+        limited_debug!(
+            self.winit.log_limiter,
+            LogKind::Configure,
+            "Configure of {window_id:?}: {:?} {:?}",
+            configure.new_size,
+            configure.state
+        );
+
+        let window = match self.winit.windows.get_mut(&window_id) {
+            Some(window) => window,
+            None => return,
+        };
+
+        // The configures are acked right away, so only the latest one of the
+        // batch has to be applied. The initial one creates the window though.
+        if window.resize_delivery != ResizeDelivery::Every && window.last_configure.is_some() {
+            window.pending_configure = Some(configure);
+            return;
+        }
+
+        self.apply_configure(window_id, configure);
+    }
+}
+
+impl<T: Application + 'static> RuntimeState<T> {
+    /// Apply the `configure` of the window and notify the user.
+    fn apply_configure(&mut self, window_id: WindowId, configure: WindowConfigure) {
         let winit = &mut self.winit;
-        let window_id = crate::make_wid(window.wl_surface());
+        let queue_handle = winit.queue_handle.clone();
         let window = match winit.windows.get_mut(&window_id) {
             Some(window) => window,
             None => return,
@@ -416,7 +1637,19 @@ impl<T: Application + 'static> WindowHandler for RuntimeState<T> {
         // should be delivered before the first configure, thus apply it to
         // properly scale the physical sizes provided by the users.
         if let Some(initial_size) = window.initial_size.take() {
-            window.size = initial_size.to_logical(scale_factor);
+            // Resolve the policy against the bounds suggested by the compositor, or
+            // the monitor the window is likely to be placed on.
+            let bounds = configure.suggested_bounds.map(LogicalSize::from).or_else(|| {
+                let monitor = winit.monitors.first()?;
+                Some(monitor.size().to_logical(monitor.scale_factor()))
+            });
+
+            window.size = match bounds {
+                Some(bounds) => {
+                    window.size_policy.resolve(initial_size, bounds, scale_factor).cast()
+                },
+                None => initial_size.to_logical(scale_factor),
+            };
             window.stateless_size = window.size;
         }
 
@@ -453,15 +1686,69 @@ impl<T: Application + 'static> WindowHandler for RuntimeState<T> {
         }
 
         let (new_size, constrain): (LogicalSize<u32>, bool) = match configure.new_size {
-            (Some(width), Some(height)) => ((width.get(), height.get()).into(), false),
-            _ => (window.size, true),
+            (Some(width), Some(height)) => {
+                // The suggested size is of the window geometry, so add the margins
+                // drawn by the user back.
+                let insets = window.logical_content_insets();
+                let width = width.get() as i32 + insets.left + insets.right;
+                let height = height.get() as i32 + insets.top + insets.bottom;
+                ((width.max(1) as u32, height.max(1) as u32).into(), false)
+            },
+            // Restore the size we had before the window was maximized or tiled.
+            _ if Window::<T>::is_stateless(&configure) => (window.stateless_size, true),
+            _ => (window.pending_resize.map_or(window.size, |(size, _)| size), true),
+        };
+
+        // The tests get the same size whatever the compositor suggests.
+        #[cfg(feature = "testing")]
+        let (new_size, constrain) = match window.pinned_content {
+            Some((size, _)) => (size, false),
+            None => (new_size, constrain),
         };
 
         let user = self.user.as_mut().unwrap();
         let initial_configue = window.last_configure.is_none();
+        let state_changed = window
+            .last_configure
+            .as_ref()
+            .is_some_and(|last_configure| last_configure.state != configure.state);
+        let bounds_changed = window.last_configure.as_ref().is_some_and(|last_configure| {
+            last_configure.suggested_bounds != configure.suggested_bounds
+        });
+        let decoration_mode = window
+            .last_configure
+            .as_ref()
+            .is_none_or(|last_configure| {
+                last_configure.decoration_mode != configure.decoration_mode
+            })
+            .then_some(match configure.decoration_mode {
+                DecorationMode::Server => CoreDecorationMode::Server,
+                DecorationMode::Client => CoreDecorationMode::Client,
+            });
         window.last_configure = Some(configure);
 
-        window.resize(new_size);
+        // The configure is acked by the toolkit once received, which takes effect
+        // with the next commit of the surface, so the staged size is applied
+        // before every commit made by winit, and the frame state along with it.
+        window.stage_resize(new_size, initial_configue);
+
+        // The window shown again is mapped with this configure.
+        window.remapped();
+
+        let physical_size = window.rounding_policy.to_physical_size(new_size, scale_factor);
+
+        // Track the presentation of the initial redraw as well.
+        if initial_configue {
+            // The initial configure delivers the scale factor as well.
+            window.scale_changed = false;
+            window.replay_pending_requests();
+            window.request_frame_callback(&queue_handle);
+
+            let startup = &mut winit.startup;
+            if startup.timings.first_configure.is_none() {
+                startup.timings.first_configure = Some(startup.started_at.elapsed());
+            }
+        }
 
         if let Some(foo) = self.vtable.foo {
             println!("Calling optional method!");
@@ -476,7 +1763,19 @@ impl<T: Application + 'static> WindowHandler for RuntimeState<T> {
             user.scale_factor_changed(winit, window_id, scale_factor);
         }
 
-        user.resized(winit, window_id, logical_to_physical_rounded(new_size, scale_factor));
+        if let Some(decoration_mode) = decoration_mode {
+            user.decoration_mode_changed(winit, window_id, decoration_mode);
+        }
+
+        if state_changed {
+            user.state_changed(winit, window_id);
+        }
+
+        if bounds_changed {
+            user.recommended_bounds_changed(winit, window_id);
+        }
+
+        user.resized(winit, window_id, physical_size);
 
         if initial_configue {
             user.redraw_requested(winit, window_id);
@@ -484,6 +1783,25 @@ impl<T: Application + 'static> WindowHandler for RuntimeState<T> {
     }
 }
 
+impl<T: Application + 'static> Drop for Window<T> {
+    fn drop(&mut self) {
+        // Destroy before the surface it's attached to.
+        if let Some(syncobj_surface) = self.syncobj_surface.take() {
+            syncobj_surface.destroy();
+        }
+
+        if let Some(fractional_scale) = self.fractional_scale.take() {
+            fractional_scale.destroy();
+        }
+
+        if let Some(viewport) = self.viewport.take() {
+            viewport.destroy();
+        }
+
+        self.ids.release(self.id.0);
+    }
+}
+
 unsafe impl<T: Application + 'static> HasRawWindowHandle05 for Window<T> {
     fn raw_window_handle(&self) -> raw_window_handle_05::RawWindowHandle {
         let mut window_handle = raw_window_handle_05::WaylandWindowHandle::empty();
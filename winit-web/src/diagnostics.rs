@@ -0,0 +1,29 @@
+//! The diagnostics of the web backend.
+
+use winit_core::diagnostics::{BackendProbe, BackendReport};
+
+/// The probe of the web backend, see [`winit_core::diagnostics::probe`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WebProbe;
+
+impl BackendProbe for WebProbe {
+    fn name(&self) -> &'static str {
+        "web"
+    }
+
+    fn version(&self) -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    fn probe(&self) -> BackendReport {
+        let mut report = BackendReport::new(self.name());
+
+        // The workers have neither the window nor the document.
+        match web_sys::window().and_then(|window| window.document()) {
+            Some(_) => report.connectable = true,
+            None => report.missing.push(String::from("the page has no document")),
+        }
+
+        report
+    }
+}
@@ -0,0 +1,540 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::mem;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use raw_window_handle::{DisplayHandle, HandleError, HasDisplayHandle};
+use raw_window_handle_05::HasRawDisplayHandle as HasRawDisplayHandle05;
+
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::BroadcastChannel;
+use web_time::Instant;
+
+use winit_core::application::{Application, StartCause};
+use winit_core::event_loop::proxy::EventLoopProxy as CoreEventLoopProxy;
+use winit_core::event_loop::{EventLoopError, EventLoopHandle, EventLoopRequests};
+use winit_core::window::{Surface as CoreSurface, WindowId};
+
+use crate::state::WinitState;
+use crate::EventListener;
+
+/// The DOM event, queued to be delivered to the application.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Event {
+    Created(WindowId),
+    Resized(WindowId),
+    Focused(WindowId, bool),
+    ScaleFactorChanged,
+    VisibilityChanged,
+}
+
+/// The iteration scheduled to run as soon as possible.
+#[derive(Debug, Clone, Copy)]
+enum Scheduled {
+    /// The handle of the animation frame.
+    AnimationFrame(i32),
+
+    /// The handle of the timeout, used while the page is hidden.
+    Timeout(i32),
+}
+
+/// The scheduling of the iterations of the event loop.
+struct Scheduler {
+    window: web_sys::Window,
+
+    /// The iteration, called by the animation frames and the timeouts.
+    iteration: Closure<dyn FnMut()>,
+
+    /// The iteration scheduled to run as soon as possible.
+    scheduled: Option<Scheduled>,
+
+    /// The timeout of the closest timer.
+    timeout: Option<i32>,
+
+    /// The wakeups of the proxy sent from the other threads.
+    _proxy_listener: Option<EventListener>,
+}
+
+impl Scheduler {
+    /// Cancel the iteration scheduled to run as soon as possible.
+    fn cancel(&mut self) {
+        match self.scheduled.take() {
+            Some(Scheduled::AnimationFrame(handle)) => {
+                let _ = self.window.cancel_animation_frame(handle);
+            },
+            Some(Scheduled::Timeout(handle)) => self.window.clear_timeout_with_handle(handle),
+            None => (),
+        }
+    }
+}
+
+thread_local! {
+    /// The events waiting to be delivered.
+    static QUEUE: RefCell<VecDeque<Event>> = RefCell::new(VecDeque::new());
+
+    /// The scheduler of the running loop.
+    static SCHEDULER: RefCell<Option<Scheduler>> = RefCell::new(None);
+
+    /// The channel the proxy posts to from this thread, along with its name.
+    static PROXY_CHANNEL: RefCell<Option<(String, BroadcastChannel)>> = RefCell::new(None);
+}
+
+/// Queue the `event` to be delivered on the next iteration.
+pub(crate) fn queue_event(event: Event) {
+    QUEUE.with(|queue| queue.borrow_mut().push_back(event));
+}
+
+fn next_event() -> Option<Event> {
+    QUEUE.with(|queue| queue.borrow_mut().pop_front())
+}
+
+/// Run the iteration of the event loop on the next animation frame.
+///
+/// Does nothing off the thread of the loop, or before it runs.
+pub(crate) fn schedule() {
+    SCHEDULER.with(|scheduler| {
+        let mut scheduler = scheduler.borrow_mut();
+        let scheduler = match scheduler.as_mut() {
+            Some(scheduler) if scheduler.scheduled.is_none() => scheduler,
+            _ => return,
+        };
+
+        let callback = scheduler.iteration.as_ref().unchecked_ref();
+
+        // The animation frames are paused for the hidden pages, the events
+        // still need to be delivered then.
+        let hidden = scheduler.window.document().is_some_and(|document| document.hidden());
+        scheduler.scheduled = if hidden {
+            scheduler.window.set_timeout_with_callback(callback).ok().map(Scheduled::Timeout)
+        } else {
+            scheduler.window.request_animation_frame(callback).ok().map(Scheduled::AnimationFrame)
+        };
+    });
+}
+
+/// Schedule the iteration again after the page was hidden or shown.
+///
+/// The animation frame requested for the visible page doesn't run once it's
+/// hidden, so it's replaced with the timeout, and the other way around.
+pub(crate) fn reschedule() {
+    SCHEDULER.with(|scheduler| {
+        if let Some(scheduler) = scheduler.borrow_mut().as_mut() {
+            scheduler.cancel();
+        }
+    });
+
+    schedule();
+}
+
+/// Run the iteration of the event loop once the `timeout` passes, unless it's
+/// scheduled to run earlier.
+fn schedule_timeout(timeout: Duration) {
+    SCHEDULER.with(|scheduler| {
+        let mut scheduler = scheduler.borrow_mut();
+        let scheduler = match scheduler.as_mut() {
+            Some(scheduler) => scheduler,
+            None => return,
+        };
+
+        if let Some(handle) = scheduler.timeout.take() {
+            scheduler.window.clear_timeout_with_handle(handle);
+        }
+
+        let millis = timeout.as_millis().min(i32::MAX as u128) as i32;
+        let callback = scheduler.iteration.as_ref().unchecked_ref();
+        scheduler.timeout = scheduler
+            .window
+            .set_timeout_with_callback_and_timeout_and_arguments_0(callback, millis)
+            .ok();
+    });
+}
+
+/// Reset the scheduling at the start of the iteration.
+fn start_iteration() {
+    SCHEDULER.with(|scheduler| {
+        if let Some(scheduler) = scheduler.borrow_mut().as_mut() {
+            scheduler.scheduled = None;
+            if let Some(handle) = scheduler.timeout.take() {
+                scheduler.window.clear_timeout_with_handle(handle);
+            }
+        }
+    });
+}
+
+/// The web event loop.
+///
+/// The application type could be provided statically, or left as the
+/// `Box<dyn Application>` when the event loop is used through the
+/// [`EventLoopRequests`].
+pub struct EventLoop<T: Application + 'static = Box<dyn Application>> {
+    pub(crate) state: RuntimeState<T>,
+}
+
+impl<T: Application + 'static> EventLoop<T> {
+    pub fn new() -> Result<Self, EventLoopError> {
+        let started_at = Instant::now();
+
+        if web_sys::window().is_none() {
+            let reason = "the event loop must be created on the main thread of the page";
+            return Err(EventLoopError::Os(reason.into()));
+        }
+
+        let proxy = EventLoopProxy::new();
+        let mut state = RuntimeState { user: None, winit: WinitState::new(proxy) };
+
+        let startup = &mut state.winit.startup;
+        startup.started_at = started_at;
+        startup.timings.connected = Some(started_at.elapsed());
+        startup.timings.globals_bound = Some(started_at.elapsed());
+
+        Ok(Self { state })
+    }
+
+    /// Run the event loop.
+    ///
+    /// The browser can't be blocked, so this returns right away and the loop
+    /// runs in the animation frames until the exit, owning the `state`.
+    pub fn run(self, state: T) {
+        let window = match web_sys::window() {
+            Some(window) => window,
+            None => return,
+        };
+
+        let mut runtime = self.state;
+        runtime.user = Some(state);
+        let runtime = Rc::new(RefCell::new(runtime));
+
+        let iteration = {
+            let runtime = runtime.clone();
+            Closure::<dyn FnMut()>::new(move || runtime.borrow_mut().iteration())
+        };
+
+        // The proxies on the other threads can't reach the scheduler, so they
+        // wake up the loop through the channel.
+        let proxy_listener = match BroadcastChannel::new(&runtime.borrow().winit.proxy.channel) {
+            Ok(channel) => Some(EventListener::new(&channel, "message", |_| schedule())),
+            Err(_) => {
+                log::warn!("Failed to create the channel of the proxy");
+                None
+            },
+        };
+
+        // NOTE: the iteration is kept after the exit, it can't be dropped while
+        // running.
+        let scheduler = Scheduler {
+            window,
+            iteration,
+            scheduled: None,
+            timeout: None,
+            _proxy_listener: proxy_listener,
+        };
+        SCHEDULER.with(|cell| *cell.borrow_mut() = Some(scheduler));
+
+        let mut runtime = runtime.borrow_mut();
+        let RuntimeState { user, winit } = &mut *runtime;
+        user.as_mut().unwrap().new_events(winit, StartCause::Init);
+        schedule();
+    }
+
+    /// Get the proxy to wakeup the event loop.
+    pub fn proxy(&self) -> Arc<dyn CoreEventLoopProxy> {
+        self.state.winit.proxy()
+    }
+}
+
+impl EventLoopRequests for EventLoop {
+    fn new() -> Result<Self, EventLoopError> {
+        EventLoop::new()
+    }
+
+    fn run(self: Box<Self>, app: Box<dyn Application>) {
+        EventLoop::run(*self, app)
+    }
+
+    fn proxy(&self) -> Arc<dyn CoreEventLoopProxy> {
+        EventLoop::proxy(self)
+    }
+}
+
+impl<T: Application + 'static> HasDisplayHandle for EventLoop<T> {
+    fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+        self.state.winit.display_handle()
+    }
+}
+
+unsafe impl<T: Application + 'static> HasRawDisplayHandle05 for EventLoop<T> {
+    fn raw_display_handle(&self) -> raw_window_handle_05::RawDisplayHandle {
+        self.state.winit.raw_display_handle()
+    }
+}
+
+/// Runtime state passed around.
+pub struct RuntimeState<T: Application + 'static> {
+    /// The user state, owned by the loop once it runs.
+    pub(crate) user: Option<T>,
+
+    /// The state of the winit.
+    pub(crate) winit: WinitState<T>,
+}
+
+impl<T: Application + 'static> RuntimeState<T> {
+    /// Run one iteration of the event loop.
+    fn iteration(&mut self) {
+        start_iteration();
+
+        // The loop is gone after the exit.
+        if self.user.is_none() {
+            return;
+        }
+
+        self.dispatch_proxy();
+        while let Some(event) = next_event() {
+            self.dispatch_event(event);
+        }
+
+        let winit = &mut self.winit;
+        let user = self.user.as_mut().unwrap();
+
+        let timer_timeout = winit.fire_timers(user);
+
+        // The iterations run in the animation frames, which is the time to
+        // draw.
+        winit.deliver_redraws(user);
+        winit.resolve_queries(user);
+
+        user.about_to_wait(winit);
+
+        if winit.exit {
+            user.loop_exiting(winit);
+            self.user = None;
+            self.winit.windows.clear();
+            QUEUE.with(|queue| queue.borrow_mut().clear());
+            return;
+        }
+
+        // Don't wait for the events queued by the `about_to_wait`, or for the
+        // requested redraws.
+        let pending = QUEUE.with(|queue| !queue.borrow().is_empty())
+            || winit.proxy.is_pending()
+            || winit.windows.values().any(|window| window.created && window.redraw_requested);
+        if pending {
+            schedule();
+        } else if let Some(timeout) = timer_timeout {
+            schedule_timeout(timeout);
+        }
+    }
+
+    /// Deliver the requests made with the proxy.
+    fn dispatch_proxy(&mut self) {
+        let winit = &mut self.winit;
+        let user = self.user.as_mut().unwrap();
+
+        if winit.proxy.wakeup_pending.swap(false, Ordering::Relaxed) {
+            user.user_wakeup(winit);
+        }
+
+        if winit.proxy.exit_pending.swap(false, Ordering::Relaxed) && user.exit_requested(winit) {
+            winit.exit = true;
+        }
+    }
+
+    fn dispatch_event(&mut self, event: Event) {
+        let winit = &mut self.winit;
+        let user = self.user.as_mut().unwrap();
+
+        let window_id = match event {
+            Event::Created(window_id) => {
+                self.window_created(window_id);
+                return;
+            },
+            Event::ScaleFactorChanged => {
+                self.scale_factor_changed();
+                return;
+            },
+            Event::VisibilityChanged => {
+                let hidden = web_sys::window()
+                    .and_then(|window| window.document())
+                    .is_some_and(|document| document.hidden());
+
+                let window_ids: Vec<WindowId> = winit
+                    .windows
+                    .iter_mut()
+                    .filter(|(_, window)| window.created)
+                    .filter_map(|(window_id, window)| {
+                        (mem::replace(&mut window.occluded, hidden) != hidden).then_some(*window_id)
+                    })
+                    .collect();
+
+                for window_id in window_ids {
+                    user.occluded(winit, window_id, hidden);
+                }
+                return;
+            },
+            Event::Resized(window_id) | Event::Focused(window_id, _) => window_id,
+        };
+
+        // The events sent before the canvas is on the page.
+        let window = match winit.windows.get_mut(&window_id) {
+            Some(window) if window.created => window,
+            _ => return,
+        };
+
+        match event {
+            Event::Resized(_) => {
+                if window.update_size() {
+                    // The resized canvas is cleared.
+                    window.redraw_requested = true;
+                    let size = window.inner_size();
+                    user.resized(winit, window_id, size);
+                }
+            },
+            Event::Focused(_, focused) => {
+                if mem::replace(&mut window.has_focus, focused) != focused {
+                    user.focused(winit, window_id, focused);
+                }
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    /// Add the created canvas to the page and deliver its initial state.
+    fn window_created(&mut self, window_id: WindowId) {
+        let winit = &mut self.winit;
+        let user = self.user.as_mut().unwrap();
+
+        let window = match winit.windows.get_mut(&window_id) {
+            Some(window) => window,
+            None => return,
+        };
+
+        if window.show().is_err() {
+            log::warn!("Failed to add the canvas to the page");
+            return;
+        }
+
+        window.update_scale_factor();
+        window.update_size();
+        window.created = true;
+        let scale_factor = window.scale_factor;
+        let size = window.inner_size();
+
+        let startup = &mut winit.startup;
+        if startup.timings.first_configure.is_none() {
+            startup.timings.first_configure = Some(startup.started_at.elapsed());
+        }
+
+        user.created(winit, window_id);
+        user.scale_factor_changed(winit, window_id, scale_factor);
+        user.resized(winit, window_id, size);
+    }
+
+    /// Deliver the new scale factor to all the windows, the page has one.
+    fn scale_factor_changed(&mut self) {
+        let winit = &mut self.winit;
+        let user = self.user.as_mut().unwrap();
+
+        winit.watch_scale_factor();
+        winit.update_monitors(user);
+
+        let window_ids: Vec<WindowId> = winit.windows.keys().copied().collect();
+        for window_id in window_ids {
+            let window = match winit.windows.get_mut(&window_id) {
+                Some(window) if window.created => window,
+                _ => continue,
+            };
+
+            if !window.update_scale_factor() {
+                continue;
+            }
+
+            // The size in the CSS pixels stays the same, so the size in pixels
+            // changes along with the scale.
+            let resized = window.update_size();
+            let scale_factor = window.scale_factor;
+            let size = window.inner_size();
+            window.redraw_requested = true;
+
+            user.scale_factor_changed(winit, window_id, scale_factor);
+            if resized {
+                user.resized(winit, window_id, size);
+            }
+        }
+    }
+}
+
+/// The proxy of the web event loop.
+///
+/// The workers can't schedule the iteration on the main thread of the page,
+/// so they post to the [`BroadcastChannel`] the loop listens to.
+pub struct EventLoopProxy {
+    /// The name of the channel the loop listens to.
+    channel: String,
+
+    /// Whether the wakeup is requested and not delivered yet, the wakeups made
+    /// meanwhile are merged.
+    wakeup_pending: AtomicBool,
+
+    /// Same as the `wakeup_pending`, but for the
+    /// [`CoreEventLoopProxy::request_exit`].
+    exit_pending: AtomicBool,
+}
+
+impl EventLoopProxy {
+    fn new() -> Self {
+        // The channels are shared by all the pages of the origin.
+        let channel = format!("winit-proxy-{}", js_sys::Math::random());
+        Self {
+            channel,
+            wakeup_pending: AtomicBool::new(false),
+            exit_pending: AtomicBool::new(false),
+        }
+    }
+
+    /// Whether there're requests not delivered yet.
+    fn is_pending(&self) -> bool {
+        self.wakeup_pending.load(Ordering::Relaxed) || self.exit_pending.load(Ordering::Relaxed)
+    }
+
+    /// Schedule the iteration of the loop from any thread.
+    fn schedule(&self) {
+        if SCHEDULER.with(|scheduler| scheduler.borrow().is_some()) {
+            schedule();
+            return;
+        }
+
+        PROXY_CHANNEL.with(|cell| {
+            let mut cell = cell.borrow_mut();
+            if !matches!(&*cell, Some((name, _)) if *name == self.channel) {
+                match BroadcastChannel::new(&self.channel) {
+                    Ok(channel) => *cell = Some((self.channel.clone(), channel)),
+                    Err(_) => {
+                        log::warn!("Failed to create the channel of the proxy");
+                        return;
+                    },
+                }
+            }
+
+            if let Some((_, channel)) = cell.as_ref() {
+                let _ = channel.post_message(&JsValue::NULL);
+            }
+        });
+    }
+}
+
+impl CoreEventLoopProxy for EventLoopProxy {
+    fn wakeup(&self) {
+        if !self.wakeup_pending.swap(true, Ordering::Relaxed) {
+            self.schedule();
+        }
+    }
+
+    fn request_exit(&self) {
+        if !self.exit_pending.swap(true, Ordering::Relaxed) {
+            self.schedule();
+        }
+    }
+}
@@ -0,0 +1,49 @@
+//! The web backend.
+//!
+//! The structure follows the Wayland backend: the [`EventLoop`] owns the
+//! [`WinitState`], which implements the [`EventLoopHandle`] passed to the
+//! application. Each window is an HTML canvas added to the page.
+//!
+//! The browser can't be blocked, so the loop runs in the animation frames:
+//! the DOM events are queued and delivered on the next frame, along with the
+//! redraws.
+//!
+//! [`EventLoop`]: crate::event_loop::EventLoop
+//! [`WinitState`]: crate::state::WinitState
+//! [`EventLoopHandle`]: winit_core::event_loop::EventLoopHandle
+#![cfg(target_arch = "wasm32")]
+
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{Event, EventTarget};
+
+pub mod diagnostics;
+pub mod event_loop;
+pub mod monitor;
+pub mod state;
+pub mod window;
+
+/// The listener of the DOM events, removed once dropped.
+pub(crate) struct EventListener {
+    target: EventTarget,
+    kind: &'static str,
+    callback: Closure<dyn FnMut(Event)>,
+}
+
+impl EventListener {
+    pub(crate) fn new<F>(target: &EventTarget, kind: &'static str, callback: F) -> Self
+    where
+        F: FnMut(Event) + 'static,
+    {
+        let callback = Closure::<dyn FnMut(Event)>::new(callback);
+        let _ = target.add_event_listener_with_callback(kind, callback.as_ref().unchecked_ref());
+        Self { target: target.clone(), kind, callback }
+    }
+}
+
+impl Drop for EventListener {
+    fn drop(&mut self) {
+        let callback = self.callback.as_ref().unchecked_ref();
+        let _ = self.target.remove_event_listener_with_callback(self.kind, callback);
+    }
+}
@@ -0,0 +1,66 @@
+//! The screen of the page.
+
+use winit_core::dpi::{PhysicalPosition, PhysicalSize};
+use winit_core::monitor::{Monitor as CoreMonitor, MonitorId};
+
+/// The screen the page is shown on, the browsers don't reveal the others.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Monitor {
+    size: PhysicalSize<u32>,
+    scale_factor: f64,
+}
+
+impl Monitor {
+    /// The id of the only monitor.
+    pub(crate) const ID: MonitorId = MonitorId(0);
+
+    /// Query the screen of the `window`.
+    pub(crate) fn new(window: &web_sys::Window) -> Option<Self> {
+        let screen = window.screen().ok()?;
+        let scale_factor = window.device_pixel_ratio();
+
+        // The screen is measured in the CSS pixels.
+        let width = screen.width().ok()?.max(0) as f64 * scale_factor;
+        let height = screen.height().ok()?.max(0) as f64 * scale_factor;
+
+        Some(Self {
+            size: PhysicalSize::new(width.round() as u32, height.round() as u32),
+            scale_factor,
+        })
+    }
+
+    /// The monitors known to the page.
+    pub(crate) fn enumerate() -> Vec<Self> {
+        web_sys::window().and_then(|window| Self::new(&window)).into_iter().collect()
+    }
+}
+
+impl CoreMonitor for Monitor {
+    fn id(&self) -> MonitorId {
+        Self::ID
+    }
+
+    fn name(&self) -> Option<String> {
+        None
+    }
+
+    fn connection_name(&self) -> Option<String> {
+        None
+    }
+
+    fn size(&self) -> PhysicalSize<u32> {
+        self.size
+    }
+
+    fn position(&self) -> PhysicalPosition<i32> {
+        PhysicalPosition::new(0, 0)
+    }
+
+    fn refresh_rate_millihertz(&self) -> Option<u32> {
+        None
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+}
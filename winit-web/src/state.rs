@@ -0,0 +1,483 @@
+use std::collections::HashMap;
+use std::io;
+use std::mem;
+use std::sync::Arc;
+use std::time::Duration;
+
+use raw_window_handle::{DisplayHandle, HandleError, HasDisplayHandle};
+use raw_window_handle_05::HasRawDisplayHandle as HasRawDisplayHandle05;
+
+use web_time::Instant;
+
+use smallvec::SmallVec;
+
+use winit_core::application::Application;
+use winit_core::clipboard::Clipboard as CoreClipboard;
+use winit_core::dpi::{PhysicalPosition, Rounding, RoundingPolicy};
+use winit_core::event_loop::proxy::EventLoopProxy as CoreEventLoopProxy;
+use winit_core::event_loop::{
+    EventLoopHandle, Query, QueryResult, RequestId, StartupTimings, TimerId,
+};
+use winit_core::input::filter::{EventFilter, EventFilterId};
+use winit_core::input::pointer::MotionCompression;
+use winit_core::input::{DeviceId, DeviceInfo};
+use winit_core::monitor::{Monitor as CoreMonitor, MonitorId};
+use winit_core::window::{
+    CreateWindowError, GroupId, RoleAttributes, Surface as CoreSurface, Theme, Tombstones,
+    WindowError, WindowId,
+};
+
+use crate::event_loop::{self, Event, EventLoopProxy};
+use crate::monitor::Monitor;
+use crate::window::Window;
+use crate::EventListener;
+
+impl<T: Application + 'static> EventLoopHandle for WinitState<T> {
+    fn proxy(&self) -> Arc<dyn CoreEventLoopProxy> {
+        self.proxy.clone()
+    }
+
+    fn create_window(&mut self, attributes: RoleAttributes) -> Result<(), CreateWindowError> {
+        let attributes = winit_core::env::overrides().apply(attributes);
+        let lints = attributes.validate().map_err(CreateWindowError::InvalidAttributes)?;
+        for lint in lints {
+            log::warn!("Suspicious window attributes: {lint:?}");
+        }
+
+        let attributes = match attributes {
+            RoleAttributes::Toplevel(attributes) => attributes,
+            // TODO: popups and subsurfaces.
+            RoleAttributes::Popup(_) | RoleAttributes::Subsurface(_) => {
+                return Err(CreateWindowError::Unsupported)
+            },
+        };
+
+        // The zero id is reserved by the raw window handle.
+        self.next_window_id += 1;
+        let window_id = WindowId(self.next_window_id as u128);
+
+        let window = Window::new(window_id, attributes, self.rounding_policy)
+            .map_err(|_| CreateWindowError::Unsupported)?;
+        self.tombstones.revive(window_id);
+        self.windows.insert(window_id, window);
+
+        // The canvas is added once the user is done with the current callback.
+        event_loop::queue_event(Event::Created(window_id));
+        event_loop::schedule();
+        Ok(())
+    }
+
+    fn num_windows(&self) -> usize {
+        self.windows.len()
+    }
+
+    fn get_window(&self, window_id: WindowId) -> Option<&dyn CoreSurface> {
+        self.tombstones.debug_assert_alive(window_id);
+
+        let window = self.windows.get(&window_id)?;
+        if !window.created {
+            return None;
+        } else {
+            Some(window as &dyn CoreSurface)
+        }
+    }
+
+    fn get_window_mut(&mut self, window_id: WindowId) -> Option<&mut dyn CoreSurface> {
+        self.tombstones.debug_assert_alive(window_id);
+
+        let window = self.windows.get_mut(&window_id)?;
+        if !window.created {
+            return None;
+        } else {
+            Some(window as &mut dyn CoreSurface)
+        }
+    }
+
+    fn try_get_window(&self, window_id: WindowId) -> Result<&dyn CoreSurface, WindowError> {
+        match self.windows.get(&window_id) {
+            Some(window) if window.created => Ok(window as &dyn CoreSurface),
+            Some(_) => Err(WindowError::NotConfigured),
+            None => Err(self.missing_window_error(window_id)),
+        }
+    }
+
+    fn try_get_window_mut(
+        &mut self,
+        window_id: WindowId,
+    ) -> Result<&mut dyn CoreSurface, WindowError> {
+        let error = self.missing_window_error(window_id);
+        match self.windows.get_mut(&window_id) {
+            Some(window) if window.created => Ok(window as &mut dyn CoreSurface),
+            Some(_) => Err(WindowError::NotConfigured),
+            None => Err(error),
+        }
+    }
+
+    fn set_timer(&mut self, interval: Duration, timer_id: TimerId) {
+        // The timers are checked by the event loop, which sets the timeout for
+        // the closest one.
+        self.timers.insert(timer_id, (Instant::now() + interval, interval));
+        event_loop::schedule();
+    }
+
+    fn cancel_timer(&mut self, timer_id: TimerId) {
+        self.timers.remove(&timer_id);
+    }
+
+    fn request(&mut self, query: Query) -> RequestId {
+        let request_id = RequestId(self.next_request_id);
+        self.next_request_id += 1;
+
+        // The answer is delivered once the user is done with the current
+        // callback, like on the other backends.
+        self.pending_queries.push((request_id, query));
+        event_loop::schedule();
+
+        request_id
+    }
+
+    fn clipboard(&mut self) -> Option<&mut dyn CoreClipboard> {
+        // TODO: the clipboard.
+        None
+    }
+
+    fn set_motion_compression(&mut self, compression: MotionCompression) {
+        self.motion_compression = compression;
+    }
+
+    fn push_event_filter(&mut self, filter: Box<dyn EventFilter>) -> EventFilterId {
+        // TODO: the input isn't delivered yet, the filters are kept until it is.
+        let filter_id = EventFilterId(self.next_filter);
+        self.next_filter += 1;
+        self.event_filters.push((filter_id, filter));
+        filter_id
+    }
+
+    fn remove_event_filter(&mut self, filter_id: EventFilterId) {
+        self.event_filters.retain(|(id, _)| *id != filter_id);
+    }
+
+    fn idle_since(&self) -> Option<Duration> {
+        // The browsers only reveal the idleness behind the permission prompt.
+        None
+    }
+
+    fn set_idle_threshold(&mut self, threshold: Option<Duration>) {
+        let _ = threshold;
+    }
+
+    fn startup_timings(&self) -> StartupTimings {
+        self.startup.timings
+    }
+
+    fn rounding_policy(&self) -> RoundingPolicy {
+        self.rounding_policy
+    }
+
+    fn set_rounding_policy(&mut self, policy: RoundingPolicy) {
+        self.rounding_policy = policy;
+        for window in self.windows.values_mut() {
+            window.rounding_policy = policy;
+        }
+    }
+
+    fn coalesced_pointer_positions(&self) -> &[PhysicalPosition<f64>] {
+        &[]
+    }
+
+    fn dump_state(&self, writer: &mut dyn io::Write) -> io::Result<()> {
+        let monitors: Vec<_> = self
+            .monitors
+            .iter()
+            .map(|monitor| {
+                let size = monitor.size();
+                serde_json::json!({
+                    "id": monitor.id().0,
+                    "size": (size.width, size.height),
+                    "scale_factor": monitor.scale_factor(),
+                })
+            })
+            .collect();
+
+        let windows: Vec<_> = self.windows.values().map(|window| window.dump()).collect();
+
+        let state = serde_json::json!({
+            "backend": "web",
+            "monitors": monitors,
+            "windows": windows,
+            "timers": self.timers.len(),
+            "motion_compression": format!("{:?}", self.motion_compression),
+        });
+
+        serde_json::to_writer_pretty(writer, &state).map_err(io::Error::from)
+    }
+
+    fn exit(&mut self) {
+        self.exit = true;
+        event_loop::schedule();
+    }
+
+    fn group_members(&self, group: GroupId) -> SmallVec<[WindowId; 4]> {
+        let mut members: SmallVec<[(Instant, WindowId); 4]> = self
+            .windows
+            .iter()
+            .filter_map(|(window_id, window)| match window.group {
+                Some((window_group, joined_at)) if window_group == group => {
+                    Some((joined_at, *window_id))
+                },
+                _ => None,
+            })
+            .collect();
+        members.sort_unstable_by_key(|&(joined_at, window_id)| (joined_at, window_id.0));
+        members.into_iter().map(|(_, window_id)| window_id).collect()
+    }
+
+    fn get_monitor(&self, monitor_id: MonitorId) -> Option<&dyn CoreMonitor> {
+        self.monitors
+            .iter()
+            .find(|monitor| monitor.id() == monitor_id)
+            .map(|monitor| monitor as &dyn CoreMonitor)
+    }
+
+    fn monitors(&self) -> SmallVec<[&dyn CoreMonitor; 4]> {
+        self.monitors.iter().map(|monitor| monitor as &dyn CoreMonitor).collect()
+    }
+
+    fn monitor_generation(&self) -> u64 {
+        self.monitor_generation
+    }
+
+    fn device_info(&self, device_id: DeviceId) -> Option<DeviceInfo> {
+        // TODO: the input devices.
+        let _ = device_id;
+        None
+    }
+}
+
+impl<T: Application + 'static> HasDisplayHandle for WinitState<T> {
+    fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+        Ok(DisplayHandle::web())
+    }
+}
+
+unsafe impl<T: Application + 'static> HasRawDisplayHandle05 for WinitState<T> {
+    fn raw_display_handle(&self) -> raw_window_handle_05::RawDisplayHandle {
+        let display_handle = raw_window_handle_05::WebDisplayHandle::empty();
+        raw_window_handle_05::RawDisplayHandle::Web(display_handle)
+    }
+}
+
+/// Winit's web state.
+pub struct WinitState<T: Application + 'static> {
+    pub windows: HashMap<WindowId, Window>,
+
+    /// The recently destroyed windows.
+    pub(crate) tombstones: Tombstones,
+
+    /// The id of the last created window.
+    next_window_id: u64,
+
+    pub monitors: Vec<Monitor>,
+
+    /// The counter of the changes to the monitors.
+    pub(crate) monitor_generation: u64,
+
+    /// The timers registered by the user, along with their deadlines and
+    /// intervals.
+    pub(crate) timers: HashMap<TimerId, (Instant, Duration)>,
+
+    /// The id of the next query issued by the user.
+    next_request_id: u64,
+
+    /// The queries waiting to be answered.
+    pub(crate) pending_queries: Vec<(RequestId, Query)>,
+
+    /// How the pointer motion is delivered.
+    pub(crate) motion_compression: MotionCompression,
+
+    /// The rounding of the window sizes.
+    pub(crate) rounding_policy: RoundingPolicy,
+
+    /// The startup milestones reached so far.
+    pub(crate) startup: Startup,
+
+    /// The filters the input events pass through.
+    event_filters: Vec<(EventFilterId, Box<dyn EventFilter>)>,
+
+    /// The id of the next filter.
+    next_filter: u64,
+
+    /// The listener of the change of the current scale factor, replaced
+    /// along with the scale.
+    scale_listener: Option<EventListener>,
+
+    /// The listener of the visibility of the page.
+    _visibility_listener: Option<EventListener>,
+
+    pub proxy: Arc<EventLoopProxy>,
+
+    pub exit: bool,
+}
+
+impl<T: Application + 'static> WinitState<T> {
+    pub(crate) fn new(proxy: EventLoopProxy) -> Self {
+        let visibility_listener =
+            web_sys::window().and_then(|window| window.document()).map(|document| {
+                EventListener::new(&document, "visibilitychange", |_| {
+                    event_loop::queue_event(Event::VisibilityChanged);
+                    event_loop::reschedule();
+                })
+            });
+
+        let mut state = Self {
+            windows: Default::default(),
+            tombstones: Default::default(),
+            next_window_id: 0,
+            monitors: Monitor::enumerate(),
+            monitor_generation: 0,
+            timers: Default::default(),
+            next_request_id: 0,
+            pending_queries: Default::default(),
+            motion_compression: Default::default(),
+            rounding_policy: RoundingPolicy::uniform(Rounding::Round),
+            startup: Startup::default(),
+            event_filters: Default::default(),
+            next_filter: 0,
+            scale_listener: None,
+            _visibility_listener: visibility_listener,
+            proxy: Arc::new(proxy),
+            exit: Default::default(),
+        };
+
+        state.watch_scale_factor();
+        state
+    }
+
+    /// Why the window with the `window_id` is not in the state.
+    fn missing_window_error(&self, window_id: WindowId) -> WindowError {
+        if self.tombstones.is_destroyed(window_id) {
+            WindowError::Destroyed
+        } else {
+            WindowError::Unknown
+        }
+    }
+
+    /// Listen for the change of the current scale factor.
+    ///
+    /// There's no event for the scale factor, so the media query matching
+    /// the current one is watched instead, and replaced once it changes.
+    pub(crate) fn watch_scale_factor(&mut self) {
+        self.scale_listener = web_sys::window().and_then(|window| {
+            let query = format!("(resolution: {}dppx)", window.device_pixel_ratio());
+            let query = window.match_media(&query).ok()??;
+            Some(EventListener::new(&query, "change", |_| {
+                event_loop::queue_event(Event::ScaleFactorChanged);
+                event_loop::schedule();
+            }))
+        });
+    }
+
+    /// Fire the timers past their deadlines, returning the time until the
+    /// next one.
+    pub(crate) fn fire_timers(&mut self, user: &mut T) -> Option<Duration> {
+        let now = Instant::now();
+        let expired: Vec<TimerId> = self
+            .timers
+            .iter()
+            .filter(|(_, (deadline, _))| *deadline <= now)
+            .map(|(timer_id, _)| *timer_id)
+            .collect();
+
+        for timer_id in expired {
+            // The timer could be cancelled by the previous callback.
+            match self.timers.get_mut(&timer_id) {
+                Some((deadline, interval)) => *deadline = now + *interval,
+                None => continue,
+            }
+
+            user.timer_fired(self, timer_id);
+        }
+
+        let now = Instant::now();
+        self.timers.values().map(|(deadline, _)| deadline.saturating_duration_since(now)).min()
+    }
+
+    /// Deliver the redraws requested by the user.
+    pub(crate) fn deliver_redraws(&mut self, user: &mut T) {
+        let window_ids: Vec<WindowId> = self
+            .windows
+            .iter_mut()
+            .filter_map(|(window_id, window)| {
+                (window.created && mem::take(&mut window.redraw_requested)).then_some(*window_id)
+            })
+            .collect();
+
+        if window_ids.is_empty() {
+            return;
+        }
+
+        for window_id in window_ids {
+            user.redraw_requested(self, window_id);
+        }
+
+        // The frame is shown once the animation frame callback returns.
+        let startup = &mut self.startup;
+        if startup.timings.first_frame_presented.is_none() {
+            startup.timings.first_frame_presented = Some(startup.started_at.elapsed());
+        }
+    }
+
+    /// Answer the queries issued by the user.
+    pub(crate) fn resolve_queries(&mut self, user: &mut T) {
+        for (request_id, query) in mem::take(&mut self.pending_queries) {
+            let result = self.resolve_query(&query);
+            user.query_complete(self, request_id, result);
+        }
+    }
+
+    fn resolve_query(&self, query: &Query) -> QueryResult {
+        match query {
+            Query::MonitorDescription(_) => QueryResult::MonitorDescription(None),
+            Query::Theme => QueryResult::Theme(system_theme()),
+            _ => QueryResult::Unsupported,
+        }
+    }
+
+    /// Query the screen again, notifying the user when it changed.
+    pub(crate) fn update_monitors(&mut self, user: &mut T) {
+        let monitors = Monitor::enumerate();
+        if monitors == self.monitors {
+            return;
+        }
+
+        self.monitors = monitors;
+        self.monitor_generation += 1;
+        user.monitor_changed(self, Monitor::ID);
+    }
+}
+
+/// The theme preferred by the user for the pages.
+fn system_theme() -> Option<Theme> {
+    let window = web_sys::window()?;
+    let query = window.match_media("(prefers-color-scheme: dark)").ok()??;
+    if query.matches() {
+        Some(Theme::Dark)
+    } else {
+        Some(Theme::Light)
+    }
+}
+
+/// The tracking of the startup milestones.
+#[derive(Debug)]
+pub(crate) struct Startup {
+    /// The time the event loop creation started at.
+    pub(crate) started_at: Instant,
+
+    pub(crate) timings: StartupTimings,
+}
+
+impl Default for Startup {
+    fn default() -> Self {
+        Self { started_at: Instant::now(), timings: Default::default() }
+    }
+}
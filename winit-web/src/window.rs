@@ -0,0 +1,476 @@
+//! The toplevel windows, which are the canvases on the page.
+
+use std::ffi::c_void;
+use std::mem;
+use std::ptr::NonNull;
+
+use raw_window_handle::{
+    HandleError, HasWindowHandle, RawWindowHandle, WebCanvasWindowHandle, WindowHandle,
+};
+use raw_window_handle_05::HasRawWindowHandle as HasRawWindowHandle05;
+
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{HtmlCanvasElement, ResizeObserver};
+use web_time::Instant;
+
+use winit_core::cursor::CursorIcon;
+use winit_core::dpi::{
+    LogicalSize, PhysicalPosition, PhysicalSize, Position, Rect, RoundingPolicy, Size,
+};
+use winit_core::input::DeviceId;
+use winit_core::monitor::{Monitor as CoreMonitor, MonitorId};
+use winit_core::window::{
+    CloseBehavior, GroupId, RegionKind, Surface as CoreSurface, Theme, TiledEdges, Title,
+    TitlePolicy, Toplevel, ToplevelAttributes, WindowId,
+};
+
+use crate::event_loop::{self, Event};
+use crate::monitor::Monitor;
+use crate::EventListener;
+
+pub struct Window {
+    canvas: HtmlCanvasElement,
+    id: WindowId,
+
+    /// Whether the user got the [`ApplicationWindow::created`].
+    ///
+    /// [`ApplicationWindow::created`]: winit_core::application::ApplicationWindow::created
+    pub(crate) created: bool,
+
+    title: String,
+    title_policy: TitlePolicy,
+    theme: Option<Theme>,
+
+    /// The size of the canvas buffer.
+    pub(crate) size: PhysicalSize<u32>,
+
+    pub(crate) scale_factor: f64,
+    pub(crate) rounding_policy: RoundingPolicy,
+    pub(crate) close_behavior: CloseBehavior,
+
+    /// The group the window belongs to along with the time it joined it.
+    pub(crate) group: Option<(GroupId, Instant)>,
+
+    pub(crate) has_focus: bool,
+    pub(crate) occluded: bool,
+
+    /// Whether the user asked for the redraw.
+    pub(crate) redraw_requested: bool,
+
+    /// The observer of the size of the canvas, along with its callback.
+    resize_observer: ResizeObserver,
+    _resize_callback: Closure<dyn FnMut()>,
+
+    /// The focus listeners.
+    _listeners: [EventListener; 2],
+}
+
+impl Window {
+    pub(crate) fn new(
+        id: WindowId,
+        attributes: ToplevelAttributes,
+        rounding_policy: RoundingPolicy,
+    ) -> Result<Self, ()> {
+        let web_window = web_sys::window().ok_or(())?;
+        let document = web_window.document().ok_or(())?;
+        let canvas: HtmlCanvasElement =
+            document.create_element("canvas").map_err(|_| ())?.unchecked_into();
+
+        // Take the keyboard focus like the other elements.
+        canvas.set_tab_index(0);
+
+        let scale_factor =
+            winit_core::env::overrides().window_scale_factor(web_window.device_pixel_ratio());
+        let screen = Monitor::new(&web_window);
+        let screen = screen.as_ref().map(|screen| screen as &dyn CoreMonitor);
+        let size = attributes.initial_inner_size(screen, scale_factor);
+        let style = canvas.style();
+        let _ = style.set_property("width", &format!("{}px", size.width));
+        let _ = style.set_property("height", &format!("{}px", size.height));
+
+        if let Some(position) = attributes.position() {
+            let position = position.to_logical::<f64>(scale_factor);
+            let _ = style.set_property("position", "absolute");
+            let _ = style.set_property("left", &format!("{}px", position.x));
+            let _ = style.set_property("top", &format!("{}px", position.y));
+        }
+
+        let resize_callback = Closure::<dyn FnMut()>::new(move || {
+            event_loop::queue_event(Event::Resized(id));
+            event_loop::schedule();
+        });
+        let resize_observer =
+            ResizeObserver::new(resize_callback.as_ref().unchecked_ref()).map_err(|_| ())?;
+        resize_observer.observe(&canvas);
+
+        let listeners = [
+            EventListener::new(&canvas, "focus", move |_| {
+                event_loop::queue_event(Event::Focused(id, true));
+                event_loop::schedule();
+            }),
+            EventListener::new(&canvas, "blur", move |_| {
+                event_loop::queue_event(Event::Focused(id, false));
+                event_loop::schedule();
+            }),
+        ];
+
+        let title_policy = attributes.title_policy();
+        let title = title_policy.apply(attributes.title());
+
+        // The page has its own title, the window one is for the accessibility.
+        let _ = canvas.set_attribute("aria-label", title.as_str());
+
+        let mut window = Self {
+            canvas,
+            id,
+            created: false,
+            title: title.into(),
+            title_policy,
+            theme: attributes.theme(),
+            size: PhysicalSize::new(0, 0),
+            scale_factor,
+            rounding_policy,
+            close_behavior: attributes.close_behavior(),
+            group: None,
+            has_focus: false,
+            occluded: false,
+            redraw_requested: true,
+            resize_observer,
+            _resize_callback: resize_callback,
+            _listeners: listeners,
+        };
+
+        window.set_min_inner_size(attributes.min_inner_size());
+        window.set_max_inner_size(attributes.max_inner_size());
+        window.apply_theme();
+
+        Ok(window)
+    }
+
+    #[inline]
+    pub fn canvas(&self) -> &HtmlCanvasElement {
+        &self.canvas
+    }
+
+    /// Add the canvas to the page.
+    pub(crate) fn show(&mut self) -> Result<(), ()> {
+        let body = web_sys::window().and_then(|window| window.document()?.body()).ok_or(())?;
+        body.append_child(&self.canvas).map_err(|_| ())?;
+        Ok(())
+    }
+
+    /// Read the size of the canvas on the page, resizing its buffer to match,
+    /// and returning whether it changed.
+    pub(crate) fn update_size(&mut self) -> bool {
+        // The bounding rectangle isn't rounded, unlike the client size.
+        let rect = self.canvas.get_bounding_client_rect();
+        let size = LogicalSize::new(rect.width(), rect.height());
+        let size = self.rounding_policy.to_physical_size(size, self.scale_factor);
+
+        if mem::replace(&mut self.size, size) == size {
+            return false;
+        }
+
+        self.canvas.set_width(size.width);
+        self.canvas.set_height(size.height);
+        true
+    }
+
+    /// Read the scale factor, returning whether it changed.
+    pub(crate) fn update_scale_factor(&mut self) -> bool {
+        let scale_factor = match web_sys::window() {
+            Some(window) => window.device_pixel_ratio(),
+            None => return false,
+        };
+        let scale_factor = winit_core::env::overrides().window_scale_factor(scale_factor);
+
+        mem::replace(&mut self.scale_factor, scale_factor) != scale_factor
+    }
+
+    pub fn set_min_inner_size(&mut self, size: Option<Size>) {
+        self.set_size_limit("min-width", "min-height", size);
+    }
+
+    pub fn set_max_inner_size(&mut self, size: Option<Size>) {
+        self.set_size_limit("max-width", "max-height", size);
+    }
+
+    fn set_size_limit(&self, width: &str, height: &str, size: Option<Size>) {
+        let style = self.canvas.style();
+        match size {
+            Some(size) => {
+                let size: LogicalSize<f64> = size.to_logical(self.scale_factor);
+                let _ = style.set_property(width, &format!("{}px", size.width));
+                let _ = style.set_property(height, &format!("{}px", size.height));
+            },
+            None => {
+                let _ = style.remove_property(width);
+                let _ = style.remove_property(height);
+            },
+        }
+    }
+
+    /// Apply the theme to the canvas, following the page without one.
+    fn apply_theme(&self) {
+        let style = self.canvas.style();
+        let _ = match self.theme {
+            Some(Theme::Dark) => style.set_property("color-scheme", "dark"),
+            Some(Theme::Light) => style.set_property("color-scheme", "light"),
+            None => style.remove_property("color-scheme").map(drop),
+        };
+    }
+
+    /// The state of the window for the [`EventLoopHandle::dump_state`],
+    /// without the title.
+    ///
+    /// [`EventLoopHandle::dump_state`]: winit_core::event_loop::EventLoopHandle::dump_state
+    pub(crate) fn dump(&self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id.0,
+            "created": self.created,
+            "size": (self.size.width, self.size.height),
+            "scale_factor": self.scale_factor,
+            "fullscreen": self.is_fullscreen(),
+            "occluded": self.occluded,
+            "focused": self.has_focus,
+            "group": self.group.map(|(group, _)| group.0),
+        })
+    }
+}
+
+impl CoreSurface for Window {
+    fn id(&self) -> WindowId {
+        self.id
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    fn request_redraw(&mut self) {
+        // The redraw is delivered on the next animation frame.
+        self.redraw_requested = true;
+        event_loop::schedule();
+    }
+
+    fn inner_size(&self) -> PhysicalSize<u32> {
+        self.size
+    }
+
+    fn surface_geometry(&self) -> Rect<u32> {
+        Rect::new(PhysicalPosition::new(0, 0), self.size)
+    }
+
+    fn current_monitor(&self) -> Option<MonitorId> {
+        Some(Monitor::ID)
+    }
+
+    fn primary_monitor(&self) -> Option<MonitorId> {
+        Some(Monitor::ID)
+    }
+
+    fn set_opaque_region(&mut self, region: Option<&[Rect<u32>]>) {
+        // The browser composes the page without the hint.
+        let _ = region;
+    }
+
+    fn set_input_region(&mut self, region: Option<&[Rect<u32>]>) {
+        // The page gets the input through the whole canvas or nothing, the
+        // partial regions need the hit testing by the application.
+        let pass_through = region.is_some_and(|rects| {
+            rects.iter().all(|rect| rect.size.width == 0 || rect.size.height == 0)
+        });
+
+        let style = self.canvas.style();
+        let _ = if pass_through {
+            style.set_property("pointer-events", "none")
+        } else {
+            style.remove_property("pointer-events").map(drop)
+        };
+    }
+
+    fn is_throttled(&self) -> bool {
+        // The animation frames are paused for the hidden pages.
+        self.occluded
+    }
+
+    fn set_cursor(&mut self, cursor: CursorIcon, seat: Option<DeviceId>) {
+        // The browser merges all the mice into one pointer.
+        let _ = seat;
+        let _ = self.canvas.style().set_property("cursor", cursor.name());
+    }
+
+    fn as_toplevel(&self) -> Option<&dyn Toplevel> {
+        Some(self)
+    }
+
+    fn as_toplevel_mut(&mut self) -> Option<&mut dyn Toplevel> {
+        Some(self)
+    }
+}
+
+impl Toplevel for Window {
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    fn set_title(&mut self, title: Title) -> Title {
+        let title = self.title_policy.apply(title.as_str());
+        if title.is_truncated() {
+            log::debug!("Window title truncated to {} bytes", self.title_policy.max_len);
+        }
+
+        let _ = self.canvas.set_attribute("aria-label", title.as_str());
+        self.title = title.as_str().to_owned();
+        title
+    }
+
+    fn title_policy(&self) -> TitlePolicy {
+        self.title_policy
+    }
+
+    fn theme(&self) -> Option<Theme> {
+        self.theme
+    }
+
+    fn set_theme(&mut self, theme: Option<Theme>) {
+        self.theme = theme;
+        self.apply_theme();
+    }
+
+    fn is_decorated(&self) -> bool {
+        false
+    }
+
+    fn set_decorations(&mut self, decorate: bool) {
+        // The canvas has no decorations, the page draws its own.
+        let _ = decorate;
+    }
+
+    fn request_inner_size(&mut self, size: Size) -> Option<PhysicalSize<u32>> {
+        let size: LogicalSize<f64> = size.to_logical(self.scale_factor);
+        let style = self.canvas.style();
+        let _ = style.set_property("width", &format!("{}px", size.width));
+        let _ = style.set_property("height", &format!("{}px", size.height));
+
+        // The change is delivered by the resize observer, after the layout.
+        None
+    }
+
+    fn set_minimized(&mut self, minimize: bool) {
+        let _ = minimize;
+    }
+
+    fn set_maximized(&mut self, maximized: bool) {
+        let _ = maximized;
+    }
+
+    fn is_maximized(&self) -> bool {
+        false
+    }
+
+    fn request_activation(&mut self) {
+        // The focus arrives with the `focus` event.
+        let _ = self.canvas.focus();
+    }
+
+    fn is_fullscreen(&self) -> bool {
+        let document = match web_sys::window().and_then(|window| window.document()) {
+            Some(document) => document,
+            None => return false,
+        };
+
+        let canvas: &JsValue = self.canvas.as_ref();
+        document.fullscreen_element().is_some_and(|element| {
+            let element: &JsValue = element.as_ref();
+            element == canvas
+        })
+    }
+
+    fn close_behavior(&self) -> CloseBehavior {
+        self.close_behavior
+    }
+
+    fn set_close_behavior(&mut self, close_behavior: CloseBehavior) {
+        self.close_behavior = close_behavior;
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        let style = self.canvas.style();
+        if visible {
+            let _ = style.remove_property("display");
+        } else {
+            let _ = style.set_property("display", "none");
+        }
+    }
+
+    fn hit_test(&self, position: Position) -> RegionKind {
+        let position: PhysicalPosition<f64> = position.to_physical(self.scale_factor);
+        let inside = position.x >= 0.
+            && position.y >= 0.
+            && position.x < self.size.width as f64
+            && position.y < self.size.height as f64;
+
+        if inside {
+            RegionKind::Content
+        } else {
+            RegionKind::Outside
+        }
+    }
+
+    fn tiled_edges(&self) -> TiledEdges {
+        TiledEdges::empty()
+    }
+
+    fn recommended_bounds(&self) -> Option<LogicalSize<u32>> {
+        // The viewport of the page, the canvas can't get bigger without the
+        // scrolling.
+        let window = web_sys::window()?;
+        let width = window.inner_width().ok()?.as_f64()?;
+        let height = window.inner_height().ok()?.as_f64()?;
+        Some(LogicalSize::new(width as u32, height as u32))
+    }
+
+    fn group(&self) -> Option<GroupId> {
+        self.group.map(|(group, _)| group)
+    }
+
+    fn set_group(&mut self, group: Option<GroupId>) {
+        if self.group() == group {
+            return;
+        }
+
+        // The canvases are ordered by the page, the group is only tracked for
+        // the `group_members`.
+        self.group = group.map(|group| (group, Instant::now()));
+    }
+}
+
+impl Drop for Window {
+    fn drop(&mut self) {
+        self.resize_observer.disconnect();
+        self.canvas.remove();
+    }
+}
+
+impl HasWindowHandle for Window {
+    fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+        let canvas: &JsValue = self.canvas.as_ref();
+        let handle = WebCanvasWindowHandle::new(NonNull::from(canvas).cast::<c_void>());
+
+        unsafe { Ok(WindowHandle::borrow_raw(RawWindowHandle::WebCanvas(handle))) }
+    }
+}
+
+unsafe impl HasRawWindowHandle05 for Window {
+    fn raw_window_handle(&self) -> raw_window_handle_05::RawWindowHandle {
+        // The canvas is found by the `data-raw-handle` attribute.
+        let id = self.id.0 as u32;
+        let _ = self.canvas.set_attribute("data-raw-handle", &id.to_string());
+
+        let mut window_handle = raw_window_handle_05::WebWindowHandle::empty();
+        window_handle.id = id;
+        raw_window_handle_05::RawWindowHandle::Web(window_handle)
+    }
+}
@@ -0,0 +1,56 @@
+//! The diagnostics of the Win32 backend.
+
+use std::mem;
+use std::ptr;
+
+use windows_sys::Win32::System::StationsAndDesktops::{
+    GetProcessWindowStation, GetUserObjectInformationW, UOI_FLAGS, USEROBJECTFLAGS,
+};
+
+use winit_core::diagnostics::{BackendProbe, BackendReport};
+
+/// The flag of the window station visible to the user.
+const WSF_VISIBLE: u32 = 0x0001;
+
+/// The probe of the Win32 backend, see [`winit_core::diagnostics::probe`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Win32Probe;
+
+impl BackendProbe for Win32Probe {
+    fn name(&self) -> &'static str {
+        "win32"
+    }
+
+    fn version(&self) -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    fn probe(&self) -> BackendReport {
+        let mut report = BackendReport::new(self.name());
+
+        let station = unsafe { GetProcessWindowStation() };
+        if station == 0 {
+            report.missing.push(String::from("the process has no window station"));
+            return report;
+        }
+
+        report.connectable = true;
+
+        // The services run in the window station the user can't see.
+        let mut flags: USEROBJECTFLAGS = unsafe { mem::zeroed() };
+        let ok = unsafe {
+            GetUserObjectInformationW(
+                station,
+                UOI_FLAGS,
+                &mut flags as *mut _ as *mut _,
+                mem::size_of::<USEROBJECTFLAGS>() as u32,
+                ptr::null_mut(),
+            )
+        };
+        if ok != 0 && flags.dwFlags & WSF_VISIBLE == 0 {
+            report.missing.push(String::from("the window station is not interactive"));
+        }
+
+        report
+    }
+}
@@ -0,0 +1,769 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use std::{mem, ptr};
+
+use raw_window_handle::{DisplayHandle, HandleError, HasDisplayHandle};
+use raw_window_handle_05::HasRawDisplayHandle as HasRawDisplayHandle05;
+
+use windows_sys::Win32::Foundation::{HWND, LPARAM, LRESULT, POINT, RECT, WPARAM};
+use windows_sys::Win32::Graphics::Gdi::{ScreenToClient, ValidateRect};
+use windows_sys::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows_sys::Win32::UI::HiDpi::{
+    GetDpiForWindow, SetProcessDpiAwarenessContext, DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2,
+};
+use windows_sys::Win32::UI::Input::KeyboardAndMouse::{
+    ReleaseCapture, SetCapture, TrackMouseEvent, TME_LEAVE, TRACKMOUSEEVENT,
+};
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, GetWindowLongW,
+    MsgWaitForMultipleObjectsEx, PeekMessageW, PostMessageW, RegisterClassExW, SetCursor,
+    SetWindowPos, TranslateMessage, CS_HREDRAW, CS_VREDRAW, GWL_EXSTYLE, GWL_STYLE, HTCLIENT,
+    HTTRANSPARENT, HWND_MESSAGE, INFINITE, MINMAXINFO, MSG, MWMO_INPUTAVAILABLE, PM_REMOVE,
+    QS_ALLINPUT, SIZE_MINIMIZED, SWP_NOACTIVATE, SWP_NOZORDER, WHEEL_DELTA, WM_CLOSE,
+    WM_DISPLAYCHANGE, WM_DPICHANGED, WM_GETMINMAXINFO, WM_KILLFOCUS, WM_LBUTTONDOWN, WM_LBUTTONUP,
+    WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEHWHEEL, WM_MOUSELEAVE, WM_MOUSEMOVE, WM_MOUSEWHEEL,
+    WM_NCHITTEST, WM_PAINT, WM_RBUTTONDOWN, WM_RBUTTONUP, WM_SETCURSOR, WM_SETFOCUS,
+    WM_SETTINGCHANGE, WM_SIZE, WM_TIMER, WM_USER, WM_XBUTTONDOWN, WM_XBUTTONUP, WNDCLASSEXW,
+};
+
+use winit_core::application::{Application, StartCause};
+use winit_core::dpi::PhysicalPosition;
+use winit_core::event_loop::proxy::EventLoopProxy as CoreEventLoopProxy;
+use winit_core::event_loop::{EventLoopError, EventLoopHandle, EventLoopRequests};
+use winit_core::input::filter::InputEvent;
+use winit_core::input::pointer::{
+    MotionCompression, PointerButton, PointerButtonEvent, PointerScroll, ScrollDelta,
+};
+use winit_core::input::ElementState;
+use winit_core::window::{CloseBehavior, DecorationMode, Surface as CoreSurface, WindowId};
+
+use crate::input::{self, POINTER_DEVICE_ID};
+use crate::monitor;
+use crate::state::WinitState;
+use crate::window::{self as win32_window, WINDOW_CLASS};
+
+/// The message posted by the [`CoreEventLoopProxy::wakeup`].
+const WM_WAKEUP: u32 = WM_USER;
+
+/// The message posted by the [`CoreEventLoopProxy::request_exit`].
+const WM_EXIT_REQUESTED: u32 = WM_USER + 1;
+
+/// The `MK_*` flags of the mouse buttons in the `wparam` of the mouse messages.
+const MK_BUTTONS: usize = 0x0001 | 0x0002 | 0x0010 | 0x0020 | 0x0040;
+
+/// The message sent by the window procedure, queued to be delivered to the
+/// application.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Event {
+    Created(WindowId),
+    Redraw(WindowId),
+    CloseRequested(WindowId),
+    /// The size changed, along with whether the window got minimized.
+    Resized(WindowId, bool),
+    ScaleFactorChanged(WindowId, f64),
+    Focused(WindowId, bool),
+    PointerMoved(WindowId, PhysicalPosition<f64>),
+    PointerLeft(WindowId),
+    PointerButton(WindowId, PointerButton, ElementState),
+    /// The wheel scroll in the 120ths of the click.
+    Wheel(WindowId, PhysicalPosition<i32>),
+    Timer(usize),
+    Wakeup,
+    ExitRequested,
+    DisplayChanged,
+    SettingChanged,
+}
+
+thread_local! {
+    /// The events waiting to be delivered.
+    static QUEUE: RefCell<VecDeque<Event>> = RefCell::new(VecDeque::new());
+
+    /// The [`RuntimeState`] of the running loop along with its dispatcher.
+    static RUNTIME: Cell<Option<(*mut c_void, unsafe fn(*mut c_void))>> = Cell::new(None);
+
+    /// Whether the loop is pumping the messages, so the window procedure can
+    /// deliver the events right away.
+    ///
+    /// The procedure is also entered from within the application callbacks,
+    /// for example by `SetWindowPos`, the events are only queued then.
+    static PUMPING: Cell<bool> = Cell::new(false);
+}
+
+/// Queue the `event` to be delivered once the current callback returns.
+pub(crate) fn queue_event(event: Event) {
+    QUEUE.with(|queue| queue.borrow_mut().push_back(event));
+}
+
+fn next_event() -> Option<Event> {
+    QUEUE.with(|queue| queue.borrow_mut().pop_front())
+}
+
+fn has_events() -> bool {
+    QUEUE.with(|queue| !queue.borrow().is_empty())
+}
+
+/// Deliver the queued events when the loop is pumping the messages.
+///
+/// This is the case for the modal loops as well, like the one of the
+/// interactive resize, which doesn't return to the event loop until done.
+fn dispatch_if_pumping() {
+    if !PUMPING.with(Cell::get) {
+        return;
+    }
+
+    if let Some((state, dispatch)) = RUNTIME.with(Cell::get) {
+        PUMPING.with(|pumping| pumping.set(false));
+        // SAFETY: the loop doesn't access the state while pumping.
+        unsafe { dispatch(state) };
+        PUMPING.with(|pumping| pumping.set(true));
+    }
+}
+
+/// The window procedure of all the windows, including the message-only one.
+unsafe extern "system" fn wnd_proc(
+    hwnd: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    let window_id = crate::make_wid(hwnd);
+
+    let event = match message {
+        WM_PAINT => {
+            // The application draws on its own, so just acknowledge the paint.
+            ValidateRect(hwnd, ptr::null());
+            Event::Redraw(window_id)
+        },
+        WM_CLOSE => Event::CloseRequested(window_id),
+        WM_SIZE => Event::Resized(window_id, wparam == SIZE_MINIMIZED as usize),
+        WM_DPICHANGED => {
+            let (dpi, _) = crate::split_param(wparam);
+            queue_event(Event::ScaleFactorChanged(
+                window_id,
+                monitor::window_scale_factor(dpi as u32),
+            ));
+
+            // Take the size suggested for the new scale, it keeps the window on
+            // the monitor it moved to.
+            let rect = &*(lparam as *const RECT);
+            SetWindowPos(
+                hwnd,
+                0,
+                rect.left,
+                rect.top,
+                rect.right - rect.left,
+                rect.bottom - rect.top,
+                SWP_NOZORDER | SWP_NOACTIVATE,
+            );
+
+            dispatch_if_pumping();
+            return 0;
+        },
+        WM_SETFOCUS => Event::Focused(window_id, true),
+        WM_KILLFOCUS => Event::Focused(window_id, false),
+        WM_MOUSEMOVE => {
+            let (x, y) = crate::lparam_position(lparam);
+            Event::PointerMoved(window_id, PhysicalPosition::new(x as f64, y as f64))
+        },
+        WM_MOUSELEAVE => Event::PointerLeft(window_id),
+        WM_LBUTTONDOWN | WM_LBUTTONUP | WM_RBUTTONDOWN | WM_RBUTTONUP | WM_MBUTTONDOWN
+        | WM_MBUTTONUP | WM_XBUTTONDOWN | WM_XBUTTONUP => {
+            let (button, state) = match input::pointer_button(message, wparam) {
+                Some(button) => button,
+                None => return DefWindowProcW(hwnd, message, wparam, lparam),
+            };
+
+            // Keep getting the pointer events while any button is held outside.
+            if state == ElementState::Pressed {
+                SetCapture(hwnd);
+            } else if wparam & MK_BUTTONS == 0 {
+                ReleaseCapture();
+            }
+
+            queue_event(Event::PointerButton(window_id, button, state));
+            dispatch_if_pumping();
+
+            // The extra buttons are reported as handled with `TRUE`.
+            return matches!(message, WM_XBUTTONDOWN | WM_XBUTTONUP) as LRESULT;
+        },
+        WM_MOUSEWHEEL | WM_MOUSEHWHEEL => {
+            let (_, delta) = crate::split_param(wparam);
+            let delta = delta as i16 as i32;
+
+            // The wheel rotated forward scrolls up, which is the negative scroll.
+            let value120 = if message == WM_MOUSEWHEEL {
+                PhysicalPosition::new(0, -delta)
+            } else {
+                PhysicalPosition::new(delta, 0)
+            };
+
+            Event::Wheel(window_id, value120)
+        },
+        WM_SETCURSOR => {
+            let (hit, _) = crate::split_param(lparam as usize);
+            match win32_window::shared_data(hwnd) {
+                Some(shared) if hit as u32 == HTCLIENT => {
+                    SetCursor(shared.cursor.get());
+                    return 1;
+                },
+                _ => return DefWindowProcW(hwnd, message, wparam, lparam),
+            }
+        },
+        WM_NCHITTEST => {
+            let hit = DefWindowProcW(hwnd, message, wparam, lparam);
+            let shared = match win32_window::shared_data(hwnd) {
+                Some(shared) if hit as u32 == HTCLIENT => shared,
+                _ => return hit,
+            };
+
+            // Pass the input outside of the input region to the window beneath.
+            let (x, y) = crate::lparam_position(lparam);
+            let mut point = POINT { x, y };
+            ScreenToClient(hwnd, &mut point);
+            let accepts_input = shared.accepts_input(PhysicalPosition::new(point.x, point.y));
+            return if accepts_input { hit } else { HTTRANSPARENT as LRESULT };
+        },
+        WM_GETMINMAXINFO => {
+            if let Some(shared) = win32_window::shared_data(hwnd) {
+                let info = &mut *(lparam as *mut MINMAXINFO);
+                let dpi = GetDpiForWindow(hwnd);
+                let scale_factor = monitor::window_scale_factor(dpi);
+                let style = GetWindowLongW(hwnd, GWL_STYLE) as u32;
+                let ex_style = GetWindowLongW(hwnd, GWL_EXSTYLE) as u32;
+
+                if let Some(size) = shared.min_inner_size.get() {
+                    let size = size.to_physical(scale_factor);
+                    let (width, height) = win32_window::outer_size(size, style, ex_style, dpi);
+                    info.ptMinTrackSize.x = width;
+                    info.ptMinTrackSize.y = height;
+                }
+
+                if let Some(size) = shared.max_inner_size.get() {
+                    let size = size.to_physical(scale_factor);
+                    let (width, height) = win32_window::outer_size(size, style, ex_style, dpi);
+                    info.ptMaxTrackSize.x = width;
+                    info.ptMaxTrackSize.y = height;
+                }
+            }
+
+            return 0;
+        },
+        WM_TIMER => Event::Timer(wparam),
+        WM_WAKEUP => Event::Wakeup,
+        WM_EXIT_REQUESTED => Event::ExitRequested,
+        WM_DISPLAYCHANGE => {
+            queue_event(Event::DisplayChanged);
+            dispatch_if_pumping();
+            return DefWindowProcW(hwnd, message, wparam, lparam);
+        },
+        WM_SETTINGCHANGE => {
+            queue_event(Event::SettingChanged);
+            dispatch_if_pumping();
+            return DefWindowProcW(hwnd, message, wparam, lparam);
+        },
+        _ => return DefWindowProcW(hwnd, message, wparam, lparam),
+    };
+
+    queue_event(event);
+    dispatch_if_pumping();
+
+    0
+}
+
+/// The Win32 event loop.
+///
+/// The application type could be provided statically, or left as the
+/// `Box<dyn Application>` when the event loop is used through the
+/// [`EventLoopRequests`].
+pub struct EventLoop<T: Application + 'static = Box<dyn Application>> {
+    pub(crate) state: RuntimeState<T>,
+}
+
+impl<T: Application + 'static> EventLoop<T> {
+    pub fn new() -> Result<Self, EventLoopError> {
+        let started_at = Instant::now();
+
+        // The sizes are in the physical pixels of the monitor the window is on.
+        unsafe { SetProcessDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2) };
+
+        let hinstance = unsafe { GetModuleHandleW(ptr::null()) };
+        let class_name = crate::encode_wide(WINDOW_CLASS);
+        let class = WNDCLASSEXW {
+            cbSize: mem::size_of::<WNDCLASSEXW>() as u32,
+            style: CS_HREDRAW | CS_VREDRAW,
+            lpfnWndProc: Some(wnd_proc),
+            cbClsExtra: 0,
+            cbWndExtra: 0,
+            hInstance: hinstance,
+            hIcon: 0,
+            hCursor: 0,
+            hbrBackground: 0,
+            lpszMenuName: ptr::null(),
+            lpszClassName: class_name.as_ptr(),
+            hIconSm: 0,
+        };
+
+        // The class is registered only once per process, the next event loops
+        // reuse it.
+        unsafe { RegisterClassExW(&class) };
+
+        let message_window = unsafe {
+            CreateWindowExW(
+                0,
+                class_name.as_ptr(),
+                ptr::null(),
+                0,
+                0,
+                0,
+                0,
+                0,
+                HWND_MESSAGE,
+                0,
+                hinstance,
+                ptr::null(),
+            )
+        };
+
+        if message_window == 0 {
+            let err = std::io::Error::last_os_error();
+            return Err(EventLoopError::Os(format!("failed to create the message window: {err}")));
+        }
+
+        let proxy = EventLoopProxy::new(message_window);
+        let mut state = RuntimeState { user: None, winit: WinitState::new(hinstance, proxy) };
+
+        let startup = &mut state.winit.startup;
+        startup.started_at = started_at;
+        startup.timings.connected = Some(started_at.elapsed());
+        startup.timings.globals_bound = Some(started_at.elapsed());
+
+        Ok(Self { state })
+    }
+
+    /// Run the event loop.
+    pub fn run(mut self, mut state: T) {
+        // SAFETY: The user state is being used only inside the loop, the pointer is
+        // cleared before returning.
+        self.state.user =
+            Some(unsafe { std::mem::transmute::<&mut T, &'static mut T>(&mut state) });
+
+        let runtime_ptr: *mut RuntimeState<T> = &mut self.state;
+        let dispatch: unsafe fn(*mut c_void) = RuntimeState::<T>::dispatch_raw;
+        RUNTIME.with(|cell| cell.set(Some((runtime_ptr as *mut c_void, dispatch))));
+
+        // SAFETY: the window procedure only accesses the state while pumping, the
+        // references below never live across the pumping.
+        let runtime = unsafe { &mut *runtime_ptr };
+        runtime.user.as_mut().unwrap().new_events(&mut runtime.winit, StartCause::Init);
+
+        let mut timeout = Some(Duration::ZERO);
+        loop {
+            wait_for_messages(timeout);
+            unsafe { (*runtime_ptr).winit.dispatch.start_iteration() };
+            let exhausted = pump_messages(runtime_ptr);
+
+            let runtime = unsafe { &mut *runtime_ptr };
+
+            // The events queued from within the callbacks of the previous
+            // iteration.
+            runtime.dispatch_events();
+
+            let winit = &mut runtime.winit;
+            let user = runtime.user.as_mut().unwrap();
+
+            // Deliver the compressed pointer motion before redrawing.
+            winit.flush_all_pointer_motion(user);
+
+            winit.deliver_activation_denied(user);
+            winit.update_window_groups();
+            winit.resolve_queries(user);
+            let idle_timeout = winit.update_idle(user);
+
+            user.about_to_wait(winit);
+
+            if runtime.winit.exit {
+                break;
+            }
+
+            // Don't block on the events queued by the `about_to_wait`, or on the
+            // messages left over the budget.
+            timeout = if has_events() || exhausted { Some(Duration::ZERO) } else { idle_timeout };
+        }
+
+        let runtime = unsafe { &mut *runtime_ptr };
+        runtime.user.as_mut().unwrap().loop_exiting(&mut runtime.winit);
+
+        RUNTIME.with(|cell| cell.set(None));
+        QUEUE.with(|queue| queue.borrow_mut().clear());
+        self.state.user = None;
+    }
+
+    /// Get the proxy to wakeup the event loop.
+    pub fn proxy(&self) -> Arc<dyn CoreEventLoopProxy> {
+        self.state.winit.proxy()
+    }
+}
+
+impl<T: Application + 'static> Drop for EventLoop<T> {
+    fn drop(&mut self) {
+        // The proxies could outlive the loop on the other threads, where the
+        // window can't be destroyed.
+        unsafe { DestroyWindow(self.state.winit.message_window) };
+    }
+}
+
+impl EventLoopRequests for EventLoop {
+    fn new() -> Result<Self, EventLoopError> {
+        EventLoop::new()
+    }
+
+    fn run(self: Box<Self>, app: Box<dyn Application>) {
+        EventLoop::run(*self, app)
+    }
+
+    fn proxy(&self) -> Arc<dyn CoreEventLoopProxy> {
+        EventLoop::proxy(self)
+    }
+}
+
+impl<T: Application + 'static> HasDisplayHandle for EventLoop<T> {
+    fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+        self.state.winit.display_handle()
+    }
+}
+
+unsafe impl<T: Application + 'static> HasRawDisplayHandle05 for EventLoop<T> {
+    fn raw_display_handle(&self) -> raw_window_handle_05::RawDisplayHandle {
+        self.state.winit.raw_display_handle()
+    }
+}
+
+/// Block until there're messages or the `timeout` passes.
+fn wait_for_messages(timeout: Option<Duration>) {
+    let millis = match timeout {
+        Some(timeout) if timeout.is_zero() => return,
+        Some(timeout) => timeout.as_millis().min(INFINITE as u128 - 1) as u32,
+        None => INFINITE,
+    };
+
+    unsafe {
+        MsgWaitForMultipleObjectsEx(0, ptr::null(), millis, QS_ALLINPUT, MWMO_INPUTAVAILABLE)
+    };
+}
+
+/// Dispatch the pending messages within the budget, delivering the events
+/// right away.
+///
+/// Returns whether the budget ran out before all the messages were
+/// dispatched.
+fn pump_messages<T: Application + 'static>(runtime: *mut RuntimeState<T>) -> bool {
+    PUMPING.with(|pumping| pumping.set(true));
+
+    let mut msg: MSG = unsafe { mem::zeroed() };
+    let mut exhausted = false;
+    loop {
+        // SAFETY: the window procedure is done with the state in between the
+        // messages.
+        if !unsafe { (*runtime).winit.dispatch.try_continue() } {
+            exhausted = true;
+            break;
+        }
+
+        if unsafe { PeekMessageW(&mut msg, 0, 0, 0, PM_REMOVE) } == 0 {
+            break;
+        }
+
+        unsafe {
+            TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    }
+
+    PUMPING.with(|pumping| pumping.set(false));
+    exhausted
+}
+
+/// Runtime state passed around.
+pub struct RuntimeState<T: Application + 'static> {
+    /// The user state we're using during the runtime.
+    pub(crate) user: Option<&'static mut T>,
+
+    /// The state of the winit.
+    pub(crate) winit: WinitState<T>,
+}
+
+impl<T: Application + 'static> RuntimeState<T> {
+    /// The type-erased [`RuntimeState::dispatch_events`] for the window
+    /// procedure.
+    unsafe fn dispatch_raw(state: *mut c_void) {
+        let state = &mut *(state as *mut Self);
+        state.dispatch_events();
+    }
+
+    /// Deliver the queued events, including the ones queued meanwhile.
+    pub(crate) fn dispatch_events(&mut self) {
+        while let Some(event) = next_event() {
+            self.dispatch_event(event);
+        }
+    }
+
+    fn dispatch_event(&mut self, event: Event) {
+        let winit = &mut self.winit;
+        let user = self.user.as_mut().unwrap();
+        winit.dispatch.record_event();
+
+        let window_id = match event {
+            Event::Timer(id) => {
+                if let Some(timer_id) = winit.timer_ids.get(&id).copied() {
+                    user.timer_fired(winit, timer_id);
+                }
+                return;
+            },
+            Event::Wakeup => {
+                winit.proxy.wakeup_pending.store(false, Ordering::Relaxed);
+                user.user_wakeup(winit);
+                return;
+            },
+            Event::ExitRequested => {
+                winit.proxy.exit_pending.store(false, Ordering::Relaxed);
+                if user.exit_requested(winit) {
+                    winit.exit = true;
+                }
+                return;
+            },
+            Event::DisplayChanged => {
+                winit.update_monitors(user);
+                return;
+            },
+            Event::SettingChanged => {
+                winit.system_theme_changed();
+                return;
+            },
+            Event::Created(window_id) => {
+                self.window_created(window_id);
+                return;
+            },
+            Event::Redraw(window_id)
+            | Event::CloseRequested(window_id)
+            | Event::Resized(window_id, _)
+            | Event::ScaleFactorChanged(window_id, _)
+            | Event::Focused(window_id, _)
+            | Event::PointerMoved(window_id, _)
+            | Event::PointerLeft(window_id)
+            | Event::PointerButton(window_id, ..)
+            | Event::Wheel(window_id, _) => window_id,
+        };
+
+        // The messages sent while creating or destroying the window.
+        let window = match winit.windows.get_mut(&window_id) {
+            Some(window) if window.created => window,
+            _ => return,
+        };
+
+        match event {
+            Event::Redraw(_) => user.redraw_requested(winit, window_id),
+            Event::CloseRequested(_) => self.request_window_close(window_id),
+            Event::Resized(_, minimized) => {
+                let occluded = mem::replace(&mut window.minimized, minimized) != minimized;
+
+                // The minimized windows are reported with the zero size.
+                let resized = !minimized && window.update_size();
+                let size = window.inner_size();
+
+                if occluded {
+                    user.occluded(winit, window_id, minimized);
+                }
+
+                if resized {
+                    user.resized(winit, window_id, size);
+                }
+            },
+            Event::ScaleFactorChanged(_, scale_factor) => {
+                if window.scale_factor != scale_factor {
+                    window.scale_factor = scale_factor;
+                    user.scale_factor_changed(winit, window_id, scale_factor);
+                }
+            },
+            Event::Focused(_, focused) => {
+                if mem::replace(&mut window.has_focus, focused) != focused {
+                    user.focused(winit, window_id, focused);
+                }
+            },
+            Event::PointerMoved(_, position) => {
+                if !window.pointer_inside {
+                    window.pointer_inside = true;
+
+                    // Get the `WM_MOUSELEAVE` once the pointer leaves.
+                    let mut track = TRACKMOUSEEVENT {
+                        cbSize: mem::size_of::<TRACKMOUSEEVENT>() as u32,
+                        dwFlags: TME_LEAVE,
+                        hwndTrack: window.hwnd(),
+                        dwHoverTime: 0,
+                    };
+                    unsafe { TrackMouseEvent(&mut track) };
+
+                    window.pointer_position = Some(position);
+                    let event =
+                        InputEvent::PointerEntered { position, device_id: POINTER_DEVICE_ID };
+                    winit.deliver_input(user, window_id, event);
+                    return;
+                }
+
+                // Windows repeats the last position on some occasions.
+                if window.pointer_position == Some(position) {
+                    return;
+                }
+
+                window.pointer_position = Some(position);
+                winit.pending_motion.entry(window_id).or_default().positions.push(position);
+                if winit.motion_compression == MotionCompression::None {
+                    // Over the budget the motion is compressed until the next
+                    // iteration.
+                    if winit.dispatch.try_continue() {
+                        winit.flush_pointer_motion(user, window_id);
+                    } else {
+                        winit.dispatch.record_deferred();
+                    }
+                }
+            },
+            Event::PointerLeft(_) => {
+                window.pointer_inside = false;
+                window.pointer_position = None;
+
+                winit.flush_pointer_motion(user, window_id);
+                winit.pending_motion.remove(&window_id);
+                let event = InputEvent::PointerLeft { device_id: POINTER_DEVICE_ID };
+                winit.deliver_input(user, window_id, event);
+            },
+            Event::PointerButton(_, button, state) => {
+                let position = window.pointer_position.unwrap_or_default();
+
+                // The button applies at the latest position.
+                winit.flush_pointer_motion(user, window_id);
+
+                let event =
+                    PointerButtonEvent { button, state, position, device_id: POINTER_DEVICE_ID };
+                winit.deliver_input(user, window_id, InputEvent::PointerButton(event));
+            },
+            Event::Wheel(_, value120) => {
+                let (x, y) = &mut winit.wheel_remainder;
+                *x += value120.x;
+                *y += value120.y;
+
+                let clicks =
+                    PhysicalPosition::new(*x / WHEEL_DELTA as i32, *y / WHEEL_DELTA as i32);
+                *x %= WHEEL_DELTA as i32;
+                *y %= WHEEL_DELTA as i32;
+
+                let delta = ScrollDelta::Wheel { clicks, value120 };
+                let scroll = PointerScroll { delta, device_id: POINTER_DEVICE_ID };
+                winit.deliver_input(user, window_id, InputEvent::PointerScrolled(scroll));
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    /// Show the created window and deliver its initial state.
+    fn window_created(&mut self, window_id: WindowId) {
+        let winit = &mut self.winit;
+        let user = self.user.as_mut().unwrap();
+
+        let window = match winit.windows.get_mut(&window_id) {
+            Some(window) => window,
+            None => return,
+        };
+
+        // The messages sent while showing are delivered after the creation.
+        window.show(&winit.monitors);
+        window.update_size();
+        window.created = true;
+        let scale_factor = window.scale_factor;
+        let size = window.inner_size();
+
+        let startup = &mut winit.startup;
+        if startup.timings.first_configure.is_none() {
+            startup.timings.first_configure = Some(startup.started_at.elapsed());
+        }
+
+        user.created(winit, window_id);
+        user.scale_factor_changed(winit, window_id, scale_factor);
+
+        // The frame is always drawn by the system.
+        user.decoration_mode_changed(winit, window_id, DecorationMode::Server);
+        user.resized(winit, window_id, size);
+    }
+
+    pub(crate) fn request_window_close(&mut self, window_id: WindowId) {
+        if !self.winit.windows.contains_key(&window_id) {
+            return;
+        }
+
+        let user_state = self.user.as_mut().unwrap();
+        if !user_state.close_requested(&mut self.winit, window_id) {
+            return;
+        }
+
+        // The user could change the behavior while asked.
+        let close_behavior = match self.winit.windows.get(&window_id) {
+            Some(window) => window.close_behavior,
+            None => return,
+        };
+
+        match close_behavior {
+            CloseBehavior::NotifyOnly => (),
+            CloseBehavior::DestroyImmediately => {
+                drop(self.winit.windows.remove(&window_id));
+                self.winit.pending_motion.remove(&window_id);
+                self.winit.tombstones.bury(window_id);
+                user_state.destroyed(&mut self.winit, window_id);
+            },
+            CloseBehavior::HideInstead => {
+                if let Some(window) = self.winit.windows.get_mut(&window_id) {
+                    window.hide();
+                }
+            },
+        }
+    }
+}
+
+pub struct EventLoopProxy {
+    /// The message-only window the messages are posted to.
+    hwnd: HWND,
+
+    /// Whether the wakeup is posted and not delivered yet, the wakeups made
+    /// meanwhile are merged.
+    wakeup_pending: AtomicBool,
+
+    /// Same as the `wakeup_pending`, but for the
+    /// [`CoreEventLoopProxy::request_exit`].
+    exit_pending: AtomicBool,
+}
+
+impl EventLoopProxy {
+    fn new(hwnd: HWND) -> Self {
+        Self { hwnd, wakeup_pending: AtomicBool::new(false), exit_pending: AtomicBool::new(false) }
+    }
+
+    #[inline]
+    pub(crate) fn hwnd(&self) -> HWND {
+        self.hwnd
+    }
+}
+
+impl CoreEventLoopProxy for EventLoopProxy {
+    fn wakeup(&self) {
+        if !self.wakeup_pending.swap(true, Ordering::Relaxed) {
+            unsafe { PostMessageW(self.hwnd, WM_WAKEUP, 0, 0) };
+        }
+    }
+
+    fn request_exit(&self) {
+        if !self.exit_pending.swap(true, Ordering::Relaxed) {
+            unsafe { PostMessageW(self.hwnd, WM_EXIT_REQUESTED, 0, 0) };
+        }
+    }
+}
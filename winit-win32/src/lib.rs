@@ -0,0 +1,55 @@
+//! The Win32 backend.
+//!
+//! The structure follows the Wayland backend: the [`EventLoop`] owns the
+//! [`WinitState`], which implements the [`EventLoopHandle`] passed to the
+//! application.
+//!
+//! The window procedure can be entered from within the application
+//! callbacks, for example by `SetWindowPos`, so the messages are queued and
+//! delivered once the callback returns.
+//!
+//! [`EventLoop`]: crate::event_loop::EventLoop
+//! [`WinitState`]: crate::state::WinitState
+//! [`EventLoopHandle`]: winit_core::event_loop::EventLoopHandle
+#![cfg(windows)]
+
+use windows_sys::Win32::Foundation::HWND;
+
+use winit_core::window::WindowId;
+
+pub mod diagnostics;
+pub mod event_loop;
+mod input;
+pub mod monitor;
+pub mod state;
+pub mod window;
+
+/// Get the WindowId out of the window handle.
+#[inline]
+pub(crate) fn make_wid(hwnd: HWND) -> WindowId {
+    WindowId(hwnd as u128)
+}
+
+/// The null terminated UTF-16 string for the Win32 calls.
+pub(crate) fn encode_wide(string: &str) -> Vec<u16> {
+    string.encode_utf16().chain(Some(0)).collect()
+}
+
+/// The string out of the null terminated UTF-16 buffer.
+pub(crate) fn decode_wide(buffer: &[u16]) -> String {
+    let len = buffer.iter().position(|c| *c == 0).unwrap_or(buffer.len());
+    String::from_utf16_lossy(&buffer[..len])
+}
+
+/// The low and the high words of the message parameter.
+#[inline]
+pub(crate) fn split_param(param: usize) -> (u16, u16) {
+    ((param & 0xffff) as u16, ((param >> 16) & 0xffff) as u16)
+}
+
+/// The signed coordinates packed into the `lparam`.
+#[inline]
+pub(crate) fn lparam_position(lparam: isize) -> (i32, i32) {
+    let (x, y) = split_param(lparam as usize);
+    (x as i16 as i32, y as i16 as i32)
+}
@@ -0,0 +1,206 @@
+//! The monitors enumerated with the GDI.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::{mem, ptr};
+
+use windows_sys::Win32::Foundation::{BOOL, HWND, LPARAM, POINT, RECT, TRUE};
+use windows_sys::Win32::Graphics::Gdi::{
+    EnumDisplayDevicesW, EnumDisplayMonitors, EnumDisplaySettingsW, GetMonitorInfoW,
+    MonitorFromPoint, MonitorFromWindow, DEVMODEW, DISPLAY_DEVICEW, ENUM_CURRENT_SETTINGS, HDC,
+    HMONITOR, MONITORINFO, MONITORINFOEXW, MONITOR_DEFAULTTONEAREST, MONITOR_DEFAULTTOPRIMARY,
+};
+use windows_sys::Win32::UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
+use windows_sys::Win32::UI::WindowsAndMessaging::{MONITORINFOF_PRIMARY, USER_DEFAULT_SCREEN_DPI};
+
+use winit_core::dpi::{PhysicalPosition, PhysicalSize, Rect};
+use winit_core::monitor::{Monitor as CoreMonitor, MonitorId};
+
+/// The monitor known to the system.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Monitor {
+    id: MonitorId,
+    hmonitor: HMONITOR,
+
+    /// The GDI name of the monitor, like `\\.\DISPLAY1`.
+    device_name: String,
+
+    /// The name of the monitor itself, reported by its driver.
+    name: Option<String>,
+
+    size: PhysicalSize<u32>,
+    position: PhysicalPosition<i32>,
+
+    /// The area not covered by the taskbar and the docked toolbars.
+    work_area: Rect<i32>,
+
+    refresh_rate_millihertz: Option<u32>,
+    scale_factor: f64,
+    primary: bool,
+}
+
+impl Monitor {
+    /// Query the state of the `hmonitor`, `None` when it's gone.
+    pub(crate) fn new(hmonitor: HMONITOR) -> Option<Self> {
+        // SAFETY: the structures are plain data, zero is valid for them.
+        let mut info: MONITORINFOEXW = unsafe { mem::zeroed() };
+        info.monitorInfo.cbSize = mem::size_of::<MONITORINFOEXW>() as u32;
+        let info_ptr = &mut info as *mut MONITORINFOEXW as *mut MONITORINFO;
+        if unsafe { GetMonitorInfoW(hmonitor, info_ptr) } == 0 {
+            return None;
+        }
+
+        let device_name = crate::decode_wide(&info.szDevice);
+        let rect = info.monitorInfo.rcMonitor;
+
+        // The GDI names are reused for the same connector, so the id survives
+        // the reconnects.
+        let mut hasher = DefaultHasher::new();
+        device_name.hash(&mut hasher);
+        let id = MonitorId(hasher.finish() as u128);
+
+        Some(Self {
+            id,
+            hmonitor,
+            name: device_string(&info.szDevice),
+            size: PhysicalSize::new(
+                (rect.right - rect.left) as u32,
+                (rect.bottom - rect.top) as u32,
+            ),
+            position: PhysicalPosition::new(rect.left, rect.top),
+            work_area: to_rect(info.monitorInfo.rcWork),
+            refresh_rate_millihertz: refresh_rate_millihertz(&info.szDevice),
+            scale_factor: monitor_scale_factor(hmonitor),
+            primary: info.monitorInfo.dwFlags & MONITORINFOF_PRIMARY != 0,
+            device_name,
+        })
+    }
+
+    /// All the monitors of the desktop.
+    pub(crate) fn enumerate() -> Vec<Self> {
+        unsafe extern "system" fn push(
+            hmonitor: HMONITOR,
+            _: HDC,
+            _: *mut RECT,
+            data: LPARAM,
+        ) -> BOOL {
+            let hmonitors = &mut *(data as *mut Vec<HMONITOR>);
+            hmonitors.push(hmonitor);
+            TRUE
+        }
+
+        let mut hmonitors: Vec<HMONITOR> = Vec::new();
+        let data = &mut hmonitors as *mut Vec<HMONITOR> as LPARAM;
+        unsafe { EnumDisplayMonitors(0, ptr::null(), Some(push), data) };
+
+        hmonitors.into_iter().filter_map(Self::new).collect()
+    }
+
+    #[inline]
+    pub(crate) fn hmonitor(&self) -> HMONITOR {
+        self.hmonitor
+    }
+
+    #[inline]
+    pub(crate) fn is_primary(&self) -> bool {
+        self.primary
+    }
+
+    #[inline]
+    pub(crate) fn work_area(&self) -> Rect<i32> {
+        self.work_area
+    }
+}
+
+impl CoreMonitor for Monitor {
+    fn id(&self) -> MonitorId {
+        self.id
+    }
+
+    fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    fn connection_name(&self) -> Option<String> {
+        Some(self.device_name.clone())
+    }
+
+    fn size(&self) -> PhysicalSize<u32> {
+        self.size
+    }
+
+    fn position(&self) -> PhysicalPosition<i32> {
+        self.position
+    }
+
+    fn refresh_rate_millihertz(&self) -> Option<u32> {
+        self.refresh_rate_millihertz
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+}
+
+/// The monitor the most of the `hwnd` is on.
+pub(crate) fn window_monitor(hwnd: HWND) -> HMONITOR {
+    unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST) }
+}
+
+/// The monitor with the taskbar.
+pub(crate) fn primary_monitor() -> HMONITOR {
+    unsafe { MonitorFromPoint(POINT { x: 0, y: 0 }, MONITOR_DEFAULTTOPRIMARY) }
+}
+
+/// The scale factor of the `dpi`, the 96 DPI is the scale of 1.
+#[inline]
+pub(crate) fn dpi_to_scale_factor(dpi: u32) -> f64 {
+    dpi as f64 / USER_DEFAULT_SCREEN_DPI as f64
+}
+
+/// The scale factor of the window on the `dpi`, unless it's overridden from
+/// the environment.
+#[inline]
+pub(crate) fn window_scale_factor(dpi: u32) -> f64 {
+    winit_core::env::overrides().window_scale_factor(dpi_to_scale_factor(dpi))
+}
+
+fn monitor_scale_factor(hmonitor: HMONITOR) -> f64 {
+    let (mut dpi_x, mut dpi_y) = (0, 0);
+    let result = unsafe { GetDpiForMonitor(hmonitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y) };
+    match result {
+        0 if dpi_x != 0 => dpi_to_scale_factor(dpi_x),
+        _ => 1.,
+    }
+}
+
+/// The name of the monitor connected to the GDI `device`.
+fn device_string(device: &[u16; 32]) -> Option<String> {
+    // SAFETY: the structure is plain data, zero is valid for it.
+    let mut display_device: DISPLAY_DEVICEW = unsafe { mem::zeroed() };
+    display_device.cb = mem::size_of::<DISPLAY_DEVICEW>() as u32;
+    if unsafe { EnumDisplayDevicesW(device.as_ptr(), 0, &mut display_device, 0) } == 0 {
+        return None;
+    }
+
+    Some(crate::decode_wide(&display_device.DeviceString)).filter(|name| !name.is_empty())
+}
+
+fn refresh_rate_millihertz(device: &[u16; 32]) -> Option<u32> {
+    // SAFETY: the structure is plain data, zero is valid for it.
+    let mut mode: DEVMODEW = unsafe { mem::zeroed() };
+    mode.dmSize = mem::size_of::<DEVMODEW>() as u16;
+    if unsafe { EnumDisplaySettingsW(device.as_ptr(), ENUM_CURRENT_SETTINGS, &mut mode) } == 0 {
+        return None;
+    }
+
+    // The 0 and 1 mean the default refresh rate of the hardware.
+    (mode.dmDisplayFrequency > 1).then(|| mode.dmDisplayFrequency * 1000)
+}
+
+pub(crate) fn to_rect(rect: RECT) -> Rect<i32> {
+    Rect::new(
+        PhysicalPosition::new(rect.left, rect.top),
+        PhysicalSize::new(rect.right - rect.left, rect.bottom - rect.top),
+    )
+}
@@ -0,0 +1,554 @@
+use std::collections::HashMap;
+use std::io;
+use std::mem;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use raw_window_handle::{DisplayHandle, HandleError, HasDisplayHandle};
+use raw_window_handle_05::HasRawDisplayHandle as HasRawDisplayHandle05;
+
+use windows_sys::Win32::Foundation::{HINSTANCE, HWND};
+use windows_sys::Win32::System::SystemInformation::GetTickCount;
+use windows_sys::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+use windows_sys::Win32::UI::WindowsAndMessaging::{KillTimer, SetTimer};
+
+use smallvec::SmallVec;
+
+use winit_core::application::Application;
+use winit_core::clipboard::Clipboard as CoreClipboard;
+use winit_core::dpi::{PhysicalPosition, Rounding, RoundingPolicy};
+use winit_core::event_loop::budget::{DispatchBudget, DispatchMeter, DispatchStats};
+use winit_core::event_loop::proxy::EventLoopProxy as CoreEventLoopProxy;
+use winit_core::event_loop::{
+    EventLoopHandle, Query, QueryResult, RequestId, StartupTimings, TimerId,
+};
+use winit_core::input::filter::{EventFilter, EventFilterId};
+use winit_core::input::pointer::MotionCompression;
+use winit_core::input::{DeviceCapabilities, DeviceId, DeviceInfo};
+use winit_core::monitor::{Monitor as CoreMonitor, MonitorId};
+use winit_core::window::{
+    CreateWindowError, GroupId, RoleAttributes, Surface as CoreSurface, Tombstones, Toplevel,
+    WindowError, WindowId,
+};
+
+use crate::event_loop::{self, Event, EventLoopProxy};
+use crate::input::{EventFilters, PendingMotion, POINTER_DEVICE_ID};
+use crate::monitor::Monitor;
+use crate::window::{self, Window};
+
+impl<T: Application + 'static> EventLoopHandle for WinitState<T> {
+    fn proxy(&self) -> Arc<dyn CoreEventLoopProxy> {
+        self.proxy.clone()
+    }
+
+    fn create_window(&mut self, attributes: RoleAttributes) -> Result<(), CreateWindowError> {
+        let attributes = winit_core::env::overrides().apply(attributes);
+        let lints = attributes.validate().map_err(CreateWindowError::InvalidAttributes)?;
+        for lint in lints {
+            log::warn!("Suspicious window attributes: {lint:?}");
+        }
+
+        let attributes = match attributes {
+            RoleAttributes::Toplevel(attributes) => attributes,
+            // TODO: popups and subsurfaces.
+            RoleAttributes::Popup(_) | RoleAttributes::Subsurface(_) => {
+                return Err(CreateWindowError::Unsupported)
+            },
+        };
+
+        let window = Window::new(self.hinstance, attributes, &self.monitors, self.rounding_policy)?;
+        let window_id = window.id();
+        self.tombstones.revive(window_id);
+        self.windows.insert(window_id, window);
+
+        // The window is shown once the user is done with the current callback.
+        event_loop::queue_event(Event::Created(window_id));
+        Ok(())
+    }
+
+    fn num_windows(&self) -> usize {
+        self.windows.len()
+    }
+
+    fn get_window(&self, window_id: WindowId) -> Option<&dyn CoreSurface> {
+        self.tombstones.debug_assert_alive(window_id);
+
+        let window = self.windows.get(&window_id)?;
+        if !window.created {
+            return None;
+        } else {
+            Some(window as &dyn CoreSurface)
+        }
+    }
+
+    fn get_window_mut(&mut self, window_id: WindowId) -> Option<&mut dyn CoreSurface> {
+        self.tombstones.debug_assert_alive(window_id);
+
+        let window = self.windows.get_mut(&window_id)?;
+        if !window.created {
+            return None;
+        } else {
+            Some(window as &mut dyn CoreSurface)
+        }
+    }
+
+    fn try_get_window(&self, window_id: WindowId) -> Result<&dyn CoreSurface, WindowError> {
+        match self.windows.get(&window_id) {
+            Some(window) if window.created => Ok(window as &dyn CoreSurface),
+            Some(_) => Err(WindowError::NotConfigured),
+            None => Err(self.missing_window_error(window_id)),
+        }
+    }
+
+    fn try_get_window_mut(
+        &mut self,
+        window_id: WindowId,
+    ) -> Result<&mut dyn CoreSurface, WindowError> {
+        let error = self.missing_window_error(window_id);
+        match self.windows.get_mut(&window_id) {
+            Some(window) if window.created => Ok(window as &mut dyn CoreSurface),
+            Some(_) => Err(WindowError::NotConfigured),
+            None => Err(error),
+        }
+    }
+
+    fn set_timer(&mut self, interval: Duration, timer_id: TimerId) {
+        self.cancel_timer(timer_id);
+
+        let id = self.next_timer;
+        self.next_timer += 1;
+
+        // The system timers repeat until killed.
+        let millis = interval.as_millis().min(u32::MAX as u128) as u32;
+        if unsafe { SetTimer(self.message_window, id, millis, None) } == 0 {
+            log::warn!("Failed to set timer: {}", io::Error::last_os_error());
+            return;
+        }
+
+        self.timers.insert(timer_id, id);
+        self.timer_ids.insert(id, timer_id);
+    }
+
+    fn cancel_timer(&mut self, timer_id: TimerId) {
+        if let Some(id) = self.timers.remove(&timer_id) {
+            self.timer_ids.remove(&id);
+            unsafe { KillTimer(self.message_window, id) };
+        }
+    }
+
+    fn request(&mut self, query: Query) -> RequestId {
+        let request_id = RequestId(self.next_request_id);
+        self.next_request_id += 1;
+
+        // The answer is delivered once the user is done with the current
+        // callback, like on the other backends.
+        self.pending_queries.push((request_id, query));
+
+        request_id
+    }
+
+    fn clipboard(&mut self) -> Option<&mut dyn CoreClipboard> {
+        // TODO: the clipboard.
+        None
+    }
+
+    fn set_motion_compression(&mut self, compression: MotionCompression) {
+        self.motion_compression = compression;
+    }
+
+    fn set_dispatch_budget(&mut self, budget: DispatchBudget) {
+        self.dispatch.set_budget(budget);
+    }
+
+    fn dispatch_stats(&self) -> DispatchStats {
+        self.dispatch.stats()
+    }
+
+    fn push_event_filter(&mut self, filter: Box<dyn EventFilter>) -> EventFilterId {
+        self.event_filters.push(filter)
+    }
+
+    fn remove_event_filter(&mut self, filter_id: EventFilterId) {
+        self.event_filters.remove(filter_id);
+    }
+
+    fn idle_since(&self) -> Option<Duration> {
+        let inactive = inactive_duration()?;
+        (inactive >= self.idle_threshold?).then_some(inactive)
+    }
+
+    fn set_idle_threshold(&mut self, threshold: Option<Duration>) {
+        self.idle_threshold = threshold;
+        if threshold.is_none() {
+            self.idle = false;
+        }
+    }
+
+    fn startup_timings(&self) -> StartupTimings {
+        self.startup.timings
+    }
+
+    fn rounding_policy(&self) -> RoundingPolicy {
+        self.rounding_policy
+    }
+
+    fn set_rounding_policy(&mut self, policy: RoundingPolicy) {
+        self.rounding_policy = policy;
+        for window in self.windows.values_mut() {
+            window.rounding_policy = policy;
+        }
+    }
+
+    fn coalesced_pointer_positions(&self) -> &[PhysicalPosition<f64>] {
+        &self.coalesced_positions
+    }
+
+    fn dump_state(&self, writer: &mut dyn io::Write) -> io::Result<()> {
+        let monitors: Vec<_> = self
+            .monitors
+            .iter()
+            .map(|monitor| {
+                let size = monitor.size();
+                let position = monitor.position();
+                serde_json::json!({
+                    "id": monitor.id().0,
+                    "connection_name": monitor.connection_name(),
+                    "size": (size.width, size.height),
+                    "position": (position.x, position.y),
+                    "refresh_rate_millihertz": monitor.refresh_rate_millihertz(),
+                    "scale_factor": monitor.scale_factor(),
+                    "primary": monitor.is_primary(),
+                })
+            })
+            .collect();
+
+        let windows: Vec<_> = self.windows.values().map(|window| window.dump()).collect();
+
+        let state = serde_json::json!({
+            "backend": "win32",
+            "monitors": monitors,
+            "windows": windows,
+            "timers": self.timers.len(),
+            "motion_compression": format!("{:?}", self.motion_compression),
+            "pending_motion": self.pending_motion.len(),
+            "dispatch_stats": format!("{:?}", self.dispatch.stats()),
+        });
+
+        serde_json::to_writer_pretty(writer, &state).map_err(io::Error::from)
+    }
+
+    fn exit(&mut self) {
+        self.exit = true;
+    }
+
+    fn group_members(&self, group: GroupId) -> SmallVec<[WindowId; 4]> {
+        let mut members: SmallVec<[(Instant, WindowId); 4]> = self
+            .windows
+            .iter()
+            .filter_map(|(window_id, window)| match window.group {
+                Some((window_group, joined_at)) if window_group == group => {
+                    Some((joined_at, *window_id))
+                },
+                _ => None,
+            })
+            .collect();
+        members.sort_unstable_by_key(|&(joined_at, window_id)| (joined_at, window_id.0));
+        members.into_iter().map(|(_, window_id)| window_id).collect()
+    }
+
+    fn get_monitor(&self, monitor_id: MonitorId) -> Option<&dyn CoreMonitor> {
+        self.monitors
+            .iter()
+            .find(|monitor| monitor.id() == monitor_id)
+            .map(|monitor| monitor as &dyn CoreMonitor)
+    }
+
+    fn monitors(&self) -> SmallVec<[&dyn CoreMonitor; 4]> {
+        self.monitors.iter().map(|monitor| monitor as &dyn CoreMonitor).collect()
+    }
+
+    fn monitor_generation(&self) -> u64 {
+        self.monitor_generation
+    }
+
+    fn device_info(&self, device_id: DeviceId) -> Option<DeviceInfo> {
+        if device_id != POINTER_DEVICE_ID {
+            return None;
+        }
+
+        // NOTE: the messages merge all the mice and the keyboards, the raw input
+        // is needed to tell the devices apart.
+        let capabilities = DeviceCapabilities::POINTER | DeviceCapabilities::KEYBOARD;
+        Some(DeviceInfo { name: None, vendor_id: None, product_id: None, capabilities })
+    }
+}
+
+impl<T: Application + 'static> HasDisplayHandle for WinitState<T> {
+    fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+        Ok(DisplayHandle::windows())
+    }
+}
+
+unsafe impl<T: Application + 'static> HasRawDisplayHandle05 for WinitState<T> {
+    fn raw_display_handle(&self) -> raw_window_handle_05::RawDisplayHandle {
+        let display_handle = raw_window_handle_05::WindowsDisplayHandle::empty();
+        raw_window_handle_05::RawDisplayHandle::Windows(display_handle)
+    }
+}
+
+/// Winit's Win32 state.
+pub struct WinitState<T: Application + 'static> {
+    /// The module the window classes are registered for.
+    pub(crate) hinstance: HINSTANCE,
+
+    /// The message-only window receiving the timers and the proxy messages.
+    pub(crate) message_window: HWND,
+
+    pub windows: HashMap<WindowId, Window>,
+
+    /// The recently destroyed windows.
+    pub(crate) tombstones: Tombstones,
+
+    pub monitors: Vec<Monitor>,
+
+    /// The counter of the changes to the monitors.
+    pub(crate) monitor_generation: u64,
+
+    /// The timers registered by the user, along with their system ids.
+    pub(crate) timers: HashMap<TimerId, usize>,
+
+    /// The user timers by their system ids.
+    pub(crate) timer_ids: HashMap<usize, TimerId>,
+
+    /// The system id of the next timer.
+    next_timer: usize,
+
+    /// The id of the next query issued by the user.
+    next_request_id: u64,
+
+    /// The queries waiting to be answered.
+    pub(crate) pending_queries: Vec<(RequestId, Query)>,
+
+    /// How the pointer motion is delivered.
+    pub(crate) motion_compression: MotionCompression,
+
+    /// The budget of the event delivery per iteration.
+    pub(crate) dispatch: DispatchMeter,
+
+    /// The rounding of the window sizes.
+    pub(crate) rounding_policy: RoundingPolicy,
+
+    /// The startup milestones reached so far.
+    pub(crate) startup: Startup,
+
+    /// The inactivity after which the user is considered idle.
+    pub(crate) idle_threshold: Option<Duration>,
+
+    /// Whether the user was idle on the last check.
+    pub(crate) idle: bool,
+
+    /// The pointer motion waiting to be delivered for the windows under the
+    /// pointer.
+    pub(crate) pending_motion: HashMap<WindowId, PendingMotion>,
+
+    /// The positions of the motion being delivered.
+    pub(crate) coalesced_positions: Vec<PhysicalPosition<f64>>,
+
+    /// The filters the input events pass through.
+    pub(crate) event_filters: EventFilters,
+
+    /// The fractions of the wheel clicks not delivered yet.
+    pub(crate) wheel_remainder: (i32, i32),
+
+    pub proxy: Arc<EventLoopProxy>,
+
+    pub exit: bool,
+}
+
+impl<T: Application + 'static> WinitState<T> {
+    pub(crate) fn new(hinstance: HINSTANCE, proxy: EventLoopProxy) -> Self {
+        Self {
+            hinstance,
+            message_window: proxy.hwnd(),
+            windows: Default::default(),
+            tombstones: Default::default(),
+            monitors: Monitor::enumerate(),
+            monitor_generation: 0,
+            timers: Default::default(),
+            timer_ids: Default::default(),
+            next_timer: 1,
+            next_request_id: 0,
+            pending_queries: Default::default(),
+            motion_compression: Default::default(),
+            dispatch: Default::default(),
+            rounding_policy: RoundingPolicy::uniform(Rounding::Round),
+            startup: Startup::default(),
+            idle_threshold: None,
+            idle: false,
+            pending_motion: Default::default(),
+            coalesced_positions: Default::default(),
+            event_filters: Default::default(),
+            wheel_remainder: (0, 0),
+            proxy: Arc::new(proxy),
+            exit: Default::default(),
+        }
+    }
+
+    /// Why the window with the `window_id` is not in the state.
+    fn missing_window_error(&self, window_id: WindowId) -> WindowError {
+        if self.tombstones.is_destroyed(window_id) {
+            WindowError::Destroyed
+        } else {
+            WindowError::Unknown
+        }
+    }
+
+    /// Answer the queries issued by the user.
+    pub(crate) fn resolve_queries(&mut self, user: &mut T) {
+        for (request_id, query) in mem::take(&mut self.pending_queries) {
+            let result = self.resolve_query(&query);
+            user.query_complete(self, request_id, result);
+        }
+    }
+
+    fn resolve_query(&self, query: &Query) -> QueryResult {
+        match query {
+            Query::MonitorDescription(monitor_id) => {
+                let monitor = self.monitors.iter().find(|monitor| monitor.id() == *monitor_id);
+                QueryResult::MonitorDescription(monitor.and_then(|monitor| monitor.name()))
+            },
+            Query::Theme => QueryResult::Theme(window::system_theme()),
+            _ => QueryResult::Unsupported,
+        }
+    }
+
+    /// Enumerate the monitors again, notifying the user about the changed
+    /// ones.
+    pub(crate) fn update_monitors(&mut self, user: &mut T) {
+        let monitors = Monitor::enumerate();
+
+        // The monitors which were added, removed, or changed.
+        let mut changed: Vec<MonitorId> = monitors
+            .iter()
+            .filter(|monitor| !self.monitors.contains(monitor))
+            .map(|monitor| monitor.id())
+            .collect();
+        for monitor in &self.monitors {
+            if !monitors.iter().any(|new| new.id() == monitor.id()) {
+                changed.push(monitor.id());
+            }
+        }
+
+        if changed.is_empty() {
+            return;
+        }
+
+        self.monitors = monitors;
+        self.monitor_generation += 1;
+        for monitor_id in changed {
+            user.monitor_changed(self, monitor_id);
+        }
+    }
+
+    /// Keep the windows of each group above the main window of their group.
+    pub(crate) fn update_window_groups(&mut self) {
+        let mut owners: HashMap<GroupId, (Instant, WindowId)> = HashMap::new();
+        for (window_id, window) in &self.windows {
+            if let Some((group, joined_at)) = window.group {
+                let owner = owners.entry(group).or_insert((joined_at, *window_id));
+                if (joined_at, window_id.0) < (owner.0, owner.1 .0) {
+                    *owner = (joined_at, *window_id);
+                }
+            }
+        }
+
+        let owners: HashMap<GroupId, HWND> = owners
+            .into_iter()
+            .filter_map(|(group, (_, window_id))| {
+                Some((group, self.windows.get(&window_id)?.hwnd()))
+            })
+            .collect();
+
+        for window in self.windows.values_mut() {
+            let owner = match window.group {
+                Some((group, _)) => owners[&group],
+                None => 0,
+            };
+
+            // The main window of the group isn't owned.
+            let owner = if owner == window.hwnd() { 0 } else { owner };
+            window.set_owner(owner);
+        }
+    }
+
+    /// Deliver the refused activations.
+    pub(crate) fn deliver_activation_denied(&mut self, user: &mut T) {
+        let window_ids: Vec<WindowId> = self
+            .windows
+            .iter_mut()
+            .filter_map(|(window_id, window)| {
+                mem::take(&mut window.activation_denied).then_some(*window_id)
+            })
+            .collect();
+
+        for window_id in window_ids {
+            user.activation_denied(self, window_id);
+        }
+    }
+
+    /// Apply the system theme to the windows following it.
+    pub(crate) fn system_theme_changed(&mut self) {
+        for window in self.windows.values() {
+            if window.theme().is_none() {
+                window.apply_theme();
+            }
+        }
+    }
+
+    /// Notify the user when they become idle or active, returning the time
+    /// until the next check.
+    pub(crate) fn update_idle(&mut self, user: &mut T) -> Option<Duration> {
+        let threshold = self.idle_threshold?;
+        let inactive = inactive_duration()?;
+
+        let idle = inactive >= threshold;
+        if mem::replace(&mut self.idle, idle) != idle {
+            user.user_idle(self, idle);
+        }
+
+        // The input to the other applications doesn't wake up the loop, so poll
+        // for the activity while idle.
+        if idle {
+            Some(Duration::from_secs(1))
+        } else {
+            Some(threshold - inactive)
+        }
+    }
+}
+
+/// The time since the last input to any application.
+fn inactive_duration() -> Option<Duration> {
+    let mut info = LASTINPUTINFO { cbSize: mem::size_of::<LASTINPUTINFO>() as u32, dwTime: 0 };
+    if unsafe { GetLastInputInfo(&mut info) } == 0 {
+        return None;
+    }
+
+    // The tick count wraps around every 49.7 days.
+    let millis = unsafe { GetTickCount() }.wrapping_sub(info.dwTime);
+    Some(Duration::from_millis(millis as u64))
+}
+
+/// The tracking of the startup milestones.
+#[derive(Debug)]
+pub(crate) struct Startup {
+    /// The time the event loop creation started at.
+    pub(crate) started_at: Instant,
+
+    pub(crate) timings: StartupTimings,
+}
+
+impl Default for Startup {
+    fn default() -> Self {
+        Self { started_at: Instant::now(), timings: Default::default() }
+    }
+}
@@ -0,0 +1,869 @@
+//! The toplevel windows.
+
+use std::cell::{Cell, RefCell};
+use std::ffi::c_void;
+use std::num::NonZeroIsize;
+use std::time::Instant;
+use std::{mem, ptr};
+
+use raw_window_handle::{
+    HandleError, HasWindowHandle, RawWindowHandle, Win32WindowHandle, WindowHandle,
+};
+use raw_window_handle_05::HasRawWindowHandle as HasRawWindowHandle05;
+
+use windows_sys::Win32::Foundation::{BOOL, HINSTANCE, HWND, POINT, RECT};
+use windows_sys::Win32::Graphics::Dwm::{
+    DwmExtendFrameIntoClientArea, DwmSetWindowAttribute, DWMWA_USE_IMMERSIVE_DARK_MODE,
+    DWMWA_WINDOW_CORNER_PREFERENCE, DWMWCP_DEFAULT, DWMWCP_DONOTROUND, DWMWCP_ROUND,
+    DWMWCP_ROUNDSMALL, DWM_WINDOW_CORNER_PREFERENCE,
+};
+use windows_sys::Win32::Graphics::Gdi::InvalidateRect;
+use windows_sys::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD};
+use windows_sys::Win32::UI::Controls::MARGINS;
+use windows_sys::Win32::UI::HiDpi::{AdjustWindowRectExForDpi, GetDpiForWindow};
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DestroyWindow, GetClientRect, GetWindowLongW, GetWindowRect, IsIconic,
+    IsZoomed, LoadCursorW, SendMessageW, SetCursor, SetForegroundWindow,
+    SetLayeredWindowAttributes, SetWindowLongW, SetWindowPos, SetWindowTextW, ShowWindow,
+    CW_USEDEFAULT, GWLP_HINSTANCE, GWLP_HWNDPARENT, GWLP_USERDATA, GWL_EXSTYLE, GWL_STYLE, HCURSOR,
+    HTBORDER, HTBOTTOM, HTBOTTOMLEFT, HTBOTTOMRIGHT, HTCAPTION, HTCLIENT, HTCLOSE, HTLEFT,
+    HTMAXBUTTON, HTMINBUTTON, HTRIGHT, HTSYSMENU, HTTOP, HTTOPLEFT, HTTOPRIGHT, HWND_TOP,
+    IDC_APPSTARTING, IDC_ARROW, IDC_CROSS, IDC_HAND, IDC_HELP, IDC_IBEAM, IDC_NO, IDC_SIZEALL,
+    IDC_SIZENESW, IDC_SIZENS, IDC_SIZENWSE, IDC_SIZEWE, IDC_WAIT, LWA_ALPHA, SWP_FRAMECHANGED,
+    SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOOWNERZORDER, SWP_NOSIZE, SWP_NOZORDER, SW_HIDE, SW_MAXIMIZE,
+    SW_MINIMIZE, SW_RESTORE, SW_SHOW, SW_SHOWMAXIMIZED, WINDOW_EX_STYLE, WINDOW_STYLE,
+    WM_NCHITTEST, WS_EX_APPWINDOW, WS_EX_LAYERED, WS_EX_NOACTIVATE, WS_EX_TRANSPARENT,
+    WS_MAXIMIZEBOX, WS_MINIMIZEBOX, WS_OVERLAPPEDWINDOW, WS_POPUP, WS_SYSMENU, WS_THICKFRAME,
+    WS_VISIBLE,
+};
+#[cfg(target_pointer_width = "64")]
+use windows_sys::Win32::UI::WindowsAndMessaging::{GetWindowLongPtrW, SetWindowLongPtrW};
+#[cfg(target_pointer_width = "32")]
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    GetWindowLongW as GetWindowLongPtrW, SetWindowLongW as SetWindowLongPtrW,
+};
+
+use winit_core::cursor::CursorIcon;
+use winit_core::dpi::{
+    Border, LogicalSize, PhysicalPosition, PhysicalSize, Position, Rect, RoundingPolicy, Size,
+};
+use winit_core::input::DeviceId;
+use winit_core::monitor::{Monitor as CoreMonitor, MonitorId};
+use winit_core::window::{
+    CloseBehavior, CreateWindowError, GroupId, RegionKind, Surface as CoreSurface, Theme,
+    TiledEdges, Title, TitlePolicy, Toplevel, ToplevelAttributes, WindowId,
+};
+
+use crate::monitor::{self, Monitor};
+
+/// The class all the windows are created with.
+pub(crate) const WINDOW_CLASS: &str = "winit-win32";
+
+/// The state read by the window procedure, which can't access the
+/// [`Window`] itself.
+///
+/// The pointer to it is stored in the `GWLP_USERDATA` of the window.
+#[derive(Debug)]
+pub(crate) struct SharedData {
+    /// The cursor shown over the content.
+    pub(crate) cursor: Cell<HCURSOR>,
+
+    pub(crate) min_inner_size: Cell<Option<Size>>,
+    pub(crate) max_inner_size: Cell<Option<Size>>,
+
+    /// The region of the client area accepting the input, `None` for the
+    /// whole area.
+    pub(crate) input_region: RefCell<Option<Vec<Rect<u32>>>>,
+}
+
+impl SharedData {
+    /// Whether the `position` in the client area accepts the input.
+    pub(crate) fn accepts_input(&self, position: PhysicalPosition<i32>) -> bool {
+        match &*self.input_region.borrow() {
+            Some(rects) => rects.iter().any(|rect| rect.contains(position)),
+            None => true,
+        }
+    }
+}
+
+/// The [`SharedData`] of the `hwnd`, `None` until the window is set up.
+///
+/// # Safety
+///
+/// The `hwnd` must be the window of the [`WINDOW_CLASS`].
+pub(crate) unsafe fn shared_data<'a>(hwnd: HWND) -> Option<&'a SharedData> {
+    let data = GetWindowLongPtrW(hwnd, GWLP_USERDATA) as *const SharedData;
+    data.as_ref()
+}
+
+pub struct Window {
+    hwnd: HWND,
+    id: WindowId,
+
+    /// The state read by the window procedure, boxed to keep its address.
+    shared: Box<SharedData>,
+
+    /// Whether the user got the [`ApplicationWindow::created`].
+    ///
+    /// [`ApplicationWindow::created`]: winit_core::application::ApplicationWindow::created
+    pub(crate) created: bool,
+
+    title: String,
+    title_policy: TitlePolicy,
+    theme: Option<Theme>,
+    decorate: bool,
+    resizable: bool,
+    focusable: bool,
+
+    /// The size of the client area.
+    pub(crate) size: PhysicalSize<u32>,
+
+    pub(crate) scale_factor: f64,
+    pub(crate) rounding_policy: RoundingPolicy,
+    pub(crate) close_behavior: CloseBehavior,
+
+    /// Whether the window should be maximized when it's shown.
+    initial_maximized: bool,
+
+    /// The monitor to make the window fullscreen on when it's shown.
+    initial_fullscreen: Option<String>,
+
+    /// The outer rectangle to restore after the fullscreen.
+    fullscreen: Option<RECT>,
+
+    /// The group the window belongs to along with the time it joined it.
+    pub(crate) group: Option<(GroupId, Instant)>,
+
+    /// The owner the window is kept above, the main window of its group.
+    pub(crate) owner: HWND,
+
+    pub(crate) has_focus: bool,
+    pub(crate) minimized: bool,
+
+    /// Whether the user asked to focus the window and the system refused.
+    pub(crate) activation_denied: bool,
+
+    /// Whether the pointer is over the window, the leave is tracked then.
+    pub(crate) pointer_inside: bool,
+
+    pub(crate) pointer_position: Option<PhysicalPosition<f64>>,
+}
+
+impl Window {
+    pub(crate) fn new(
+        hinstance: HINSTANCE,
+        attributes: ToplevelAttributes,
+        monitors: &[Monitor],
+        rounding_policy: RoundingPolicy,
+    ) -> Result<Self, CreateWindowError> {
+        let decorate = attributes.decorations();
+        let resizable = attributes.resizable();
+        let style = window_style(decorate, resizable);
+
+        // The window is likely to be placed on the primary monitor, the size is
+        // fixed up once the actual one is known.
+        let primary = monitors.iter().find(|monitor| monitor.is_primary());
+        let scale_factor = primary.map_or(1., |monitor| monitor.scale_factor());
+        let scale_factor = winit_core::env::overrides().window_scale_factor(scale_factor);
+        let (x, y) = match attributes.position() {
+            Some(position) => {
+                let position = position.to_physical::<i32>(scale_factor);
+                (position.x, position.y)
+            },
+            None => (CW_USEDEFAULT, CW_USEDEFAULT),
+        };
+
+        let title_policy = attributes.title_policy();
+        let title = title_policy.apply(attributes.title());
+        let wide_title = crate::encode_wide(title.as_str());
+        let class = crate::encode_wide(WINDOW_CLASS);
+        let hwnd = unsafe {
+            CreateWindowExW(
+                WS_EX_APPWINDOW,
+                class.as_ptr(),
+                wide_title.as_ptr(),
+                style,
+                x,
+                y,
+                CW_USEDEFAULT,
+                CW_USEDEFAULT,
+                0,
+                0,
+                hinstance,
+                ptr::null(),
+            )
+        };
+
+        if hwnd == 0 {
+            return Err(CreateWindowError::Os(std::io::Error::last_os_error().to_string()));
+        }
+
+        let scale_factor = monitor::window_scale_factor(unsafe { GetDpiForWindow(hwnd) });
+        let shared = Box::new(SharedData {
+            cursor: Cell::new(load_cursor(CursorIcon::Default)),
+            min_inner_size: Cell::new(attributes.min_inner_size()),
+            max_inner_size: Cell::new(attributes.max_inner_size()),
+            input_region: RefCell::new(None),
+        });
+
+        // SAFETY: the data outlives the window, the pointer is cleared before
+        // destroying it.
+        unsafe { SetWindowLongPtrW(hwnd, GWLP_USERDATA, &*shared as *const SharedData as _) };
+
+        let mut window = Self {
+            hwnd,
+            id: crate::make_wid(hwnd),
+            shared,
+            created: false,
+            title: title.into(),
+            title_policy,
+            theme: attributes.theme(),
+            decorate,
+            resizable,
+            focusable: true,
+            size: PhysicalSize::new(0, 0),
+            scale_factor,
+            rounding_policy,
+            close_behavior: attributes.close_behavior(),
+            initial_maximized: attributes.maximized(),
+            initial_fullscreen: attributes.fullscreen_monitor().map(str::to_owned),
+            fullscreen: None,
+            group: None,
+            owner: 0,
+            has_focus: false,
+            minimized: false,
+            activation_denied: false,
+            pointer_inside: false,
+            pointer_position: None,
+        };
+
+        let primary = primary.map(|monitor| monitor as &dyn CoreMonitor);
+        let size = attributes.initial_inner_size(primary, window.scale_factor);
+        let size = window.to_physical_size(size.into());
+        window.apply_inner_size(size);
+        window.update_size();
+        window.apply_theme();
+
+        Ok(window)
+    }
+
+    #[inline]
+    pub fn hwnd(&self) -> HWND {
+        self.hwnd
+    }
+
+    /// Show the window for the first time, along with the initial state.
+    pub(crate) fn show(&mut self, monitors: &[Monitor]) {
+        let command = if self.initial_maximized { SW_SHOWMAXIMIZED } else { SW_SHOW };
+        unsafe { ShowWindow(self.hwnd, command) };
+
+        if let Some(connection_name) = self.initial_fullscreen.take() {
+            // Use the current monitor when the requested one is gone.
+            let hmonitor = monitors
+                .iter()
+                .find(|monitor| monitor.connection_name().as_deref() == Some(&*connection_name))
+                .map_or_else(|| monitor::window_monitor(self.hwnd), Monitor::hmonitor);
+            self.enter_fullscreen(hmonitor);
+        }
+    }
+
+    /// Hide the window, for the [`CloseBehavior::HideInstead`].
+    pub(crate) fn hide(&mut self) {
+        self.set_visible(false);
+    }
+
+    /// Read the size of the client area, returning whether it changed.
+    pub(crate) fn update_size(&mut self) -> bool {
+        let mut rect: RECT = unsafe { mem::zeroed() };
+        unsafe { GetClientRect(self.hwnd, &mut rect) };
+
+        let size =
+            PhysicalSize::new((rect.right - rect.left) as u32, (rect.bottom - rect.top) as u32);
+        mem::replace(&mut self.size, size) != size
+    }
+
+    /// Set the resizable state on the window.
+    #[inline]
+    pub fn set_resizable(&mut self, resizable: bool) {
+        if self.resizable == resizable {
+            return;
+        }
+
+        self.resizable = resizable;
+        self.update_style();
+    }
+
+    pub fn set_min_inner_size(&mut self, size: Option<Size>) {
+        self.shared.min_inner_size.set(size);
+
+        // Clamp the current size to the new limits.
+        self.apply_inner_size(self.size);
+    }
+
+    pub fn set_max_inner_size(&mut self, size: Option<Size>) {
+        self.shared.max_inner_size.set(size);
+        self.apply_inner_size(self.size);
+    }
+
+    /// Make the window cover the whole `hmonitor`, without the decorations.
+    fn enter_fullscreen(&mut self, hmonitor: isize) {
+        let monitor = match Monitor::new(hmonitor) {
+            Some(monitor) => monitor,
+            None => return,
+        };
+
+        let mut rect: RECT = unsafe { mem::zeroed() };
+        unsafe { GetWindowRect(self.hwnd, &mut rect) };
+        self.fullscreen = Some(rect);
+
+        self.set_style(WS_POPUP | WS_VISIBLE);
+        let (position, size) = (monitor.position(), monitor.size());
+        unsafe {
+            SetWindowPos(
+                self.hwnd,
+                HWND_TOP,
+                position.x,
+                position.y,
+                size.width as i32,
+                size.height as i32,
+                SWP_FRAMECHANGED | SWP_NOOWNERZORDER,
+            )
+        };
+    }
+
+    /// Leave the fullscreen, restoring the decorations and the outer
+    /// rectangle the window had before.
+    pub fn exit_fullscreen(&mut self) {
+        let rect = match self.fullscreen.take() {
+            Some(rect) => rect,
+            None => return,
+        };
+
+        // The decorations and the resizability could change meanwhile.
+        self.set_style(self.style());
+        unsafe {
+            SetWindowPos(
+                self.hwnd,
+                0,
+                rect.left,
+                rect.top,
+                rect.right - rect.left,
+                rect.bottom - rect.top,
+                SWP_FRAMECHANGED | SWP_NOOWNERZORDER | SWP_NOZORDER,
+            )
+        };
+    }
+
+    /// The style of the window, according to its state.
+    fn style(&self) -> WINDOW_STYLE {
+        match self.fullscreen {
+            Some(_) => WS_POPUP | WS_VISIBLE,
+            None => window_style(self.decorate, self.resizable) | WS_VISIBLE,
+        }
+    }
+
+    fn set_style(&self, style: WINDOW_STYLE) {
+        unsafe { SetWindowLongW(self.hwnd, GWL_STYLE, style as i32) };
+    }
+
+    /// Apply the style after the change of the decorations or the
+    /// resizability, keeping the outer size.
+    fn update_style(&mut self) {
+        // The style is restored when leaving the fullscreen.
+        if self.fullscreen.is_some() {
+            return;
+        }
+
+        self.set_style(self.style());
+        let flags = SWP_FRAMECHANGED | SWP_NOMOVE | SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE;
+        unsafe { SetWindowPos(self.hwnd, 0, 0, 0, 0, 0, flags) };
+    }
+
+    fn ex_style(&self) -> WINDOW_EX_STYLE {
+        unsafe { GetWindowLongW(self.hwnd, GWL_EXSTYLE) as WINDOW_EX_STYLE }
+    }
+
+    /// Resize the window for the client area of the `size`, the change is
+    /// delivered with the `WM_SIZE`.
+    fn apply_inner_size(&self, size: PhysicalSize<u32>) {
+        let dpi = unsafe { GetDpiForWindow(self.hwnd) };
+        let (width, height) = outer_size(size, self.style(), self.ex_style(), dpi);
+        let flags = SWP_NOMOVE | SWP_NOZORDER | SWP_NOACTIVATE;
+        unsafe { SetWindowPos(self.hwnd, 0, 0, 0, width, height, flags) };
+    }
+
+    fn to_physical_size(&self, size: Size) -> PhysicalSize<u32> {
+        match size {
+            Size::Physical(size) => size,
+            Size::Logical(size) => self.rounding_policy.to_physical_size(size, self.scale_factor),
+        }
+    }
+
+    /// Switch the system decorations to the dark variant for the dark theme.
+    pub(crate) fn apply_theme(&self) {
+        let dark = self.theme.or_else(system_theme) == Some(Theme::Dark);
+        let value = dark as BOOL;
+        unsafe {
+            DwmSetWindowAttribute(
+                self.hwnd,
+                DWMWA_USE_IMMERSIVE_DARK_MODE as _,
+                &value as *const BOOL as *const c_void,
+                mem::size_of::<BOOL>() as u32,
+            )
+        };
+    }
+
+    /// Keep the window above the `owner`, the main window of its group.
+    pub(crate) fn set_owner(&mut self, owner: HWND) {
+        if self.owner == owner {
+            return;
+        }
+
+        self.owner = owner;
+        unsafe { SetWindowLongPtrW(self.hwnd, GWLP_HWNDPARENT, owner as _) };
+    }
+
+    /// The state of the window for the [`EventLoopHandle::dump_state`],
+    /// without the title.
+    ///
+    /// [`EventLoopHandle::dump_state`]: winit_core::event_loop::EventLoopHandle::dump_state
+    pub(crate) fn dump(&self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id.0,
+            "created": self.created,
+            "size": (self.size.width, self.size.height),
+            "scale_factor": self.scale_factor,
+            "decorated": self.decorate,
+            "resizable": self.resizable,
+            "maximized": self.is_maximized(),
+            "minimized": self.minimized,
+            "fullscreen": self.fullscreen.is_some(),
+            "focused": self.has_focus,
+            "group": self.group.map(|(group, _)| group.0),
+        })
+    }
+}
+
+impl CoreSurface for Window {
+    fn id(&self) -> WindowId {
+        self.id
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    fn request_redraw(&mut self) {
+        // The redraw is delivered with the `WM_PAINT`.
+        unsafe { InvalidateRect(self.hwnd, ptr::null(), 0) };
+    }
+
+    fn inner_size(&self) -> PhysicalSize<u32> {
+        self.size
+    }
+
+    fn surface_geometry(&self) -> Rect<u32> {
+        // The decorations are outside of the client area.
+        Rect::new(PhysicalPosition::new(0, 0), self.size)
+    }
+
+    fn current_monitor(&self) -> Option<MonitorId> {
+        Monitor::new(monitor::window_monitor(self.hwnd)).map(|monitor| monitor.id())
+    }
+
+    fn primary_monitor(&self) -> Option<MonitorId> {
+        Monitor::new(monitor::primary_monitor()).map(|monitor| monitor.id())
+    }
+
+    fn set_opaque_region(&mut self, region: Option<&[Rect<u32>]>) {
+        // DWM composes the windows without the hint.
+        let _ = region;
+    }
+
+    fn set_input_region(&mut self, region: Option<&[Rect<u32>]>) {
+        let pass_through = region.is_some_and(|rects| {
+            rects.iter().all(|rect| rect.size.width == 0 || rect.size.height == 0)
+        });
+        *self.shared.input_region.borrow_mut() = region.map(|rects| rects.to_vec());
+
+        // The hit testing passes the input only to the windows of the same
+        // thread, the transparent style passes it to the other applications as
+        // well, but only for the whole window.
+        let mut ex_style = self.ex_style();
+        if pass_through {
+            ex_style |= WS_EX_LAYERED | WS_EX_TRANSPARENT;
+        } else {
+            ex_style &= !(WS_EX_LAYERED | WS_EX_TRANSPARENT);
+        }
+
+        unsafe {
+            SetWindowLongW(self.hwnd, GWL_EXSTYLE, ex_style as i32);
+
+            // The layered window is invisible until its opacity is set.
+            if pass_through {
+                SetLayeredWindowAttributes(self.hwnd, 0, 255, LWA_ALPHA);
+            }
+        }
+    }
+
+    fn is_throttled(&self) -> bool {
+        self.minimized
+    }
+
+    fn is_focusable(&self) -> bool {
+        self.focusable
+    }
+
+    fn set_focusable(&mut self, focusable: bool) {
+        self.focusable = focusable;
+
+        let mut ex_style = self.ex_style();
+        if focusable {
+            ex_style &= !WS_EX_NOACTIVATE;
+        } else {
+            ex_style |= WS_EX_NOACTIVATE;
+        }
+
+        unsafe { SetWindowLongW(self.hwnd, GWL_EXSTYLE, ex_style as i32) };
+    }
+
+    fn set_cursor(&mut self, cursor: CursorIcon, seat: Option<DeviceId>) {
+        // Windows merges all the mice into one pointer.
+        let _ = seat;
+
+        let cursor = load_cursor(cursor);
+        self.shared.cursor.set(cursor);
+
+        // The cursor is set with the `WM_SETCURSOR` otherwise.
+        if self.pointer_inside {
+            unsafe { SetCursor(cursor) };
+        }
+    }
+
+    fn cursor_position(&self, seat: Option<DeviceId>) -> Option<PhysicalPosition<f64>> {
+        let _ = seat;
+        self.pointer_position
+    }
+
+    fn as_toplevel(&self) -> Option<&dyn Toplevel> {
+        Some(self)
+    }
+
+    fn as_toplevel_mut(&mut self) -> Option<&mut dyn Toplevel> {
+        Some(self)
+    }
+}
+
+impl Toplevel for Window {
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    fn set_title(&mut self, title: Title) -> Title {
+        let title = self.title_policy.apply(title.as_str());
+        if title.is_truncated() {
+            log::debug!("Window title truncated to {} bytes", self.title_policy.max_len);
+        }
+
+        let wide_title = crate::encode_wide(title.as_str());
+        unsafe { SetWindowTextW(self.hwnd, wide_title.as_ptr()) };
+        self.title = title.as_str().to_owned();
+        title
+    }
+
+    fn title_policy(&self) -> TitlePolicy {
+        self.title_policy
+    }
+
+    fn theme(&self) -> Option<Theme> {
+        self.theme
+    }
+
+    fn set_theme(&mut self, theme: Option<Theme>) {
+        self.theme = theme;
+        self.apply_theme();
+    }
+
+    fn is_decorated(&self) -> bool {
+        self.decorate
+    }
+
+    fn set_decorations(&mut self, decorate: bool) {
+        if self.decorate == decorate {
+            return;
+        }
+
+        self.decorate = decorate;
+        self.update_style();
+    }
+
+    fn request_inner_size(&mut self, size: Size) -> Option<PhysicalSize<u32>> {
+        // The maximized and the fullscreen windows keep their size.
+        if self.fullscreen.is_none() && !self.is_maximized() {
+            self.apply_inner_size(self.to_physical_size(size));
+        }
+
+        None
+    }
+
+    fn set_minimized(&mut self, minimize: bool) {
+        if minimize {
+            unsafe { ShowWindow(self.hwnd, SW_MINIMIZE) };
+        } else if unsafe { IsIconic(self.hwnd) } != 0 {
+            unsafe { ShowWindow(self.hwnd, SW_RESTORE) };
+        }
+    }
+
+    fn set_maximized(&mut self, maximized: bool) {
+        if maximized {
+            unsafe { ShowWindow(self.hwnd, SW_MAXIMIZE) };
+        } else if self.is_maximized() {
+            unsafe { ShowWindow(self.hwnd, SW_RESTORE) };
+        }
+    }
+
+    fn is_maximized(&self) -> bool {
+        unsafe { IsZoomed(self.hwnd) != 0 }
+    }
+
+    fn request_activation(&mut self) {
+        if !self.focusable {
+            return;
+        }
+
+        // The system refuses the focus stealing.
+        if unsafe { SetForegroundWindow(self.hwnd) } == 0 {
+            self.activation_denied = true;
+        }
+    }
+
+    fn is_fullscreen(&self) -> bool {
+        self.fullscreen.is_some()
+    }
+
+    fn outer_position(&self) -> Option<PhysicalPosition<i32>> {
+        let mut rect: RECT = unsafe { mem::zeroed() };
+        if unsafe { GetWindowRect(self.hwnd, &mut rect) } == 0 {
+            return None;
+        }
+
+        Some(PhysicalPosition::new(rect.left, rect.top))
+    }
+
+    fn close_behavior(&self) -> CloseBehavior {
+        self.close_behavior
+    }
+
+    fn set_close_behavior(&mut self, close_behavior: CloseBehavior) {
+        self.close_behavior = close_behavior;
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        let command = if visible { SW_SHOW } else { SW_HIDE };
+        unsafe { ShowWindow(self.hwnd, command) };
+    }
+
+    fn hit_test(&self, position: Position) -> RegionKind {
+        let position: PhysicalPosition<i32> = position.to_physical(self.scale_factor);
+
+        let mut rect: RECT = unsafe { mem::zeroed() };
+        unsafe { GetWindowRect(self.hwnd, &mut rect) };
+        let point = POINT { x: rect.left + position.x, y: rect.top + position.y };
+
+        // Ask the default window procedure, which knows the system metrics.
+        let lparam = ((point.y as u16 as u32) << 16 | point.x as u16 as u32) as isize;
+        let hit = unsafe { SendMessageW(self.hwnd, WM_NCHITTEST, 0, lparam) } as u32;
+        match hit {
+            HTCLIENT => RegionKind::Content,
+            HTCAPTION | HTSYSMENU | HTMINBUTTON | HTMAXBUTTON | HTCLOSE | HTBORDER => {
+                RegionKind::Frame
+            },
+            HTTOP => RegionKind::ResizeBorder(Border::Top),
+            HTBOTTOM => RegionKind::ResizeBorder(Border::Bottom),
+            HTLEFT => RegionKind::ResizeBorder(Border::Left),
+            HTRIGHT => RegionKind::ResizeBorder(Border::Right),
+            HTTOPLEFT => RegionKind::ResizeBorder(Border::TopLeft),
+            HTTOPRIGHT => RegionKind::ResizeBorder(Border::TopRight),
+            HTBOTTOMLEFT => RegionKind::ResizeBorder(Border::BottomLeft),
+            HTBOTTOMRIGHT => RegionKind::ResizeBorder(Border::BottomRight),
+            _ => RegionKind::Outside,
+        }
+    }
+
+    fn tiled_edges(&self) -> TiledEdges {
+        // The snapping isn't revealed to the applications, only the maximized
+        // windows are known to touch the edges.
+        if self.is_maximized() {
+            TiledEdges::all()
+        } else {
+            TiledEdges::empty()
+        }
+    }
+
+    fn recommended_bounds(&self) -> Option<LogicalSize<u32>> {
+        let monitor = Monitor::new(monitor::window_monitor(self.hwnd))?;
+        let size = monitor.work_area().size;
+        let size = PhysicalSize::new(size.width.max(0) as u32, size.height.max(0) as u32);
+        Some(size.to_logical(self.scale_factor))
+    }
+
+    fn group(&self) -> Option<GroupId> {
+        self.group.map(|(group, _)| group)
+    }
+
+    fn set_group(&mut self, group: Option<GroupId>) {
+        if self.group() == group {
+            return;
+        }
+
+        // The owners are updated on the next loop iteration, since they need
+        // the rest of the group.
+        self.group = group.map(|group| (group, Instant::now()));
+    }
+
+    fn set_shadow(&mut self, shadow: bool) {
+        // The DWM draws the shadow around the windows without decorations
+        // once the frame is extended into them.
+        let inset = shadow as i32;
+        let margins = MARGINS {
+            cxLeftWidth: inset,
+            cxRightWidth: inset,
+            cyTopHeight: inset,
+            cyBottomHeight: inset,
+        };
+        unsafe { DwmExtendFrameIntoClientArea(self.hwnd, &margins) };
+    }
+
+    fn set_corner_radius(&mut self, radius: Option<f32>) {
+        // Only the small and the regular radius, 4 and 8 pixels, are offered
+        // since Windows 11, the older versions ignore the preference.
+        let preference: DWM_WINDOW_CORNER_PREFERENCE = match radius {
+            None => DWMWCP_DEFAULT,
+            Some(radius) if radius <= 0. => DWMWCP_DONOTROUND,
+            Some(radius) if radius < 6. => DWMWCP_ROUNDSMALL,
+            Some(_) => DWMWCP_ROUND,
+        };
+        unsafe {
+            DwmSetWindowAttribute(
+                self.hwnd,
+                DWMWA_WINDOW_CORNER_PREFERENCE as _,
+                &preference as *const DWM_WINDOW_CORNER_PREFERENCE as *const c_void,
+                mem::size_of::<DWM_WINDOW_CORNER_PREFERENCE>() as u32,
+            )
+        };
+    }
+}
+
+impl Drop for Window {
+    fn drop(&mut self) {
+        unsafe {
+            SetWindowLongPtrW(self.hwnd, GWLP_USERDATA, 0);
+            DestroyWindow(self.hwnd);
+        }
+    }
+}
+
+impl HasWindowHandle for Window {
+    fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+        let hwnd = NonZeroIsize::new(self.hwnd).expect("the window handle is never null");
+        let mut handle = Win32WindowHandle::new(hwnd);
+        let hinstance = unsafe { GetWindowLongPtrW(self.hwnd, GWLP_HINSTANCE) };
+        handle.hinstance = NonZeroIsize::new(hinstance as isize);
+
+        unsafe { Ok(WindowHandle::borrow_raw(RawWindowHandle::Win32(handle))) }
+    }
+}
+
+unsafe impl HasRawWindowHandle05 for Window {
+    fn raw_window_handle(&self) -> raw_window_handle_05::RawWindowHandle {
+        let mut window_handle = raw_window_handle_05::Win32WindowHandle::empty();
+        window_handle.hwnd = self.hwnd as *mut _;
+        window_handle.hinstance = unsafe { GetWindowLongPtrW(self.hwnd, GWLP_HINSTANCE) as *mut _ };
+        raw_window_handle_05::RawWindowHandle::Win32(window_handle)
+    }
+}
+
+/// The style of the window with the `decorate` and `resizable` state.
+fn window_style(decorate: bool, resizable: bool) -> WINDOW_STYLE {
+    let style = if decorate {
+        WS_OVERLAPPEDWINDOW
+    } else {
+        // Keep the minimize box, so the taskbar could still minimize it.
+        WS_POPUP | WS_MINIMIZEBOX | WS_SYSMENU
+    };
+
+    if resizable {
+        style
+    } else {
+        style & !(WS_THICKFRAME | WS_MAXIMIZEBOX)
+    }
+}
+
+/// The size of the window with the client area of the `size`.
+pub(crate) fn outer_size(
+    size: PhysicalSize<u32>,
+    style: WINDOW_STYLE,
+    ex_style: WINDOW_EX_STYLE,
+    dpi: u32,
+) -> (i32, i32) {
+    let mut rect = RECT { left: 0, top: 0, right: size.width as i32, bottom: size.height as i32 };
+    unsafe { AdjustWindowRectExForDpi(&mut rect, style, 0, ex_style, dpi) };
+
+    (rect.right - rect.left, rect.bottom - rect.top)
+}
+
+/// The theme preferred by the user for the applications.
+pub(crate) fn system_theme() -> Option<Theme> {
+    let key =
+        crate::encode_wide("Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize");
+    let value = crate::encode_wide("AppsUseLightTheme");
+
+    let mut light: u32 = 0;
+    let mut size = mem::size_of::<u32>() as u32;
+    let result = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            key.as_ptr(),
+            value.as_ptr(),
+            RRF_RT_REG_DWORD,
+            ptr::null_mut(),
+            &mut light as *mut u32 as *mut c_void,
+            &mut size,
+        )
+    };
+
+    match result {
+        0 if light == 0 => Some(Theme::Dark),
+        0 => Some(Theme::Light),
+        _ => None,
+    }
+}
+
+/// The system cursor closest to the `icon`.
+fn load_cursor(icon: CursorIcon) -> HCURSOR {
+    let name = match icon {
+        CursorIcon::Pointer => IDC_HAND,
+        CursorIcon::Text | CursorIcon::VerticalText => IDC_IBEAM,
+        CursorIcon::Wait => IDC_WAIT,
+        CursorIcon::Progress => IDC_APPSTARTING,
+        CursorIcon::Help => IDC_HELP,
+        CursorIcon::Crosshair | CursorIcon::Cell => IDC_CROSS,
+        CursorIcon::Move | CursorIcon::AllScroll | CursorIcon::Grab | CursorIcon::Grabbing => {
+            IDC_SIZEALL
+        },
+        CursorIcon::NotAllowed | CursorIcon::NoDrop => IDC_NO,
+        CursorIcon::EResize
+        | CursorIcon::WResize
+        | CursorIcon::EwResize
+        | CursorIcon::ColResize => IDC_SIZEWE,
+        CursorIcon::NResize
+        | CursorIcon::SResize
+        | CursorIcon::NsResize
+        | CursorIcon::RowResize => IDC_SIZENS,
+        CursorIcon::NeResize | CursorIcon::SwResize | CursorIcon::NeswResize => IDC_SIZENESW,
+        CursorIcon::NwResize | CursorIcon::SeResize | CursorIcon::NwseResize => IDC_SIZENWSE,
+        _ => IDC_ARROW,
+    };
+
+    unsafe { LoadCursorW(0, name) }
+}